@@ -13,6 +13,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .subcommand(ClientCommand::get_status_command())
         .subcommand(ClientCommand::get_broadcast_command())
         .subcommand(ClientCommand::get_automatic_command())
+        .subcommand(ClientCommand::get_watchtower_command())
+        .subcommand(ClientCommand::get_export_command())
         .subcommand(ClientCommand::get_interactive_command());
 
     let matches = command.clone().get_matches();
@@ -28,7 +30,13 @@ async fn main() -> Result<(), Box<dyn Error>> {
         let _ = client_command.handle_broadcast_command(sub_matches).await;
     } else if let Some(sub_matches) = matches.subcommand_matches("automatic") {
         let mut client_command = ClientCommand::new(sub_matches).await;
-        let _ = client_command.handle_automatic_command().await;
+        let _ = client_command.handle_automatic_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("watchtower") {
+        let mut client_command = ClientCommand::new(sub_matches).await;
+        let _ = client_command.handle_watchtower_command(sub_matches).await;
+    } else if let Some(sub_matches) = matches.subcommand_matches("export") {
+        let mut client_command = ClientCommand::new(sub_matches).await;
+        let _ = client_command.handle_export_command(sub_matches).await;
     } else if let Some(sub_matches) = matches.subcommand_matches("interactive") {
         let mut client_command = ClientCommand::new(sub_matches).await;
         let _ = client_command.handle_interactive_command(&command).await;
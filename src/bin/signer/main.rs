@@ -0,0 +1,41 @@
+use bitvm::bridge::signer::{local::LocalSigner, remote::serve, Signer};
+use clap::{arg, command};
+use std::{env, error::Error, net::TcpListener};
+
+// The secret key is read from the environment rather than the command line, where other users
+// could see it
+const SECRET_KEY_VAR: &str = "BRIDGE_SIGNER_SECRET_KEY";
+// Hex encoded key the clients authenticate their requests with, see `RemoteSigner::connect`
+const AUTH_KEY_VAR: &str = "BRIDGE_SIGNER_AUTH_KEY";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let command = command!() // requires `cargo` feature
+        .about("Sign bridge transactions for a RemoteSigner without exposing the secret key")
+        .arg(
+            arg!(-l --listen <ADDRESS> "Address to listen on")
+                .required(false)
+                .default_value("127.0.0.1:9100"),
+        )
+        .arg(
+            arg!(-n --nonce_file <PATH> "File the secret MuSig2 nonces are kept in across restarts")
+                .required(false)
+                .default_value("bridge_signer_nonces.json"),
+        );
+
+    let matches = command.get_matches();
+    let address = matches.get_one::<String>("listen").unwrap();
+    let nonce_file = matches.get_one::<String>("nonce_file").unwrap();
+
+    let secret = env::var(SECRET_KEY_VAR)
+        .map_err(|_| format!("Set the secret key to sign with in {}", SECRET_KEY_VAR))?;
+    let auth_key = env::var(AUTH_KEY_VAR)
+        .map_err(|_| format!("Set the key clients authenticate with in {}", AUTH_KEY_VAR))?;
+    let auth_key = hex::decode(auth_key)?;
+    let signer = LocalSigner::from_secret(&secret)?.with_nonce_file(nonce_file);
+
+    let listener = TcpListener::bind(address)?;
+    println!("Signing for {} on {}", signer.public_key(), address);
+    serve(listener, &signer, &auth_key)?;
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use bitvm::tracer::{Trace, TraceStep};
+use clap::{arg, command};
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "n/<enter>: next, p: previous, g <step>: go to step, f <gadget>: find gadget, q: quit";
+
+fn print_step(step: &TraceStep, total: usize) {
+    println!("--- step {} of {} @ byte {}", step.step, total, step.offset);
+    println!("gadget:      {}", step.gadget.as_deref().unwrap_or("-"));
+    println!("instruction: {}", step.instruction.as_deref().unwrap_or("-"));
+    println!("stack ({}):", step.stack.len());
+    for (i, item) in step.stack.iter().rev().enumerate() {
+        println!("  {:>4}: {}", i, item);
+    }
+    println!("altstack ({}):", step.altstack.len());
+    for (i, item) in step.altstack.iter().rev().enumerate() {
+        println!("  {:>4}: {}", i, item);
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let command = command!() // requires `cargo` feature
+        .arg(arg!(<TRACE> "Path of a JSON trace written by bitvm::tracer"))
+        .arg(arg!(-s --step <STEP> "Step to start at").required(false));
+
+    let matches = command.get_matches();
+    let trace = Trace::read(matches.get_one::<String>("TRACE").unwrap())?;
+    let total = trace.num_steps;
+    match &trace.error {
+        Some(error) => println!("execution failed after {} steps: {}", total, error),
+        None => println!("execution {} after {} steps", if trace.success { "succeeded" } else { "failed" }, total),
+    }
+    println!("{}", HELP);
+
+    // Index into the snapshots, not the step number
+    let mut current = match matches.get_one::<String>("step") {
+        Some(step) => {
+            let step = step.parse::<usize>()?;
+            trace.steps.iter().position(|s| s.step >= step).unwrap_or(trace.steps.len() - 1)
+        }
+        None => 0,
+    };
+    print_step(&trace.steps[current], total);
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next()) {
+            (None, _) | (Some("n"), _) => current = (current + 1).min(trace.steps.len() - 1),
+            (Some("p"), _) => current = current.saturating_sub(1),
+            (Some("g"), Some(step)) => match step.parse::<usize>() {
+                Ok(step) => {
+                    current = trace.steps.iter().position(|s| s.step >= step).unwrap_or(trace.steps.len() - 1)
+                }
+                Err(_) => {
+                    eprintln!("Invalid step: {}", step);
+                    continue;
+                }
+            },
+            (Some("f"), Some(gadget)) => {
+                let found = trace.steps[current + 1..]
+                    .iter()
+                    .position(|s| s.gadget.as_deref().is_some_and(|g| g.contains(gadget)));
+                match found {
+                    Some(i) => current += 1 + i,
+                    None => {
+                        eprintln!("No later step in gadget {}", gadget);
+                        continue;
+                    }
+                }
+            }
+            (Some("q"), _) => break,
+            _ => {
+                println!("{}", HELP);
+                continue;
+            }
+        }
+        print_step(&trace.steps[current], total);
+    }
+    Ok(())
+}
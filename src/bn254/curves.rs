@@ -3,7 +3,10 @@ use num_bigint::BigUint;
 use crate::bigint::U254;
 use crate::bn254::fp254impl::Fp254Impl;
 use crate::bn254::fq::Fq;
+use crate::bn254::fq2::Fq2;
 use crate::bn254::fr::Fr;
+use crate::bn254::utils::fq2_push;
+use ark_ec::short_weierstrass::SWCurveConfig;
 use crate::treepp::{script, Script};
 use std::cmp::min;
 use std::sync::OnceLock;
@@ -573,7 +576,7 @@ impl G1Projective {
         }
     }
 
-    fn dfs_with_constant_mul(index: u32, depth: u32,  mask: u32, p_mul: &Vec<ark_bn254::G1Projective>) -> Script {
+    pub(crate) fn dfs_with_constant_mul(index: u32, depth: u32,  mask: u32, p_mul: &Vec<ark_bn254::G1Projective>) -> Script {
         if depth == 0 {
             return script!{
                 OP_IF
@@ -714,6 +717,26 @@ impl G1Affine {
     pub fn into_projective() -> Script { script!({ Fq::push_one() }) }
 }
 
+pub struct G2Affine;
+
+impl G2Affine {
+    // Input Stack: [x, y], where x and y are Fq2 elements
+    // Output Stack: [y^2 == x^3 + b'], b' is the coefficient of the twist
+    pub fn is_on_curve() -> Script {
+        script! {
+            { Fq2::copy(2) }
+            { Fq2::square() }
+            { Fq2::roll(4) }
+            { Fq2::mul(2, 0) }
+            { fq2_push(ark_bn254::g2::Config::COEFF_B) }
+            { Fq2::add(2, 0) }
+            { Fq2::roll(2) }
+            { Fq2::square() }
+            { Fq2::equal() }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -1238,6 +1261,35 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_g2_affine_is_on_curve() {
+        let g2_is_on_curve = crate::bn254::curves::G2Affine::is_on_curve();
+        println!("G2.affine_is_on_curve: {} bytes", g2_is_on_curve.len());
+
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        for _ in 0..3 {
+            let q = ark_bn254::G2Affine::rand(&mut prng);
+
+            let script = script! {
+                { crate::bn254::utils::fq2_push(q.x) }
+                { crate::bn254::utils::fq2_push(q.y) }
+                { g2_is_on_curve.clone() }
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+
+            let script = script! {
+                { crate::bn254::utils::fq2_push(q.x) }
+                { crate::bn254::utils::fq2_push(q.y.double()) }
+                { g2_is_on_curve.clone() }
+                OP_NOT
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
     #[test]
     fn test_affine_is_on_curve() {
         let affine_is_on_curve = G1Affine::is_on_curve();
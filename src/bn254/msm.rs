@@ -1,11 +1,14 @@
+use crate::bigint::bits::limb_to_le_bits;
+use crate::bigint::U254;
 use crate::bn254::curves::G1Projective;
 use crate::bn254::fp254impl::Fp254Impl;
 use crate::bn254::fq::Fq;
 use crate::bn254::fr::Fr;
-use std::ops::Mul;
 use crate::treepp::{script, Script};
+use ark_ec::AffineRepr;
 use ark_ff::Field;
 use num_bigint::BigUint;
+use std::cmp::min;
 
 pub fn g1_affine_push(point: ark_bn254::G1Affine) -> Script {
     script! {
@@ -52,28 +55,168 @@ pub fn msm(bases: &[ark_bn254::G1Affine], scalars: &[ark_bn254::Fr]) -> Script {
 // Output Stack: [x,y]
 pub fn msm_with_constant_bases(bases: &[ark_bn254::G1Affine], scalars: &[ark_bn254::Fr]) -> Script {
     assert_eq!(bases.len(), scalars.len());
-    let bases: Vec<ark_ec::short_weierstrass::Projective<ark_bn254::g1::Config>> =
-        bases.iter().map(|&p| p.into()).collect();
-    let len = bases.len();
 
-    println!("len: {len}");
     script! {
-        // 1. init the sum=0;
-        {G1Projective::push_zero()}
-        for i in 0..len {
-            // 2. scalar mul
-            if scalars[i] != ark_bn254::Fr::ONE {
-                { fr_push(scalars[i]) }
-                { G1Projective::scalar_mul_by_constant_g1(bases[i]) }
-            } else {
-                { G1Projective::push(bases[i]) }
+        for scalar in scalars {
+            { fr_push(*scalar) }
+        }
+        { msm_with_constant_bases_in_stack(bases, MSM_WINDOW) }
+        // convert into Affine
+        { G1Projective::into_affine() }
+    }
+}
+
+// Window width of `msm_with_constant_bases`, options: 1-12
+//
+// Every (window, base) pair costs one addition and a lookup table of 2^window - 1 points, see
+// `test_msm_with_constant_bases_script_size` for the script sizes
+pub const MSM_WINDOW: u32 = 6;
+
+// Interleaved windowed msm with constant bases: the doublings are shared by all the bases and
+// the multiples of every base are pushed as constants
+//
+// The scalars are processed limb by limb, most significant first, and every limb is split into
+// windows of at most `window` bits. Only the bits of the current limb of every scalar are on
+// the stack at the same time, so the stack grows by about 38 elements per base instead of 254
+// Input Stack: [s_0, ..., s_{n-1}]
+// Output Stack: [sum(s_i * bases[i])] in projective
+pub fn msm_with_constant_bases_in_stack(bases: &[ark_bn254::G1Affine], window: u32) -> Script {
+    assert!((1..=12).contains(&window));
+    let n = bases.len();
+    if n == 0 {
+        return G1Projective::push_zero();
+    }
+
+    // p_mul[j] = (j + 1) * base
+    let tables: Vec<Vec<ark_bn254::G1Projective>> = bases
+        .iter()
+        .map(|&base| {
+            let mut p_mul = vec![base.into_group()];
+            for _ in 1..(1 << window) - 1 {
+                p_mul.push(*p_mul.last().unwrap() + base);
             }
-            // 3. sum the base
-            // { G1Projective::add() }
-            { G1Projective::push(bases[i].mul(scalars[i])) }
+            p_mul
+        })
+        .collect();
+
+    // limb widths, most significant limb first (deepest on the stack)
+    let limb_size = (U254::N_BITS - U254::HEAD) / (U254::N_LIMBS - 1);
+    let limb_widths: Vec<u32> = (0..U254::N_LIMBS)
+        .map(|l| if l == 0 { U254::HEAD } else { limb_size })
+        .collect();
+
+    // Track the stack to compute the roll depths: the scalar limbs, the bits of the current limb
+    // and the accumulator on top
+    #[derive(PartialEq)]
+    enum Item {
+        Limb(usize, u32),
+        Bit(usize, u32),
+        Acc,
+    }
+    let size = |item: &Item| match item {
+        Item::Acc => 3 * Fq::N_LIMBS,
+        _ => 1,
+    };
+    let mut stack: Vec<Item> = (0..n)
+        .flat_map(|k| (0..U254::N_LIMBS).map(move |l| Item::Limb(k, l)))
+        .collect();
+    stack.push(Item::Acc);
+    let roll_to_top = |stack: &mut Vec<Item>, item: Item| -> u32 {
+        let position = stack.iter().rposition(|x| *x == item).unwrap();
+        let depth = stack[position + 1..].iter().map(size).sum::<u32>();
+        stack.remove(position);
+        depth
+    };
+
+    let mut limb_scripts = Vec::new();
+    for (l, &width) in limb_widths.iter().enumerate() {
+        let l = l as u32;
+
+        // windows from the most significant bits of the limb, as (lowest bit, width)
+        let mut windows = Vec::new();
+        let mut i = 0;
+        while i < width {
+            let depth = min(width - i, window);
+            windows.push((width - i - depth, depth));
+            i += depth;
+        }
+
+        // 1. convert the current limb of every scalar into bits, the least significant on top
+        let mut to_bits = Vec::new();
+        for k in 0..n {
+            let depth = roll_to_top(&mut stack, Item::Limb(k, l));
+            to_bits.push(script! {
+                { depth } OP_ROLL
+                { limb_to_le_bits(width) }
+            });
+            stack.extend((0..width).rev().map(|b| Item::Bit(k, b)));
+        }
+
+        // 2. the bits are consumed window by window, base by base, most significant bit
+        // first, roll them into the altstack in the reverse order
+        let mut consumed = Vec::new();
+        for &(low, depth) in windows.iter() {
+            for k in 0..n {
+                for b in (low..low + depth).rev() {
+                    consumed.push(Item::Bit(k, b));
+                }
+            }
+        }
+        let roll_depths: Vec<u32> = consumed
+            .into_iter()
+            .rev()
+            .map(|bit| roll_to_top(&mut stack, bit))
+            .collect();
+
+        // 3. add the windows to the accumulator
+        let first_limb = l == 0;
+        limb_scripts.push(script! {
+            for script in to_bits {
+                { script }
+            }
+            for depth in roll_depths {
+                if depth > 0 {
+                    { depth } OP_ROLL
+                }
+                OP_TOALTSTACK
+            }
+
+            for (t, &(_, depth)) in windows.iter().enumerate() {
+                if !first_limb || t > 0 {
+                    for _ in 0..depth {
+                        { G1Projective::double() }
+                    }
+                }
+
+                for k in 0..n {
+                    for _ in 0..depth {
+                        OP_FROMALTSTACK
+                    }
+                    OP_TRUE
+                    OP_TOALTSTACK
+                    { G1Projective::dfs_with_constant_mul(0, depth - 1, 0, &tables[k]) }
+                    OP_FROMALTSTACK
+
+                    OP_IF
+                        { G1Projective::add() }
+                    OP_ENDIF
+                }
+            }
+        });
+    }
+
+    script! {
+        // decode the scalars in place
+        for _ in 0..n {
+            { Fr::roll(n as u32 - 1) }
+            { Fr::decode_montgomery() }
+        }
+
+        { G1Projective::push_zero() }
+
+        for script in limb_scripts {
+            { script }
         }
-        // // convert into Affine
-        // { G1Projective::into_affine() }
     }
 }
 
@@ -136,9 +279,9 @@ mod test {
         let start = start_timer!(|| "collect_script");
         let script = script! {
             { msm.clone() }
-            // { g1_affine_push(expect) }
-            // { G1Affine::equalverify() }
-            // OP_TRUE
+            { g1_affine_push(expect) }
+            { G1Affine::equalverify() }
+            OP_TRUE
         };
         end_timer!(start);
 
@@ -148,4 +291,61 @@ mod test {
         end_timer!(start);
         assert!(exec_result.success);
     }
+
+    #[test]
+    fn test_msm_with_constant_bases_in_stack() {
+        let rng = &mut test_rng();
+
+        // (8, 6) keeps 8 scalars on the stack, which would not fit into the stack limit as bits
+        for (n, window) in [(1, 1), (3, 5), (2, 8), (8, 6)] {
+            let scalars = (0..n).map(|_| ark_bn254::Fr::rand(rng)).collect::<Vec<_>>();
+            let bases = (0..n)
+                .map(|_| ark_bn254::G1Projective::rand(rng).into_affine())
+                .collect::<Vec<_>>();
+            let expect = ark_bn254::G1Projective::msm(&bases, &scalars)
+                .unwrap()
+                .into_affine();
+
+            let script = script! {
+                for scalar in scalars.iter() {
+                    { fr_push(*scalar) }
+                }
+                { msm_with_constant_bases_in_stack(&bases, window) }
+                { G1Projective::into_affine() }
+                { g1_affine_push(expect) }
+                { G1Affine::equalverify() }
+                OP_TRUE
+            };
+            let exec_result = execute_script(script);
+            assert!(exec_result.success);
+        }
+    }
+
+    #[test]
+    fn test_msm_with_constant_bases_script_size() {
+        let rng = &mut test_rng();
+
+        // one scalar_mul_by_constant_g1 per base, which grows linearly with the number of bases
+        let base = ark_bn254::G1Projective::rand(rng);
+        let single = script! {
+            { Fq::roll(3) }
+            { G1Projective::scalar_mul_by_constant_g1(base) }
+            { G1Projective::add() }
+        }
+        .len();
+
+        for n in [4, 16] {
+            let bases = (0..n)
+                .map(|_| ark_bn254::G1Projective::rand(rng).into_affine())
+                .collect::<Vec<_>>();
+            let separate = G1Projective::push_zero().len() + n * single;
+            let interleaved = msm_with_constant_bases_in_stack(&bases, MSM_WINDOW).len();
+            println!(
+                "msm with {} bases: scalar_mul_by_constant_g1 {} bytes, interleaved {} bytes",
+                n, separate, interleaved
+            );
+            // the doublings are shared and the lookup tables are 2^6 instead of 2^12 points
+            assert!(interleaved < separate);
+        }
+    }
 }
@@ -468,6 +468,268 @@ impl Pairing {
         };
         script
     }
+
+    // same as `quad_miller_loop_with_c_wi`, but the line coefficients of the non-fixed point Q4 are
+    // supplied as witness and checked against T4 with tangent/chord line checks, so the script only
+    // depends on the fixed points Q1, Q2 and Q3
+    //
+    // input on stack:
+    //     [L(Q4), beta_12, beta_13, beta_22, P1', P2', P3', P4', Q4, c, c_inv, wi, T4]
+    //     L(Q4) is pushed by `utils::non_fixed_line_coeffs_push`, every line coefficient (c3, c4)
+    //     is rolled from the bottom of the stack at the moment it is needed
+    //
+    // input of parameters:
+    //     [L(Q1), L(Q2), L(Q3)] (line coefficients in affine mode)
+    pub fn quad_miller_loop_with_c_wi_non_fixed(constants: Vec<G2Prepared>) -> Script {
+        assert_eq!(constants.len(), 3);
+        let num_constant = constants.len();
+
+        let line_coeffs = utils::collect_line_coeffs(constants);
+        let num_lines = line_coeffs.len();
+
+        // roll the next line coefficient (c3, c4) of Q4 to the top of the stack,
+        // depth is the number of elements above L(Q4)
+        let roll_line_coeff = |depth: u32| {
+            script! {
+                { Fq2::roll(depth) }
+                { Fq2::roll(depth + 2) }
+            }
+        };
+
+        // evaluate the line of Q4 with P4' and update f, where the line coefficients are left
+        // below T4 by the tangent/chord checks
+        //
+        // input: [..., P4'(2), Q4(4), c(12), c_inv(12), wi(12), c3(2), c4(2), T4(4) | f(12)]
+        // output: [..., P4'(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
+        let non_fixed_line_eval = script! {
+            { Fq12::fromaltstack() }
+            // [..., P4'(2), Q4(4), c(12), c_inv(12), wi(12), c3(2), c4(2), T4(4), f(12)]
+            { Fq2::copy(60) }
+            // [..., P4'(2), Q4(4), c(12), c_inv(12), wi(12), c3(2), c4(2), T4(4), f(12), P4'(2)]
+            { Fq2::roll(20) }
+            { Fq2::roll(20) }
+            // [..., P4'(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12), P4'(2), c3(2), c4(2)]
+            { utils::ell_by_non_constant_affine() }
+            // [..., P4'(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
+        };
+
+        let script = script! {
+            // [L(Q4), beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4)]
+            // 1. f = c_inv
+            { Fq12::copy(16) }
+            // [L(Q4), beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
+
+            // ATE_LOOP_COUNT len: 65
+            for i in (1..ark_bn254::Config::ATE_LOOP_COUNT.len()).rev() {
+                // update f, squaring
+                { Fq12::square() }
+
+                // update f, multiplying
+                // f = f * c_inv, if digit == 1
+                // f = f * c, if digit == -1
+                if ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == 1 {
+                    { Fq12::copy(28) }
+                    { Fq12::mul(12, 0) }
+                } else if ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == -1 {
+                    { Fq12::copy(40) }
+                    { Fq12::mul(12, 0) }
+                }
+                // [L(Q4), beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
+
+                // update f with double line evaluation of fixed points
+                for j in 0..num_constant {
+                    { Fq2::copy((26 + 36 - j * 2) as u32) }
+                    { utils::ell_by_constant_affine(&line_coeffs[num_lines - (i + 2)][j][0]) }
+                }
+
+                // non-fixed part, double line of Q4
+                { Fq12::toaltstack() }
+                // [L(Q4), beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4) | f(12)]
+                { roll_line_coeff(58) }
+                // [..., T4(4), c3(2), c4(2) | f(12)]
+                { Fq2::copy(6) }
+                { Fq2::copy(6) }
+                { Fq2::copy(6) }
+                { Fq2::copy(6) }
+                // [..., T4(4), c3(2), c4(2), T4(4), c3(2), c4(2) | f(12)]
+                { utils::check_tangent_line_in_stack() }
+                // [..., T4(4), c3(2), c4(2) | f(12)]
+
+                // update T4, keep a copy of the line coefficients for the line evaluation
+                { Fq2::copy(2) }
+                { Fq2::copy(2) }
+                // [..., T4(4), c3(2), c4(2), c3(2), c4(2) | f(12)]
+                { Fq2::roll(8) }
+                { Fq2::drop() }
+                { Fq2::roll(8) }
+                // [..., c3(2), c4(2), c3(2), c4(2), T4.x(2) | f(12)]
+                { Fq2::roll(4) }
+                { Fq2::roll(4) }
+                // [..., c3(2), c4(2), T4.x(2), c3(2), c4(2) | f(12)]
+                { utils::affine_double_line_in_stack() }
+                // [..., c3(2), c4(2), T4(4) | f(12)]
+                { non_fixed_line_eval.clone() }
+                // [L(Q4), beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
+
+                // update f with add line evaluation
+                if ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == 1 || ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == -1 {
+                    for j in 0..num_constant {
+                        { Fq2::copy((26 + 36 - j * 2) as u32) }
+                        { utils::ell_by_constant_affine(&line_coeffs[num_lines - (i + 2)][j][1]) }
+                    }
+
+                    // non-fixed part, add line of Q4
+                    { Fq12::toaltstack() }
+                    { roll_line_coeff(58) }
+                    // [..., Q4(4), c(12), c_inv(12), wi(12), T4(4), c3(2), c4(2) | f(12)]
+                    { Fq2::copy(6) }
+                    { Fq2::copy(6) }
+                    // [..., Q4(4), c(12), c_inv(12), wi(12), T4(4), c3(2), c4(2), T4(4) | f(12)]
+                    { Fq2::copy(50) }
+                    { Fq2::copy(50) }
+                    if ark_bn254::Config::ATE_LOOP_COUNT[i - 1] == -1 {
+                        { Fq2::neg(0) }
+                    }
+                    // [..., Q4(4), c(12), c_inv(12), wi(12), T4(4), c3(2), c4(2), T4(4), Q4(4) | f(12)]
+                    { Fq2::copy(10) }
+                    { Fq2::copy(10) }
+                    // [..., Q4(4), c(12), c_inv(12), wi(12), T4(4), c3(2), c4(2), T4(4), Q4(4), c3(2), c4(2) | f(12)]
+                    { utils::check_chord_line_in_stack() }
+                    // [..., Q4(4), c(12), c_inv(12), wi(12), T4(4), c3(2), c4(2) | f(12)]
+
+                    // update T4, keep a copy of the line coefficients for the line evaluation
+                    { Fq2::copy(2) }
+                    { Fq2::copy(2) }
+                    { Fq2::roll(8) }
+                    { Fq2::drop() }
+                    { Fq2::roll(8) }
+                    // [..., Q4(4), c(12), c_inv(12), wi(12), c3(2), c4(2), c3(2), c4(2), T4.x(2) | f(12)]
+                    { Fq2::copy(48) }
+                    // [..., Q4(4), c(12), c_inv(12), wi(12), c3(2), c4(2), c3(2), c4(2), T4.x(2), Q4.x(2) | f(12)]
+                    { Fq2::roll(6) }
+                    { Fq2::roll(6) }
+                    // [..., Q4(4), c(12), c_inv(12), wi(12), c3(2), c4(2), T4.x(2), Q4.x(2), c3(2), c4(2) | f(12)]
+                    { utils::affine_add_line_in_stack() }
+                    // [..., Q4(4), c(12), c_inv(12), wi(12), c3(2), c4(2), T4(4) | f(12)]
+                    { non_fixed_line_eval.clone() }
+                    // [L(Q4), beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c(12), c_inv(12), wi(12), T4(4), f(12)]
+                }
+            }
+
+            // update f with frobenius of c, say f = f * c_inv^p * c^{p^2}
+            { Fq12::roll(28) }
+            { Fq12::frobenius_map(1) }
+            { Fq12::mul(12, 0) }
+            { Fq12::roll(28) }
+            { Fq12::frobenius_map(2) }
+            { Fq12::mul(12, 0) }
+            // [L(Q4), beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), wi(12), T4(4), f(12)]
+
+            // update f with scalar wi, say f = f * wi
+            { Fq12::roll(16) }
+            { Fq12::mul(12, 0) }
+            // [L(Q4), beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), f(12)]
+
+            // update f with add line evaluation of one-time of frobenius map on Q4
+            for j in 0..num_constant {
+                { Fq2::copy((26 - j * 2) as u32) }
+                { utils::ell_by_constant_affine(&line_coeffs[num_lines - 2][j][0]) }
+            }
+
+            // non-fixed part
+            { Fq12::toaltstack() }
+            { roll_line_coeff(22) }
+            // [L(Q4), beta_12(2), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), c3(2), c4(2) | f(12)]
+
+            // Qx' = Qx.conjugate * beta^{2 * (p - 1) / 6}
+            { Fq2::copy(10) }
+            { Fq::neg(0) }
+            { Fq2::roll(26) }
+            { Fq2::mul(2, 0) }
+            // [L(Q4), beta_13(2), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), c3(2), c4(2), Q4.x'(2) | f(12)]
+
+            // Qy' = Qy.conjugate * beta^{3 * (p - 1) / 6}
+            { Fq2::copy(10) }
+            { Fq::neg(0) }
+            { Fq2::roll(26) }
+            { Fq2::mul(2, 0) }
+            // [L(Q4), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), c3(2), c4(2), phi(Q4)(4) | f(12)]
+
+            // check chord line
+            { Fq2::copy(10) }
+            { Fq2::copy(10) }
+            { Fq2::copy(6) }
+            { Fq2::copy(6) }
+            { Fq2::copy(14) }
+            { Fq2::copy(14) }
+            // [..., T4(4), c3(2), c4(2), phi(Q4)(4), T4(4), phi(Q4)(4), c3(2), c4(2) | f(12)]
+            { utils::check_chord_line_in_stack() }
+            // [..., T4(4), c3(2), c4(2), phi(Q4)(4) | f(12)]
+
+            // update T4
+            { Fq2::drop() }
+            { Fq2::roll(6) }
+            { Fq2::drop() }
+            // [..., T4.x(2), c3(2), c4(2), phi(Q4).x(2) | f(12)]
+            { Fq2::copy(4) }
+            { Fq2::copy(4) }
+            { Fq2::roll(10) }
+            // [..., c3(2), c4(2), phi(Q4).x(2), c3(2), c4(2), T4.x(2) | f(12)]
+            { Fq2::roll(6) }
+            { Fq2::roll(6) }
+            { Fq2::roll(6) }
+            // [..., c3(2), c4(2), T4.x(2), phi(Q4).x(2), c3(2), c4(2) | f(12)]
+            { utils::affine_add_line_in_stack() }
+            // [L(Q4), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), c3(2), c4(2), T4(4) | f(12)]
+            { Fq12::fromaltstack() }
+            { Fq2::copy(24) }
+            { Fq2::roll(20) }
+            { Fq2::roll(20) }
+            { utils::ell_by_non_constant_affine() }
+            // [L(Q4), beta_22(2), P1(2), P2(2), P3(2), P4(2), Q4(4), T4(4), f(12)]
+
+            // update f with add line evaluation of two-times of frobenius map on Q4
+            for j in 0..num_constant {
+                { Fq2::roll((26 - j * 2) as u32) }
+                { utils::ell_by_constant_affine(&line_coeffs[num_lines - 1][j][0]) }
+            }
+            // [L(Q4), beta_22(2), P4(2), Q4(4), T4(4), f(12)]
+
+            // non-fixed part(Q4)
+            { Fq12::toaltstack() }
+            { roll_line_coeff(12) }
+            // [beta_22(2), P4(2), Q4(4), T4(4), c3(2), c4(2) | f(12)]
+            { Fq2::copy(2) }
+            { Fq2::copy(2) }
+            { Fq2::roll(10) }
+            { Fq2::roll(10) }
+            // [beta_22(2), P4(2), Q4(4), c3(2), c4(2), c3(2), c4(2), T4(4) | f(12)]
+
+            // Q4.x' = Q4.x * beta^{2 * (p^2 - 1) / 6}
+            { Fq2::roll(18) }
+            { Fq2::roll(16) }
+            { Fq2::mul(2, 0) }
+            // [P4(2), Q4.y(2), c3(2), c4(2), c3(2), c4(2), T4(4), Q4.x'(2) | f(12)]
+            { Fq2::roll(14) }
+            // phi(Q4)^2 = (Q4.x', Qy)
+            // [P4(2), c3(2), c4(2), c3(2), c4(2), T4(4), phi(Q4)^2(4) | f(12)]
+            { Fq2::roll(10) }
+            { Fq2::roll(10) }
+            // [P4(2), c3(2), c4(2), T4(4), phi(Q4)^2(4), c3(2), c4(2) | f(12)]
+
+            // check whether the chord line through T4 and phi(Q4)^2
+            { utils::check_chord_line_in_stack() }
+            // [P4(2), c3(2), c4(2) | f(12)]
+            { Fq12::fromaltstack() }
+            { Fq2::roll(16) }
+            { Fq2::roll(16) }
+            { Fq2::roll(16) }
+            // [f(12), P4(2), c3(2), c4(2)]
+            { utils::ell_by_non_constant_affine() }
+            // [f(12)]
+        };
+        script
+    }
 }
 
 #[cfg(test)]
@@ -702,6 +964,107 @@ mod test {
         assert!(exec_result.success);
     }
 
+    #[test]
+    fn test_quad_miller_loop_with_c_wi_non_fixed() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        // exp = 6x + 2 + p - p^2 = lambda - p^3
+        let p_pow3 = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap().pow(3_u32);
+        let lambda = BigUint::from_str(
+            "10486551571378427818905133077457505975146652579011797175399169355881771981095211883813744499745558409789005132135496770941292989421431235276221147148858384772096778432243207188878598198850276842458913349817007302752534892127325269"
+        ).unwrap();
+        let (exp, sign) = if lambda > p_pow3 {
+            (lambda - p_pow3, true)
+        } else {
+            (p_pow3 - lambda, false)
+        };
+        // random c and wi
+        let c = ark_bn254::Fq12::rand(&mut prng);
+        let c_inv = c.inverse().unwrap();
+        let wi = ark_bn254::Fq12::rand(&mut prng);
+
+        let p1 = ark_bn254::G1Affine::rand(&mut prng);
+        let p2 = ark_bn254::G1Affine::rand(&mut prng);
+        let p3 = ark_bn254::G1Affine::rand(&mut prng);
+        let p4 = ark_bn254::G1Affine::rand(&mut prng);
+
+        let q1 = ark_bn254::g2::G2Affine::rand(&mut prng);
+        let q2 = ark_bn254::g2::G2Affine::rand(&mut prng);
+        let q3 = ark_bn254::g2::G2Affine::rand(&mut prng);
+        let q4 = ark_bn254::g2::G2Affine::rand(&mut prng);
+        let q1_prepared = G2Prepared::from_affine(q1);
+        let q2_prepared = G2Prepared::from_affine(q2);
+        let q3_prepared = G2Prepared::from_affine(q3);
+        // only used to generate the witness
+        let q4_prepared = G2Prepared::from_affine(q4);
+
+        let t4 = q4;
+
+        // the script only depends on q1, q2 and q3
+        let quad_miller_loop_affine_script = Pairing::quad_miller_loop_with_c_wi_non_fixed(
+            [q1_prepared, q2_prepared, q3_prepared].to_vec(),
+        );
+        println!(
+            "Pairing.quad_miller_loop_non_fixed: {} bytes",
+            quad_miller_loop_affine_script.len()
+        );
+
+        let f = Bn254::multi_miller_loop_affine([p1, p2, p3, p4], [q1, q2, q3, q4]).0;
+        println!("Bn254::multi_miller_loop_affine done!");
+
+        let hint = if sign {
+            f * wi * (c_inv.pow(exp.to_u64_digits()))
+        } else {
+            f * wi * (c_inv.pow(exp.to_u64_digits()).inverse().unwrap())
+        };
+
+        // [L(Q4), beta_12, beta_13, beta_22, P1, P2, P3, P4, Q4, c, c_inv, wi, T4]
+        let script = script! {
+            // line coefficients of q4
+            { utils::non_fixed_line_coeffs_push(&q4_prepared) }
+
+            // beta_12
+            { Fq::push_u32_le(&BigUint::from_str("21575463638280843010398324269430826099269044274347216827212613867836435027261").unwrap().to_u32_digits()) }
+            { Fq::push_u32_le(&BigUint::from_str("10307601595873709700152284273816112264069230130616436755625194854815875713954").unwrap().to_u32_digits()) }
+            // beta_13
+            { Fq::push_u32_le(&BigUint::from_str("2821565182194536844548159561693502659359617185244120367078079554186484126554").unwrap().to_u32_digits()) }
+            { Fq::push_u32_le(&BigUint::from_str("3505843767911556378687030309984248845540243509899259641013678093033130930403").unwrap().to_u32_digits()) }
+            // beta_22
+            { Fq::push_u32_le(&BigUint::from_str("21888242871839275220042445260109153167277707414472061641714758635765020556616").unwrap().to_u32_digits()) }
+            { Fq::push_u32_le(&BigUint::from_str("0").unwrap().to_u32_digits()) }
+
+            // p1, p2, p3, p4
+            { utils::from_eval_point(p1) }
+            { utils::from_eval_point(p2) }
+            { utils::from_eval_point(p3) }
+            { utils::from_eval_point(p4) }
+
+            // q4
+            { fq2_push(q4.x) }
+            { fq2_push(q4.y) }
+
+            // c, c_inv, wi
+            { fq12_push(c) }
+            { fq12_push(c_inv) }
+            { fq12_push(wi) }
+
+            // t4
+            { fq2_push(t4.x) }
+            { fq2_push(t4.y) }
+
+            { quad_miller_loop_affine_script.clone() }
+
+            { fq12_push(hint) }
+
+            { Fq12::equalverify() }
+
+            OP_TRUE
+        };
+        let exec_result = execute_script_without_stack_limit(script);
+        println!("{}", exec_result);
+        assert!(exec_result.success);
+    }
+
     #[test]
     fn test_mul_by_char() {
         let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
@@ -445,6 +445,386 @@ pub fn check_chord_line(c3: ark_bn254::Fq2, c4: ark_bn254::Fq2) -> Script {
     }
 }
 
+/// push the line coefficients (c3, c4) of a non-fixed G2 point, they are consumed one by one
+/// from the bottom of the stack by `Pairing::quad_miller_loop_with_c_wi_non_fixed`, so the
+/// coefficient of the first line lies on top of the group
+///
+/// input of parameters:
+///     constant: line coefficients of the non-fixed point (affine mode)
+///
+/// output on stack:
+///     [..., c4_1 (2 elements), c3_1 (2 elements), c4_0 (2 elements), c3_0 (2 elements)]
+pub fn non_fixed_line_coeffs_push(constant: &G2Prepared) -> Script {
+    script! {
+        for coeff in constant.ell_coeffs.iter().rev() {
+            { fq2_push(coeff.2) }
+            { fq2_push(coeff.1) }
+        }
+    }
+}
+
+// stack input:
+//  f            12 elements
+//  x': -p.x / p.y   1 element
+//  y': 1 / p.y      1 element
+//  c3           2 elements
+//  c4           2 elements
+//
+// output:
+//  new f        12 elements
+pub fn ell_by_non_constant_affine() -> Script {
+    script! {
+        // [f, x', y', c3, c4]
+        { Fq::roll(5) }
+        // [f, y', c3, c4, x']
+        { Fq2::mul_by_fq(3, 0) }
+        // [f, y', c4, x' * c3]
+        { Fq::roll(4) }
+        // [f, c4, x' * c3, y']
+        { Fq2::mul_by_fq(3, 0) }
+        // [f, x' * c3, y' * c4]
+        // [f, c3', c4']
+
+        // compute the new f with c3' and c4', where c0 is trival value 1
+        { Fq12::mul_by_34() }
+        // [f]
+    }
+}
+
+/// add two points T and Q, same as `affine_add_line` but the line coefficients are on stack
+///
+/// input on stack:
+///     T.x (2 elements)
+///     Q.x (2 elements)
+///     c3: alpha (2 elements)
+///     c4: -bias (2 elements)
+///
+/// output on stack:
+///     T'.x (2 elements)
+///     T'.y (2 elements)
+pub fn affine_add_line_in_stack() -> Script {
+    script! {
+        // [T.x, Q.x, alpha, -bias]
+        { Fq2::roll(6) }
+        { Fq2::roll(6) }
+        // [alpha, -bias, T.x, Q.x]
+        { Fq2::add(2, 0) }
+        { Fq2::neg(0) }
+        // [alpha, -bias, -T.x - Q.x]
+        { affine_line_from_sum() }
+        // [x', y']
+    }
+}
+
+/// double a point T, same as `affine_double_line` but the line coefficients are on stack
+///
+/// input on stack:
+///     T.x (2 elements)
+///     c3: alpha (2 elements)
+///     c4: -bias (2 elements)
+///
+/// output on stack:
+///     T'.x (2 elements)
+///     T'.y (2 elements)
+pub fn affine_double_line_in_stack() -> Script {
+    script! {
+        // [T.x, alpha, -bias]
+        { Fq2::roll(4) }
+        // [alpha, -bias, T.x]
+        { Fq2::double(0) }
+        { Fq2::neg(0) }
+        // [alpha, -bias, -2 * T.x]
+        { affine_line_from_sum() }
+        // [x', y']
+    }
+}
+
+/// shared tail of `affine_add_line_in_stack` and `affine_double_line_in_stack`
+///
+/// input on stack:
+///     alpha (2 elements)
+///     -bias (2 elements)
+///     s: -T.x - Q.x (2 elements)
+///
+/// output on stack:
+///     x' = alpha^2 + s (2 elements)
+///     y' = -bias - alpha * x' (2 elements)
+fn affine_line_from_sum() -> Script {
+    script! {
+        // [alpha, -bias, s]
+        { Fq2::copy(4) }
+        { Fq2::square() }
+        // [alpha, -bias, s, alpha^2]
+        { Fq2::add(2, 0) }
+        // [alpha, -bias, x']
+        { Fq2::copy(0) }
+        // [alpha, -bias, x', x']
+        { Fq2::roll(6) }
+        // [-bias, x', x', alpha]
+        { Fq2::mul(2, 0) }
+        { Fq2::neg(0) }
+        // [-bias, x', -alpha * x']
+        { Fq2::roll(4) }
+        // [x', -alpha * x', -bias]
+        { Fq2::add(2, 0) }
+        // [x', y']
+    }
+}
+
+/// same as `check_line_through_point` but the line coefficients are on stack
+///
+/// input on stack:
+///     x (2 elements)
+///     y (2 elements)
+///     c3: alpha (2 elements)
+///     c4: -bias (2 elements)
+///
+/// output:
+///     true or false (consumed on stack)
+pub fn check_line_through_point_in_stack() -> Script {
+    script! {
+        // [x, y, alpha, -bias]
+        { Fq2::roll(6) }
+        // [y, alpha, -bias, x]
+        { Fq2::roll(4) }
+        // [y, -bias, x, alpha]
+        { Fq2::mul(2, 0) }
+        // [y, -bias, alpha * x]
+        { Fq2::sub(2, 0) }
+        // [y, -bias - alpha * x]
+        { Fq2::add(2, 0) }
+        // [y - alpha * x - bias]
+
+        { Fq2::push_zero() }
+        // [y - alpha * x - bias, 0]
+        { Fq2::equalverify() }
+    }
+}
+
+/// same as `check_tangent_line` but the line coefficients are on stack
+///
+/// input on stack:
+///     T.x (2 elements)
+///     T.y (2 elements)
+///     c3: alpha (2 elements)
+///     c4: -bias (2 elements)
+///
+/// output:
+///     true or false (consumed on stack)
+pub fn check_tangent_line_in_stack() -> Script {
+    script! {
+        // alpha * (2 * T.y) = 3 * T.x^2
+        { Fq2::copy(2) }
+        { Fq2::copy(6) }
+        { Fq2::double(0) }
+        { Fq2::mul(2, 0) }
+        // [T.x, T.y, alpha, -bias, alpha * (2 * T.y)]
+        { Fq2::copy(8) }
+        { Fq2::square() }
+        { Fq2::copy(0) }
+        { Fq2::double(0) }
+        { Fq2::add(2, 0) }
+        // [T.x, T.y, alpha, -bias, alpha * (2 * T.y), 3 * T.x^2]
+        { Fq2::sub(2, 0) }
+        { Fq2::push_zero() }
+        { Fq2::equalverify() }
+        // [T.x, T.y, alpha, -bias]
+
+        // check: T.y - alpha * T.x - bias = 0
+        { check_line_through_point_in_stack() }
+        // []
+    }
+}
+
+/// same as `check_chord_line` but the line coefficients are on stack
+///
+/// input on stack:
+///     T.x (2 elements)
+///     T.y (2 elements)
+///     Q.x (2 elements)
+///     Q.y (2 elements)
+///     c3: alpha (2 elements)
+///     c4: -bias (2 elements)
+///
+/// output:
+///     true or false (consumed on stack)
+pub fn check_chord_line_in_stack() -> Script {
+    script! {
+        // [T, Q, alpha, -bias]
+        { Fq2::copy(2) }
+        { Fq2::copy(2) }
+        // [T, Q, alpha, -bias, alpha, -bias]
+        { Fq2::roll(10) }
+        { Fq2::roll(10) }
+        // [T, alpha, -bias, alpha, -bias, Q]
+        { Fq2::roll(6) }
+        { Fq2::roll(6) }
+        // [T, alpha, -bias, Q, alpha, -bias]
+        // check: Q.y - alpha * Q.x - bias = 0
+        { check_line_through_point_in_stack() }
+        // [T, alpha, -bias]
+        // check: T.y - alpha * T.x - bias = 0
+        { check_line_through_point_in_stack() }
+        // []
+    }
+}
+
+/// bits of 6x^2 (most significant first), where x is the BN parameter. Since p = r + 6x^2, the
+/// endomorphism psi acts as [6x^2] on G2, which is used for the subgroup membership test
+fn g2_subgroup_scalar_bits() -> Vec<bool> {
+    let x = BigUint::from(ark_bn254::Config::X[0]);
+    let k = &x * &x * 6_u32;
+    (0..k.bits()).rev().map(|i| k.bit(i)).collect()
+}
+
+/// psi(Q) = (conj(Q.x) * beta^{2 * (p - 1) / 6}, conj(Q.y) * beta^{3 * (p - 1) / 6})
+pub fn g2_psi(q: ark_bn254::G2Affine) -> ark_bn254::G2Affine {
+    let mut x = q.x;
+    x.frobenius_map_in_place(1);
+    let mut y = q.y;
+    y.frobenius_map_in_place(1);
+    ark_bn254::G2Affine::new_unchecked(
+        x * ark_bn254::Config::TWIST_MUL_BY_Q_X,
+        y * ark_bn254::Config::TWIST_MUL_BY_Q_Y,
+    )
+}
+
+/// line coefficients (alpha, -bias) of the double-and-add chain computing [6x^2]Q, in the order
+/// they are consumed by `check_g2_subgroup_in_stack`
+///
+/// the coefficients are computed for any point on the twist, a zero slope is used where the chain
+/// degenerates, so the script rejects the point instead of the prover panicking
+pub fn g2_subgroup_hints(q: ark_bn254::G2Affine) -> Vec<(ark_bn254::Fq2, ark_bn254::Fq2)> {
+    let zero = ark_bn254::Fq2::default();
+    let mut hints = vec![];
+    let (mut tx, mut ty) = (q.x, q.y);
+    for bit in g2_subgroup_scalar_bits().into_iter().skip(1) {
+        // tangent line at T
+        let alpha = (tx.square().double() + tx.square()) * ty.double().inverse().unwrap_or(zero);
+        let bias_minus = alpha * tx - ty;
+        let x = alpha.square() - tx.double();
+        (tx, ty) = (x, bias_minus - alpha * x);
+        hints.push((alpha, bias_minus));
+
+        if bit {
+            // chord line through T and Q
+            let alpha = (ty - q.y) * (tx - q.x).inverse().unwrap_or(zero);
+            let bias_minus = alpha * tx - ty;
+            let x = alpha.square() - tx - q.x;
+            (tx, ty) = (x, bias_minus - alpha * x);
+            hints.push((alpha, bias_minus));
+        }
+    }
+    hints
+}
+
+/// push the hints of `g2_subgroup_hints`, the first consumed line lies on top
+pub fn g2_subgroup_hints_push(q: ark_bn254::G2Affine) -> Script {
+    script! {
+        for (alpha, bias_minus) in g2_subgroup_hints(q).into_iter().rev() {
+            { fq2_push(bias_minus) }
+            { fq2_push(alpha) }
+        }
+    }
+}
+
+/// a random point on the twist outside of the G2 subgroup
+#[cfg(test)]
+pub(crate) fn g2_off_subgroup_rand<R: ark_std::rand::Rng>(rng: &mut R) -> ark_bn254::G2Affine {
+    use ark_std::UniformRand;
+    loop {
+        let x = ark_bn254::Fq2::rand(rng);
+        if let Some(q) = ark_bn254::G2Affine::get_point_from_x_unchecked(x, rng.gen()) {
+            if !q.is_in_correct_subgroup_assuming_on_curve() {
+                return q;
+            }
+        }
+    }
+}
+
+/// check that a point Q on the twist lies in the G2 subgroup, namely psi(Q) = [6x^2]Q
+///
+/// [6x^2]Q is computed by double-and-add in affine mode, every line is supplied by the hints
+/// of `g2_subgroup_hints_push` and checked with the tangent/chord line checks. The chord is only
+/// well defined for T != ±Q, so T.x != Q.x is checked before every addition
+///
+/// input on stack:
+///     [S(Q), Q.x (2 elements), Q.y (2 elements)], S(Q) is pushed by `g2_subgroup_hints_push`
+///
+/// output on stack:
+///     [Q.x (2 elements), Q.y (2 elements)], fails if Q is not in G2
+pub fn check_g2_subgroup_in_stack() -> Script {
+    // roll the next line coefficients (c3, c4) from below Q and T to the top of the stack
+    let roll_line_coeff = script! {
+        { Fq2::roll(8) }
+        { Fq2::roll(10) }
+    };
+
+    script! {
+        // T = Q
+        { Fq2::copy(2) }
+        { Fq2::copy(2) }
+        // [S(Q), Q(4), T(4)]
+
+        for bit in g2_subgroup_scalar_bits().into_iter().skip(1) {
+            // T = 2T
+            { roll_line_coeff.clone() }
+            // [..., Q(4), T(4), c3(2), c4(2)]
+            { Fq2::copy(6) }
+            { Fq2::copy(6) }
+            { Fq2::copy(6) }
+            { Fq2::copy(6) }
+            { check_tangent_line_in_stack() }
+            { Fq2::roll(4) }
+            { Fq2::drop() }
+            // [..., Q(4), T.x(2), c3(2), c4(2)]
+            { affine_double_line_in_stack() }
+            // [..., Q(4), T(4)]
+
+            if bit {
+                // T = T + Q
+                { roll_line_coeff.clone() }
+                // [..., Q(4), T(4), c3(2), c4(2)]
+                { Fq2::copy(6) }
+                { Fq2::copy(12) }
+                { Fq2::equal() }
+                OP_NOT
+                OP_VERIFY
+                { Fq2::copy(6) }
+                { Fq2::copy(6) }
+                { Fq2::copy(14) }
+                { Fq2::copy(14) }
+                { Fq2::copy(10) }
+                { Fq2::copy(10) }
+                // [..., Q(4), T(4), c3(2), c4(2), T(4), Q(4), c3(2), c4(2)]
+                { check_chord_line_in_stack() }
+                { Fq2::roll(4) }
+                { Fq2::drop() }
+                { Fq2::copy(8) }
+                { Fq2::roll(4) }
+                { Fq2::roll(4) }
+                // [..., Q(4), T.x(2), Q.x(2), c3(2), c4(2)]
+                { affine_add_line_in_stack() }
+                // [..., Q(4), T(4)]
+            }
+        }
+
+        // psi(Q)
+        { Fq2::copy(6) }
+        { Fq::neg(0) }
+        { Fq2::mul_by_constant(&ark_bn254::Config::TWIST_MUL_BY_Q_X) }
+        { Fq2::copy(6) }
+        { Fq::neg(0) }
+        { Fq2::mul_by_constant(&ark_bn254::Config::TWIST_MUL_BY_Q_Y) }
+        // [Q(4), T(4), psi(Q)(4)]
+
+        { Fq2::roll(4) }
+        { Fq2::equalverify() }
+        { Fq2::equalverify() }
+        // [Q(4)]
+    }
+}
+
 // stack data: beta^{2 * (p - 1) / 6}, beta^{3 * (p - 1) / 6}, beta^{2 * (p^2 - 1) / 6}, 1/2, B,
 // P1, P2, P3, P4, Q4, c, c', wi, f, Px, Py, Tx, Ty, Tz, Qx, Qy
 // [..., Fq12, Fq12, Fq12, Fq12, Fq, Fq, (Fq, Fq), (Fq, Fq), (Fq, Fq), (Fq, Fq), (Fq, Fq)]
@@ -1067,4 +1447,133 @@ mod test {
         let exec_result = execute_script(script);
         assert!(exec_result.success);
     }
+    #[test]
+    fn test_ell_by_non_constant_affine() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        let f = ark_bn254::Fq12::rand(&mut prng);
+        let b = ark_bn254::g2::G2Affine::rand(&mut prng);
+        let p = ark_bn254::g1::G1Affine::rand(&mut prng);
+
+        let coeffs = G2Prepared::from_affine(b);
+
+        let script = script! {
+            { fq12_push(f) }
+            { from_eval_point(p) }
+            { fq2_push(coeffs.ell_coeffs[0].1) }
+            { fq2_push(coeffs.ell_coeffs[0].2) }
+            { ell_by_non_constant_affine() }
+            { fq12_push(f) }
+            { from_eval_point(p) }
+            { ell_by_constant_affine(&coeffs.ell_coeffs[0]) }
+            { Fq12::equalverify() }
+            OP_TRUE
+        };
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
+    }
+
+    #[test]
+    fn test_affine_line_in_stack() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let t = ark_bn254::G2Affine::rand(&mut prng);
+        let q = ark_bn254::G2Affine::rand(&mut prng);
+
+        // chord line through T and Q
+        let alpha = (t.y - q.y) / (t.x - q.x);
+        let bias_minus = alpha * t.x - t.y;
+        let script = script! {
+            { fq2_push(t.x) }
+            { fq2_push(t.y) }
+            { fq2_push(q.x) }
+            { fq2_push(q.y) }
+            { fq2_push(alpha) }
+            { fq2_push(bias_minus) }
+            { check_chord_line_in_stack() }
+            { fq2_push(t.x) }
+            { fq2_push(q.x) }
+            { fq2_push(alpha) }
+            { fq2_push(bias_minus) }
+            { affine_add_line_in_stack() }
+            { fq2_push(t.x) }
+            { fq2_push(q.x) }
+            { affine_add_line(alpha, bias_minus) }
+            { Fq2::roll(4) }
+            { Fq2::equalverify() }
+            { Fq2::equalverify() }
+            OP_TRUE
+        };
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
+
+        // tangent line at T
+        let two_inv = ark_bn254::Fq::one().double().inverse().unwrap();
+        let three_div_two = (ark_bn254::Fq::one().double() + ark_bn254::Fq::one()) * two_inv;
+        let mut alpha = t.x.square();
+        alpha /= t.y;
+        alpha.mul_assign_by_fp(&three_div_two);
+        let bias_minus = alpha * t.x - t.y;
+        let script = script! {
+            { fq2_push(t.x) }
+            { fq2_push(t.y) }
+            { fq2_push(alpha) }
+            { fq2_push(bias_minus) }
+            { check_tangent_line_in_stack() }
+            { fq2_push(t.x) }
+            { fq2_push(alpha) }
+            { fq2_push(bias_minus) }
+            { affine_double_line_in_stack() }
+            { fq2_push(t.x) }
+            { affine_double_line(alpha, bias_minus) }
+            { Fq2::roll(4) }
+            { Fq2::equalverify() }
+            { Fq2::equalverify() }
+            OP_TRUE
+        };
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
+    }
+
+    #[test]
+    fn test_check_g2_subgroup_in_stack() {
+        use ark_ec::CurveGroup;
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+
+        let x = BigUint::from(ark_bn254::Config::X[0]);
+        let k = &x * &x * 6_u32;
+
+        let check = check_g2_subgroup_in_stack();
+        println!("check_g2_subgroup_in_stack: {} bytes", check.len());
+
+        let q = ark_bn254::G2Affine::rand(&mut prng);
+        assert_eq!(g2_psi(q), q.mul_bigint(k.to_u64_digits()).into_affine());
+        let script = script! {
+            { g2_subgroup_hints_push(q) }
+            { fq2_push(q.x) }
+            { fq2_push(q.y) }
+            { check.clone() }
+            { fq2_push(q.y) }
+            { Fq2::equalverify() }
+            { fq2_push(q.x) }
+            { Fq2::equalverify() }
+            OP_TRUE
+        };
+        let exec_result = crate::execute_script_without_stack_limit(script);
+        assert!(exec_result.success);
+
+        // on the twist, but not in G2
+        let q = g2_off_subgroup_rand(&mut prng);
+        assert_ne!(g2_psi(q), q.mul_bigint(k.to_u64_digits()).into_affine());
+        let script = script! {
+            { g2_subgroup_hints_push(q) }
+            { fq2_push(q.x) }
+            { fq2_push(q.y) }
+            { check.clone() }
+            { Fq2::drop() }
+            { Fq2::drop() }
+            OP_TRUE
+        };
+        let exec_result = crate::execute_script_without_stack_limit(script);
+        assert!(!exec_result.success);
+    }
 }
@@ -0,0 +1,361 @@
+use bitcoin_script_stack::stack::{StackTracker, StackVariable};
+use num_bigint::BigUint;
+
+use crate::bn254::curves::G1Projective;
+use crate::bn254::fp254impl::Fp254Impl;
+use crate::bn254::fq::Fq;
+use crate::bn254::fq12::Fq12;
+use crate::bn254::fq2::Fq2;
+use crate::bn254::utils::{fq12_push, fq2_push};
+use crate::treepp::Script;
+
+// Typed variables on top of `StackTracker`. Every operation takes its operands by value, moves
+// them to the top of the stack (unless they already are there) and emits the positional script
+// with the depths of the operands in Fq elements, e.g. (1, 0) for Fq, (2, 0) for Fq2 and (12, 0)
+// for Fq12. Operands that are still needed afterwards have to be copied first.
+
+/// A bn254 value of a fixed number of stack elements
+pub trait StackVar: Copy {
+    /// Number of stack elements
+    const SIZE: u32;
+
+    fn from_var(var: StackVariable) -> Self;
+
+    fn var(&self) -> StackVariable;
+
+    fn copy(&self, stack: &mut StackTracker) -> Self { Self::from_var(stack.copy_var(self.var())) }
+
+    fn drop(self, stack: &mut StackTracker) { stack.drop(self.var()) }
+
+    fn rename(&self, stack: &mut StackTracker, name: &str) { stack.rename(self.var(), name) }
+}
+
+// Move `vars` to the top of the stack, the last one on top, where the positional script
+// reads them at `depths` (in Fq elements)
+fn move_to_top(stack: &mut StackTracker, vars: &[StackVariable], depths: &[u32]) {
+    let mut offset = 0;
+    let mut in_place = true;
+    for var in vars.iter().rev() {
+        in_place &= stack.get_offset(*var) == offset;
+        offset += var.size();
+    }
+    let vars = if in_place {
+        vars.to_vec()
+    } else {
+        vars.iter().map(|var| stack.move_var(*var)).collect()
+    };
+
+    if cfg!(debug_assertions) {
+        assert_eq!(vars.len(), depths.len());
+        for (var, depth) in vars.iter().zip(depths) {
+            assert_eq!(
+                stack.get_offset(*var),
+                depth * Fq::N_LIMBS,
+                "operand is not at the depth the script reads it from"
+            );
+        }
+    }
+}
+
+// Consume `inputs` with `script`, leaving a single output of type `O`
+fn apply<O: StackVar>(
+    stack: &mut StackTracker, inputs: &[StackVariable], depths: &[u32], script: Script, name: &str,
+) -> O {
+    move_to_top(stack, inputs, depths);
+    stack.custom(script, inputs.len() as u32, false, 0, name);
+    O::from_var(stack.define(O::SIZE, name))
+}
+
+macro_rules! impl_stack_var {
+    ($var:ident, $size:expr) => {
+        impl StackVar for $var {
+            const SIZE: u32 = $size;
+
+            fn from_var(var: StackVariable) -> Self {
+                debug_assert_eq!(var.size(), Self::SIZE);
+                $var(var)
+            }
+
+            fn var(&self) -> StackVariable { self.0 }
+        }
+    };
+}
+
+#[derive(Clone, Copy)]
+pub struct FqVar(StackVariable);
+impl_stack_var!(FqVar, Fq::N_LIMBS);
+
+impl FqVar {
+    pub fn push(stack: &mut StackTracker, x: ark_bn254::Fq, name: &str) -> Self {
+        let script = Fq::push_u32_le(&BigUint::from(x).to_u32_digits());
+        Self::from_var(stack.var(Self::SIZE, script, name))
+    }
+
+    pub fn add(stack: &mut StackTracker, a: Self, b: Self) -> Self {
+        apply(stack, &[a.0, b.0], &[1, 0], Fq::add(1, 0), "fq_add")
+    }
+
+    pub fn sub(stack: &mut StackTracker, a: Self, b: Self) -> Self {
+        apply(stack, &[a.0, b.0], &[1, 0], Fq::sub(1, 0), "fq_sub")
+    }
+
+    pub fn mul(stack: &mut StackTracker, a: Self, b: Self) -> Self {
+        apply(stack, &[a.0, b.0], &[1, 0], Fq::mul(), "fq_mul")
+    }
+
+    pub fn double(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], Fq::double(0), "fq_double")
+    }
+
+    pub fn square(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], Fq::square(), "fq_square")
+    }
+
+    pub fn neg(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], Fq::neg(0), "fq_neg")
+    }
+
+    pub fn inv(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], Fq::inv(), "fq_inv")
+    }
+
+    pub fn equalverify(stack: &mut StackTracker, a: Self, b: Self) {
+        move_to_top(stack, &[a.0, b.0], &[1, 0]);
+        stack.custom(Fq::equalverify(1, 0), 2, false, 0, "fq_equalverify");
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Fq2Var(StackVariable);
+impl_stack_var!(Fq2Var, 2 * Fq::N_LIMBS);
+
+impl Fq2Var {
+    pub fn push(stack: &mut StackTracker, x: ark_bn254::Fq2, name: &str) -> Self {
+        Self::from_var(stack.var(Self::SIZE, fq2_push(x), name))
+    }
+
+    pub fn add(stack: &mut StackTracker, a: Self, b: Self) -> Self {
+        apply(stack, &[a.0, b.0], &[2, 0], Fq2::add(2, 0), "fq2_add")
+    }
+
+    pub fn sub(stack: &mut StackTracker, a: Self, b: Self) -> Self {
+        apply(stack, &[a.0, b.0], &[2, 0], Fq2::sub(2, 0), "fq2_sub")
+    }
+
+    pub fn mul(stack: &mut StackTracker, a: Self, b: Self) -> Self {
+        apply(stack, &[a.0, b.0], &[2, 0], Fq2::mul(2, 0), "fq2_mul")
+    }
+
+    pub fn mul_by_fq(stack: &mut StackTracker, a: Self, b: FqVar) -> Self {
+        apply(stack, &[a.0, b.0], &[1, 0], Fq2::mul_by_fq(1, 0), "fq2_mul_by_fq")
+    }
+
+    pub fn double(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], Fq2::double(0), "fq2_double")
+    }
+
+    pub fn square(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], Fq2::square(), "fq2_square")
+    }
+
+    pub fn neg(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], Fq2::neg(0), "fq2_neg")
+    }
+
+    pub fn inv(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], Fq2::inv(), "fq2_inv")
+    }
+
+    pub fn equalverify(stack: &mut StackTracker, a: Self, b: Self) {
+        move_to_top(stack, &[a.0, b.0], &[2, 0]);
+        stack.custom(Fq2::equalverify(), 2, false, 0, "fq2_equalverify");
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Fq12Var(StackVariable);
+impl_stack_var!(Fq12Var, 12 * Fq::N_LIMBS);
+
+impl Fq12Var {
+    pub fn push(stack: &mut StackTracker, x: ark_bn254::Fq12, name: &str) -> Self {
+        Self::from_var(stack.var(Self::SIZE, fq12_push(x), name))
+    }
+
+    pub fn add(stack: &mut StackTracker, a: Self, b: Self) -> Self {
+        apply(stack, &[a.0, b.0], &[12, 0], Fq12::add(12, 0), "fq12_add")
+    }
+
+    pub fn sub(stack: &mut StackTracker, a: Self, b: Self) -> Self {
+        apply(stack, &[a.0, b.0], &[12, 0], Fq12::sub(12, 0), "fq12_sub")
+    }
+
+    pub fn mul(stack: &mut StackTracker, a: Self, b: Self) -> Self {
+        apply(stack, &[a.0, b.0], &[12, 0], Fq12::mul(12, 0), "fq12_mul")
+    }
+
+    pub fn square(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], Fq12::square(), "fq12_square")
+    }
+
+    pub fn inv(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], Fq12::inv(), "fq12_inv")
+    }
+
+    pub fn frobenius_map(stack: &mut StackTracker, a: Self, i: usize) -> Self {
+        apply(stack, &[a.0], &[0], Fq12::frobenius_map(i), "fq12_frobenius_map")
+    }
+
+    pub fn equalverify(stack: &mut StackTracker, a: Self, b: Self) {
+        move_to_top(stack, &[a.0, b.0], &[12, 0]);
+        stack.custom(Fq12::equalverify(), 2, false, 0, "fq12_equalverify");
+    }
+}
+
+/// A G1 point in projective coordinates (x, y, z)
+#[derive(Clone, Copy)]
+pub struct G1Var(StackVariable);
+impl_stack_var!(G1Var, 3 * Fq::N_LIMBS);
+
+impl G1Var {
+    pub fn push(stack: &mut StackTracker, p: ark_bn254::G1Projective, name: &str) -> Self {
+        Self::from_var(stack.var(Self::SIZE, G1Projective::push(p), name))
+    }
+
+    pub fn add(stack: &mut StackTracker, a: Self, b: Self) -> Self {
+        apply(stack, &[a.0, b.0], &[3, 0], G1Projective::add(), "g1_add")
+    }
+
+    pub fn double(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], G1Projective::double(), "g1_double")
+    }
+
+    pub fn neg(stack: &mut StackTracker, a: Self) -> Self {
+        apply(stack, &[a.0], &[0], G1Projective::neg(), "g1_neg")
+    }
+
+    pub fn equalverify(stack: &mut StackTracker, a: Self, b: Self) {
+        move_to_top(stack, &[a.0, b.0], &[3, 0]);
+        stack.custom(G1Projective::equalverify(), 2, false, 0, "g1_equalverify");
+    }
+
+    /// Convert into affine coordinates (x, y), y on top
+    pub fn into_affine(stack: &mut StackTracker, a: Self) -> (FqVar, FqVar) {
+        move_to_top(stack, &[a.0], &[0]);
+        stack.custom(G1Projective::into_affine(), 1, false, 0, "g1_into_affine");
+        let x = FqVar::from_var(stack.define(FqVar::SIZE, "x"));
+        let y = FqVar::from_var(stack.define(FqVar::SIZE, "y"));
+        (x, y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::treepp::*;
+    use ark_ec::CurveGroup;
+    use ark_ff::Field;
+    use ark_std::UniformRand;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_fq_vars() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let a = ark_bn254::Fq::rand(&mut prng);
+        let b = ark_bn254::Fq::rand(&mut prng);
+        let c = ark_bn254::Fq::rand(&mut prng);
+
+        // (a * b + c) / a - c^2, with b and c pushed before a
+        let mut stack = StackTracker::new();
+        let b_var = FqVar::push(&mut stack, b, "b");
+        let c_var = FqVar::push(&mut stack, c, "c");
+        let a_var = FqVar::push(&mut stack, a, "a");
+        let a_copy = a_var.copy(&mut stack);
+        let c_copy = c_var.copy(&mut stack);
+        let ab = FqVar::mul(&mut stack, b_var, a_var);
+        let abc = FqVar::add(&mut stack, ab, c_var);
+        let a_inv = FqVar::inv(&mut stack, a_copy);
+        let quotient = FqVar::mul(&mut stack, abc, a_inv);
+        let c_square = FqVar::square(&mut stack, c_copy);
+        let result = FqVar::sub(&mut stack, quotient, c_square);
+
+        let expected = FqVar::push(&mut stack, (a * b + c) / a - c.square(), "expected");
+        FqVar::equalverify(&mut stack, result, expected);
+        stack.op_true();
+
+        let res = execute_script(stack.get_script());
+        assert!(res.success);
+        assert_eq!(res.final_stack.len(), 1);
+    }
+
+    #[test]
+    fn test_fq2_fq12_vars() {
+        let mut prng = ChaCha20Rng::seed_from_u64(1);
+        let a = ark_bn254::Fq2::rand(&mut prng);
+        let b = ark_bn254::Fq2::rand(&mut prng);
+        let x = ark_bn254::Fq::rand(&mut prng);
+
+        let mut stack = StackTracker::new();
+        let a_var = Fq2Var::push(&mut stack, a, "a");
+        let b_var = Fq2Var::push(&mut stack, b, "b");
+        let x_var = FqVar::push(&mut stack, x, "x");
+        let ab = Fq2Var::mul(&mut stack, a_var, b_var);
+        let result = Fq2Var::mul_by_fq(&mut stack, ab, x_var);
+        let mut ab_x = a * b;
+        ab_x.mul_assign_by_fp(&x);
+        let expected = Fq2Var::push(&mut stack, ab_x, "expected");
+        Fq2Var::equalverify(&mut stack, result, expected);
+        stack.op_true();
+        assert!(execute_script(stack.get_script()).success);
+
+        let f = ark_bn254::Fq12::rand(&mut prng);
+        let g = ark_bn254::Fq12::rand(&mut prng);
+        let mut stack = StackTracker::new();
+        let f_var = Fq12Var::push(&mut stack, f, "f");
+        let g_var = Fq12Var::push(&mut stack, g, "g");
+        let f_copy = f_var.copy(&mut stack);
+        let fg = Fq12Var::mul(&mut stack, f_var, g_var);
+        let result = Fq12Var::mul(&mut stack, fg, f_copy);
+        let expected = Fq12Var::push(&mut stack, f * g * f, "expected");
+        Fq12Var::equalverify(&mut stack, result, expected);
+        stack.op_true();
+        println!("fq12 vars: {} bytes", stack.get_script_len());
+        assert!(execute_script(stack.get_script()).success);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "operand is not at the depth the script reads it from")]
+    fn test_wrong_depth() {
+        let mut prng = ChaCha20Rng::seed_from_u64(3);
+        let mut stack = StackTracker::new();
+        let a = Fq2Var::push(&mut stack, ark_bn254::Fq2::rand(&mut prng), "a");
+        let b = Fq2Var::push(&mut stack, ark_bn254::Fq2::rand(&mut prng), "b");
+        // Fq2 operands are two Fq elements apart
+        let _: Fq2Var = apply(&mut stack, &[a.0, b.0], &[1, 0], Fq2::add(1, 0), "fq2_add");
+    }
+
+    #[test]
+    fn test_g1_vars() {
+        let mut prng = ChaCha20Rng::seed_from_u64(2);
+        let p = ark_bn254::G1Projective::rand(&mut prng);
+        let q = ark_bn254::G1Projective::rand(&mut prng);
+
+        let mut stack = StackTracker::new();
+        let p_var = G1Var::push(&mut stack, p, "p");
+        let q_var = G1Var::push(&mut stack, q, "q");
+        let p_copy = p_var.copy(&mut stack);
+        let doubled = G1Var::double(&mut stack, p_copy);
+        let sum = G1Var::add(&mut stack, q_var, p_var);
+        let sum = G1Var::add(&mut stack, doubled, sum);
+        let (x, y) = G1Var::into_affine(&mut stack, sum);
+
+        let expected = (p + p + p + q).into_affine();
+        let expected_y = FqVar::push(&mut stack, expected.y, "expected_y");
+        FqVar::equalverify(&mut stack, y, expected_y);
+        let expected_x = FqVar::push(&mut stack, expected.x, "expected_x");
+        FqVar::equalverify(&mut stack, x, expected_x);
+        stack.op_true();
+        assert!(execute_script(stack.get_script()).success);
+    }
+}
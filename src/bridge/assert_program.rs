@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use bitcoin::ScriptBuf;
+
+use crate::{
+    chunker::{stack_bytes, Chunk, Chunker},
+    execute_script_buf,
+    signatures::winternitz_hash::sign_hash,
+    treepp::{script, Script},
+    u32::{u32_add::u32_add_drop, u32_std::u32_push},
+};
+
+use super::transactions::signing_winternitz::WinternitzSecret;
+
+/// Steps of the placeholder assert program
+const ASSERT_PROGRAM_STEPS: u32 = 8;
+/// Size in bytes the assert program's chunks are cut at
+const ASSERT_CHUNK_SIZE: usize = 160;
+/// Largest stack handed from one chunk of the assert program to the next
+const ASSERT_MAX_TRANSFER: usize = 8;
+
+/// Witness items of the operator's signatures of the output stacks of the assert program's chunks,
+/// by chunk index
+pub type AssertCommitment = Vec<Vec<Vec<u8>>>;
+
+/// Program the operator asserts to have run. A placeholder for the verifier of the peg-out proof:
+/// a chain of u32 additions.
+pub fn assert_program() -> Script {
+    script! {
+        { u32_push(0x0123_4567) }
+        for step in 0..ASSERT_PROGRAM_STEPS {
+            { u32_push(0x89ab_cdef ^ step) }
+            { u32_add_drop(0, 1) }
+        }
+    }
+}
+
+/// The assert program split into the chunks connector C can disprove one at a time
+pub fn assert_chunks() -> Vec<Chunk> {
+    Chunker::new(
+        ASSERT_CHUNK_SIZE,
+        ASSERT_CHUNK_SIZE / 2,
+        ASSERT_MAX_TRANSFER,
+    )
+    .chunk(assert_program(), 0)
+}
+
+/// Output stacks of the assert program's chunks, each listed from the bottom
+pub fn assert_program_stacks() -> Vec<Vec<u32>> {
+    let mut stacks: Vec<Vec<u32>> = Vec::new();
+    for chunk in assert_chunks() {
+        let input = stacks.last().cloned().unwrap_or_default();
+        let mut script = script! {
+            for element in input {
+                { element }
+            }
+        }
+        .compile()
+        .to_bytes();
+        script.extend(chunk.script.as_bytes());
+
+        let result = execute_script_buf(ScriptBuf::from_bytes(script));
+        stacks.push(
+            (0..result.final_stack.len())
+                .map(|i| decode_stack_element(&result.final_stack.get(i)))
+                .collect(),
+        );
+    }
+
+    stacks
+}
+
+/// Witness items of a Winternitz signature of `stack`, as the leaves of connector C check it
+pub fn sign_assert_stack(winternitz_secret: &WinternitzSecret, stack: &[u32]) -> Vec<Vec<u8>> {
+    sign_hash(winternitz_secret, &stack_bytes(stack))
+        .into_iter()
+        .flat_map(|signature| {
+            [
+                signature.hash_bytes,
+                encode_stack_element(signature.message_digit as u32),
+            ]
+        })
+        .collect()
+}
+
+/// The operator's commitment to `stacks`, the output stacks of the assert program's chunks
+pub fn sign_assert_stacks(
+    winternitz_secrets: &HashMap<u8, WinternitzSecret>,
+    stacks: &[Vec<u32>],
+) -> AssertCommitment {
+    stacks
+        .iter()
+        .enumerate()
+        .map(|(chunk_index, stack)| {
+            sign_assert_stack(&winternitz_secrets[&(chunk_index as u8)], stack)
+        })
+        .collect()
+}
+
+/// Minimal script number encoding of a stack element in [0, 2^31)
+pub(crate) fn encode_stack_element(element: u32) -> Vec<u8> {
+    let mut bytes = element.to_le_bytes().to_vec();
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    if bytes.last().is_some_and(|byte| byte & 0x80 != 0) {
+        bytes.push(0);
+    }
+    bytes
+}
+
+fn decode_stack_element(item: &[u8]) -> u32 {
+    if item.len() > 4 || item.last().is_some_and(|byte| byte & 0x80 != 0) {
+        panic!("Stack element out of range: {:?}", item);
+    }
+    let mut bytes = [0u8; 4];
+    bytes[..item.len()].copy_from_slice(item);
+    u32::from_le_bytes(bytes)
+}
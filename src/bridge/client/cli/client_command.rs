@@ -1,20 +1,38 @@
 use super::key_command::KeysCommand;
 use crate::bridge::client::client::BitVMClient;
+use crate::bridge::client::watcher::watcher::{Watcher, WatcherConfig};
+use crate::bridge::client::watchtower::{
+    base::Notifier, file::FileNotifier, stdout::StdoutNotifier, watchtower::Watchtower,
+    webhook::WebhookNotifier,
+};
 use crate::bridge::constants::DestinationNetwork;
 use crate::bridge::contexts::base::generate_keys_from_secret;
-use crate::bridge::graphs::base::{BaseGraph, VERIFIER_0_SECRET, VERIFIER_1_SECRET};
+use crate::bridge::graphs::base::{
+    get_block_height, BaseGraph, VERIFIER_0_SECRET, VERIFIER_1_SECRET,
+};
+use crate::bridge::graphs::export::ExportFormat;
 use crate::bridge::graphs::peg_in::PegInDepositorStatus;
-use crate::bridge::graphs::peg_out::PegOutOperatorStatus;
-use crate::bridge::superblock::{find_superblock, get_superblock_message};
+use crate::bridge::graphs::peg_out::{PegOutGraph, PegOutOperatorStatus, PegOutVerifierStatus};
+use crate::bridge::params::BridgeParams;
+use crate::bridge::signer::{local::LocalSigner, remote::RemoteSigner, Signer};
+use crate::bridge::superblock::{find_superblock, get_superblock_message, SuperblockPeriodScan};
 use bitcoin::Network;
 use bitcoin::PublicKey;
+use bitcoin::ScriptBuf;
 use clap::{arg, ArgMatches, Command};
 use colored::Colorize;
+use std::collections::HashMap;
 use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::time::sleep;
 
 pub struct ClientCommand {
     client: BitVMClient,
+    source_network: Network,
+    superblock_period_scans: HashMap<u32, SuperblockPeriodScan>,
 }
 
 impl ClientCommand {
@@ -44,41 +62,188 @@ impl ClientCommand {
         n_of_n_public_keys.push(verifier_0_public_key);
         n_of_n_public_keys.push(verifier_1_public_key);
 
-        let bitvm_client = BitVMClient::new(
+        let params = match &config.bridge.params {
+            Some(path) => BridgeParams::load(path, source_network).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }),
+            None => BridgeParams::for_network(source_network),
+        };
+
+        let signer = |address: &Option<String>, secret: &Option<String>| {
+            Self::signer(address, &config.signers.auth_key, secret, &|public_key| {
+                BitVMClient::secret_nonces_file_path(
+                    source_network,
+                    destination_network,
+                    &n_of_n_public_keys,
+                    public_key,
+                )
+            })
+        };
+        let registered_operators = config
+            .operators
+            .public_keys
+            .iter()
+            .map(|public_key| PublicKey::from_str(public_key))
+            .collect::<Result<Vec<PublicKey>, _>>()
+            .unwrap_or_else(|e| {
+                eprintln!("Invalid operator public key: {}", e);
+                std::process::exit(1);
+            });
+
+        let bitvm_client = BitVMClient::new_with_signers(
             source_network,
             destination_network,
+            params,
             &n_of_n_public_keys,
-            config.keys.depositor.as_deref(),
-            config.keys.operator.as_deref(),
-            config.keys.verifier.as_deref(),
-            config.keys.withdrawer.as_deref(),
+            &registered_operators,
+            signer(&config.signers.depositor, &config.keys.depositor),
+            signer(&config.signers.operator, &config.keys.operator),
+            signer(&config.signers.verifier, &config.keys.verifier),
+            signer(&config.signers.withdrawer, &config.keys.withdrawer),
             None,
         )
         .await;
 
         Self {
             client: bitvm_client,
+            source_network,
+            superblock_period_scans: HashMap::new(),
+        }
+    }
+
+    // A remote signer takes precedence over a secret key in the config. A local signer keeps its
+    // secret nonces in the file `nonce_file` names for its public key.
+    fn signer(
+        address: &Option<String>,
+        auth_key: &Option<String>,
+        secret: &Option<String>,
+        nonce_file: &dyn Fn(&PublicKey) -> String,
+    ) -> Option<Arc<dyn Signer>> {
+        match (address, secret) {
+            (Some(address), _) => {
+                let auth_key = match auth_key.as_ref().map(hex::decode) {
+                    Some(Ok(auth_key)) => auth_key,
+                    _ => {
+                        eprintln!(
+                            "Set the hex encoded `auth_key` of the remote signers in the \
+                            `[signers]` table of the config file."
+                        );
+                        std::process::exit(1);
+                    }
+                };
+                match RemoteSigner::connect(address, &auth_key) {
+                    Ok(signer) => Some(Arc::new(signer)),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            (None, Some(secret)) => {
+                let signer = LocalSigner::from_secret(secret).expect("Invalid secret key");
+                let nonce_file = nonce_file(&signer.public_key());
+                Some(Arc::new(signer.with_nonce_file(nonce_file)))
+            }
+            (None, None) => None,
         }
     }
 
     pub fn get_automatic_command() -> Command {
         Command::new("automatic")
             .short_flag('a')
-            .about("Automatic mode: Poll for status updates and sign or broadcast transactions")
+            .about("Automatic mode: Watch new blocks and sign or broadcast transactions")
+            .after_help("Verifiers pre-sign new graphs and broadcast timeouts and disproves. Their payouts go to the hex encoded script pubkey set as `verifier` in the `[payouts]` table of the config file.")
             .arg(arg!(-e --environment <ENVIRONMENT> "Specify the Bitcoin network environment (mainnet, testnet )").required(false)
         .default_value("mainnet"))
+            .arg(arg!(-s --start_height <HEIGHT> "Block height to start watching from, defaults to the earliest height of the known graphs").required(false)
+                .value_parser(clap::value_parser!(u32)))
+            .arg(arg!(-c --confirmations <CONFIRMATIONS> "Confirmations before a transaction counts as confirmed").required(false)
+                .value_parser(clap::value_parser!(u32)))
+    }
+
+    // Scan the blocks of the known graphs unless told otherwise, so that the transactions they
+    // already have on chain are recognized
+    async fn start_height(&mut self, sub_matches: &ArgMatches) -> u32 {
+        if let Some(start_height) = sub_matches.get_one::<u32>("start_height") {
+            return *start_height;
+        }
+        self.client.sync().await;
+        let graph_ids = self.all_graph_ids();
+        match self.client.get_earliest_graph_height(&graph_ids).await {
+            Some(height) => height,
+            None => get_block_height(&self.client.esplora).await,
+        }
+    }
+
+    fn all_graph_ids(&self) -> Vec<String> {
+        let data = self.client.get_data();
+        data.peg_in_graphs
+            .iter()
+            .map(|peg_in_graph| peg_in_graph.id().clone())
+            .chain(
+                data.peg_out_graphs
+                    .iter()
+                    .map(|peg_out_graph| peg_out_graph.id().clone()),
+            )
+            .collect()
     }
 
-    pub async fn handle_automatic_command(&mut self) -> io::Result<()> {
+    pub async fn handle_automatic_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let mut config = WatcherConfig::for_network(self.source_network);
+        if let Some(confirmations) = sub_matches.get_one::<u32>("confirmations") {
+            config.confirmations = *confirmations;
+        }
+        let start_height = self.start_height(sub_matches).await;
+        let poll_interval = config.poll_interval;
+        let mut watcher = Watcher::new(config, start_height);
+        watcher.watch_graphs(self.client.get_data());
+
+        let verifier_payout = match KeysCommand::new().read_config()?.payouts.verifier {
+            Some(script_pubkey) => match ScriptBuf::from_hex(&script_pubkey) {
+                Ok(script_pubkey) => Some(script_pubkey),
+                Err(e) => {
+                    eprintln!("Invalid verifier payout script pubkey: {}", e);
+                    std::process::exit(1);
+                }
+            },
+            None => None,
+        };
+
         loop {
             self.client.sync().await;
+            // graphs shared after their first transactions got included are scanned from there
+            let new_graph_ids = watcher.watch_graphs(self.client.get_data());
+            if !new_graph_ids.is_empty() {
+                if let Some(height) = self.client.get_earliest_graph_height(&new_graph_ids).await {
+                    watcher.rewind(height);
+                }
+            }
+
+            // wait for the next block instead of polling the graph statuses in a busy loop
+            match watcher.wait_for_block(&self.client.esplora).await {
+                Ok(events) => self.client.apply_watcher_events(&events),
+                Err(e) => {
+                    eprintln!("Failed to read blocks: {}", e);
+                    sleep(poll_interval).await;
+                    continue;
+                }
+            }
+
+            // the statuses are read from the blocks the watcher scanned, so that they only act on
+            // transactions with the configured number of confirmations
+            if self.client.is_verifier() {
+                if let Err(e) = self.client.pre_sign_new_graphs() {
+                    eprintln!("Failed to pre-sign new graphs: {}", e);
+                }
+                self.handle_verifier_statuses(&watcher, &verifier_payout).await;
+            }
 
             let peg_in_graphs = self.client.get_data().peg_in_graphs.clone();
 
             for peg_in_graph in peg_in_graphs.iter() {
-                let status = peg_in_graph.depositor_status(&self.client.esplora).await;
+                let status = peg_in_graph.depositor_status(&watcher).await;
 
-                self.client.pre_sign_peg_in(peg_in_graph.id());
                 match status {
                     PegInDepositorStatus::PegInDepositWait => {
                         self.client
@@ -100,12 +265,24 @@ impl ClientCommand {
                 }
             }
 
-            let peg_out_graphs = self.client.get_data().peg_out_graphs.clone();
+            // other operators drive their own peg-out graphs over the same peg-ins
+            let peg_out_graphs: Vec<PegOutGraph> = self
+                .client
+                .get_operated_peg_out_graphs()
+                .into_iter()
+                .cloned()
+                .collect();
             for peg_out_graph in peg_out_graphs.iter() {
-                let status = peg_out_graph.operator_status(&self.client.esplora).await;
+                let status = peg_out_graph.operator_status(&watcher).await;
                 match status {
                     PegOutOperatorStatus::PegOutStartTimeAvailable => {
-                        self.client.broadcast_start_time(peg_out_graph.id()).await
+                        if let Err(e) = self.client.broadcast_start_time(peg_out_graph.id()).await {
+                            eprintln!(
+                                "Failed to broadcast start time of peg-out graph {}: {}",
+                                peg_out_graph.id(),
+                                e
+                            );
+                        }
                     }
                     PegOutOperatorStatus::PegOutPegOutConfirmAvailable => {
                         self.client
@@ -113,16 +290,30 @@ impl ClientCommand {
                             .await
                     }
                     PegOutOperatorStatus::PegOutKickOff1Available => {
-                        self.client.broadcast_kick_off_1(peg_out_graph.id()).await
+                        if let Err(e) = self.client.broadcast_kick_off_1(peg_out_graph.id()).await {
+                            eprintln!(
+                                "Failed to broadcast kick-off 1 of peg-out graph {}: {}",
+                                peg_out_graph.id(),
+                                e
+                            );
+                        }
                     }
                     PegOutOperatorStatus::PegOutKickOff2Available => {
                         let (sb, sb_hash) = find_superblock();
-                        self.client
+                        if let Err(e) = self
+                            .client
                             .broadcast_kick_off_2(
                                 peg_out_graph.id(),
                                 &get_superblock_message(&sb, &sb_hash),
                             )
                             .await
+                        {
+                            eprintln!(
+                                "Failed to broadcast kick-off 2 of peg-out graph {}: {}",
+                                peg_out_graph.id(),
+                                e
+                            );
+                        }
                     }
                     PegOutOperatorStatus::PegOutAssertAvailable => {
                         self.client.broadcast_assert(peg_out_graph.id()).await
@@ -147,6 +338,183 @@ impl ClientCommand {
         }
     }
 
+    async fn handle_verifier_statuses(
+        &mut self,
+        watcher: &Watcher,
+        output_script_pubkey: &Option<ScriptBuf>,
+    ) {
+        let payout = || output_script_pubkey.clone().unwrap();
+        let peg_out_graphs = self.client.get_data().peg_out_graphs.clone();
+        for peg_out_graph in peg_out_graphs.iter() {
+            let status = peg_out_graph.verifier_status(watcher).await;
+            match status {
+                PegOutVerifierStatus::PegOutStartTimeTimeoutAvailable
+                | PegOutVerifierStatus::PegOutKickOffTimeoutAvailable
+                | PegOutVerifierStatus::PegOutDisproveChainAvailable
+                | PegOutVerifierStatus::PegOutDisproveAvailable
+                    if output_script_pubkey.is_none() =>
+                {
+                    println!(
+                        "Peg-out graph {} is in status: {} (no verifier payout configured)",
+                        peg_out_graph.id(),
+                        status
+                    );
+                }
+                PegOutVerifierStatus::PegOutStartTimeTimeoutAvailable => {
+                    self.client
+                        .broadcast_start_time_timeout(peg_out_graph.id(), payout())
+                        .await
+                }
+                PegOutVerifierStatus::PegOutKickOffTimeoutAvailable => {
+                    self.client
+                        .broadcast_kick_off_timeout(peg_out_graph.id(), payout())
+                        .await
+                }
+                PegOutVerifierStatus::PegOutDisproveChainAvailable => {
+                    match peg_out_graph
+                        .is_superblock_commitment_invalid(
+                            &self.client.esplora,
+                            &mut self.superblock_period_scans,
+                        )
+                        .await
+                    {
+                        Ok(true) => {
+                            self.client
+                                .broadcast_disprove_chain(peg_out_graph.id(), payout())
+                                .await
+                        }
+                        Ok(false) => {}
+                        Err(e) => eprintln!(
+                            "Failed to check superblock of peg-out graph {}: {}",
+                            peg_out_graph.id(),
+                            e
+                        ),
+                    }
+                }
+                PegOutVerifierStatus::PegOutDisproveAvailable => {
+                    match peg_out_graph
+                        .disprove_script_index(&self.client.esplora)
+                        .await
+                    {
+                        Ok(Some(input_script_index)) => {
+                            self.client
+                                .broadcast_disprove(
+                                    peg_out_graph.id(),
+                                    input_script_index,
+                                    payout(),
+                                )
+                                .await
+                        }
+                        Ok(None) => {}
+                        Err(e) => eprintln!(
+                            "Failed to check assertion of peg-out graph {}: {}",
+                            peg_out_graph.id(),
+                            e
+                        ),
+                    }
+                }
+                _ => {
+                    println!(
+                        "Peg-out graph {} is in status: {}",
+                        peg_out_graph.id(),
+                        status
+                    );
+                }
+            }
+        }
+    }
+
+    pub fn get_watchtower_command() -> Command {
+        Command::new("watchtower")
+            .short_flag('w')
+            .about("Watchtower mode: Monitor all peg-out graphs without signing anything")
+            .after_help("Checks the transactions spending graph outputs against the pre-signed graphs and raises alerts on unexpected spends, missed challenge windows and equivocating commitments. No keys are needed.")
+            .arg(arg!(-e --environment <ENVIRONMENT> "Specify the Bitcoin network environment (mainnet, testnet)").required(false)
+                .default_value("mainnet"))
+            .arg(arg!(-s --start_height <HEIGHT> "Block height to start watching from, defaults to the earliest height of the known graphs").required(false)
+                .value_parser(clap::value_parser!(u32)))
+            .arg(arg!(-n --notifier <NOTIFIER> "Where to send alerts to").required(false)
+                .value_parser(["stdout", "file", "webhook"]).default_value("stdout"))
+            .arg(arg!(-t --target <TARGET> "File path or URL for the file and webhook notifiers").required(false))
+    }
+
+    pub async fn handle_watchtower_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let target = sub_matches.get_one::<String>("target");
+        let notifier: Box<dyn Notifier> = match (
+            sub_matches.get_one::<String>("notifier").unwrap().as_str(),
+            target,
+        ) {
+            ("file", Some(path)) => Box::new(FileNotifier::new(PathBuf::from(path))),
+            ("webhook", Some(url)) => match WebhookNotifier::new(url) {
+                Ok(notifier) => Box::new(notifier),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            },
+            ("stdout", _) => Box::new(StdoutNotifier),
+            _ => {
+                eprintln!("The file and webhook notifiers require a target.");
+                std::process::exit(1);
+            }
+        };
+
+        let config = WatcherConfig::for_network(self.source_network);
+        let start_height = self.start_height(sub_matches).await;
+        let poll_interval = config.poll_interval;
+        let mut watchtower = Watchtower::new(config, start_height, notifier);
+
+        loop {
+            self.client.sync().await;
+            watchtower.watch_graphs(self.client.get_data());
+
+            if let Err(e) = watchtower.poll(&self.client.esplora).await {
+                eprintln!("Failed to read blocks: {}", e);
+            }
+            sleep(poll_interval).await;
+        }
+    }
+
+    pub fn get_export_command() -> Command {
+        Command::new("export")
+            .short_flag('x')
+            .about("Export a peg-in or peg-out graph as a diagram")
+            .after_help("Export the transactions of a graph as nodes and the connectors between them as edges, with amounts, timelocks and confirmation status, in Graphviz DOT or Mermaid format.")
+            .arg(arg!(-e --environment <ENVIRONMENT> "Specify the Bitcoin network environment (mainnet, testnet)").required(false)
+                .default_value("mainnet"))
+            .arg(arg!(-g --graph_id <GRAPH_ID> "Peg-in or peg-out graph ID").required(true))
+            .arg(arg!(-f --format <FORMAT> "Diagram format").required(false)
+                .value_parser(["dot", "mermaid"]).default_value("dot"))
+            .arg(arg!(-o --output <FILE> "File to write the diagram to, defaults to stdout").required(false))
+    }
+
+    pub async fn handle_export_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let graph_id = sub_matches.get_one::<String>("graph_id").unwrap();
+        let format = sub_matches
+            .get_one::<String>("format")
+            .unwrap()
+            .parse::<ExportFormat>()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        self.client.sync().await;
+        let mut export = match self.client.export_graph(graph_id) {
+            Ok(export) => export,
+            Err(e) => {
+                eprintln!("{}", e);
+                return Ok(());
+            }
+        };
+        export.update_confirmations(&self.client.esplora).await;
+
+        let diagram = export.render(format);
+        match sub_matches.get_one::<String>("output") {
+            Some(path) => std::fs::write(path, diagram)?,
+            None => print!("{}", diagram),
+        }
+
+        Ok(())
+    }
+
     pub fn get_broadcast_command() -> Command {
         Command::new("broadcast")
             .short_flag('b')
@@ -183,11 +551,23 @@ impl ClientCommand {
         let subcommand = sub_matches.subcommand();
         let graph_id = subcommand.unwrap().1.get_one::<String>("graph_id").unwrap();
 
-        match subcommand.unwrap().1.subcommand() {
-            Some(("deposit", _)) => self.client.broadcast_peg_in_deposit(graph_id).await,
-            Some(("refund", _)) => self.client.broadcast_peg_in_refund(graph_id).await,
-            Some(("confirm", _)) => self.client.broadcast_peg_in_confirm(graph_id).await,
-            Some(("peg_out_confirm", _)) => self.client.broadcast_peg_out_confirm(graph_id).await,
+        let result = match subcommand.unwrap().1.subcommand() {
+            Some(("deposit", _)) => {
+                self.client.broadcast_peg_in_deposit(graph_id).await;
+                Ok(())
+            }
+            Some(("refund", _)) => {
+                self.client.broadcast_peg_in_refund(graph_id).await;
+                Ok(())
+            }
+            Some(("confirm", _)) => {
+                self.client.broadcast_peg_in_confirm(graph_id).await;
+                Ok(())
+            }
+            Some(("peg_out_confirm", _)) => {
+                self.client.broadcast_peg_out_confirm(graph_id).await;
+                Ok(())
+            }
             Some(("kick_off_1", _)) => self.client.broadcast_kick_off_1(graph_id).await,
             Some(("kick_off_2", _)) => {
                 let (sb, sb_hash) = find_superblock();
@@ -196,11 +576,23 @@ impl ClientCommand {
                     .await
             }
             Some(("start_time", _)) => self.client.broadcast_start_time(graph_id).await,
-            Some(("assert", _)) => self.client.broadcast_assert(graph_id).await,
-            Some(("take_1", _)) => self.client.broadcast_take_1(graph_id).await,
-            Some(("take_2", _)) => self.client.broadcast_take_2(graph_id).await,
+            Some(("assert", _)) => {
+                self.client.broadcast_assert(graph_id).await;
+                Ok(())
+            }
+            Some(("take_1", _)) => {
+                self.client.broadcast_take_1(graph_id).await;
+                Ok(())
+            }
+            Some(("take_2", _)) => {
+                self.client.broadcast_take_2(graph_id).await;
+                Ok(())
+            }
             _ => unreachable!(),
         };
+        if let Err(e) = result {
+            eprintln!("{}", e);
+        }
 
         Ok(())
     }
@@ -269,8 +661,12 @@ impl ClientCommand {
                 self.handle_status_command().await?;
             } else if let Some(sub_matches) = matches.subcommand_matches("broadcast") {
                 self.handle_broadcast_command(sub_matches).await?;
-            } else if let Some(_sub_matches) = matches.subcommand_matches("automatic") {
-                self.handle_automatic_command().await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("automatic") {
+                self.handle_automatic_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("watchtower") {
+                self.handle_watchtower_command(sub_matches).await?;
+            } else if let Some(sub_matches) = matches.subcommand_matches("export") {
+                self.handle_export_command(sub_matches).await?;
             } else if let Some(_sub_matches) = matches.subcommand_matches("interactive") {
                 println!("{}", "Already in interactive mode.".yellow());
             } else {
@@ -9,6 +9,14 @@ use toml;
 #[derive(Serialize, Deserialize, Default)]
 pub struct Config {
     pub keys: Keys,
+    #[serde(default)]
+    pub signers: Signers,
+    #[serde(default)]
+    pub payouts: Payouts,
+    #[serde(default)]
+    pub operators: Operators,
+    #[serde(default)]
+    pub bridge: Bridge,
 }
 
 #[derive(Serialize, Deserialize, Default)]
@@ -19,6 +27,36 @@ pub struct Keys {
     pub withdrawer: Option<String>,
 }
 
+// Addresses of remote signer processes, used instead of the secret key of the same context, and
+// the hex encoded key they were started with in `BRIDGE_SIGNER_AUTH_KEY`
+#[derive(Serialize, Deserialize, Default)]
+pub struct Signers {
+    pub depositor: Option<String>,
+    pub operator: Option<String>,
+    pub verifier: Option<String>,
+    pub withdrawer: Option<String>,
+    pub auth_key: Option<String>,
+}
+
+// Hex encoded script pubkeys that receive the funds of transactions broadcast in automatic mode
+#[derive(Serialize, Deserialize, Default)]
+pub struct Payouts {
+    pub verifier: Option<String>,
+}
+
+// Hex encoded public keys of the operators that present a peg-out graph for every peg-in
+#[derive(Serialize, Deserialize, Default)]
+pub struct Operators {
+    pub public_keys: Vec<String>,
+}
+
+// Path of a TOML file with the bridge parameters of each network, see `BridgeParams::load`.
+// The defaults of the network are used if it is not set.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Bridge {
+    pub params: Option<String>,
+}
+
 pub struct KeysCommand {
     pub config_path: PathBuf,
 }
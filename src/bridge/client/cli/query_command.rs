@@ -37,6 +37,7 @@ impl QueryCommand {
             source_network,
             destination_network,
             &n_of_n_public_keys,
+            &[], // queries do not pre-sign, so no operators need to be registered
             Some(FAKE_SECRET),
             Some(FAKE_SECRET),
             Some(FAKE_SECRET),
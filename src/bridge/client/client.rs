@@ -1,11 +1,12 @@
 use futures::future::join_all;
-use musig2::SecNonce;
+use musig2::{PubNonce, SecNonce};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::{
     collections::HashMap,
     fs::{self},
     path::Path,
+    sync::Arc,
 };
 
 use bitcoin::{absolute::Height, Address, Amount, Network, OutPoint, PublicKey, ScriptBuf, Txid};
@@ -13,8 +14,10 @@ use esplora_client::{AsyncClient, Builder, TxStatus, Utxo};
 
 use crate::bridge::{
     connectors::base::ConnectorId, constants::DestinationNetwork,
-    contexts::base::generate_n_of_n_public_key, graphs::base::get_tx_statuses,
-    superblock::SuperblockMessage, transactions::signing_winternitz::WinternitzSecret,
+    contexts::base::generate_n_of_n_public_key,
+    graphs::base::{get_tx_statuses, GraphTransaction},
+    params::BridgeParams, superblock::SuperblockMessage,
+    transactions::signing_winternitz::WinternitzSecret,
 };
 
 use super::{
@@ -25,24 +28,35 @@ use super::{
         },
         graphs::{
             base::BaseGraph,
+            export::GraphExport,
             peg_in::{generate_id as peg_in_generate_id, PegInGraph},
             peg_out::{generate_id as peg_out_generate_id, PegOutGraph},
+            state::{PegInState, PegOutState, StateMachine},
         },
         serialization::{serialize, try_deserialize},
+        signer::{
+            local::{store_secret_nonces, LocalSigner},
+            Signer,
+        },
         transactions::{
             base::{Input, InputWithScript},
+            challenge_crowdfunding::{
+                ChallengeCrowdfunding, CrowdfundingInput, CrowdfundingPledge,
+            },
             pre_signed::PreSignedTransaction,
         },
     },
-    chain::chain::Chain,
+    chain::chain::{Chain, PegOutEvent},
     data_store::data_store::DataStore,
     sdk::query::GraphQuery,
+    watcher::watcher::WatcherEvent,
 };
 
 const ESPLORA_URL: &str = "https://mutinynet.com/api";
 const TEN_MINUTES: u64 = 10 * 60;
 
 const PRIVATE_DATA_FILE_NAME: &str = "secret_data.json";
+const SECRET_NONCES_FILE_PREFIX: &str = "secret_nonces_";
 
 pub type UtxoSet = HashMap<OutPoint, Height>;
 
@@ -51,17 +65,31 @@ pub struct BitVMClientPublicData {
     pub version: u32,
     pub peg_in_graphs: Vec<PegInGraph>,
     pub peg_out_graphs: Vec<PegOutGraph>,
+    #[serde(default)]
+    pub challenge_crowdfundings: Vec<ChallengeCrowdfunding>,
 }
 
 #[derive(Serialize, Deserialize, Eq, PartialEq)]
 pub struct BitVMClientPrivateData {
     // Peg in and peg out nonces all go into the same file for now
-    // Verifier public key -> Graph ID -> Tx ID -> Input index -> Secret nonce
-    pub secret_nonces: HashMap<PublicKey, HashMap<String, HashMap<Txid, HashMap<usize, SecNonce>>>>,
+    // Verifier public key -> Graph ID -> Tx ID -> Input index -> Public nonce. The secret nonces
+    // stay with the verifier's signer.
+    #[serde(default)]
+    pub nonces: HashMap<PublicKey, HashMap<String, HashMap<Txid, HashMap<usize, PubNonce>>>>,
+    // Secret nonces of files written before they moved to the signer, only read to migrate them
+    #[serde(default, rename = "secret_nonces", skip_serializing)]
+    legacy_secret_nonces:
+        HashMap<PublicKey, HashMap<String, HashMap<Txid, HashMap<usize, SecNonce>>>>,
     // Operator Winternitz secrets for all the graphs.
     // Operator public key -> Graph ID -> Connector ID -> Leaf index -> Winternitz secret
     pub winternitz_secrets:
         HashMap<PublicKey, HashMap<String, HashMap<ConnectorId, HashMap<u8, WinternitzSecret>>>>,
+    // States of the graphs as observed by this client's watcher, which are not taken from peers.
+    // Graph ID -> State machine
+    #[serde(default)]
+    pub peg_in_graph_states: HashMap<String, StateMachine<PegInState>>,
+    #[serde(default)]
+    pub peg_out_graph_states: HashMap<String, StateMachine<PegOutState>>,
 }
 
 pub struct BitVMClient {
@@ -72,6 +100,10 @@ pub struct BitVMClient {
     verifier_context: Option<VerifierContext>,
     withdrawer_context: Option<WithdrawerContext>,
 
+    // Params all graphs must be built with, so that this client never accepts or signs a graph
+    // with timelocks or amounts it did not agree to
+    params: BridgeParams,
+
     data_store: DataStore,
     data: BitVMClientPublicData,
     pub fetched_file_name: Option<String>,
@@ -81,6 +113,11 @@ pub struct BitVMClient {
     private_data: BitVMClientPrivateData,
 
     chain_adaptor: Chain,
+
+    // Operators that present a peg-out graph for every peg-in. Verifiers only pre-sign a peg-in
+    // once each of them has a pre-signed peg-out graph over it, so that all of them can be
+    // reimbursed for the peg-outs they front.
+    registered_operators: Vec<PublicKey>,
 }
 
 impl BitVMClient {
@@ -88,54 +125,91 @@ impl BitVMClient {
         source_network: Network,
         destination_network: DestinationNetwork,
         n_of_n_public_keys: &Vec<PublicKey>,
+        registered_operators: &[PublicKey],
         depositor_secret: Option<&str>,
         operator_secret: Option<&str>,
         verifier_secret: Option<&str>,
         withdrawer_secret: Option<&str>,
         file_path_prefix: Option<&str>,
     ) -> Self {
-        let mut depositor_context = None;
-        if depositor_secret.is_some() {
-            depositor_context = Some(DepositorContext::new(
+        let local_signer = |secret: Option<&str>| -> Option<Arc<dyn Signer>> {
+            secret.map(|secret| {
+                let signer = LocalSigner::from_secret(secret).unwrap();
+                let nonce_file = Self::secret_nonces_file_path(
+                    source_network,
+                    destination_network,
+                    n_of_n_public_keys,
+                    &signer.public_key(),
+                );
+                Arc::new(signer.with_nonce_file(nonce_file)) as Arc<dyn Signer>
+            })
+        };
+
+        Self::new_with_signers(
+            source_network,
+            destination_network,
+            BridgeParams::for_network(source_network),
+            n_of_n_public_keys,
+            registered_operators,
+            local_signer(depositor_secret),
+            local_signer(operator_secret),
+            local_signer(verifier_secret),
+            local_signer(withdrawer_secret),
+            file_path_prefix,
+        )
+        .await
+    }
+
+    pub async fn new_with_signers(
+        source_network: Network,
+        destination_network: DestinationNetwork,
+        params: BridgeParams,
+        n_of_n_public_keys: &Vec<PublicKey>,
+        registered_operators: &[PublicKey],
+        depositor_signer: Option<Arc<dyn Signer>>,
+        operator_signer: Option<Arc<dyn Signer>>,
+        verifier_signer: Option<Arc<dyn Signer>>,
+        withdrawer_signer: Option<Arc<dyn Signer>>,
+        file_path_prefix: Option<&str>,
+    ) -> Self {
+        let depositor_context = depositor_signer.map(|signer| {
+            DepositorContext::new_with_params(
                 source_network,
-                depositor_secret.unwrap(),
+                params.clone(),
+                signer,
                 n_of_n_public_keys,
-            ));
-        }
-
-        let mut operator_context = None;
-        if operator_secret.is_some() {
-            operator_context = Some(OperatorContext::new(
+            )
+        });
+        let operator_context = operator_signer.map(|signer| {
+            OperatorContext::new_with_params(
                 source_network,
-                operator_secret.unwrap(),
+                params.clone(),
+                signer,
                 n_of_n_public_keys,
-            ));
-        }
-
-        let mut verifier_context = None;
-        if verifier_secret.is_some() {
-            verifier_context = Some(VerifierContext::new(
+            )
+        });
+        let verifier_context = verifier_signer.map(|signer| {
+            VerifierContext::new_with_params(
                 source_network,
-                verifier_secret.unwrap(),
+                params.clone(),
+                signer,
                 n_of_n_public_keys,
-            ));
-        }
-
-        let mut withdrawer_context = None;
-        if withdrawer_secret.is_some() {
-            withdrawer_context = Some(WithdrawerContext::new(
+            )
+        });
+        let withdrawer_context = withdrawer_signer.map(|signer| {
+            WithdrawerContext::new_with_params(
                 source_network,
-                withdrawer_secret.unwrap(),
+                params.clone(),
+                signer,
                 n_of_n_public_keys,
-            ));
-        }
+            )
+        });
 
         // TODO scope data and private data by n of n public keys
         // Prepend files with prefix
-        let (n_of_n_public_key, _) = generate_n_of_n_public_key(n_of_n_public_keys);
         let file_path_prefix = file_path_prefix.unwrap_or("").to_string();
         let file_path =
-            format! {"bridge_data/{source_network}/{destination_network}/{n_of_n_public_key}"};
+            Self::data_file_path(source_network, destination_network, n_of_n_public_keys);
         let full_path = format! {"{file_path_prefix}{file_path}"};
         Self::create_directories_if_non_existent(&full_path);
 
@@ -143,6 +217,7 @@ impl BitVMClient {
             version: 1,
             peg_in_graphs: vec![],
             peg_out_graphs: vec![],
+            challenge_crowdfundings: vec![],
         };
 
         let data_store = DataStore::new();
@@ -161,6 +236,8 @@ impl BitVMClient {
             verifier_context,
             withdrawer_context,
 
+            params,
+
             data_store,
             data,
             fetched_file_name: None,
@@ -170,6 +247,8 @@ impl BitVMClient {
             private_data,
 
             chain_adaptor,
+
+            registered_operators: registered_operators.to_vec(),
         }
     }
 
@@ -207,6 +286,7 @@ impl BitVMClient {
                     &self.data_store,
                     &mut latest_file_names,
                     Some(&self.file_path),
+                    &self.params,
                 )
                 .await;
                 if latest_file.is_some() && latest_file_name.is_some() {
@@ -240,20 +320,53 @@ impl BitVMClient {
         self.chain_adaptor = chain_adaptor;
     }
 
+    pub fn get_registered_operators(&self) -> &Vec<PublicKey> { &self.registered_operators }
+
     async fn read_from_l2(&mut self) {
         let peg_out_result = self.chain_adaptor.get_peg_out_init().await;
         if peg_out_result.is_ok() {
-            let mut events = peg_out_result.unwrap();
-            for peg_out_graph in self.data.peg_out_graphs.iter_mut() {
-                if !peg_out_graph.is_peg_out_initiated() {
-                    let _ = peg_out_graph.match_and_set_peg_out_event(&mut events).await;
-                }
-            }
+            self.route_peg_out_events(peg_out_result.unwrap());
         } else {
             panic!("Get event failed from L2 chain: {:?}", peg_out_result.err());
         }
     }
 
+    // Each event names the operator that fronts the peg-out, so it goes to that operator's graph
+    fn route_peg_out_events(&mut self, events: Vec<PegOutEvent>) {
+        for event in events {
+            let peg_out_graph = self
+                .data
+                .peg_out_graphs
+                .iter_mut()
+                .find(|peg_out_graph| peg_out_graph.is_peg_out_event_of(&event));
+            match peg_out_graph {
+                Some(peg_out_graph) => match &peg_out_graph.peg_out_chain_event {
+                    None => peg_out_graph.peg_out_chain_event = Some(event),
+                    Some(peg_out_chain_event) if peg_out_chain_event.eq(&event) => (),
+                    Some(_) => eprintln!(
+                        "Ignoring another peg-out event for peg-out graph {}",
+                        peg_out_graph.id()
+                    ),
+                },
+                None => {
+                    let is_known_peg_in = self.data.peg_in_graphs.iter().any(|peg_in_graph| {
+                        peg_in_graph
+                            .peg_in_confirm_transaction_ref()
+                            .tx()
+                            .compute_txid()
+                            .eq(&event.source_outpoint.txid)
+                    });
+                    if is_known_peg_in {
+                        eprintln!(
+                            "Operator {} has no peg-out graph for peg-in confirm {}",
+                            event.operator_public_key, event.source_outpoint.txid
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     async fn get_latest_file_names(
         data_store: &DataStore,
         file_path: Option<&str>,
@@ -354,7 +467,7 @@ impl BitVMClient {
                 if result.is_ok() && result.as_ref().unwrap().is_some() {
                     let data =
                         try_deserialize::<BitVMClientPublicData>(&(result.unwrap()).unwrap());
-                    if data.is_ok() && Self::validate_data(&data.as_ref().unwrap()) {
+                    if data.is_ok() && Self::validate_data(data.as_ref().unwrap(), &self.params) {
                         // merge the file if the data is valid
                         println!("Merging {} data...", { file_name });
                         self.merge_data(data.unwrap());
@@ -376,6 +489,7 @@ impl BitVMClient {
         data_store: &DataStore,
         file_names: &mut Vec<String>,
         file_path: Option<&str>,
+        params: &BridgeParams,
     ) -> (Option<BitVMClientPublicData>, Option<String>) {
         let mut latest_valid_file: Option<BitVMClientPublicData> = None;
         let mut latest_valid_file_name: Option<String> = None;
@@ -386,7 +500,9 @@ impl BitVMClient {
                 let file_name = file_name_result.unwrap();
                 let (latest_data, latest_data_len) =
                     Self::fetch_by_key(data_store, &file_name, file_path).await;
-                if latest_data.is_some() && Self::validate_data(&latest_data.as_ref().unwrap()) {
+                if latest_data.is_some()
+                    && Self::validate_data(latest_data.as_ref().unwrap(), params)
+                {
                     // data is valid
                     println!(
                         "Fetched valid file: {} (size: {})",
@@ -456,8 +572,16 @@ impl BitVMClient {
         }
     }
 
-    pub fn validate_data(data: &BitVMClientPublicData) -> bool {
+    /// Whether all graphs of `data` are well formed and built with `params`
+    pub fn validate_data(data: &BitVMClientPublicData, params: &BridgeParams) -> bool {
         for peg_in_graph in data.peg_in_graphs.iter() {
+            if peg_in_graph.params().ne(params) {
+                println!(
+                    "Encountered peg in graph with different params (Graph id: {})",
+                    peg_in_graph.id()
+                );
+                return false;
+            }
             if !peg_in_graph.validate() {
                 println!(
                     "Encountered invalid peg in graph (Graph id: {})",
@@ -467,6 +591,13 @@ impl BitVMClient {
             }
         }
         for peg_out_graph in data.peg_out_graphs.iter() {
+            if peg_out_graph.params().ne(params) {
+                println!(
+                    "Encountered peg out graph with different params (Graph id: {})",
+                    peg_out_graph.id()
+                );
+                return false;
+            }
             if !peg_out_graph.validate() {
                 println!(
                     "Encountered invalid peg out graph (Graph id: {})",
@@ -475,6 +606,19 @@ impl BitVMClient {
                 return false;
             }
         }
+        for crowdfunding in data.challenge_crowdfundings.iter() {
+            if !data
+                .peg_out_graphs
+                .iter()
+                .any(|peg_out_graph| peg_out_graph.id().eq(crowdfunding.peg_out_graph_id()))
+            {
+                println!(
+                    "Encountered challenge crowdfunding of unknown peg out graph (Graph id: {})",
+                    crowdfunding.peg_out_graph_id()
+                );
+                return false;
+            }
+        }
 
         // println!("All graph data is valid");
         true
@@ -494,6 +638,13 @@ impl BitVMClient {
 
         let mut peg_in_graphs_to_add: Vec<&PegInGraph> = Vec::new();
         for peg_in_graph in data.peg_in_graphs.iter() {
+            if peg_in_graph.params().ne(&self.params) {
+                eprintln!(
+                    "Skipping peg in graph with different params (Graph id: {})",
+                    peg_in_graph.id()
+                );
+                continue;
+            }
             let graph = peg_in_graphs_by_id.get_mut(peg_in_graph.id());
             if graph.is_some() {
                 graph.unwrap().merge(peg_in_graph);
@@ -515,6 +666,13 @@ impl BitVMClient {
 
         let mut peg_out_graphs_to_add: Vec<&PegOutGraph> = Vec::new();
         for peg_out_graph in data.peg_out_graphs.iter() {
+            if peg_out_graph.params().ne(&self.params) {
+                eprintln!(
+                    "Skipping peg out graph with different params (Graph id: {})",
+                    peg_out_graph.id()
+                );
+                continue;
+            }
             let graph = peg_out_graphs_by_id.get_mut(peg_out_graph.id());
             if graph.is_some() {
                 graph.unwrap().merge(peg_out_graph);
@@ -526,6 +684,82 @@ impl BitVMClient {
         for graph in peg_out_graphs_to_add.into_iter() {
             self.data.peg_out_graphs.push(graph.clone());
         }
+
+        // challenge crowdfundings, the amounts and pledges are taken from the graph and checked
+        // instead of trusting the copy of the other party
+        for crowdfunding in data.challenge_crowdfundings.iter() {
+            let peg_out_graph = match self
+                .data
+                .peg_out_graphs
+                .iter()
+                .find(|peg_out_graph| peg_out_graph.id().eq(crowdfunding.peg_out_graph_id()))
+            {
+                Some(peg_out_graph) => peg_out_graph,
+                None => continue,
+            };
+            let merged = match self
+                .data
+                .challenge_crowdfundings
+                .iter_mut()
+                .find(|c| c.peg_out_graph_id().eq(crowdfunding.peg_out_graph_id()))
+            {
+                Some(c) => peg_out_graph.merge_challenge_crowdfunding(c, crowdfunding),
+                None => {
+                    let mut c = peg_out_graph.new_challenge_crowdfunding();
+                    let merged = peg_out_graph.merge_challenge_crowdfunding(&mut c, crowdfunding);
+                    self.data.challenge_crowdfundings.push(c);
+                    merged
+                }
+            };
+            if let Err(e) = merged {
+                eprintln!(
+                    "Challenge crowdfunding of graph {}: {}",
+                    crowdfunding.peg_out_graph_id(),
+                    e
+                );
+            }
+        }
+
+        self.restore_graph_states();
+    }
+
+    // The graph states are not part of the public data, so the ones recorded locally are put
+    // back onto the graphs
+    fn restore_graph_states(&mut self) {
+        for peg_in_graph in self.data.peg_in_graphs.iter_mut() {
+            if let Some(state) = self.private_data.peg_in_graph_states.get(peg_in_graph.id()) {
+                *peg_in_graph.state_mut() = state.clone();
+            }
+        }
+        for peg_out_graph in self.data.peg_out_graphs.iter_mut() {
+            if let Some(state) = self.private_data.peg_out_graph_states.get(peg_out_graph.id()) {
+                *peg_out_graph.state_mut() = state.clone();
+            }
+        }
+    }
+
+    fn store_graph_states(&mut self) {
+        for peg_in_graph in self.data.peg_in_graphs.iter() {
+            let id = peg_in_graph.id().clone();
+            match peg_in_graph.state().history().is_empty() {
+                true => self.private_data.peg_in_graph_states.remove(&id),
+                false => self
+                    .private_data
+                    .peg_in_graph_states
+                    .insert(id, peg_in_graph.state().clone()),
+            };
+        }
+        for peg_out_graph in self.data.peg_out_graphs.iter() {
+            let id = peg_out_graph.id().clone();
+            match peg_out_graph.state().history().is_empty() {
+                true => self.private_data.peg_out_graph_states.remove(&id),
+                false => self
+                    .private_data
+                    .peg_out_graph_states
+                    .insert(id, peg_out_graph.state().clone()),
+            };
+        }
+        Self::save_local_private_file(&self.file_path, &serialize(&self.private_data));
     }
 
     // fn process(&self) {
@@ -588,24 +822,18 @@ impl BitVMClient {
             panic!("Operator context must be initialized");
         }
 
-        let mut peg_out_graphs_by_id: HashMap<&String, &PegOutGraph> = HashMap::new();
-        for peg_out_graph in self.data.peg_out_graphs.iter() {
-            peg_out_graphs_by_id.insert(&peg_out_graph.id(), peg_out_graph);
-        }
-
         let operator_public_key = &self.operator_context.as_ref().unwrap().operator_public_key;
         for peg_in_graph in self.data.peg_in_graphs.iter() {
-            let peg_out_graph_id = peg_out_generate_id(peg_in_graph, operator_public_key);
-            if !peg_out_graphs_by_id.contains_key(&peg_out_graph_id) {
-                println!(
+            match self.get_peg_out_graph_of_operator(peg_in_graph.id(), operator_public_key) {
+                Some(peg_out_graph) => {
+                    let status = peg_out_graph.operator_status(&self.esplora).await;
+                    println!("Graph id: {} status: {}\n", peg_out_graph.id(), status);
+                }
+                None => println!(
                     "Graph id: {} status: {}\n",
                     peg_in_graph.id(),
                     "Missing peg out graph" // TODO update this to ask the operator to create a new peg out graph
-                );
-            } else {
-                let peg_out_graph = peg_out_graphs_by_id.get(&peg_out_graph_id).unwrap();
-                let status = peg_out_graph.operator_status(&self.esplora).await;
-                println!("Graph id: {} status: {}\n", peg_out_graph.id(), status);
+                ),
             }
         }
     }
@@ -615,21 +843,224 @@ impl BitVMClient {
             panic!("Verifier context must be initialized");
         }
 
+        let n_of_n_public_keys = &self.verifier_context.as_ref().unwrap().n_of_n_public_keys;
+        for peg_in_graph in self.data.peg_in_graphs.iter() {
+            let pending_operators = self.get_pending_operators(peg_in_graph.id());
+            if !peg_in_graph.has_signatures(n_of_n_public_keys) && !pending_operators.is_empty() {
+                println!(
+                    "Graph id: {} status: Waiting for the peg-out graphs of {} operators\n",
+                    peg_in_graph.id(),
+                    pending_operators.len()
+                );
+            }
+        }
         for peg_out_graph in self.data.peg_out_graphs.iter() {
             let status = peg_out_graph.verifier_status(&self.esplora).await;
             println!("Graph id: {} status: {}\n", peg_out_graph.id(), status);
         }
     }
 
-    pub async fn create_peg_in_graph(&mut self, input: Input, evm_address: &str) -> String {
+    /// The peg-out graphs presented by the operators over a peg-in graph
+    pub fn get_peg_out_graphs(&self, peg_in_graph_id: &str) -> Vec<&PegOutGraph> {
+        self.data
+            .peg_out_graphs
+            .iter()
+            .filter(|peg_out_graph| peg_out_graph.peg_in_graph_id.eq(peg_in_graph_id))
+            .collect()
+    }
+
+    pub fn get_peg_out_graph_of_operator(
+        &self,
+        peg_in_graph_id: &str,
+        operator_public_key: &PublicKey,
+    ) -> Option<&PegOutGraph> {
+        self.get_peg_out_graphs(peg_in_graph_id)
+            .into_iter()
+            .find(|peg_out_graph| peg_out_graph.operator_public_key().eq(operator_public_key))
+    }
+
+    /// The peg-out graphs of this client's operator, empty if it is not an operator
+    pub fn get_operated_peg_out_graphs(&self) -> Vec<&PegOutGraph> {
+        match &self.operator_context {
+            Some(operator_context) => self
+                .data
+                .peg_out_graphs
+                .iter()
+                .filter(|peg_out_graph| {
+                    peg_out_graph
+                        .operator_public_key()
+                        .eq(&operator_context.operator_public_key)
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Registered operators whose peg-out graph over the peg-in graph is missing or not yet
+    /// pre-signed by all verifiers
+    pub fn get_pending_operators(&self, peg_in_graph_id: &str) -> Vec<PublicKey> {
+        if self.verifier_context.is_none() {
+            panic!("Can only be called by a verifier!");
+        }
+
+        let n_of_n_public_keys = &self.verifier_context.as_ref().unwrap().n_of_n_public_keys;
+        self.registered_operators
+            .iter()
+            .filter(|operator_public_key| {
+                !self
+                    .get_peg_out_graph_of_operator(peg_in_graph_id, operator_public_key)
+                    .is_some_and(|peg_out_graph| peg_out_graph.has_signatures(n_of_n_public_keys))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Lowest height the transactions of the given graphs can be included at. Peg-in deposits
+    /// spend outputs confirmed there at the earliest, and peg-out graphs only spend the outputs
+    /// of their peg-in transactions.
+    pub async fn get_earliest_graph_height(&self, graph_ids: &[String]) -> Option<u32> {
+        let peg_in_graph_ids: Vec<&String> = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .filter(|peg_out_graph| graph_ids.contains(peg_out_graph.id()))
+            .map(|peg_out_graph| &peg_out_graph.peg_in_graph_id)
+            .chain(graph_ids.iter())
+            .collect();
+        let funding_txids: Vec<Txid> = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .filter(|peg_in_graph| peg_in_graph_ids.contains(&peg_in_graph.id()))
+            .flat_map(|peg_in_graph| peg_in_graph.peg_in_deposit_transaction.tx().input.iter())
+            .map(|input| input.previous_output.txid)
+            .collect();
+
+        get_tx_statuses(&self.esplora, &funding_txids)
+            .await
+            .into_iter()
+            .filter_map(|status| status.ok().and_then(|status| status.block_height))
+            .min()
+    }
+
+    /// Moves the state machines of the graphs along the transactions the watcher confirmed and
+    /// records them with the private data. Transactions that are only seen can still be reorged
+    /// and do not change the states.
+    pub fn apply_watcher_events(&mut self, events: &[WatcherEvent]) {
+        if events.is_empty() {
+            return;
+        }
+
+        // undo the reorged transactions before the ones of the new best chain are applied
+        for event in events.iter() {
+            if let WatcherEvent::Reorged {
+                graph_id,
+                transaction,
+                ..
+            } = event
+            {
+                if let Err(e) = self.update_graph_state(graph_id, *transaction, None) {
+                    eprintln!("Graph {}: {}", graph_id, e);
+                }
+            }
+        }
+
+        let mut confirmed: Vec<(&String, GraphTransaction, Txid, u32)> = events
+            .iter()
+            .filter_map(|event| match event {
+                WatcherEvent::Confirmed {
+                    graph_id,
+                    transaction,
+                    txid,
+                    height,
+                } => Some((graph_id, *transaction, *txid, *height)),
+                _ => None,
+            })
+            .collect();
+        confirmed.sort_by_key(|(_, _, _, height)| *height);
+
+        // transactions confirmed in the same block come in no particular order, so the ones that
+        // cannot follow yet are retried as long as others move the states on
+        let mut errors = vec![];
+        loop {
+            let pending = confirmed.len();
+            errors.clear();
+            confirmed.retain(|(graph_id, transaction, txid, height)| {
+                match self.update_graph_state(graph_id, *transaction, Some((*txid, *height))) {
+                    Ok(()) => false,
+                    Err(e) => {
+                        errors.push(format!("Graph {}: {}", graph_id, e));
+                        true
+                    }
+                }
+            });
+            if confirmed.is_empty() || confirmed.len() == pending {
+                break;
+            }
+        }
+        for e in errors.iter() {
+            eprintln!("{}", e);
+        }
+
+        self.store_graph_states();
+    }
+
+    // Applies `transaction` included with the given txid and height to the state of a graph, or
+    // rolls it back if there is no inclusion
+    fn update_graph_state(
+        &mut self,
+        graph_id: &str,
+        transaction: GraphTransaction,
+        inclusion: Option<(Txid, u32)>,
+    ) -> Result<(), String> {
+        if let Some(peg_in_graph) = self
+            .data
+            .peg_in_graphs
+            .iter_mut()
+            .find(|peg_in_graph| peg_in_graph.id().eq(graph_id))
+        {
+            let state = peg_in_graph.state_mut();
+            return match inclusion {
+                Some((txid, height)) => state.apply(transaction, txid, height).map(|_| ()),
+                None => {
+                    state.roll_back(transaction);
+                    Ok(())
+                }
+            };
+        }
+
+        let peg_out_graph = self
+            .data
+            .peg_out_graphs
+            .iter_mut()
+            .find(|peg_out_graph| peg_out_graph.id().eq(graph_id))
+            .ok_or("Unknown graph".to_string())?;
+        let state = peg_out_graph.state_mut();
+        match inclusion {
+            Some((txid, height)) => state.apply(transaction, txid, height).map(|_| ()),
+            None => {
+                state.roll_back(transaction);
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn create_peg_in_graph(
+        &mut self,
+        input: Input,
+        evm_address: &str,
+    ) -> Result<String, String> {
         if self.depositor_context.is_none() {
             panic!("Depositor context must be initialized");
         }
 
         let peg_in_graph =
-            PegInGraph::new(self.depositor_context.as_ref().unwrap(), input, evm_address);
+            PegInGraph::new(self.depositor_context.as_ref().unwrap(), input, evm_address)?;
 
-        let peg_in_graph_id = peg_in_generate_id(&peg_in_graph.peg_in_deposit_transaction);
+        let peg_in_graph_id = peg_in_generate_id(
+            &peg_in_graph.peg_in_deposit_transaction,
+            peg_in_graph.params(),
+        );
 
         let graph = self
             .data
@@ -642,7 +1073,7 @@ impl BitVMClient {
 
         self.data.peg_in_graphs.push(peg_in_graph);
 
-        peg_in_graph_id
+        Ok(peg_in_graph_id)
     }
 
     pub async fn broadcast_peg_in_deposit(&mut self, peg_in_graph_id: &str) {
@@ -688,7 +1119,7 @@ impl BitVMClient {
         &mut self,
         peg_in_graph_id: &str,
         kickoff_input: Input,
-    ) -> String {
+    ) -> Result<String, String> {
         if self.operator_context.is_none() {
             panic!("Operator context must be initialized");
         }
@@ -703,7 +1134,11 @@ impl BitVMClient {
             panic!("Invalid graph id");
         }
 
-        let peg_out_graph_id = peg_out_generate_id(peg_in_graph.unwrap(), operator_public_key);
+        let peg_out_graph_id = peg_out_generate_id(
+            peg_in_graph.unwrap(),
+            operator_public_key,
+            &self.operator_context.as_ref().unwrap().params,
+        );
         let peg_out_graph = self
             .data
             .peg_out_graphs
@@ -717,20 +1152,25 @@ impl BitVMClient {
             self.operator_context.as_ref().unwrap(),
             peg_in_graph.unwrap(),
             kickoff_input,
-        );
+        )?;
 
-        self.private_data.winternitz_secrets = HashMap::from([(
-            operator_public_key.clone(),
-            HashMap::from([(peg_out_graph_id.to_string(), winternitz_secrets)]),
-        )]);
+        self.private_data
+            .winternitz_secrets
+            .entry(operator_public_key.clone())
+            .or_default()
+            .insert(peg_out_graph_id.to_string(), winternitz_secrets);
         Self::save_local_private_file(&self.file_path, &serialize(&self.private_data));
 
         self.data.peg_out_graphs.push(peg_out_graph);
 
-        peg_out_graph_id
+        Ok(peg_out_graph_id)
     }
 
-    pub async fn broadcast_peg_out(&mut self, peg_out_graph_id: &str, input: Input) {
+    pub async fn broadcast_peg_out(
+        &mut self,
+        peg_out_graph_id: &str,
+        input: Input,
+    ) -> Result<(), String> {
         let peg_out_graph = self
             .data
             .peg_out_graphs
@@ -748,8 +1188,10 @@ impl BitVMClient {
                     self.operator_context.as_ref().unwrap(),
                     input,
                 )
-                .await;
+                .await?;
         }
+
+        Ok(())
     }
 
     pub async fn broadcast_peg_out_confirm(&mut self, peg_out_graph_id: &str) {
@@ -765,7 +1207,7 @@ impl BitVMClient {
         peg_out_graph.unwrap().peg_out_confirm(&self.esplora).await;
     }
 
-    pub async fn broadcast_kick_off_1(&mut self, peg_out_graph_id: &str) {
+    pub async fn broadcast_kick_off_1(&mut self, peg_out_graph_id: &str) -> Result<(), String> {
         let peg_out_graph = self
             .data
             .peg_out_graphs
@@ -786,11 +1228,13 @@ impl BitVMClient {
                         [&self.operator_context.as_ref().unwrap().operator_public_key]
                         [peg_out_graph_id][&connector_6_id],
                 )
-                .await;
+                .await?;
         }
+
+        Ok(())
     }
 
-    pub async fn broadcast_start_time(&mut self, peg_out_graph_id: &str) {
+    pub async fn broadcast_start_time(&mut self, peg_out_graph_id: &str) -> Result<(), String> {
         let peg_out_graph = self
             .data
             .peg_out_graphs
@@ -811,8 +1255,10 @@ impl BitVMClient {
                         [&self.operator_context.as_ref().unwrap().operator_public_key]
                         [peg_out_graph_id][&connector_2_id],
                 )
-                .await;
+                .await?;
         }
+
+        Ok(())
     }
 
     pub async fn broadcast_start_time_timeout(
@@ -839,7 +1285,7 @@ impl BitVMClient {
         &mut self,
         peg_out_graph_id: &str,
         sb_message: &SuperblockMessage,
-    ) {
+    ) -> Result<(), String> {
         let peg_out_graph = self
             .data
             .peg_out_graphs
@@ -860,7 +1306,7 @@ impl BitVMClient {
                     [peg_out_graph_id][&connector_1_id],
                 sb_message,
             )
-            .await;
+            .await
     }
 
     pub async fn broadcast_kick_off_timeout(
@@ -888,7 +1334,7 @@ impl BitVMClient {
         peg_out_graph_id: &str,
         crowdfundng_inputs: &Vec<InputWithScript<'_>>,
         output_script_pubkey: ScriptBuf,
-    ) {
+    ) -> Result<(), String> {
         let peg_out_graph = self
             .data
             .peg_out_graphs
@@ -905,10 +1351,14 @@ impl BitVMClient {
                     &self.esplora,
                     self.depositor_context.as_ref().unwrap(),
                     crowdfundng_inputs,
-                    &self.depositor_context.as_ref().unwrap().depositor_keypair,
+                    self.depositor_context
+                        .as_ref()
+                        .unwrap()
+                        .depositor_signer
+                        .as_ref(),
                     output_script_pubkey,
                 )
-                .await;
+                .await?;
         } else if self.operator_context.is_some() {
             peg_out_graph
                 .unwrap()
@@ -916,10 +1366,14 @@ impl BitVMClient {
                     &self.esplora,
                     self.operator_context.as_ref().unwrap(),
                     crowdfundng_inputs,
-                    &self.operator_context.as_ref().unwrap().operator_keypair,
+                    self.operator_context
+                        .as_ref()
+                        .unwrap()
+                        .operator_signer
+                        .as_ref(),
                     output_script_pubkey,
                 )
-                .await;
+                .await?;
         } else if self.verifier_context.is_some() {
             peg_out_graph
                 .unwrap()
@@ -927,10 +1381,14 @@ impl BitVMClient {
                     &self.esplora,
                     self.verifier_context.as_ref().unwrap(),
                     crowdfundng_inputs,
-                    &self.verifier_context.as_ref().unwrap().verifier_keypair,
+                    self.verifier_context
+                        .as_ref()
+                        .unwrap()
+                        .verifier_signer
+                        .as_ref(),
                     output_script_pubkey,
                 )
-                .await;
+                .await?;
         } else if self.withdrawer_context.is_some() {
             peg_out_graph
                 .unwrap()
@@ -938,11 +1396,146 @@ impl BitVMClient {
                     &self.esplora,
                     self.withdrawer_context.as_ref().unwrap(),
                     crowdfundng_inputs,
-                    &self.withdrawer_context.as_ref().unwrap().withdrawer_keypair,
+                    self.withdrawer_context
+                        .as_ref()
+                        .unwrap()
+                        .withdrawer_signer
+                        .as_ref(),
                     output_script_pubkey,
                 )
-                .await;
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Nodes and edges of the peg-in or peg-out graph with the given id, with the confirmation
+    /// status recorded in its state
+    pub fn export_graph(&self, graph_id: &str) -> Result<GraphExport, String> {
+        if let Some(peg_in_graph) = self
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id().eq(graph_id))
+        {
+            return Ok(peg_in_graph.export());
         }
+
+        self.data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(graph_id))
+            .map(|peg_out_graph| peg_out_graph.export())
+            .ok_or(format!("Graph {} not found", graph_id))
+    }
+
+    pub fn get_challenge_crowdfunding(
+        &self,
+        peg_out_graph_id: &str,
+    ) -> Option<&ChallengeCrowdfunding> {
+        self.data
+            .challenge_crowdfundings
+            .iter()
+            .find(|crowdfunding| crowdfunding.peg_out_graph_id().eq(peg_out_graph_id))
+    }
+
+    /// Pledge up to `amount` of `inputs` to the challenge of a peg-out graph, the rest is
+    /// refunded to `refund_script_pubkey`. Call `flush` to share the pledge with other verifiers.
+    pub fn pledge_challenge_crowdfunding(
+        &mut self,
+        peg_out_graph_id: &str,
+        inputs: Vec<CrowdfundingInput>,
+        amount: Amount,
+        refund_script_pubkey: ScriptBuf,
+    ) -> Result<(), String> {
+        if self.verifier_context.is_none() {
+            panic!("Can only be called by a verifier!");
+        }
+
+        let peg_out_graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id));
+        if peg_out_graph.is_none() {
+            panic!("Invalid graph id");
+        }
+
+        if self.get_challenge_crowdfunding(peg_out_graph_id).is_none() {
+            let crowdfunding = peg_out_graph.unwrap().new_challenge_crowdfunding();
+            self.data.challenge_crowdfundings.push(crowdfunding);
+        }
+
+        let pledge = CrowdfundingPledge::new(
+            peg_out_graph_id,
+            inputs,
+            amount,
+            refund_script_pubkey,
+            self.verifier_context.as_ref().unwrap().verifier_signer.as_ref(),
+        )?;
+        self.data
+            .challenge_crowdfundings
+            .iter_mut()
+            .find(|crowdfunding| crowdfunding.peg_out_graph_id().eq(peg_out_graph_id))
+            .unwrap()
+            .pledge(pledge)
+    }
+
+    /// Sign the verifier's pledged inputs once the challenge is fully funded
+    pub fn sign_challenge_crowdfunding(&mut self, peg_out_graph_id: &str) -> Result<(), String> {
+        if self.verifier_context.is_none() {
+            panic!("Can only be called by a verifier!");
+        }
+
+        let peg_out_graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id));
+        if peg_out_graph.is_none() {
+            panic!("Invalid graph id");
+        }
+
+        let crowdfunding = self
+            .data
+            .challenge_crowdfundings
+            .iter_mut()
+            .find(|crowdfunding| crowdfunding.peg_out_graph_id().eq(peg_out_graph_id))
+            .ok_or(format!("No pledges for graph {}", peg_out_graph_id))?;
+
+        let context = self.verifier_context.as_ref().unwrap();
+        peg_out_graph.unwrap().sign_challenge_crowdfunding(
+            context,
+            crowdfunding,
+            context.verifier_signer.as_ref(),
+        )
+    }
+
+    /// Broadcast the challenge with the inputs of every pledge, once all of them are signed
+    pub async fn broadcast_crowdfunded_challenge(
+        &mut self,
+        peg_out_graph_id: &str,
+    ) -> Result<(), String> {
+        let peg_out_graph = self
+            .data
+            .peg_out_graphs
+            .iter_mut()
+            .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id));
+        if peg_out_graph.is_none() {
+            panic!("Invalid graph id");
+        }
+
+        let crowdfunding = self
+            .data
+            .challenge_crowdfundings
+            .iter()
+            .find(|crowdfunding| crowdfunding.peg_out_graph_id().eq(peg_out_graph_id))
+            .ok_or(format!("No pledges for graph {}", peg_out_graph_id))?;
+
+        peg_out_graph
+            .unwrap()
+            .crowdfunded_challenge(&self.esplora, crowdfunding)
+            .await
     }
 
     pub async fn broadcast_assert(&mut self, peg_out_graph_id: &str) {
@@ -955,7 +1548,16 @@ impl BitVMClient {
             panic!("Invalid graph id");
         }
 
-        peg_out_graph.unwrap().assert(&self.esplora).await;
+        let connector_c_id = peg_out_graph.as_ref().unwrap().connector_c_id();
+        peg_out_graph
+            .unwrap()
+            .assert(
+                &self.esplora,
+                &self.private_data.winternitz_secrets
+                    [&self.operator_context.as_ref().unwrap().operator_public_key]
+                    [peg_out_graph_id][&connector_c_id],
+            )
+            .await;
     }
 
     pub async fn broadcast_disprove(
@@ -1051,7 +1653,7 @@ impl BitVMClient {
         }
     }
 
-    pub fn push_peg_in_nonces(&mut self, peg_in_graph_id: &str) {
+    pub fn push_peg_in_nonces(&mut self, peg_in_graph_id: &str) -> Result<(), String> {
         if self.verifier_context.is_none() {
             panic!("Can only be called by a verifier!");
         }
@@ -1065,20 +1667,22 @@ impl BitVMClient {
             panic!("Invalid graph id");
         }
 
-        let secret_nonces = peg_in_graph
+        let nonces = peg_in_graph
             .unwrap()
-            .push_nonces(&self.verifier_context.as_ref().unwrap());
+            .push_nonces(&self.verifier_context.as_ref().unwrap())?;
 
-        self.merge_secret_nonces(peg_in_graph_id, secret_nonces);
+        self.merge_nonces(peg_in_graph_id, nonces);
 
-        // TODO: Save secret nonces for all txs in the graph to the local file system. Later, when pre-signing the tx,
+        // TODO: Save nonces for all txs in the graph to the local file system. Later, when pre-signing the tx,
         // we'll need to retrieve these nonces for this graph ID.
 
         let json = serialize(&self.private_data);
         Self::save_local_private_file(&self.file_path, &json);
+
+        Ok(())
     }
 
-    pub fn push_peg_out_nonces(&mut self, peg_out_graph_id: &str) {
+    pub fn push_peg_out_nonces(&mut self, peg_out_graph_id: &str) -> Result<(), String> {
         if self.verifier_context.is_none() {
             panic!("Can only be called by a verifier!");
         }
@@ -1092,63 +1696,68 @@ impl BitVMClient {
             panic!("Invalid graph id");
         }
 
-        let secret_nonces = peg_out_graph
+        let nonces = peg_out_graph
             .unwrap()
-            .push_nonces(&self.verifier_context.as_ref().unwrap());
+            .push_nonces(&self.verifier_context.as_ref().unwrap())?;
 
-        self.merge_secret_nonces(peg_out_graph_id, secret_nonces);
+        self.merge_nonces(peg_out_graph_id, nonces);
 
-        // TODO: Save secret nonces for all txs in the graph to the local file system. Later, when pre-signing the tx,
+        // TODO: Save nonces for all txs in the graph to the local file system. Later, when pre-signing the tx,
         // we'll need to retrieve these nonces for this graph ID.
         let json = serialize(&self.private_data);
         Self::save_local_private_file(&self.file_path, &json);
 
         // TODO: Add public nonces in the remaining txs in this graph.
+
+        Ok(())
     }
 
-    fn merge_secret_nonces(
-        &mut self,
-        graph_id: &str,
-        secret_nonces: HashMap<Txid, HashMap<usize, SecNonce>>,
-    ) {
+    fn merge_nonces(&mut self, graph_id: &str, nonces: HashMap<Txid, HashMap<usize, PubNonce>>) {
         if self
             .private_data
-            .secret_nonces
+            .nonces
             .get(&self.verifier_context.as_ref().unwrap().verifier_public_key)
             .is_none()
         {
-            self.private_data.secret_nonces.insert(
+            self.private_data.nonces.insert(
                 self.verifier_context.as_ref().unwrap().verifier_public_key,
                 HashMap::new(),
             );
         }
 
-        if self.private_data.secret_nonces
-            [&self.verifier_context.as_ref().unwrap().verifier_public_key]
+        if self.private_data.nonces[&self.verifier_context.as_ref().unwrap().verifier_public_key]
             .get(graph_id)
             .is_none()
         {
             self.private_data
-                .secret_nonces
+                .nonces
                 .get_mut(&self.verifier_context.as_ref().unwrap().verifier_public_key)
                 .unwrap()
                 .insert(graph_id.to_string(), HashMap::new());
         }
 
         self.private_data
-            .secret_nonces
+            .nonces
             .get_mut(&self.verifier_context.as_ref().unwrap().verifier_public_key)
             .unwrap()
             .get_mut(graph_id)
             .unwrap()
-            .extend(secret_nonces);
+            .extend(nonces);
     }
 
-    pub fn pre_sign_peg_in(&mut self, peg_in_graph_id: &str) {
+    pub fn pre_sign_peg_in(&mut self, peg_in_graph_id: &str) -> Result<(), String> {
         if self.operator_context.is_none() && self.verifier_context.is_none() {
             panic!("Can only be called by an operator or a verifier!");
         }
 
+        let pending_operators = self.get_pending_operators(peg_in_graph_id);
+        if !pending_operators.is_empty() {
+            panic!(
+                "Peg-out graphs of operators {:?} are missing or not pre-signed",
+                pending_operators
+            );
+        }
+
         let peg_in_graph = self
             .data
             .peg_in_graphs
@@ -1158,14 +1767,18 @@ impl BitVMClient {
             panic!("Invalid graph id");
         }
 
+        if peg_in_graph.as_ref().unwrap().params().ne(&self.params) {
+            panic!("Peg in graph was built with different params");
+        }
+
         peg_in_graph.unwrap().pre_sign(
             &self.verifier_context.as_ref().unwrap(),
-            &self.private_data.secret_nonces
-                [&self.verifier_context.as_ref().unwrap().verifier_public_key][peg_in_graph_id],
-        );
+            &self.private_data.nonces[&self.verifier_context.as_ref().unwrap().verifier_public_key]
+                [peg_in_graph_id],
+        )
     }
 
-    pub fn pre_sign_peg_out(&mut self, peg_out_graph_id: &str) {
+    pub fn pre_sign_peg_out(&mut self, peg_out_graph_id: &str) -> Result<(), String> {
         if self.operator_context.is_none() && self.verifier_context.is_none() {
             panic!("Can only be called by an operator or a verifier!");
         }
@@ -1179,27 +1792,170 @@ impl BitVMClient {
             panic!("Invalid graph id");
         }
 
+        if peg_out_graph.as_ref().unwrap().params().ne(&self.params) {
+            panic!("Peg out graph was built with different params");
+        }
+
         peg_out_graph.unwrap().pre_sign(
             &self.verifier_context.as_ref().unwrap(),
-            &self.private_data.secret_nonces
-                [&self.verifier_context.as_ref().unwrap().verifier_public_key][peg_out_graph_id],
-        );
+            &self.private_data.nonces[&self.verifier_context.as_ref().unwrap().verifier_public_key]
+                [peg_out_graph_id],
+        )
+    }
+
+    pub fn is_verifier(&self) -> bool { self.verifier_context.is_some() }
+
+    /// Pushes this verifier's nonces to graphs that lack them and pre-signs graphs for which all
+    /// n-of-n nonces have been pushed but this verifier's signatures are missing. Graphs built
+    /// with other params than the verifier's are left alone. Stops at the first signer error.
+    pub fn pre_sign_new_graphs(&mut self) -> Result<(), String> {
+        if self.verifier_context.is_none() {
+            panic!("Can only be called by a verifier!");
+        }
+
+        let verifier_context = self.verifier_context.as_ref().unwrap();
+        let verifier_public_key = vec![verifier_context.verifier_public_key];
+        let n_of_n_public_keys = verifier_context.n_of_n_public_keys.clone();
+        let has_private_nonces = |private_data: &BitVMClientPrivateData, graph_id: &String| {
+            private_data
+                .nonces
+                .get(&verifier_public_key[0])
+                .is_some_and(|nonces| nonces.contains_key(graph_id))
+        };
+
+        let mut peg_in_graphs_to_push_nonces = vec![];
+        let mut peg_in_graphs_to_pre_sign = vec![];
+        for peg_in_graph in self.data.peg_in_graphs.iter() {
+            if peg_in_graph.params().ne(&self.params) {
+                continue;
+            }
+            if !peg_in_graph.has_nonces(&verifier_public_key) {
+                peg_in_graphs_to_push_nonces.push(peg_in_graph.id().clone());
+            } else if peg_in_graph.has_nonces(&n_of_n_public_keys)
+                && !peg_in_graph.has_signatures(&verifier_public_key)
+                && has_private_nonces(&self.private_data, peg_in_graph.id())
+                && self.get_pending_operators(peg_in_graph.id()).is_empty()
+            {
+                peg_in_graphs_to_pre_sign.push(peg_in_graph.id().clone());
+            }
+        }
+
+        let mut peg_out_graphs_to_push_nonces = vec![];
+        let mut peg_out_graphs_to_pre_sign = vec![];
+        for peg_out_graph in self.data.peg_out_graphs.iter() {
+            if peg_out_graph.params().ne(&self.params) {
+                continue;
+            }
+            if !peg_out_graph.has_nonces(&verifier_public_key) {
+                peg_out_graphs_to_push_nonces.push(peg_out_graph.id().clone());
+            } else if peg_out_graph.has_nonces(&n_of_n_public_keys)
+                && !peg_out_graph.has_signatures(&verifier_public_key)
+                && has_private_nonces(&self.private_data, peg_out_graph.id())
+            {
+                peg_out_graphs_to_pre_sign.push(peg_out_graph.id().clone());
+            }
+        }
+
+        for peg_in_graph_id in peg_in_graphs_to_push_nonces {
+            println!("Pushing nonces for peg-in graph {}", peg_in_graph_id);
+            self.push_peg_in_nonces(&peg_in_graph_id)?;
+        }
+        for peg_in_graph_id in peg_in_graphs_to_pre_sign {
+            println!("Pre-signing peg-in graph {}", peg_in_graph_id);
+            self.pre_sign_peg_in(&peg_in_graph_id)?;
+        }
+        for peg_out_graph_id in peg_out_graphs_to_push_nonces {
+            println!("Pushing nonces for peg-out graph {}", peg_out_graph_id);
+            self.push_peg_out_nonces(&peg_out_graph_id)?;
+        }
+        for peg_out_graph_id in peg_out_graphs_to_pre_sign {
+            println!("Pre-signing peg-out graph {}", peg_out_graph_id);
+            self.pre_sign_peg_out(&peg_out_graph_id)?;
+        }
+
+        Ok(())
+    }
+
+    fn data_file_path(
+        source_network: Network,
+        destination_network: DestinationNetwork,
+        n_of_n_public_keys: &Vec<PublicKey>,
+    ) -> String {
+        let (n_of_n_public_key, _) = generate_n_of_n_public_key(n_of_n_public_keys);
+        format! {"bridge_data/{source_network}/{destination_network}/{n_of_n_public_key}"}
+    }
+
+    /// File a `LocalSigner` with `public_key` keeps its secret MuSig2 nonces in, next to the
+    /// private data of the client
+    pub fn secret_nonces_file_path(
+        source_network: Network,
+        destination_network: DestinationNetwork,
+        n_of_n_public_keys: &Vec<PublicKey>,
+        public_key: &PublicKey,
+    ) -> String {
+        let file_path =
+            Self::data_file_path(source_network, destination_network, n_of_n_public_keys);
+        Self::secret_nonces_file(&file_path, public_key)
+    }
+
+    fn secret_nonces_file(file_path: &String, public_key: &PublicKey) -> String {
+        format!("{file_path}/private/{SECRET_NONCES_FILE_PREFIX}{public_key}.json")
     }
 
     fn get_private_data(file_path: &String) -> BitVMClientPrivateData {
         match Self::read_local_private_file(file_path) {
-            Some(data) => try_deserialize::<BitVMClientPrivateData>(&data)
-                .expect("Could not deserialize private data"),
+            Some(data) => {
+                let mut private_data = try_deserialize::<BitVMClientPrivateData>(&data)
+                    .expect("Could not deserialize private data");
+                if !private_data.legacy_secret_nonces.is_empty() {
+                    Self::migrate_secret_nonces(file_path, &mut private_data);
+                }
+                private_data
+            }
             None => {
                 println!("New private data will be generated.");
                 BitVMClientPrivateData {
-                    secret_nonces: HashMap::new(),
+                    nonces: HashMap::new(),
+                    legacy_secret_nonces: HashMap::new(),
                     winternitz_secrets: HashMap::new(),
+                    peg_in_graph_states: HashMap::new(),
+                    peg_out_graph_states: HashMap::new(),
                 }
             }
         }
     }
 
+    // Move the secret nonces of an older private data file to the nonce files of the verifiers'
+    // local signers, keeping their public nonces in the private data
+    fn migrate_secret_nonces(file_path: &String, private_data: &mut BitVMClientPrivateData) {
+        println!("Moving secret nonces from the private data to the signers' nonce files...");
+        for (public_key, graphs) in std::mem::take(&mut private_data.legacy_secret_nonces) {
+            let mut secret_nonces = vec![];
+            for (graph_id, txs) in graphs {
+                for (txid, inputs) in txs {
+                    for (input_index, secret_nonce) in inputs {
+                        private_data
+                            .nonces
+                            .entry(public_key)
+                            .or_default()
+                            .entry(graph_id.clone())
+                            .or_default()
+                            .entry(txid)
+                            .or_default()
+                            .insert(input_index, secret_nonce.public_nonce());
+                        secret_nonces.push(secret_nonce);
+                    }
+                }
+            }
+            let nonce_file = Self::secret_nonces_file(file_path, &public_key);
+            store_secret_nonces(Path::new(&nonce_file), secret_nonces)
+                .expect("Unable to migrate secret nonces");
+        }
+
+        // The secret nonces are not serialized anymore
+        Self::save_local_private_file(file_path, &serialize(private_data));
+    }
+
     fn save_local_public_file(file_path: &String, key: &String, json: &String) {
         Self::create_directories_if_non_existent(file_path);
         println!("Saving public data in local file: {}...", key);
@@ -1460,3 +2216,345 @@ impl GraphQuery for BitVMClient {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, secp256k1::Message, PubkeyHash};
+    use musig2::AggNonce;
+    use serial_test::serial;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use crate::bridge::{
+        contexts::base::generate_keys_from_secret,
+        graphs::base::{
+            DEPOSITOR_EVM_ADDRESS, DEPOSITOR_SECRET, FEE_AMOUNT, INITIAL_AMOUNT, OPERATOR_SECRET,
+            VERIFIER_0_SECRET, WITHDRAWER_EVM_ADDRESS, WITHDRAWER_SECRET,
+        },
+        transactions::signing_musig2::generate_nonce,
+    };
+
+    use super::*;
+
+    // Any other demo key does for a second operator
+    const OPERATOR_1_SECRET: &str = WITHDRAWER_SECRET;
+
+    fn public_key(secret: &str) -> PublicKey {
+        generate_keys_from_secret(Network::Regtest, secret).2
+    }
+
+    // A single verifier makes up the n-of-n, so that it can pre-sign graphs on its own
+    async fn test_client(registered_operators: &[PublicKey]) -> BitVMClient {
+        let (_, _, verifier_public_key) =
+            generate_keys_from_secret(Network::Regtest, VERIFIER_0_SECRET);
+        BitVMClient::new(
+            Network::Regtest,
+            DestinationNetwork::EthereumSepolia,
+            &vec![verifier_public_key],
+            registered_operators,
+            Some(DEPOSITOR_SECRET),
+            Some(OPERATOR_SECRET),
+            Some(VERIFIER_0_SECRET),
+            None,
+            None,
+        )
+        .await
+    }
+
+    fn test_input(vout: u32) -> Input {
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::all_zeros(),
+                vout,
+            },
+            amount: Amount::from_sat(INITIAL_AMOUNT + FEE_AMOUNT),
+        }
+    }
+
+    fn add_peg_in_graph(client: &mut BitVMClient, vout: u32) -> String {
+        let peg_in_graph = PegInGraph::new(
+            client.depositor_context.as_ref().unwrap(),
+            test_input(vout),
+            DEPOSITOR_EVM_ADDRESS,
+        )
+        .unwrap();
+        let peg_in_graph_id = peg_in_graph.id().clone();
+        client.data.peg_in_graphs.push(peg_in_graph);
+
+        peg_in_graph_id
+    }
+
+    fn add_peg_out_graph(
+        client: &mut BitVMClient,
+        peg_in_graph_id: &str,
+        operator_secret: &str,
+        vout: u32,
+    ) -> String {
+        let operator_context = OperatorContext::new(
+            Network::Regtest,
+            operator_secret,
+            &client.verifier_context.as_ref().unwrap().n_of_n_public_keys,
+        );
+        let peg_in_graph = client
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id().eq(peg_in_graph_id))
+            .unwrap();
+        let (peg_out_graph, _) =
+            PegOutGraph::new(&operator_context, peg_in_graph, test_input(vout)).unwrap();
+        let peg_out_graph_id = peg_out_graph.id().clone();
+        client.data.peg_out_graphs.push(peg_out_graph);
+
+        peg_out_graph_id
+    }
+
+    fn peg_out_event(
+        client: &BitVMClient,
+        peg_in_graph_id: &str,
+        operator_secret: &str,
+        amount: u64,
+    ) -> PegOutEvent {
+        let peg_in_graph = client
+            .data
+            .peg_in_graphs
+            .iter()
+            .find(|peg_in_graph| peg_in_graph.id().eq(peg_in_graph_id))
+            .unwrap();
+        PegOutEvent {
+            withdrawer_chain_address: WITHDRAWER_EVM_ADDRESS.to_string(),
+            withdrawer_public_key_hash: PubkeyHash::all_zeros(),
+            source_outpoint: OutPoint {
+                txid: peg_in_graph
+                    .peg_in_confirm_transaction_ref()
+                    .tx()
+                    .compute_txid(),
+                vout: 0,
+            },
+            amount: Amount::from_sat(amount),
+            operator_public_key: public_key(operator_secret),
+            timestamp: 0,
+            tx_hash: vec![],
+        }
+    }
+
+    fn peg_out_chain_event<'a>(
+        client: &'a BitVMClient,
+        peg_out_graph_id: &str,
+    ) -> &'a Option<PegOutEvent> {
+        &client
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id))
+            .unwrap()
+            .peg_out_chain_event
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_route_peg_out_events_to_the_named_operator() {
+        let mut client = test_client(&[]).await;
+        let peg_in_graph_id = add_peg_in_graph(&mut client, 0);
+        let peg_out_graph_0_id =
+            add_peg_out_graph(&mut client, &peg_in_graph_id, OPERATOR_SECRET, 1);
+        let peg_out_graph_1_id =
+            add_peg_out_graph(&mut client, &peg_in_graph_id, OPERATOR_1_SECRET, 2);
+
+        // both operators present a graph over the same peg-in, the event goes to the one it names
+        let event = peg_out_event(&client, &peg_in_graph_id, OPERATOR_1_SECRET, INITIAL_AMOUNT);
+        client.route_peg_out_events(vec![event.clone()]);
+        assert_eq!(peg_out_chain_event(&client, &peg_out_graph_0_id), &None);
+        assert_eq!(
+            peg_out_chain_event(&client, &peg_out_graph_1_id),
+            &Some(event.clone())
+        );
+
+        // a duplicate changes nothing and a conflicting event does not replace the first one
+        let conflicting_event =
+            peg_out_event(&client, &peg_in_graph_id, OPERATOR_1_SECRET, INITIAL_AMOUNT + 1);
+        client.route_peg_out_events(vec![event.clone(), conflicting_event]);
+        assert_eq!(peg_out_chain_event(&client, &peg_out_graph_0_id), &None);
+        assert_eq!(
+            peg_out_chain_event(&client, &peg_out_graph_1_id),
+            &Some(event.clone())
+        );
+
+        // events of operators without a graph over the peg-in are dropped
+        let unknown_operator_event =
+            peg_out_event(&client, &peg_in_graph_id, VERIFIER_0_SECRET, INITIAL_AMOUNT);
+        let operator_0_event =
+            peg_out_event(&client, &peg_in_graph_id, OPERATOR_SECRET, INITIAL_AMOUNT);
+        client.route_peg_out_events(vec![unknown_operator_event, operator_0_event.clone()]);
+        assert_eq!(
+            peg_out_chain_event(&client, &peg_out_graph_0_id),
+            &Some(operator_0_event)
+        );
+        assert_eq!(
+            peg_out_chain_event(&client, &peg_out_graph_1_id),
+            &Some(event)
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_pre_sign_peg_in_waits_for_registered_operators() {
+        let operator_public_keys = [public_key(OPERATOR_SECRET), public_key(OPERATOR_1_SECRET)];
+        let mut client = test_client(&operator_public_keys).await;
+        let n_of_n_public_keys = client
+            .verifier_context
+            .as_ref()
+            .unwrap()
+            .n_of_n_public_keys
+            .clone();
+        let is_peg_in_pre_signed = |client: &BitVMClient| {
+            client.data.peg_in_graphs[0].has_signatures(&n_of_n_public_keys)
+        };
+
+        let peg_in_graph_id = add_peg_in_graph(&mut client, 0);
+        assert_eq!(
+            client.get_pending_operators(&peg_in_graph_id),
+            operator_public_keys.to_vec()
+        );
+
+        // the first round pushes the nonces, the second one pre-signs the peg-out graph of the
+        // first operator but not the peg-in, as the second operator has no graph yet
+        let peg_out_graph_0_id =
+            add_peg_out_graph(&mut client, &peg_in_graph_id, OPERATOR_SECRET, 1);
+        client.pre_sign_new_graphs().unwrap();
+        client.pre_sign_new_graphs().unwrap();
+        assert!(client
+            .get_peg_out_graph_of_operator(&peg_in_graph_id, &operator_public_keys[0])
+            .is_some_and(|peg_out_graph| {
+                peg_out_graph.id().eq(&peg_out_graph_0_id)
+                    && peg_out_graph.has_signatures(&n_of_n_public_keys)
+            }));
+        assert_eq!(
+            client.get_pending_operators(&peg_in_graph_id),
+            vec![operator_public_keys[1]]
+        );
+        assert!(!is_peg_in_pre_signed(&client));
+        let refused = catch_unwind(AssertUnwindSafe(|| client.pre_sign_peg_in(&peg_in_graph_id)));
+        assert!(refused.is_err());
+
+        // a graph that is not pre-signed yet does not count
+        add_peg_out_graph(&mut client, &peg_in_graph_id, OPERATOR_1_SECRET, 2);
+        assert_eq!(
+            client.get_pending_operators(&peg_in_graph_id),
+            vec![operator_public_keys[1]]
+        );
+        client.pre_sign_new_graphs().unwrap();
+        client.pre_sign_new_graphs().unwrap();
+        assert!(client.get_pending_operators(&peg_in_graph_id).is_empty());
+        assert!(!is_peg_in_pre_signed(&client));
+
+        // once all registered operators have a pre-signed graph, the peg-in is pre-signed
+        client.pre_sign_new_graphs().unwrap();
+        assert!(is_peg_in_pre_signed(&client));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_pre_sign_new_graphs_skips_graphs_with_other_params() {
+        let mut client = test_client(&[]).await;
+        let verifier_public_key = client.verifier_context.as_ref().unwrap().verifier_public_key;
+
+        // a refund the depositor could take earlier than the verifier agreed to
+        let mut params = client.params.clone();
+        params.peg_in_refund_timelock -= 1;
+        let depositor_context = client.depositor_context.as_ref().unwrap();
+        let depositor_context = DepositorContext::new_with_params(
+            Network::Regtest,
+            params,
+            depositor_context.depositor_signer.clone(),
+            &depositor_context.n_of_n_public_keys,
+        );
+        client.data.peg_in_graphs.push(
+            PegInGraph::new(&depositor_context, test_input(0), DEPOSITOR_EVM_ADDRESS).unwrap(),
+        );
+        add_peg_in_graph(&mut client, 1);
+
+        // the first round pushes the nonces, the second one pre-signs
+        client.pre_sign_new_graphs().unwrap();
+        client.pre_sign_new_graphs().unwrap();
+        assert!(!client.data.peg_in_graphs[0].has_nonces(&[verifier_public_key]));
+        assert!(client.data.peg_in_graphs[1].has_signatures(&[verifier_public_key]));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_create_peg_out_graph_keeps_winternitz_secrets_of_other_graphs() {
+        let mut client = test_client(&[]).await;
+        let operator_public_key = client.operator_context.as_ref().unwrap().operator_public_key;
+
+        let mut peg_out_graph_ids = vec![];
+        for vout in 0..2 {
+            let peg_in_graph_id = add_peg_in_graph(&mut client, vout);
+            peg_out_graph_ids.push(
+                client
+                    .create_peg_out_graph(&peg_in_graph_id, test_input(vout + 2))
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let winternitz_secrets = &client.private_data.winternitz_secrets[&operator_public_key];
+        for peg_out_graph_id in peg_out_graph_ids.iter() {
+            assert!(!winternitz_secrets[peg_out_graph_id].is_empty());
+        }
+    }
+
+    #[test]
+    fn test_migrate_private_data_with_secret_nonces() {
+        let file_path = std::env::temp_dir()
+            .join("bitvm_test_private_data")
+            .to_str()
+            .unwrap()
+            .to_string();
+        let _ = fs::remove_dir_all(&file_path);
+        fs::create_dir_all(format!("{file_path}/private")).unwrap();
+
+        // Private data as written before the secret nonces moved to the signers
+        let verifier_public_key = LocalSigner::from_secret(VERIFIER_0_SECRET)
+            .unwrap()
+            .public_key();
+        let txid = Txid::all_zeros();
+        let secret_nonce = generate_nonce();
+        let public_nonce = secret_nonce.public_nonce();
+        let old_private_data = json!({
+            "secret_nonces": {
+                verifier_public_key.to_string(): {
+                    "graph": { txid.to_string(): { "0": secret_nonce } }
+                }
+            },
+            "winternitz_secrets": {},
+        });
+        fs::write(
+            format!("{file_path}/private/{PRIVATE_DATA_FILE_NAME}"),
+            old_private_data.to_string(),
+        )
+        .unwrap();
+
+        let private_data = BitVMClient::get_private_data(&file_path);
+        assert_eq!(
+            private_data.nonces[&verifier_public_key]["graph"][&txid][&0],
+            public_nonce
+        );
+        let data = BitVMClient::read_local_private_file(&file_path).unwrap();
+        assert!(!data.contains("secret_nonces"));
+
+        // The verifier's local signer signs with the migrated nonce
+        let signer = LocalSigner::from_secret(VERIFIER_0_SECRET)
+            .unwrap()
+            .with_nonce_file(BitVMClient::secret_nonces_file(&file_path, &verifier_public_key));
+        assert!(signer
+            .sign_musig2_partial(
+                &[verifier_public_key],
+                &public_nonce,
+                &AggNonce::sum([public_nonce.clone()]),
+                &Message::from_digest([3; 32]),
+            )
+            .is_ok());
+
+        fs::remove_dir_all(&file_path).unwrap();
+    }
+}
@@ -3,3 +3,5 @@ pub mod cli;
 pub mod client;
 pub mod data_store;
 pub mod sdk;
+pub mod watcher;
+pub mod watchtower;
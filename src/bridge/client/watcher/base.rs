@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+use bitcoin::{BlockHash, Transaction};
+use esplora_client::AsyncClient;
+
+/// Where the watcher reads blocks from
+#[async_trait]
+pub trait BlockSource: Send + Sync {
+    async fn get_height(&self) -> Result<u32, String>;
+    async fn get_block_hash(&self, height: u32) -> Result<BlockHash, String>;
+    async fn get_block_transactions(&self, hash: &BlockHash) -> Result<Vec<Transaction>, String>;
+}
+
+#[async_trait]
+impl BlockSource for AsyncClient {
+    async fn get_height(&self) -> Result<u32, String> {
+        AsyncClient::get_height(self)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn get_block_hash(&self, height: u32) -> Result<BlockHash, String> {
+        AsyncClient::get_block_hash(self, height)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn get_block_transactions(&self, hash: &BlockHash) -> Result<Vec<Transaction>, String> {
+        match self.get_block_by_hash(hash).await {
+            Ok(Some(block)) => Ok(block.txdata),
+            Ok(None) => Err(format!("Block {} not found", hash)),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
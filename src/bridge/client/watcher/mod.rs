@@ -0,0 +1,4 @@
+pub mod base;
+#[cfg(test)]
+pub(crate) mod test_chain;
+pub mod watcher;
@@ -0,0 +1,39 @@
+use async_trait::async_trait;
+use bitcoin::{hashes::Hash, BlockHash, Transaction};
+
+use super::base::BlockSource;
+
+/// In-memory chain for the watcher tests. Blocks can be truncated and pushed again to reorg.
+pub(crate) struct TestChain {
+    pub(crate) blocks: Vec<(BlockHash, Vec<Transaction>)>,
+}
+
+impl TestChain {
+    // `fork` tells blocks at the same height apart
+    pub(crate) fn push(&mut self, fork: u8, transactions: Vec<Transaction>) {
+        let mut hash = [fork; 32];
+        hash[..4].copy_from_slice(&(self.blocks.len() as u32).to_le_bytes());
+        self.blocks
+            .push((BlockHash::from_byte_array(hash), transactions));
+    }
+}
+
+#[async_trait]
+impl BlockSource for TestChain {
+    async fn get_height(&self) -> Result<u32, String> { Ok(self.blocks.len() as u32 - 1) }
+
+    async fn get_block_hash(&self, height: u32) -> Result<BlockHash, String> {
+        self.blocks
+            .get(height as usize)
+            .map(|(hash, _)| *hash)
+            .ok_or("Unknown height".to_string())
+    }
+
+    async fn get_block_transactions(&self, hash: &BlockHash) -> Result<Vec<Transaction>, String> {
+        self.blocks
+            .iter()
+            .find(|(block_hash, _)| block_hash == hash)
+            .map(|(_, transactions)| transactions.clone())
+            .ok_or("Unknown block".to_string())
+    }
+}
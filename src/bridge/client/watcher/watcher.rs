@@ -0,0 +1,613 @@
+use async_trait::async_trait;
+use bitcoin::{BlockHash, Network, Transaction, Txid};
+use esplora_client::{Error, TxStatus};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    time::Duration,
+};
+use tokio::time::sleep;
+
+use super::{
+    super::{
+        super::graphs::{
+            base::{
+                is_timelock_elapsed, BaseGraph, GraphChain, GraphTransaction, Timelock,
+                TransactionMatch,
+            },
+            peg_in::PegInGraph,
+            peg_out::PegOutGraph,
+        },
+        client::BitVMClientPublicData,
+    },
+    base::BlockSource,
+};
+
+pub struct WatcherConfig {
+    /// Blocks including and on top of a transaction's block before it counts as confirmed
+    pub confirmations: u32,
+    /// Deepest reorg the watcher can roll back
+    pub max_reorg_depth: u32,
+    /// How often to poll for a new tip
+    pub poll_interval: Duration,
+}
+
+impl WatcherConfig {
+    pub fn for_network(network: Network) -> Self {
+        WatcherConfig {
+            confirmations: match network {
+                Network::Bitcoin => 6,
+                _ => 1,
+            },
+            max_reorg_depth: 100,
+            poll_interval: Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum WatcherEvent {
+    /// A graph transaction got into a block
+    Seen {
+        graph_id: String,
+        transaction: GraphTransaction,
+        txid: Txid,
+        height: u32,
+    },
+    /// A graph transaction is buried under the configured number of confirmations
+    Confirmed {
+        graph_id: String,
+        transaction: GraphTransaction,
+        txid: Txid,
+        height: u32,
+    },
+    /// The block of a graph transaction is no longer in the best chain
+    Reorged {
+        graph_id: String,
+        transaction: GraphTransaction,
+        txid: Txid,
+    },
+    /// The timelock of a graph transaction expired, so it can be broadcast
+    TimelockMatured {
+        graph_id: String,
+        transaction: GraphTransaction,
+        height: u32,
+    },
+}
+
+struct WatchedGraph {
+    matches: Vec<(GraphTransaction, TransactionMatch)>,
+    timelocks: Vec<Timelock>,
+}
+
+struct Inclusion {
+    txid: Txid,
+    height: u32,
+    confirmed: bool,
+}
+
+/// Follows the best chain block by block and keeps an index of where the transactions of the
+/// watched graphs got included, rolling it back when blocks are reorged out. As a `GraphChain`,
+/// it shows the graph statuses the transactions it recognized, once they are confirmed.
+pub struct Watcher {
+    config: WatcherConfig,
+    next_height: u32,
+    // Height -> hash of the scanned blocks that may still be reorged
+    blocks: BTreeMap<u32, BlockHash>,
+    graphs: HashMap<String, WatchedGraph>,
+    // Graph ID -> transaction -> inclusion
+    inclusions: HashMap<String, HashMap<GraphTransaction, Inclusion>>,
+    matured: HashSet<(String, GraphTransaction)>,
+}
+
+impl Watcher {
+    /// Scan from `start_height` on, which should be below the blocks of all watched graphs
+    pub fn new(config: WatcherConfig, start_height: u32) -> Self {
+        Watcher {
+            config,
+            next_height: start_height,
+            blocks: BTreeMap::new(),
+            graphs: HashMap::new(),
+            inclusions: HashMap::new(),
+            matured: HashSet::new(),
+        }
+    }
+
+    /// Start or keep watching a graph, returning whether it is new. Its transactions are only
+    /// recognized in blocks scanned from now on, see `rewind`.
+    pub fn watch(
+        &mut self,
+        graph_id: &str,
+        matches: Vec<(GraphTransaction, TransactionMatch)>,
+        timelocks: Vec<Timelock>,
+    ) -> bool {
+        self.graphs
+            .insert(graph_id.to_string(), WatchedGraph { matches, timelocks })
+            .is_none()
+    }
+
+    /// Scan the blocks from `height` on again, e.g. for a graph learned after its transactions
+    /// got included. Inclusions found before are kept.
+    pub fn rewind(&mut self, height: u32) {
+        if height < self.next_height {
+            self.blocks.retain(|&block_height, _| block_height < height);
+            self.next_height = height;
+        }
+    }
+
+    /// Stop watching a graph and forget where its transactions got included
+    pub fn unwatch(&mut self, graph_id: &str) {
+        self.graphs.remove(graph_id);
+        self.inclusions.remove(graph_id);
+        self.matured.retain(|(id, _)| id != graph_id);
+    }
+
+    pub fn watch_peg_in_graph(&mut self, graph: &PegInGraph) -> bool {
+        self.watch(graph.id(), graph.transaction_matches(), graph.timelocks())
+    }
+
+    pub fn watch_peg_out_graph(&mut self, graph: &PegOutGraph) -> bool {
+        self.watch(graph.id(), graph.transaction_matches(), graph.timelocks())
+    }
+
+    /// Watch all graphs of `data`, returning the IDs of the ones not watched before
+    pub fn watch_graphs(&mut self, data: &BitVMClientPublicData) -> Vec<String> {
+        let mut new_graph_ids = vec![];
+        for graph in data.peg_in_graphs.iter() {
+            if self.watch_peg_in_graph(graph) {
+                new_graph_ids.push(graph.id().clone());
+            }
+        }
+        for graph in data.peg_out_graphs.iter() {
+            if self.watch_peg_out_graph(graph) {
+                new_graph_ids.push(graph.id().clone());
+            }
+        }
+
+        new_graph_ids
+    }
+
+    /// Height of the last scanned block
+    pub fn tip(&self) -> Option<u32> { self.blocks.last_key_value().map(|(&height, _)| height) }
+
+    /// Height of the block including `transaction` of a graph, if any
+    pub fn inclusion_height(&self, graph_id: &str, transaction: GraphTransaction) -> Option<u32> {
+        self.inclusions
+            .get(graph_id)
+            .and_then(|inclusions| inclusions.get(&transaction))
+            .map(|inclusion| inclusion.height)
+    }
+
+    pub fn is_confirmed(&self, graph_id: &str, transaction: GraphTransaction) -> bool {
+        self.inclusions
+            .get(graph_id)
+            .and_then(|inclusions| inclusions.get(&transaction))
+            .is_some_and(|inclusion| inclusion.confirmed)
+    }
+
+    /// Catch up with the tip of `source`, returning what changed since the last poll
+    pub async fn poll(&mut self, source: &dyn BlockSource) -> Result<Vec<WatcherEvent>, String> {
+        self.poll_with(source, &mut |_, _| {}).await
+    }
+
+    /// Same as `poll`, also handing every transaction of the scanned blocks to `visit`
+    pub async fn poll_with(
+        &mut self,
+        source: &dyn BlockSource,
+        visit: &mut (dyn FnMut(u32, &Transaction) + Send),
+    ) -> Result<Vec<WatcherEvent>, String> {
+        let height = source.get_height().await?;
+        let mut events = vec![];
+
+        // roll back the blocks that left the best chain
+        while let Some((&block_height, &block_hash)) = self.blocks.last_key_value() {
+            if block_height <= height && source.get_block_hash(block_height).await? == block_hash {
+                break;
+            }
+            self.blocks.remove(&block_height);
+            self.next_height = block_height;
+            events.extend(self.roll_back(block_height));
+        }
+
+        while self.next_height <= height {
+            let block_hash = source.get_block_hash(self.next_height).await?;
+            let transactions = source.get_block_transactions(&block_hash).await?;
+            for tx in transactions.iter() {
+                events.extend(self.include(tx, self.next_height));
+                visit(self.next_height, tx);
+            }
+
+            self.blocks.insert(self.next_height, block_hash);
+            if self.next_height >= self.config.max_reorg_depth {
+                self.blocks = self
+                    .blocks
+                    .split_off(&(self.next_height - self.config.max_reorg_depth + 1));
+            }
+            self.next_height += 1;
+        }
+
+        events.extend(self.update_confirmations());
+        events.extend(self.update_timelocks());
+
+        Ok(events)
+    }
+
+    /// Poll until the tip changes
+    pub async fn wait_for_block(
+        &mut self,
+        source: &dyn BlockSource,
+    ) -> Result<Vec<WatcherEvent>, String> {
+        let tip = self.tip();
+        loop {
+            let events = self.poll(source).await?;
+            if !events.is_empty() || self.tip() != tip {
+                return Ok(events);
+            }
+            sleep(self.config.poll_interval).await;
+        }
+    }
+
+    /// The watched graph transactions `tx` is recognized as
+    pub fn recognize(&self, tx: &Transaction) -> Vec<(String, GraphTransaction)> {
+        let txid = tx.compute_txid();
+        self.graphs
+            .iter()
+            .filter_map(|(graph_id, graph)| {
+                Self::match_transaction(graph, tx, txid)
+                    .map(|transaction| (graph_id.clone(), transaction))
+            })
+            .collect()
+    }
+
+    fn roll_back(&mut self, height: u32) -> Vec<WatcherEvent> {
+        let mut events = vec![];
+        for (graph_id, inclusions) in self.inclusions.iter_mut() {
+            inclusions.retain(|transaction, inclusion| {
+                if inclusion.height < height {
+                    return true;
+                }
+                events.push(WatcherEvent::Reorged {
+                    graph_id: graph_id.clone(),
+                    transaction: *transaction,
+                    txid: inclusion.txid,
+                });
+                false
+            });
+        }
+
+        events
+    }
+
+    fn include(&mut self, tx: &Transaction, height: u32) -> Vec<WatcherEvent> {
+        let txid = tx.compute_txid();
+        let mut events = vec![];
+        for (graph_id, graph) in self.graphs.iter() {
+            let transaction = match Self::match_transaction(graph, tx, txid) {
+                Some(transaction) => transaction,
+                None => continue,
+            };
+            let inclusions = self.inclusions.entry(graph_id.clone()).or_default();
+            if inclusions.contains_key(&transaction) {
+                continue;
+            }
+
+            inclusions.insert(
+                transaction,
+                Inclusion {
+                    txid,
+                    height,
+                    confirmed: false,
+                },
+            );
+            events.push(WatcherEvent::Seen {
+                graph_id: graph_id.clone(),
+                transaction,
+                txid,
+                height,
+            });
+        }
+
+        events
+    }
+
+    // A matching txid wins over matching inputs, of which the most specific match wins
+    fn match_transaction(
+        graph: &WatchedGraph,
+        tx: &Transaction,
+        txid: Txid,
+    ) -> Option<GraphTransaction> {
+        let mut best_match: Option<(GraphTransaction, usize)> = None;
+        for (transaction, transaction_match) in graph.matches.iter() {
+            match transaction_match {
+                TransactionMatch::Txid(id) if *id == txid => return Some(*transaction),
+                TransactionMatch::Inputs(outpoints) => {
+                    let spends_all = outpoints.iter().all(|outpoint| {
+                        tx.input
+                            .iter()
+                            .any(|input| input.previous_output == *outpoint)
+                    });
+                    if spends_all && !best_match.is_some_and(|(_, len)| outpoints.len() <= len) {
+                        best_match = Some((*transaction, outpoints.len()));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        best_match.map(|(transaction, _)| transaction)
+    }
+
+    fn update_confirmations(&mut self) -> Vec<WatcherEvent> {
+        let next_height = self.next_height;
+        let mut events = vec![];
+        for (graph_id, inclusions) in self.inclusions.iter_mut() {
+            for (transaction, inclusion) in inclusions.iter_mut() {
+                if !inclusion.confirmed
+                    && next_height - inclusion.height >= self.config.confirmations
+                {
+                    inclusion.confirmed = true;
+                    events.push(WatcherEvent::Confirmed {
+                        graph_id: graph_id.clone(),
+                        transaction: *transaction,
+                        txid: inclusion.txid,
+                        height: inclusion.height,
+                    });
+                }
+            }
+        }
+
+        events
+    }
+
+    fn update_timelocks(&mut self) -> Vec<WatcherEvent> {
+        let tip = match self.tip() {
+            Some(tip) => tip,
+            None => return vec![],
+        };
+
+        let mut events = vec![];
+        for (graph_id, graph) in self.graphs.iter() {
+            for timelock in graph.timelocks.iter() {
+                let key = (graph_id.clone(), timelock.transaction);
+                let matured = self.is_confirmed(graph_id, timelock.after)
+                    && self
+                        .inclusion_height(graph_id, timelock.transaction)
+                        .is_none()
+                    && self
+                        .inclusion_height(graph_id, timelock.after)
+                        .is_some_and(|height| {
+                            is_timelock_elapsed(height, timelock.num_blocks, tip)
+                        });
+                if !matured {
+                    // fires again if a reorg undid it
+                    self.matured.remove(&key);
+                } else if self.matured.insert(key) {
+                    events.push(WatcherEvent::TimelockMatured {
+                        graph_id: graph_id.clone(),
+                        transaction: timelock.transaction,
+                        height: tip,
+                    });
+                }
+            }
+        }
+
+        events
+    }
+}
+
+#[async_trait]
+impl GraphChain for Watcher {
+    async fn get_block_height(&self) -> u32 { self.next_height.saturating_sub(1) }
+
+    // Transactions are looked up by what they were recognized as, so that the ones with inputs
+    // or outputs added at broadcast are found as well
+    async fn get_tx_status(
+        &self,
+        graph_id: &str,
+        transaction: GraphTransaction,
+        _txid: &Txid,
+    ) -> Result<TxStatus, Error> {
+        let inclusion = self
+            .inclusions
+            .get(graph_id)
+            .and_then(|inclusions| inclusions.get(&transaction))
+            .filter(|inclusion| inclusion.confirmed);
+
+        Ok(TxStatus {
+            confirmed: inclusion.is_some(),
+            block_height: inclusion.map(|inclusion| inclusion.height),
+            block_hash: inclusion.and_then(|inclusion| self.blocks.get(&inclusion.height).copied()),
+            block_time: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, transaction::Version, Amount, OutPoint, ScriptBuf, TxIn, TxOut,
+    };
+
+    use super::{super::test_chain::TestChain, *};
+
+    fn transaction(previous_output: OutPoint, num_outputs: usize) -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output,
+                ..Default::default()
+            }],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(1000),
+                    script_pubkey: ScriptBuf::new(),
+                };
+                num_outputs
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watcher_rolls_back_reorged_blocks() {
+        let kick_off = transaction(OutPoint::null(), 1);
+        let kick_off_txid = kick_off.compute_txid();
+        let challenge_input = OutPoint {
+            txid: kick_off_txid,
+            vout: 0,
+        };
+        let seen = |transaction, txid, height| WatcherEvent::Seen {
+            graph_id: "graph".to_string(),
+            transaction,
+            txid,
+            height,
+        };
+
+        let mut watcher = Watcher::new(
+            WatcherConfig {
+                confirmations: 2,
+                ..WatcherConfig::for_network(Network::Regtest)
+            },
+            0,
+        );
+        watcher.watch(
+            "graph",
+            vec![
+                (
+                    GraphTransaction::KickOff1,
+                    TransactionMatch::Txid(kick_off_txid),
+                ),
+                (
+                    GraphTransaction::Challenge,
+                    TransactionMatch::Inputs(vec![challenge_input]),
+                ),
+            ],
+            vec![Timelock {
+                transaction: GraphTransaction::Challenge,
+                after: GraphTransaction::KickOff1,
+                num_blocks: 2,
+            }],
+        );
+
+        let mut chain = TestChain { blocks: vec![] };
+        chain.push(0, vec![]);
+        chain.push(0, vec![kick_off.clone()]);
+        assert_eq!(
+            watcher.poll(&chain).await.unwrap(),
+            vec![seen(GraphTransaction::KickOff1, kick_off_txid, 1)]
+        );
+
+        // The challenge can be mined in block 3, so its timelock matures at tip 2
+        chain.push(0, vec![]);
+        let events = watcher.poll(&chain).await.unwrap();
+        assert!(matches!(
+            events[..],
+            [
+                WatcherEvent::Confirmed { height: 1, .. },
+                WatcherEvent::TimelockMatured {
+                    transaction: GraphTransaction::Challenge,
+                    height: 2,
+                    ..
+                }
+            ]
+        ));
+
+        // A longer fork moves kick-off 1 to block 4
+        chain.blocks.truncate(1);
+        for _ in 1..4 {
+            chain.push(1, vec![]);
+        }
+        chain.push(1, vec![kick_off.clone()]);
+        let events = watcher.poll(&chain).await.unwrap();
+        assert_eq!(
+            events,
+            vec![
+                WatcherEvent::Reorged {
+                    graph_id: "graph".to_string(),
+                    transaction: GraphTransaction::KickOff1,
+                    txid: kick_off_txid,
+                },
+                seen(GraphTransaction::KickOff1, kick_off_txid, 4),
+            ]
+        );
+        assert!(!watcher.is_confirmed("graph", GraphTransaction::KickOff1));
+        let status = watcher
+            .get_tx_status("graph", GraphTransaction::KickOff1, &kick_off_txid)
+            .await
+            .unwrap();
+        assert!(!status.confirmed);
+
+        // The challenge is recognized by its input, whatever outputs were added
+        let challenge = transaction(challenge_input, 2);
+        chain.push(1, vec![challenge.clone()]);
+        let events = watcher.poll(&chain).await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0],
+            seen(GraphTransaction::Challenge, challenge.compute_txid(), 5)
+        );
+        assert!(matches!(
+            events[1],
+            WatcherEvent::Confirmed { height: 4, .. }
+        ));
+        assert_eq!(watcher.tip(), Some(5));
+
+        // The graph statuses see the transactions the watcher confirmed
+        assert_eq!(watcher.get_block_height().await, 5);
+        let status = watcher
+            .get_tx_status("graph", GraphTransaction::KickOff1, &kick_off_txid)
+            .await
+            .unwrap();
+        assert!(status.confirmed);
+        assert_eq!(status.block_height, Some(4));
+        assert_eq!(status.block_hash, Some(chain.blocks[4].0));
+    }
+
+    #[tokio::test]
+    async fn test_watcher_rewinds_for_late_graphs() {
+        let kick_off = transaction(OutPoint::null(), 1);
+        let kick_off_txid = kick_off.compute_txid();
+        let matches = vec![(
+            GraphTransaction::KickOff1,
+            TransactionMatch::Txid(kick_off_txid),
+        )];
+
+        let mut chain = TestChain { blocks: vec![] };
+        chain.push(0, vec![]);
+        chain.push(0, vec![kick_off.clone()]);
+        chain.push(0, vec![]);
+
+        let mut watcher = Watcher::new(WatcherConfig::for_network(Network::Regtest), 0);
+        assert!(watcher.poll(&chain).await.unwrap().is_empty());
+
+        // The graph is learned after its kick-off 1 was scanned
+        assert!(watcher.watch("graph", matches.clone(), vec![]));
+        assert!(!watcher.watch("graph", matches, vec![]));
+        assert!(watcher.poll(&chain).await.unwrap().is_empty());
+
+        watcher.rewind(1);
+        assert_eq!(watcher.tip(), Some(0));
+        let events = watcher.poll(&chain).await.unwrap();
+        assert_eq!(
+            events,
+            vec![
+                WatcherEvent::Seen {
+                    graph_id: "graph".to_string(),
+                    transaction: GraphTransaction::KickOff1,
+                    txid: kick_off_txid,
+                    height: 1,
+                },
+                WatcherEvent::Confirmed {
+                    graph_id: "graph".to_string(),
+                    transaction: GraphTransaction::KickOff1,
+                    txid: kick_off_txid,
+                    height: 1,
+                },
+            ]
+        );
+        assert_eq!(watcher.tip(), Some(2));
+
+        // Rewinding past the tip changes nothing
+        watcher.rewind(5);
+        assert!(watcher.poll(&chain).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use bitcoin::{OutPoint, Txid};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::super::super::graphs::base::GraphTransaction;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Alert {
+    /// An output the graph relies on was spent by a transaction that is not part of the graph
+    UnexpectedSpend {
+        graph_id: String,
+        outpoint: OutPoint,
+        txid: Txid,
+        height: u32,
+    },
+    /// A graph transaction differs from its pre-signed version
+    InvalidTransaction {
+        graph_id: String,
+        transaction: GraphTransaction,
+        txid: Txid,
+        height: u32,
+    },
+    /// Take 1 can be broadcast, but neither a challenge nor the graph's peg-out was seen
+    MissedChallengeWindow { graph_id: String, height: u32 },
+    /// The operator revealed Winternitz signatures of two different messages with the same key,
+    /// `transaction` in `second_txid` and an earlier graph transaction in `first_txid`
+    EquivocatingCommitment {
+        graph_id: String,
+        transaction: GraphTransaction,
+        first_txid: Txid,
+        second_txid: Txid,
+    },
+}
+
+impl Display for Alert {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Alert::UnexpectedSpend {
+                graph_id,
+                outpoint,
+                txid,
+                height,
+            } => write!(
+                f,
+                "Graph {}: output {} spent by unexpected transaction {} at height {}",
+                graph_id, outpoint, txid, height
+            ),
+            Alert::InvalidTransaction {
+                graph_id,
+                transaction,
+                txid,
+                height,
+            } => write!(
+                f,
+                "Graph {}: {:?} transaction {} at height {} does not match the pre-signed transaction",
+                graph_id, transaction, txid, height
+            ),
+            Alert::MissedChallengeWindow { graph_id, height } => write!(
+                f,
+                "Graph {}: challenge window closed at height {} without a challenge or peg-out",
+                graph_id, height
+            ),
+            Alert::EquivocatingCommitment {
+                graph_id,
+                transaction,
+                first_txid,
+                second_txid,
+            } => write!(
+                f,
+                "Graph {}: {:?} transaction {} signs a different value than {} with the same key",
+                graph_id, transaction, first_txid, second_txid
+            ),
+        }
+    }
+}
+
+/// Where the watchtower sends its alerts to
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, alert: &Alert) -> Result<(), String>;
+}
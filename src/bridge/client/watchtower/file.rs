@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+use std::path::PathBuf;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt};
+
+use super::base::{Alert, Notifier};
+
+/// Appends alerts to a file, one JSON object per line
+pub struct FileNotifier {
+    path: PathBuf,
+}
+
+impl FileNotifier {
+    pub fn new(path: PathBuf) -> Self { FileNotifier { path } }
+}
+
+#[async_trait]
+impl Notifier for FileNotifier {
+    async fn notify(&self, alert: &Alert) -> Result<(), String> {
+        let mut line = serde_json::to_string(alert).map_err(|e| e.to_string())?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| e.to_string())?;
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod base;
+pub mod file;
+pub mod stdout;
+pub mod watchtower;
+pub mod webhook;
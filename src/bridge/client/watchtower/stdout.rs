@@ -0,0 +1,13 @@
+use async_trait::async_trait;
+
+use super::base::{Alert, Notifier};
+
+pub struct StdoutNotifier;
+
+#[async_trait]
+impl Notifier for StdoutNotifier {
+    async fn notify(&self, alert: &Alert) -> Result<(), String> {
+        println!("ALERT: {}", alert);
+        Ok(())
+    }
+}
@@ -0,0 +1,514 @@
+use bitcoin::{
+    hashes::{hash160, Hash},
+    OutPoint, Transaction, Txid, Wtxid,
+};
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    super::{
+        super::graphs::{
+            base::{BaseGraph, GraphTransaction, Timelock, TransactionMatch},
+            peg_out::PegOutGraph,
+        },
+        client::BitVMClientPublicData,
+        watcher::{
+            base::BlockSource,
+            watcher::{Watcher, WatcherConfig, WatcherEvent},
+        },
+    },
+    base::{Alert, Notifier},
+};
+use crate::{bridge::transactions::base::validate_transaction, signatures::winternitz};
+
+// Once one of these is buried below the deepest reorg, no graph transaction can spend the graph's
+// outputs anymore
+const FINAL_TRANSACTIONS: [GraphTransaction; 6] = [
+    GraphTransaction::StartTimeTimeout,
+    GraphTransaction::KickOffTimeout,
+    GraphTransaction::Disprove,
+    GraphTransaction::DisproveChain,
+    GraphTransaction::Take1,
+    GraphTransaction::Take2,
+];
+
+type DigitPublicKey = [u8; 20];
+
+struct RevealedDigit {
+    graph_id: String,
+    digit: u8,
+    txid: Txid,
+}
+
+/// Read-only monitor of peg-out graphs. Checks every transaction spending an output a graph
+/// relies on against the pre-signed graph and reports what looks like operator misbehaviour.
+/// Needs no keys.
+pub struct Watchtower {
+    watcher: Watcher,
+    notifier: Box<dyn Notifier>,
+    max_reorg_depth: u32,
+    // Graph ID -> transaction -> pre-signed transaction
+    pre_signed: HashMap<String, HashMap<GraphTransaction, Transaction>>,
+    // Outpoints spent by graph transactions -> graph IDs
+    outpoints: HashMap<OutPoint, HashSet<String>>,
+    // Public key of a Winternitz signed digit -> first signature revealed for it, kept across
+    // reorgs as the signatures are public from then on
+    revealed: HashMap<DigitPublicKey, RevealedDigit>,
+    // Checked transactions -> height, until they are too deep to be scanned again after a reorg
+    checked: HashMap<Wtxid, u32>,
+    // Graphs ended by a transaction too deep to be reorged, which are no longer watched
+    finished: HashSet<String>,
+}
+
+impl Watchtower {
+    pub fn new(config: WatcherConfig, start_height: u32, notifier: Box<dyn Notifier>) -> Self {
+        Watchtower {
+            max_reorg_depth: config.max_reorg_depth,
+            watcher: Watcher::new(config, start_height),
+            notifier,
+            pre_signed: HashMap::new(),
+            outpoints: HashMap::new(),
+            revealed: HashMap::new(),
+            checked: HashMap::new(),
+            finished: HashSet::new(),
+        }
+    }
+
+    /// Start or keep watching a graph, unless it already ended
+    pub fn watch(
+        &mut self,
+        graph_id: &str,
+        matches: Vec<(GraphTransaction, TransactionMatch)>,
+        timelocks: Vec<Timelock>,
+        pre_signed_transactions: Vec<(GraphTransaction, Transaction)>,
+    ) {
+        if self.finished.contains(graph_id) {
+            return;
+        }
+
+        self.watcher.watch(graph_id, matches, timelocks);
+        self.remove_outpoints(graph_id);
+        for (_, tx) in pre_signed_transactions.iter() {
+            for input in tx.input.iter() {
+                self.outpoints
+                    .entry(input.previous_output)
+                    .or_default()
+                    .insert(graph_id.to_string());
+            }
+        }
+        self.pre_signed.insert(
+            graph_id.to_string(),
+            pre_signed_transactions.into_iter().collect(),
+        );
+    }
+
+    pub fn watch_peg_out_graph(&mut self, graph: &PegOutGraph) {
+        self.watch(
+            graph.id(),
+            graph.transaction_matches(),
+            graph.timelocks(),
+            graph.pre_signed_transactions(),
+        );
+    }
+
+    pub fn watch_graphs(&mut self, data: &BitVMClientPublicData) {
+        for graph in data.peg_out_graphs.iter() {
+            self.watch_peg_out_graph(graph);
+        }
+    }
+
+    fn unwatch(&mut self, graph_id: &str) {
+        self.watcher.unwatch(graph_id);
+        self.remove_outpoints(graph_id);
+        self.pre_signed.remove(graph_id);
+        self.revealed
+            .retain(|_, revealed| revealed.graph_id != graph_id);
+    }
+
+    fn remove_outpoints(&mut self, graph_id: &str) {
+        let transactions = match self.pre_signed.get(graph_id) {
+            Some(transactions) => transactions,
+            None => return,
+        };
+        for tx in transactions.values() {
+            for input in tx.input.iter() {
+                if let Some(graph_ids) = self.outpoints.get_mut(&input.previous_output) {
+                    graph_ids.remove(graph_id);
+                    if graph_ids.is_empty() {
+                        self.outpoints.remove(&input.previous_output);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Catch up with the tip of `source` and notify about the alerts raised on the way
+    pub async fn poll(&mut self, source: &dyn BlockSource) -> Result<Vec<Alert>, String> {
+        let outpoints = &self.outpoints;
+        let mut spends = vec![];
+        let events = self
+            .watcher
+            .poll_with(source, &mut |height: u32, tx: &Transaction| {
+                if tx
+                    .input
+                    .iter()
+                    .any(|input| outpoints.contains_key(&input.previous_output))
+                {
+                    spends.push((height, tx.clone()));
+                }
+            })
+            .await?;
+
+        let mut alerts = vec![];
+        for (height, tx) in spends.iter() {
+            alerts.extend(self.check_spend(*height, tx));
+        }
+        for event in events.iter() {
+            alerts.extend(self.check_event(event));
+        }
+        self.prune();
+
+        for alert in alerts.iter() {
+            if let Err(e) = self.notifier.notify(alert).await {
+                eprintln!("Failed to send alert: {}", e);
+            }
+        }
+
+        Ok(alerts)
+    }
+
+    fn check_spend(&mut self, height: u32, tx: &Transaction) -> Vec<Alert> {
+        // reorged blocks are scanned again
+        if self.checked.insert(tx.compute_wtxid(), height).is_some() {
+            return vec![];
+        }
+
+        let txid = tx.compute_txid();
+        let mut alerts = vec![];
+        let recognized = self.watcher.recognize(tx);
+        for (graph_id, transaction) in recognized.iter() {
+            let pre_signed_tx = self
+                .pre_signed
+                .get(graph_id)
+                .and_then(|transactions| transactions.get(transaction));
+            if pre_signed_tx.is_some_and(|pre_signed_tx| !matches_pre_signed(tx, pre_signed_tx)) {
+                alerts.push(Alert::InvalidTransaction {
+                    graph_id: graph_id.clone(),
+                    transaction: *transaction,
+                    txid,
+                    height,
+                });
+            }
+
+            // a key signing a different digit than before, in this or any other graph transaction
+            let mut equivocating_txids: Vec<Txid> = vec![];
+            for (public_key, digit) in winternitz_digits(tx) {
+                match self.revealed.get(&public_key) {
+                    Some(revealed) if revealed.digit != digit => {
+                        if !equivocating_txids.contains(&revealed.txid) {
+                            equivocating_txids.push(revealed.txid);
+                        }
+                    }
+                    Some(_) => {}
+                    None => {
+                        self.revealed.insert(
+                            public_key,
+                            RevealedDigit {
+                                graph_id: graph_id.clone(),
+                                digit,
+                                txid,
+                            },
+                        );
+                    }
+                }
+            }
+            for first_txid in equivocating_txids {
+                alerts.push(Alert::EquivocatingCommitment {
+                    graph_id: graph_id.clone(),
+                    transaction: *transaction,
+                    first_txid,
+                    second_txid: txid,
+                });
+            }
+        }
+
+        for input in tx.input.iter() {
+            let graph_ids = match self.outpoints.get(&input.previous_output) {
+                Some(graph_ids) => graph_ids,
+                None => continue,
+            };
+            for graph_id in graph_ids.iter() {
+                if !recognized.iter().any(|(id, _)| id == graph_id) {
+                    alerts.push(Alert::UnexpectedSpend {
+                        graph_id: graph_id.clone(),
+                        outpoint: input.previous_output,
+                        txid,
+                        height,
+                    });
+                }
+            }
+        }
+
+        alerts
+    }
+
+    fn check_event(&self, event: &WatcherEvent) -> Option<Alert> {
+        match event {
+            WatcherEvent::TimelockMatured {
+                graph_id,
+                transaction: GraphTransaction::Take1,
+                height,
+            } if self
+                .watcher
+                .inclusion_height(graph_id, GraphTransaction::Challenge)
+                .is_none()
+                && self
+                    .watcher
+                    .inclusion_height(graph_id, GraphTransaction::PegOut)
+                    .is_none() =>
+            {
+                Some(Alert::MissedChallengeWindow {
+                    graph_id: graph_id.clone(),
+                    height: *height,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    // Forget what can no longer be reorged: the checked transactions of blocks below the deepest
+    // reorg, and the graphs a final transaction in such a block ended
+    fn prune(&mut self) {
+        let final_height = match self.watcher.tip() {
+            Some(tip) if tip >= self.max_reorg_depth => tip - self.max_reorg_depth,
+            _ => return,
+        };
+
+        self.checked.retain(|_, height| *height > final_height);
+
+        let finished: Vec<String> = self
+            .pre_signed
+            .keys()
+            .filter(|graph_id| {
+                FINAL_TRANSACTIONS.iter().any(|transaction| {
+                    self.watcher
+                        .inclusion_height(graph_id, *transaction)
+                        .is_some_and(|height| height <= final_height)
+                })
+            })
+            .cloned()
+            .collect();
+        for graph_id in finished {
+            self.unwatch(&graph_id);
+            self.finished.insert(graph_id);
+        }
+    }
+}
+
+// `validate_transaction` expects at least the inputs and outputs of the pre-signed transaction
+fn matches_pre_signed(tx: &Transaction, pre_signed_tx: &Transaction) -> bool {
+    tx.input.len() >= pre_signed_tx.input.len()
+        && tx.output.len() >= pre_signed_tx.output.len()
+        && validate_transaction(tx, pre_signed_tx)
+}
+
+// Winternitz signed digits in the witnesses of `tx`, with the public keys they are signed with.
+// A digit signature is a 20 byte hash followed by the digit, which sets it apart from schnorr
+// signatures, scripts and control blocks.
+fn winternitz_digits(tx: &Transaction) -> Vec<(DigitPublicKey, u8)> {
+    let mut digits = vec![];
+    for input in tx.input.iter() {
+        let items: Vec<&[u8]> = input.witness.iter().collect();
+        let mut i = 0;
+        while i + 1 < items.len() {
+            match (DigitPublicKey::try_from(items[i]), digit_value(items[i + 1])) {
+                (Ok(hash), Some(digit)) => {
+                    digits.push((digit_public_key(hash, digit), digit));
+                    i += 2;
+                }
+                _ => i += 1,
+            }
+        }
+    }
+
+    digits
+}
+
+// Digits are pushed as minimal script numbers, so zero is the empty item
+fn digit_value(item: &[u8]) -> Option<u8> {
+    match item {
+        [] => Some(0),
+        [digit] if *digit as u32 <= winternitz::D => Some(*digit),
+        _ => None,
+    }
+}
+
+// The signature of `digit` hashed once for each step up to the largest digit
+fn digit_public_key(hash: [u8; 20], digit: u8) -> DigitPublicKey {
+    let mut hash = hash160::Hash::from_byte_array(hash);
+    for _ in digit as u32..winternitz::D {
+        hash = hash160::Hash::hash(&hash[..]);
+    }
+
+    hash.to_byte_array()
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, transaction::Version, Amount, Network, ScriptBuf, TxIn, TxOut, Witness,
+    };
+
+    use super::{
+        super::{super::watcher::test_chain::TestChain, stdout::StdoutNotifier},
+        *,
+    };
+
+    fn transaction(previous_output: OutPoint, witness: Vec<Vec<u8>>, value: u64) -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output,
+                witness: Witness::from_slice(&witness),
+                ..Default::default()
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(value),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        }
+    }
+
+    // Witness items of a Winternitz signature of `digits` with a fixed key
+    fn sign_digits(digits: &[u8]) -> Vec<Vec<u8>> {
+        let secret = "b138982ce17ac813d505b5b40b665d404e952800";
+        digits
+            .iter()
+            .enumerate()
+            .flat_map(|(digit_index, digit)| {
+                let signature = winternitz::digit_signature(secret, digit_index as u32, *digit);
+                let digit_item = if *digit == 0 { vec![] } else { vec![*digit] };
+                [signature.hash_bytes, digit_item]
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_watchtower_alerts() {
+        let funding = OutPoint::new(Txid::all_zeros(), 0);
+        let signature = vec![1u8; 64];
+        let kick_off_2_witness = [vec![signature.clone()], sign_digits(&[3, 7])].concat();
+        let kick_off_2 = transaction(funding, kick_off_2_witness, 1000);
+        let kick_off_2_txid = kick_off_2.compute_txid();
+        let take_1_input = OutPoint::new(kick_off_2_txid, 0);
+        let take_1 = transaction(take_1_input, vec![], 900);
+        let watch = |watchtower: &mut Watchtower| {
+            watchtower.watch(
+                "graph",
+                vec![
+                    (
+                        GraphTransaction::KickOff2,
+                        TransactionMatch::Txid(kick_off_2_txid),
+                    ),
+                    (
+                        GraphTransaction::Take1,
+                        TransactionMatch::Inputs(vec![take_1_input]),
+                    ),
+                ],
+                vec![Timelock {
+                    transaction: GraphTransaction::Take1,
+                    after: GraphTransaction::KickOff2,
+                    num_blocks: 2,
+                }],
+                vec![
+                    (GraphTransaction::KickOff2, kick_off_2.clone()),
+                    (GraphTransaction::Take1, take_1.clone()),
+                ],
+            )
+        };
+
+        let config = WatcherConfig {
+            max_reorg_depth: 4,
+            ..WatcherConfig::for_network(Network::Regtest)
+        };
+        let mut watchtower = Watchtower::new(config, 0, Box::new(StdoutNotifier));
+        watch(&mut watchtower);
+
+        let mut chain = TestChain { blocks: vec![] };
+        chain.push(0, vec![]);
+        chain.push(0, vec![kick_off_2.clone()]);
+        assert_eq!(watchtower.poll(&chain).await.unwrap(), vec![]);
+
+        // no challenge and no peg-out by the time take 1 can be broadcast
+        chain.push(0, vec![]);
+        chain.push(0, vec![]);
+        assert_eq!(
+            watchtower.poll(&chain).await.unwrap(),
+            vec![Alert::MissedChallengeWindow {
+                graph_id: "graph".to_string(),
+                height: 3,
+            }]
+        );
+
+        // a fork includes kick-off 2 with a different commitment, the txid stays the same
+        let equivocating_kick_off_2 = transaction(
+            funding,
+            [vec![signature], sign_digits(&[4, 7])].concat(),
+            1000,
+        );
+        chain.blocks.truncate(1);
+        chain.push(1, vec![equivocating_kick_off_2]);
+        let alerts = watchtower.poll(&chain).await.unwrap();
+        assert_eq!(
+            alerts,
+            vec![Alert::EquivocatingCommitment {
+                graph_id: "graph".to_string(),
+                transaction: GraphTransaction::KickOff2,
+                first_txid: kick_off_2_txid,
+                second_txid: kick_off_2_txid,
+            }]
+        );
+
+        // take 1 paying out a different amount and signing another digit with the key of kick-off
+        // 2, and an unknown spend of the operator's funding
+        let invalid_take_1 = transaction(take_1_input, sign_digits(&[3, 9]), 800);
+        let unexpected = transaction(funding, vec![], 500);
+        chain.push(1, vec![invalid_take_1.clone(), unexpected.clone()]);
+        assert_eq!(
+            watchtower.poll(&chain).await.unwrap(),
+            vec![
+                Alert::InvalidTransaction {
+                    graph_id: "graph".to_string(),
+                    transaction: GraphTransaction::Take1,
+                    txid: invalid_take_1.compute_txid(),
+                    height: 2,
+                },
+                Alert::EquivocatingCommitment {
+                    graph_id: "graph".to_string(),
+                    transaction: GraphTransaction::Take1,
+                    first_txid: kick_off_2_txid,
+                    second_txid: invalid_take_1.compute_txid(),
+                },
+                Alert::UnexpectedSpend {
+                    graph_id: "graph".to_string(),
+                    outpoint: funding,
+                    txid: unexpected.compute_txid(),
+                    height: 2,
+                },
+            ]
+        );
+        assert!(!watchtower.outpoints.is_empty());
+        assert!(!watchtower.checked.is_empty());
+
+        // once take 1 can no longer be reorged, the graph is forgotten and not watched again
+        for _ in 0..4 {
+            chain.push(1, vec![]);
+        }
+        watchtower.poll(&chain).await.unwrap();
+        watch(&mut watchtower);
+        assert!(watchtower.pre_signed.is_empty());
+        assert!(watchtower.outpoints.is_empty());
+        assert!(watchtower.revealed.is_empty());
+        assert!(watchtower.checked.is_empty());
+    }
+}
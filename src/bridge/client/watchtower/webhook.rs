@@ -0,0 +1,37 @@
+use alloy::transports::http::reqwest::{Client, Url};
+use async_trait::async_trait;
+
+use super::base::{Alert, Notifier};
+
+/// POSTs alerts as JSON to a URL
+pub struct WebhookNotifier {
+    url: Url,
+    client: Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: &str) -> Result<Self, String> {
+        Ok(WebhookNotifier {
+            url: Url::parse(url).map_err(|e| format!("Invalid webhook URL {}: {}", url, e))?,
+            client: Client::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, alert: &Alert) -> Result<(), String> {
+        let response = self
+            .client
+            .post(self.url.clone())
+            .json(alert)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("Webhook responded with {}", response.status()));
+        }
+
+        Ok(())
+    }
+}
@@ -39,7 +39,7 @@ pub fn generate_check_lock_time_tx_in(input: &Input, num_blocks: u32) -> TxIn {
     tx_in
 }
 
-#[derive(Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy, Debug)]
 pub enum ConnectorId {
     Connector0,
     Connector1,
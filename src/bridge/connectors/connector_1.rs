@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     bridge::{
+        params::BridgeParams,
         superblock::SUPERBLOCK_MESSAGE_DIGITS_LENGTH,
         transactions::signing_winternitz::{
             convert_winternitz_public_key, generate_winternitz_secret,
@@ -23,12 +24,7 @@ use crate::{
 };
 
 use super::{
-    super::{
-        constants::{NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_6_HOURS, NUM_BLOCKS_PER_DAY},
-        scripts::*,
-        transactions::base::Input,
-        utils::num_blocks_per_network,
-    },
+    super::{scripts::*, transactions::base::Input},
     base::*,
 };
 
@@ -48,6 +44,7 @@ impl Connector1 {
         network: Network,
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
+        params: &BridgeParams,
     ) -> (Self, HashMap<u8, WinternitzSecret>) {
         let leaf_index = 0;
         let winternitz_secrets = HashMap::from([(leaf_index, generate_winternitz_secret())]);
@@ -60,6 +57,7 @@ impl Connector1 {
             operator_taproot_public_key,
             n_of_n_taproot_public_key,
             &winternitz_public_keys,
+            params,
         );
 
         (this, winternitz_secrets)
@@ -70,18 +68,16 @@ impl Connector1 {
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         winternitz_public_keys: &HashMap<u8, WinternitzPublicKey>,
+        params: &BridgeParams,
     ) -> Self {
         Connector1 {
             network,
             operator_taproot_public_key: operator_taproot_public_key.clone(),
             n_of_n_taproot_public_key: n_of_n_taproot_public_key.clone(),
             winternitz_public_keys: winternitz_public_keys.clone(),
-            num_blocks_timelock_leaf_0: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
-            num_blocks_timelock_leaf_1: num_blocks_per_network(
-                network,
-                NUM_BLOCKS_PER_2_WEEKS + NUM_BLOCKS_PER_DAY,
-            ),
-            num_blocks_timelock_leaf_2: num_blocks_per_network(network, NUM_BLOCKS_PER_6_HOURS),
+            num_blocks_timelock_leaf_0: params.superblock_period,
+            num_blocks_timelock_leaf_1: params.kick_off_timeout_timelock,
+            num_blocks_timelock_leaf_2: params.start_time_window,
         }
     }
 
@@ -207,3 +203,70 @@ impl CommitmentConnector for Connector1 {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::execute_leaf;
+    use bitcoin::{
+        key::Keypair,
+        secp256k1::Message,
+        sighash::{Prevouts, SighashCache},
+        taproot::LeafVersion,
+        Amount, OutPoint, Sequence, TapLeafHash, TapSighashType, Transaction, TxOut,
+    };
+
+    fn sign_leaf(tx: &Transaction, prevouts: &[TxOut], script: &ScriptBuf, keypair: &Keypair) -> Vec<u8> {
+        let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
+        let sighash = SighashCache::new(tx)
+            .taproot_script_spend_signature_hash(0, &Prevouts::All(prevouts), leaf_hash, TapSighashType::Default)
+            .expect("Failed to construct sighash");
+        Secp256k1::new()
+            .sign_schnorr_no_aux_rand(&Message::from(sighash), keypair)
+            .serialize()
+            .to_vec()
+    }
+
+    #[test]
+    fn test_timelock_leaves() {
+        let secp = Secp256k1::new();
+        let operator_keypair = Keypair::new(&secp, &mut rand::thread_rng());
+        let n_of_n_keypair = Keypair::new(&secp, &mut rand::thread_rng());
+        let (connector, _) = Connector1::new(
+            Network::Bitcoin,
+            &operator_keypair.x_only_public_key().0,
+            &n_of_n_keypair.x_only_public_key().0,
+            &BridgeParams::for_network(Network::Bitcoin),
+        );
+
+        let input = Input { outpoint: OutPoint::null(), amount: Amount::from_sat(100_000) };
+        let prevouts = vec![TxOut {
+            value: input.amount,
+            script_pubkey: connector.generate_taproot_address().script_pubkey(),
+        }];
+        for (leaf_index, num_blocks) in [
+            (1, connector.num_blocks_timelock_leaf_1),
+            (2, connector.num_blocks_timelock_leaf_2),
+        ] {
+            let script = connector.generate_taproot_leaf_script(leaf_index);
+            let mut tx = Transaction {
+                version: bitcoin::transaction::Version::TWO,
+                lock_time: bitcoin::locktime::absolute::LockTime::ZERO,
+                input: vec![connector.generate_taproot_leaf_tx_in(leaf_index, &input)],
+                output: vec![TxOut { value: Amount::from_sat(90_000), script_pubkey: generate_burn_script() }],
+            };
+
+            let signature = sign_leaf(&tx, &prevouts, &script, &n_of_n_keypair);
+            assert!(execute_leaf(&tx, 0, &prevouts, &script, vec![signature]).success);
+
+            // Signed by the operator instead of the n-of-n
+            let signature = sign_leaf(&tx, &prevouts, &script, &operator_keypair);
+            assert!(!execute_leaf(&tx, 0, &prevouts, &script, vec![signature]).success);
+
+            // Spent a block too early
+            tx.input[0].sequence = Sequence(num_blocks - 1);
+            let signature = sign_leaf(&tx, &prevouts, &script, &n_of_n_keypair);
+            assert!(!execute_leaf(&tx, 0, &prevouts, &script, vec![signature]).success);
+        }
+    }
+}
@@ -2,11 +2,7 @@ use bitcoin::{Address, Network, PublicKey, ScriptBuf, TxIn};
 use serde::{Deserialize, Serialize};
 
 use super::{
-    super::{
-        super::bridge::{constants::NUM_BLOCKS_PER_2_WEEKS, utils::num_blocks_per_network},
-        scripts::*,
-        transactions::base::Input,
-    },
+    super::{params::BridgeParams, scripts::*, transactions::base::Input},
     base::*,
 };
 
@@ -18,11 +14,11 @@ pub struct Connector4 {
 }
 
 impl Connector4 {
-    pub fn new(network: Network, operator_public_key: &PublicKey) -> Self {
+    pub fn new(network: Network, operator_public_key: &PublicKey, params: &BridgeParams) -> Self {
         Connector4 {
             network,
             operator_public_key: operator_public_key.clone(),
-            num_blocks_timelock: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            num_blocks_timelock: params.take_2_timelock,
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use bitcoin::{
     key::Secp256k1,
     taproot::{TaprootBuilder, TaprootSpendInfo},
@@ -5,10 +7,16 @@ use bitcoin::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::{signatures::winternitz::checksig_verify, treepp::script};
+
 use super::{
     super::{
-        constants::NUM_BLOCKS_PER_3_DAYS, scripts::*, transactions::base::Input,
-        utils::num_blocks_per_network,
+        params::BridgeParams,
+        scripts::*,
+        transactions::{
+            base::Input,
+            signing_winternitz::{convert_winternitz_public_key, WinternitzPublicKey},
+        },
     },
     base::*,
 };
@@ -18,14 +26,23 @@ pub struct ConnectorB {
     pub network: Network,
     pub n_of_n_taproot_public_key: XOnlyPublicKey,
     pub num_blocks_timelock_1: u32,
+    pub winternitz_public_keys: HashMap<u8, WinternitzPublicKey>, // Chunk index -> WinternitzPublicKey
 }
 
 impl ConnectorB {
-    pub fn new(network: Network, n_of_n_taproot_public_key: &XOnlyPublicKey) -> Self {
+    /// `winternitz_public_keys` are the keys of connector C the operator commits to the outputs of
+    /// the assert program's chunks with
+    pub fn new(
+        network: Network,
+        n_of_n_taproot_public_key: &XOnlyPublicKey,
+        params: &BridgeParams,
+        winternitz_public_keys: &HashMap<u8, WinternitzPublicKey>,
+    ) -> Self {
         ConnectorB {
             network,
             n_of_n_taproot_public_key: n_of_n_taproot_public_key.clone(),
-            num_blocks_timelock_1: num_blocks_per_network(network, NUM_BLOCKS_PER_3_DAYS),
+            num_blocks_timelock_1: params.assert_timelock,
+            winternitz_public_keys: winternitz_public_keys.clone(),
         }
     }
 
@@ -36,10 +53,28 @@ impl ConnectorB {
     fn generate_taproot_leaf_0_tx_in(&self, input: &Input) -> TxIn { generate_default_tx_in(input) }
 
     fn generate_taproot_leaf_1_script(&self) -> ScriptBuf {
-        generate_timelock_taproot_script(
-            &self.n_of_n_taproot_public_key,
-            self.num_blocks_timelock_1,
-        )
+        let winternitz_public_keys = (0..self.winternitz_public_keys.len() as u8)
+            .map(|chunk_index| {
+                convert_winternitz_public_key(&self.winternitz_public_keys[&chunk_index])
+            })
+            .collect::<Vec<_>>();
+
+        script! {
+            // The commitments to the outputs of the assert program's chunks, the first chunk on top
+            for winternitz_public_key in winternitz_public_keys.iter() {
+                { checksig_verify(winternitz_public_key) }
+                // Drop the signed message
+                for _ in 0..10 {
+                    OP_2DROP
+                }
+            }
+            { self.num_blocks_timelock_1 }
+            OP_CSV
+            OP_DROP
+            { self.n_of_n_taproot_public_key }
+            OP_CHECKSIG
+        }
+        .compile()
     }
 
     fn generate_taproot_leaf_1_tx_in(&self, input: &Input) -> TxIn {
@@ -96,3 +131,94 @@ impl TaprootConnector for ConnectorB {
 impl BaseConnector for ConnectorB {
     fn id(&self) -> ConnectorId { ConnectorId::ConnectorB }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        bridge::{
+            assert_program::{assert_chunks, assert_program_stacks, sign_assert_stacks},
+            transactions::signing_winternitz::{
+                winternitz_public_key_from_secret, WinternitzSecret,
+            },
+        },
+        execute_leaf,
+    };
+    use bitcoin::{
+        key::Keypair,
+        secp256k1::Message,
+        sighash::{Prevouts, SighashCache},
+        taproot::LeafVersion,
+        Amount, OutPoint, TapLeafHash, TapSighashType, Transaction, TxOut,
+    };
+
+    fn get_test_winternitz_secrets(seed: u8) -> HashMap<u8, WinternitzSecret> {
+        (0..assert_chunks().len() as u8)
+            .map(|chunk_index| {
+                let secret = format!(
+                    "b138982ce17ac813d505b5b40b665d404e95{:02x}{:02x}",
+                    seed, chunk_index
+                );
+                (chunk_index, secret)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_assert_leaf_checks_commitment() {
+        let secp = Secp256k1::new();
+        let n_of_n_keypair = Keypair::new(&secp, &mut rand::thread_rng());
+        let winternitz_secrets = get_test_winternitz_secrets(0);
+        let winternitz_public_keys = winternitz_secrets
+            .iter()
+            .map(|(k, v)| (*k, winternitz_public_key_from_secret(v)))
+            .collect();
+        let connector = ConnectorB::new(
+            Network::Bitcoin,
+            &n_of_n_keypair.x_only_public_key().0,
+            &BridgeParams::for_network(Network::Bitcoin),
+            &winternitz_public_keys,
+        );
+
+        let leaf_index = 1;
+        let script = connector.generate_taproot_leaf_script(leaf_index);
+        let input = Input { outpoint: OutPoint::null(), amount: Amount::from_sat(100_000) };
+        let prevouts = vec![TxOut {
+            value: input.amount,
+            script_pubkey: connector.generate_taproot_address().script_pubkey(),
+        }];
+        let tx = Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::locktime::absolute::LockTime::ZERO,
+            input: vec![connector.generate_taproot_leaf_tx_in(leaf_index, &input)],
+            output: vec![TxOut {
+                value: Amount::from_sat(90_000),
+                script_pubkey: generate_burn_script(),
+            }],
+        };
+        let leaf_hash = TapLeafHash::from_script(&script, LeafVersion::TapScript);
+        let sighash = SighashCache::new(&tx)
+            .taproot_script_spend_signature_hash(
+                0,
+                &Prevouts::All(&prevouts),
+                leaf_hash,
+                TapSighashType::Default,
+            )
+            .expect("Failed to construct sighash");
+        let signature = secp
+            .sign_schnorr_no_aux_rand(&Message::from(sighash), &n_of_n_keypair)
+            .serialize()
+            .to_vec();
+
+        let stacks = assert_program_stacks();
+        let other_winternitz_secrets = get_test_winternitz_secrets(1);
+        for (secrets, valid) in [(winternitz_secrets, true), (other_winternitz_secrets, false)] {
+            // The n-of-n signature at the bottom, the commitment to the first chunk on top
+            let mut witness = vec![signature.clone()];
+            for chunk_signature in sign_assert_stacks(&secrets, &stacks).into_iter().rev() {
+                witness.extend(chunk_signature);
+            }
+            assert_eq!(execute_leaf(&tx, 0, &prevouts, &script, witness).success, valid);
+        }
+    }
+}
@@ -1,73 +1,166 @@
-use crate::treepp::script;
+use std::collections::HashMap;
+
+use crate::execute_leaf;
 use bitcoin::{
-    hashes::{ripemd160, Hash},
     key::Secp256k1,
     taproot::{TaprootBuilder, TaprootSpendInfo},
-    Address, Network, ScriptBuf, TxIn, XOnlyPublicKey,
+    Address, Network, ScriptBuf, Transaction, TxIn, TxOut, XOnlyPublicKey,
 };
-use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 
-use super::{super::transactions::base::Input, base::*};
-
-// Specialized for assert leaves currently.
-pub type LockScript = fn(index: u32) -> ScriptBuf;
-pub type UnlockWitnessData = Vec<u8>;
-pub type UnlockWitness = fn(index: u32) -> UnlockWitnessData;
-
-pub struct DisproveLeaf {
-    pub lock: LockScript,
-    pub unlock: UnlockWitness,
-}
+use super::{
+    super::{
+        assert_program::{
+            assert_chunks, assert_program_stacks, encode_stack_element, AssertCommitment,
+        },
+        transactions::{
+            base::Input,
+            signing_winternitz::{
+                convert_winternitz_public_key, generate_winternitz_secret,
+                winternitz_public_key_from_secret, WinternitzPublicKey, WinternitzSecret,
+            },
+        },
+    },
+    base::*,
+};
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct ConnectorC {
     pub network: Network,
     pub operator_taproot_public_key: XOnlyPublicKey,
-    lock_scripts: Vec<ScriptBuf>,
-    unlock_witnesses: Vec<UnlockWitnessData>,
+    pub winternitz_public_keys: HashMap<u8, WinternitzPublicKey>, // Chunk index -> WinternitzPublicKey
 }
 
 impl ConnectorC {
-    pub fn new(network: Network, operator_taproot_public_key: &XOnlyPublicKey) -> Self {
-        let leaves = generate_assert_leaves();
+    pub fn new(
+        network: Network,
+        operator_taproot_public_key: &XOnlyPublicKey,
+    ) -> (Self, HashMap<u8, WinternitzSecret>) {
+        let winternitz_secrets: HashMap<u8, WinternitzSecret> = (0..assert_chunks().len())
+            .map(|chunk_index| (chunk_index as u8, generate_winternitz_secret()))
+            .collect();
+        let winternitz_public_keys = winternitz_secrets
+            .iter()
+            .map(|(k, v)| (*k, winternitz_public_key_from_secret(v)))
+            .collect();
 
+        let this = Self::new_for_validation(
+            network,
+            operator_taproot_public_key,
+            &winternitz_public_keys,
+        );
+
+        (this, winternitz_secrets)
+    }
+
+    pub fn new_for_validation(
+        network: Network,
+        operator_taproot_public_key: &XOnlyPublicKey,
+        winternitz_public_keys: &HashMap<u8, WinternitzPublicKey>,
+    ) -> Self {
         ConnectorC {
             network,
-            operator_taproot_public_key: operator_taproot_public_key.clone(),
-            lock_scripts: leaves.0,
-            unlock_witnesses: leaves.1,
+            operator_taproot_public_key: *operator_taproot_public_key,
+            winternitz_public_keys: winternitz_public_keys.clone(),
         }
     }
 
-    pub fn generate_taproot_leaf_script_witness(&self, leaf_index: u32) -> UnlockWitnessData {
-        let index = leaf_index.to_usize().unwrap();
-        if index >= self.unlock_witnesses.len() {
+    fn leaf_witness(
+        &self,
+        leaf_index: u32,
+        stacks: &[Vec<u32>],
+        commitment: &AssertCommitment,
+    ) -> Vec<Vec<u8>> {
+        let index = leaf_index as usize;
+        if index >= self.winternitz_public_keys.len() {
             panic!("Invalid leaf index.")
         }
-        self.unlock_witnesses[index].clone()
+        if commitment.len() != stacks.len() {
+            panic!("Invalid assert commitment.")
+        }
+
+        let mut unlock_data: Vec<Vec<u8>> = Vec::new();
+        if index > 0 {
+            // The chunk starts from the output of the previous one, computed honestly. It only
+            // passes the input check if the operator committed to the same stack.
+            for element in stacks[index - 1].iter() {
+                unlock_data.push(encode_stack_element(*element));
+            }
+            unlock_data.extend(commitment[index - 1].iter().cloned());
+        }
+        unlock_data.extend(commitment[index].iter().cloned());
+
+        unlock_data
+    }
+
+    /// Witness disproving the operator's `commitment` to the output of chunk `leaf_index`
+    pub fn generate_taproot_leaf_script_witness(
+        &self,
+        leaf_index: u32,
+        commitment: &AssertCommitment,
+    ) -> Vec<Vec<u8>> {
+        self.leaf_witness(leaf_index, &assert_program_stacks(), commitment)
+    }
+
+    /// Finds the first leaf disproving the operator's `commitment` when spent as `input_index` of
+    /// `tx`. None if the operator committed to the outputs of the assert program.
+    pub fn find_disprove_leaf(
+        &self,
+        tx: &Transaction,
+        input_index: usize,
+        prev_outs: &[TxOut],
+        commitment: &AssertCommitment,
+    ) -> Option<u32> {
+        let stacks = assert_program_stacks();
+        if commitment.len() != stacks.len() {
+            return None;
+        }
+
+        (0..stacks.len() as u32).find(|leaf_index| {
+            execute_leaf(
+                tx,
+                input_index,
+                prev_outs,
+                &self.generate_taproot_leaf_script(*leaf_index),
+                self.leaf_witness(*leaf_index, &stacks, commitment),
+            )
+            .success
+        })
     }
 }
 
 impl TaprootConnector for ConnectorC {
+    // Leaf[i]: spendable if the operator's commitments to the input and output stacks of chunk i
+    // of the assert program do not match
     fn generate_taproot_leaf_script(&self, leaf_index: u32) -> ScriptBuf {
-        let index = leaf_index.to_usize().unwrap();
-        if index >= self.lock_scripts.len() {
+        let index = leaf_index as usize;
+        if index >= self.winternitz_public_keys.len() {
             panic!("Invalid leaf index.")
         }
-        self.lock_scripts[index].clone()
+        let chunk = &assert_chunks()[index];
+
+        // The first chunk has no input, so its input key is not used
+        let input_key_index = index.saturating_sub(1) as u8;
+        let input_key =
+            convert_winternitz_public_key(&self.winternitz_public_keys[&input_key_index]);
+        let output_key_index = index as u8;
+        let output_key =
+            convert_winternitz_public_key(&self.winternitz_public_keys[&output_key_index]);
+
+        chunk.disprove_leaf(&input_key, &output_key)
     }
 
     fn generate_taproot_leaf_tx_in(&self, leaf_index: u32, input: &Input) -> TxIn {
-        let index = leaf_index.to_usize().unwrap();
-        if index >= self.lock_scripts.len() {
+        let index = leaf_index as usize;
+        if index >= self.winternitz_public_keys.len() {
             panic!("Invalid leaf index.")
         }
         generate_default_tx_in(input)
     }
 
     fn generate_taproot_spend_info(&self) -> TaprootSpendInfo {
-        let script_weights = self.lock_scripts.iter().map(|script| (1, script.clone()));
+        let script_weights = (0..self.winternitz_public_keys.len() as u32)
+            .map(|leaf_index| (1, self.generate_taproot_leaf_script(leaf_index)));
 
         TaprootBuilder::with_huffman_tree(script_weights)
             .expect("Unable to add assert leaves")
@@ -83,36 +176,70 @@ impl TaprootConnector for ConnectorC {
     }
 }
 
-// Leaf[i] for some i in 1,2,…1000: spendable by multisig of OPK and VPK[1…N] plus the condition that f_{i}(z_{i-1})!=z_i
-fn disprove_leaf() -> DisproveLeaf {
-    DisproveLeaf {
-        lock: |index| {
-            script! {
-                OP_RIPEMD160
-                { ripemd160::Hash::hash(format!("SECRET_{}", index).as_bytes()).as_byte_array().to_vec() }
-                OP_EQUALVERIFY
-                { index }
-                OP_DROP
-                OP_TRUE
-            }.compile()
-        },
-        unlock: |index| format!("SECRET_{}", index).as_bytes().to_vec(),
-    }
+impl BaseConnector for ConnectorC {
+    fn id(&self) -> ConnectorId { ConnectorId::ConnectorC }
 }
 
-fn generate_assert_leaves() -> (Vec<ScriptBuf>, Vec<UnlockWitnessData>) {
-    // TODO: Scripts with n_of_n_public_key and one of the commitments disprove leaves in each leaf (Winternitz signatures)
-    let mut locks = Vec::with_capacity(1000);
-    let mut unlocks = Vec::with_capacity(1000);
-    let locking_template = disprove_leaf().lock;
-    let unlocking_template = disprove_leaf().unlock;
-    for i in 0..1000 {
-        locks.push(locking_template(i));
-        unlocks.push(unlocking_template(i));
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bridge::assert_program::sign_assert_stacks;
+    use bitcoin::{key::Keypair, Amount, OutPoint};
+
+    fn get_test_winternitz_secrets() -> HashMap<u8, WinternitzSecret> {
+        (0..assert_chunks().len())
+            .map(|chunk_index| {
+                (
+                    chunk_index as u8,
+                    format!("b138982ce17ac813d505b5b40b665d404e9528{:02x}", chunk_index),
+                )
+            })
+            .collect()
     }
-    (locks, unlocks)
-}
 
-impl BaseConnector for ConnectorC {
-    fn id(&self) -> ConnectorId { ConnectorId::ConnectorC }
+    #[test]
+    fn test_find_disprove_leaf() {
+        let secp = Secp256k1::new();
+        let operator_keypair = Keypair::new(&secp, &mut rand::thread_rng());
+        let winternitz_secrets = get_test_winternitz_secrets();
+        let winternitz_public_keys = winternitz_secrets
+            .iter()
+            .map(|(k, v)| (*k, winternitz_public_key_from_secret(v)))
+            .collect();
+        let connector = ConnectorC::new_for_validation(
+            Network::Bitcoin,
+            &operator_keypair.x_only_public_key().0,
+            &winternitz_public_keys,
+        );
+
+        let input = Input { outpoint: OutPoint::null(), amount: Amount::from_sat(100_000) };
+        let prevouts = vec![TxOut {
+            value: input.amount,
+            script_pubkey: connector.generate_taproot_address().script_pubkey(),
+        }];
+        let tx = Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::locktime::absolute::LockTime::ZERO,
+            input: vec![connector.generate_taproot_leaf_tx_in(0, &input)],
+            output: vec![],
+        };
+
+        let stacks = assert_program_stacks();
+        assert!(stacks.len() > 1);
+
+        // An operator committing to the program's outputs cannot be disproven
+        let commitment = sign_assert_stacks(&winternitz_secrets, &stacks);
+        assert_eq!(connector.find_disprove_leaf(&tx, 0, &prevouts, &commitment), None);
+
+        // A wrong output is disproven by the chunk computing it
+        for chunk_index in 0..stacks.len() {
+            let mut wrong_stacks = stacks.clone();
+            wrong_stacks[chunk_index][0] ^= 1;
+            let commitment = sign_assert_stacks(&winternitz_secrets, &wrong_stacks);
+            assert_eq!(
+                connector.find_disprove_leaf(&tx, 0, &prevouts, &commitment),
+                Some(chunk_index as u32)
+            );
+        }
+    }
 }
@@ -1,7 +1,4 @@
-use crate::{
-    bridge::{constants::NUM_BLOCKS_PER_2_WEEKS, utils::num_blocks_per_network},
-    treepp::script,
-};
+use crate::{bridge::params::BridgeParams, treepp::script};
 use bitcoin::{
     key::Secp256k1,
     taproot::{TaprootBuilder, TaprootSpendInfo},
@@ -29,13 +26,14 @@ impl ConnectorZ {
         evm_address: &str,
         depositor_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
+        params: &BridgeParams,
     ) -> Self {
         ConnectorZ {
             network,
             depositor_taproot_public_key: depositor_taproot_public_key.clone(),
             n_of_n_taproot_public_key: n_of_n_taproot_public_key.clone(),
             evm_address: evm_address.to_string(),
-            num_blocks_timelock_0: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            num_blocks_timelock_0: params.peg_in_refund_timelock,
         }
     }
 
@@ -5,8 +5,11 @@ use bitcoin::{
 };
 use musig2::{secp::Point, KeyAggContext};
 
+use super::super::params::BridgeParams;
+
 pub trait BaseContext {
     fn network(&self) -> Network;
+    fn params(&self) -> &BridgeParams;
     fn secp(&self) -> &Secp256k1<All>;
     fn n_of_n_public_keys(&self) -> &Vec<PublicKey>;
     fn n_of_n_public_key(&self) -> &PublicKey;
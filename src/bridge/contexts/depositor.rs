@@ -1,16 +1,20 @@
-use bitcoin::{
-    key::{Keypair, Secp256k1},
-    secp256k1::All,
-    Network, PublicKey, XOnlyPublicKey,
-};
+use bitcoin::{key::Secp256k1, secp256k1::All, Network, PublicKey, XOnlyPublicKey};
+use std::sync::Arc;
 
-use super::base::{generate_keys_from_secret, generate_n_of_n_public_key, BaseContext};
+use super::{
+    super::{
+        params::BridgeParams,
+        signer::{local::LocalSigner, Signer},
+    },
+    base::{generate_n_of_n_public_key, BaseContext},
+};
 
 pub struct DepositorContext {
     pub network: Network,
+    pub params: BridgeParams,
     pub secp: Secp256k1<All>,
 
-    pub depositor_keypair: Keypair,
+    pub depositor_signer: Arc<dyn Signer>,
     pub depositor_public_key: PublicKey,
     pub depositor_taproot_public_key: XOnlyPublicKey,
 
@@ -21,6 +25,7 @@ pub struct DepositorContext {
 
 impl BaseContext for DepositorContext {
     fn network(&self) -> Network { self.network }
+    fn params(&self) -> &BridgeParams { &self.params }
     fn secp(&self) -> &Secp256k1<All> { &self.secp }
     fn n_of_n_public_keys(&self) -> &Vec<PublicKey> { &self.n_of_n_public_keys }
     fn n_of_n_public_key(&self) -> &PublicKey { &self.n_of_n_public_key }
@@ -33,15 +38,35 @@ impl DepositorContext {
         depositor_secret: &str,
         n_of_n_public_keys: &Vec<PublicKey>,
     ) -> Self {
-        let (secp, keypair, public_key) = generate_keys_from_secret(network, depositor_secret);
+        let signer = LocalSigner::from_secret(depositor_secret).unwrap();
+        Self::new_with_signer(network, Arc::new(signer), n_of_n_public_keys)
+    }
+
+    pub fn new_with_signer(
+        network: Network,
+        signer: Arc<dyn Signer>,
+        n_of_n_public_keys: &Vec<PublicKey>,
+    ) -> Self {
+        let params = BridgeParams::for_network(network);
+        Self::new_with_params(network, params, signer, n_of_n_public_keys)
+    }
+
+    pub fn new_with_params(
+        network: Network,
+        params: BridgeParams,
+        signer: Arc<dyn Signer>,
+        n_of_n_public_keys: &Vec<PublicKey>,
+    ) -> Self {
+        let public_key = signer.public_key();
         let (n_of_n_public_key, n_of_n_taproot_public_key) =
             generate_n_of_n_public_key(n_of_n_public_keys);
 
         DepositorContext {
             network,
-            secp,
+            params,
+            secp: Secp256k1::new(),
 
-            depositor_keypair: keypair,
+            depositor_signer: signer,
             depositor_public_key: public_key,
             depositor_taproot_public_key: XOnlyPublicKey::from(public_key),
 
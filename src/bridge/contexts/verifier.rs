@@ -1,16 +1,20 @@
-use bitcoin::{
-    key::{Keypair, Secp256k1},
-    secp256k1::All,
-    Network, PublicKey, XOnlyPublicKey,
-};
+use bitcoin::{key::Secp256k1, secp256k1::All, Network, PublicKey, XOnlyPublicKey};
+use std::sync::Arc;
 
-use super::base::{generate_keys_from_secret, generate_n_of_n_public_key, BaseContext};
+use super::{
+    super::{
+        params::BridgeParams,
+        signer::{local::LocalSigner, Signer},
+    },
+    base::{generate_n_of_n_public_key, BaseContext},
+};
 
 pub struct VerifierContext {
     pub network: Network,
+    pub params: BridgeParams,
     pub secp: Secp256k1<All>,
 
-    pub verifier_keypair: Keypair,
+    pub verifier_signer: Arc<dyn Signer>,
     pub verifier_public_key: PublicKey,
 
     pub n_of_n_public_keys: Vec<PublicKey>,
@@ -20,6 +24,7 @@ pub struct VerifierContext {
 
 impl BaseContext for VerifierContext {
     fn network(&self) -> Network { self.network }
+    fn params(&self) -> &BridgeParams { &self.params }
     fn secp(&self) -> &Secp256k1<All> { &self.secp }
     fn n_of_n_public_keys(&self) -> &Vec<PublicKey> { &self.n_of_n_public_keys }
     fn n_of_n_public_key(&self) -> &PublicKey { &self.n_of_n_public_key }
@@ -32,15 +37,35 @@ impl VerifierContext {
         verifier_secret: &str,
         n_of_n_public_keys: &Vec<PublicKey>,
     ) -> Self {
-        let (secp, keypair, public_key) = generate_keys_from_secret(network, verifier_secret);
+        let signer = LocalSigner::from_secret(verifier_secret).unwrap();
+        Self::new_with_signer(network, Arc::new(signer), n_of_n_public_keys)
+    }
+
+    pub fn new_with_signer(
+        network: Network,
+        signer: Arc<dyn Signer>,
+        n_of_n_public_keys: &Vec<PublicKey>,
+    ) -> Self {
+        let params = BridgeParams::for_network(network);
+        Self::new_with_params(network, params, signer, n_of_n_public_keys)
+    }
+
+    pub fn new_with_params(
+        network: Network,
+        params: BridgeParams,
+        signer: Arc<dyn Signer>,
+        n_of_n_public_keys: &Vec<PublicKey>,
+    ) -> Self {
+        let public_key = signer.public_key();
         let (n_of_n_public_key, n_of_n_taproot_public_key) =
             generate_n_of_n_public_key(n_of_n_public_keys);
 
         VerifierContext {
             network,
-            secp,
+            params,
+            secp: Secp256k1::new(),
 
-            verifier_keypair: keypair,
+            verifier_signer: signer,
             verifier_public_key: public_key,
 
             n_of_n_public_keys: n_of_n_public_keys.clone(),
@@ -1,6 +1,8 @@
-use bitcoin::{Network, Txid};
+use async_trait::async_trait;
+use bitcoin::{Network, OutPoint, Transaction, Txid};
 use esplora_client::{AsyncClient, Error, TxStatus};
 use futures::future::join_all;
+use serde::{Deserialize, Serialize};
 
 pub const GRAPH_VERSION: &str = "0.1";
 
@@ -8,7 +10,7 @@ pub const INITIAL_AMOUNT: u64 = 2 << 16; // 131072
 pub const FEE_AMOUNT: u64 = 10_000;
 // TODO: Either repalce this with a routine that calculates 'min relay fee' for
 // every tx, or define local constants with appropriate values in every tx file
-// (see MIN_RELAY_FEE_AMOUNT in kick_off_2.rs).
+// (see min_relay_fee_amount in BridgeParams).
 pub const MESSAGE_COMMITMENT_FEE_AMOUNT: u64 = 27_182;
 pub const DUST_AMOUNT: u64 = 10_000;
 pub const ONE_HUNDRED: u64 = 2 << 26; // 134217728
@@ -38,6 +40,54 @@ pub trait BaseGraph {
     fn id(&self) -> &String;
 }
 
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub enum GraphTransaction {
+    PegInDeposit,
+    PegInRefund,
+    PegInConfirm,
+    PegOut,
+    PegOutConfirm,
+    KickOff1,
+    StartTime,
+    StartTimeTimeout,
+    KickOff2,
+    KickOffTimeout,
+    Challenge,
+    Assert,
+    Disprove,
+    DisproveChain,
+    Take1,
+    Take2,
+}
+
+/// How a graph transaction is recognized on chain. Transactions that get inputs or outputs
+/// added when they are broadcast, e.g. challenge, only have a known txid afterwards, so they
+/// are recognized by the inputs they are pre-signed with.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum TransactionMatch {
+    Txid(Txid),
+    Inputs(Vec<OutPoint>),
+}
+
+/// `transaction` can be broadcast once `after` is `num_blocks` deep
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Timelock {
+    pub transaction: GraphTransaction,
+    pub after: GraphTransaction,
+    pub num_blocks: u32,
+}
+
+/// Whether a relative timelock of `num_blocks` on an output confirmed at `block_height` has
+/// elapsed, i.e. a spend can be mined in the block after `blockchain_height`. Under BIP68 the
+/// earliest such block is at `block_height + num_blocks`.
+pub fn is_timelock_elapsed(block_height: u32, num_blocks: u32, blockchain_height: u32) -> bool {
+    block_height + num_blocks <= blockchain_height + 1
+}
+
+pub fn match_inputs(tx: &Transaction) -> TransactionMatch {
+    TransactionMatch::Inputs(tx.input.iter().map(|input| input.previous_output).collect())
+}
+
 pub async fn get_block_height(client: &AsyncClient) -> u32 {
     let blockchain_height_result = client.get_height().await;
     if blockchain_height_result.is_err() {
@@ -50,6 +100,35 @@ pub async fn get_block_height(client: &AsyncClient) -> u32 {
     blockchain_height_result.unwrap()
 }
 
+/// Where the graph statuses read the chain from: esplora, or the blocks a watcher scanned
+#[async_trait]
+pub trait GraphChain: Send + Sync {
+    async fn get_block_height(&self) -> u32;
+
+    /// Status of `transaction` of the graph `graph_id`, which has `txid` if it is broadcast the
+    /// way it was pre-signed
+    async fn get_tx_status(
+        &self,
+        graph_id: &str,
+        transaction: GraphTransaction,
+        txid: &Txid,
+    ) -> Result<TxStatus, Error>;
+}
+
+#[async_trait]
+impl GraphChain for AsyncClient {
+    async fn get_block_height(&self) -> u32 { get_block_height(self).await }
+
+    async fn get_tx_status(
+        &self,
+        _graph_id: &str,
+        _transaction: GraphTransaction,
+        txid: &Txid,
+    ) -> Result<TxStatus, Error> {
+        AsyncClient::get_tx_status(self, txid).await
+    }
+}
+
 pub async fn verify_if_not_mined(client: &AsyncClient, txid: Txid) {
     let tx_status = client.get_tx_status(&txid).await;
     if tx_status.as_ref().is_ok_and(|status| status.confirmed) {
@@ -76,3 +155,16 @@ pub async fn get_tx_statuses(
 ) -> Vec<Result<TxStatus, Error>> {
     join_all(txids.iter().map(|txid| client.get_tx_status(txid))).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_timelock_elapsed() {
+        assert!(!is_timelock_elapsed(100, 6, 100));
+        assert!(!is_timelock_elapsed(100, 6, 104));
+        assert!(is_timelock_elapsed(100, 6, 105));
+        assert!(is_timelock_elapsed(100, 6, 200));
+    }
+}
@@ -0,0 +1,493 @@
+use bitcoin::{script::Instruction, Amount, ScriptBuf, Txid, XOnlyPublicKey};
+use esplora_client::AsyncClient;
+use std::str::FromStr;
+
+use super::{
+    super::{connectors::base::ConnectorId, transactions::pre_signed::PreSignedTransaction},
+    base::{get_tx_statuses, GraphTransaction, TransactionMatch},
+    state::{GraphState, StateMachine},
+};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    Dot,
+    Mermaid,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "dot" => Ok(ExportFormat::Dot),
+            "mermaid" => Ok(ExportFormat::Mermaid),
+            _ => Err(format!("Unknown export format: {}", format)),
+        }
+    }
+}
+
+/// Input `input_index` of `transaction` spends the `connector` output of `from`
+pub struct ConnectorSpend {
+    pub transaction: GraphTransaction,
+    pub input_index: usize,
+    pub from: GraphTransaction,
+    pub connector: ConnectorId,
+    // None for P2WSH connectors and for leaves that are only chosen at broadcast
+    pub leaf: Option<u32>,
+}
+
+impl ConnectorSpend {
+    pub fn new(
+        transaction: GraphTransaction,
+        input_index: usize,
+        from: GraphTransaction,
+        connector: ConnectorId,
+        leaf: Option<u32>,
+    ) -> Self {
+        ConnectorSpend {
+            transaction,
+            input_index,
+            from,
+            connector,
+            leaf,
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct ExportNode {
+    pub transaction: GraphTransaction,
+    // None until broadcast for transactions that get inputs or outputs added at broadcast
+    pub txid: Option<Txid>,
+    pub confirmation_height: Option<u32>,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct ExportEdge {
+    pub from: GraphTransaction,
+    pub vout: u32,
+    pub to: GraphTransaction,
+    pub connector: ConnectorId,
+    pub leaf: Option<u32>,
+    pub amount: Amount,
+    // relative timelock in blocks
+    pub timelock: Option<u32>,
+    // parties whose keys the spending script checks, e.g. n-of-n
+    pub signers: Vec<String>,
+    // leaf script of a taproot connector or witness script of a P2WSH connector
+    pub script: ScriptBuf,
+    // the script in assembly with the signers' keys named
+    pub description: String,
+}
+
+/// Transactions of a graph as nodes and the connectors between them as edges
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct GraphExport {
+    pub name: String,
+    pub state: String,
+    pub nodes: Vec<ExportNode>,
+    pub edges: Vec<ExportEdge>,
+}
+
+impl GraphExport {
+    pub fn new<S: GraphState>(
+        name: &str,
+        state: &StateMachine<S>,
+        transaction_matches: &[(GraphTransaction, TransactionMatch)],
+        edges: Vec<ExportEdge>,
+    ) -> Self {
+        let nodes = transaction_matches
+            .iter()
+            .map(|(transaction, transaction_match)| {
+                let transition = state
+                    .history()
+                    .iter()
+                    .find(|transition| transition.transaction == *transaction);
+                ExportNode {
+                    transaction: *transaction,
+                    txid: match (transition, transaction_match) {
+                        (Some(transition), _) => Some(transition.txid),
+                        (None, TransactionMatch::Txid(txid)) => Some(*txid),
+                        (None, TransactionMatch::Inputs(_)) => None,
+                    },
+                    confirmation_height: transition.map(|transition| transition.height),
+                }
+            })
+            .collect();
+
+        GraphExport {
+            name: name.to_string(),
+            state: format!("{:?}", state.state()),
+            nodes,
+            edges,
+        }
+    }
+
+    /// Replaces the confirmation status recorded in the graph state with the live one
+    pub async fn update_confirmations(&mut self, client: &AsyncClient) {
+        let nodes: Vec<&mut ExportNode> = self
+            .nodes
+            .iter_mut()
+            .filter(|node| node.txid.is_some())
+            .collect();
+        let txids = nodes.iter().map(|node| node.txid.unwrap()).collect();
+        let statuses = get_tx_statuses(client, &txids).await;
+
+        for (node, status) in nodes.into_iter().zip(statuses) {
+            match status {
+                Ok(status) if status.confirmed => node.confirmation_height = status.block_height,
+                Ok(_) => node.confirmation_height = None,
+                Err(e) => eprintln!(
+                    "Failed to get status of {:?} {}: {}",
+                    node.transaction,
+                    node.txid.unwrap(),
+                    e
+                ),
+            }
+        }
+    }
+
+    pub fn render(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Dot => self.to_dot(),
+            ExportFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec![
+            format!("digraph \"{}\" {{", self.name),
+            format!("    label=\"{}\\nstate: {}\";", self.name, self.state),
+            "    labelloc=t;".to_string(),
+            "    node [shape=box];".to_string(),
+        ];
+        for node in &self.nodes {
+            let style = match node.confirmation_height {
+                Some(_) => ", style=filled, fillcolor=palegreen",
+                None => "",
+            };
+            lines.push(format!(
+                "    \"{:?}\" [label=\"{}\"{}];",
+                node.transaction,
+                node_label(node).join("\\n"),
+                style
+            ));
+        }
+        for edge in &self.edges {
+            let style = match edge.timelock {
+                Some(_) => ", style=dashed",
+                None => "",
+            };
+            lines.push(format!(
+                "    \"{:?}\" -> \"{:?}\" [label=\"{}\"{}];",
+                edge.from,
+                edge.to,
+                edge_label(edge).join("\\n"),
+                style
+            ));
+        }
+        lines.push("}".to_string());
+
+        lines.join("\n") + "\n"
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut lines = vec![
+            "---".to_string(),
+            format!("title: {} ({})", self.name, self.state),
+            "---".to_string(),
+            "flowchart TD".to_string(),
+        ];
+        for node in &self.nodes {
+            lines.push(format!(
+                "    {:?}[\"{}\"]",
+                node.transaction,
+                node_label(node).join("<br/>")
+            ));
+        }
+        for edge in &self.edges {
+            let arrow = match edge.timelock {
+                Some(_) => "-.->",
+                None => "-->",
+            };
+            lines.push(format!(
+                "    {:?} {}|\"{}\"| {:?}",
+                edge.from,
+                arrow,
+                edge_label(edge).join("<br/>"),
+                edge.to
+            ));
+        }
+        let confirmed: Vec<String> = self
+            .nodes
+            .iter()
+            .filter(|node| node.confirmation_height.is_some())
+            .map(|node| format!("{:?}", node.transaction))
+            .collect();
+        if !confirmed.is_empty() {
+            lines.push("    classDef confirmed fill:#9f9".to_string());
+            lines.push(format!("    class {} confirmed", confirmed.join(",")));
+        }
+
+        lines.join("\n") + "\n"
+    }
+}
+
+pub fn connector_edge(
+    spend: &ConnectorSpend,
+    transaction: &dyn PreSignedTransaction,
+    signers: &[(&str, XOnlyPublicKey)],
+) -> ExportEdge {
+    let input = &transaction.tx().input[spend.input_index];
+    let script = &transaction.prev_scripts()[spend.input_index];
+
+    ExportEdge {
+        from: spend.from,
+        vout: input.previous_output.vout,
+        to: spend.transaction,
+        connector: spend.connector,
+        leaf: spend.leaf,
+        amount: transaction.prev_outs()[spend.input_index].value,
+        timelock: input
+            .sequence
+            .is_height_locked()
+            .then_some(input.sequence.0 & 0xFFFF),
+        signers: signers
+            .iter()
+            .filter(|(_, public_key)| pushes_public_key(script, public_key))
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        script: script.clone(),
+        description: describe_script(script, signers),
+    }
+}
+
+// Matches both x-only and compressed public keys
+fn pushes_public_key(script: &ScriptBuf, public_key: &XOnlyPublicKey) -> bool {
+    script.instructions().any(|instruction| match instruction {
+        Ok(Instruction::PushBytes(bytes)) => is_public_key(bytes.as_bytes(), public_key),
+        _ => false,
+    })
+}
+
+fn is_public_key(bytes: &[u8], public_key: &XOnlyPublicKey) -> bool {
+    let public_key = public_key.serialize();
+    bytes == public_key || (bytes.len() == 33 && bytes[1..] == public_key)
+}
+
+// Scripts committing to Winternitz signatures run into thousands of opcodes, so only their
+// beginning is shown
+const MAX_DESCRIBED_INSTRUCTIONS: usize = 12;
+
+fn describe_script(script: &ScriptBuf, signers: &[(&str, XOnlyPublicKey)]) -> String {
+    let mut words = vec![];
+    for instruction in script.instructions() {
+        if words.len() == MAX_DESCRIBED_INSTRUCTIONS {
+            words.push(format!("... ({} bytes)", script.len()));
+            break;
+        }
+        let bytes = match instruction {
+            Ok(Instruction::Op(opcode)) => {
+                words.push(opcode.to_string());
+                continue;
+            }
+            Ok(Instruction::PushBytes(bytes)) => bytes.as_bytes(),
+            Err(_) => {
+                words.push("[invalid]".to_string());
+                break;
+            }
+        };
+        words.push(
+            match signers
+                .iter()
+                .find(|(_, public_key)| is_public_key(bytes, public_key))
+            {
+                Some((name, _)) => format!("[{}]", name),
+                None if bytes.len() <= 4 => format!("0x{}", hex::encode(bytes)),
+                None => format!("[{} bytes]", bytes.len()),
+            },
+        );
+    }
+
+    words.join(" ")
+}
+
+fn node_label(node: &ExportNode) -> Vec<String> {
+    vec![
+        format!("{:?}", node.transaction),
+        match node.txid {
+            Some(txid) => txid.to_string(),
+            None => "txid known at broadcast".to_string(),
+        },
+        match node.confirmation_height {
+            Some(height) => format!("confirmed at {}", height),
+            None => "unconfirmed".to_string(),
+        },
+    ]
+}
+
+fn edge_label(edge: &ExportEdge) -> Vec<String> {
+    let mut label = vec![match edge.leaf {
+        Some(leaf) => format!("{:?}:{} leaf {}", edge.connector, edge.vout, leaf),
+        None => format!("{:?}:{}", edge.connector, edge.vout),
+    }];
+    label.push(format!("{} sats", edge.amount.to_sat()));
+    if let Some(timelock) = edge.timelock {
+        label.push(format!("after {} blocks", timelock));
+    }
+    label.push(edge.description.clone());
+
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::{
+        hashes::Hash,
+        key::{Keypair, Secp256k1},
+        opcodes::all::{OP_CHECKSIG, OP_CSV, OP_DROP, OP_DUP},
+    };
+
+    fn x_only_public_key(secret: u8) -> XOnlyPublicKey {
+        Keypair::from_seckey_slice(&Secp256k1::new(), &[secret; 32])
+            .unwrap()
+            .x_only_public_key()
+            .0
+    }
+
+    fn graph_export() -> GraphExport {
+        let n_of_n = x_only_public_key(1);
+        let script = ScriptBuf::builder()
+            .push_x_only_key(&n_of_n)
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+
+        GraphExport {
+            name: "graph".to_string(),
+            state: "KickedOff1".to_string(),
+            nodes: vec![
+                ExportNode {
+                    transaction: GraphTransaction::KickOff1,
+                    txid: Some(Txid::all_zeros()),
+                    confirmation_height: Some(100),
+                },
+                ExportNode {
+                    transaction: GraphTransaction::Challenge,
+                    txid: None,
+                    confirmation_height: None,
+                },
+                ExportNode {
+                    transaction: GraphTransaction::KickOff2,
+                    txid: Some(Txid::all_zeros()),
+                    confirmation_height: None,
+                },
+            ],
+            edges: vec![
+                ExportEdge {
+                    from: GraphTransaction::KickOff1,
+                    vout: 0,
+                    to: GraphTransaction::Challenge,
+                    connector: ConnectorId::ConnectorA,
+                    leaf: Some(1),
+                    amount: Amount::from_sat(10_000),
+                    timelock: None,
+                    signers: vec!["n-of-n".to_string()],
+                    description: describe_script(&script, &[("n-of-n", n_of_n)]),
+                    script,
+                },
+                ExportEdge {
+                    from: GraphTransaction::KickOff1,
+                    vout: 1,
+                    to: GraphTransaction::KickOff2,
+                    connector: ConnectorId::Connector1,
+                    leaf: Some(0),
+                    amount: Amount::from_sat(100_000),
+                    timelock: Some(6),
+                    signers: vec![],
+                    script: ScriptBuf::new(),
+                    description: "OP_TRUE".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_describe_script() {
+        let n_of_n = x_only_public_key(1);
+        let operator = x_only_public_key(2);
+        let script = ScriptBuf::builder()
+            .push_int(144)
+            .push_opcode(OP_CSV)
+            .push_opcode(OP_DROP)
+            .push_x_only_key(&operator)
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+        assert_eq!(
+            describe_script(&script, &[("n-of-n", n_of_n)]),
+            "0x9000 OP_CSV OP_DROP [32 bytes] OP_CHECKSIG"
+        );
+        assert_eq!(
+            describe_script(&script, &[("n-of-n", n_of_n), ("operator", operator)]),
+            "0x9000 OP_CSV OP_DROP [operator] OP_CHECKSIG"
+        );
+
+        let mut builder = ScriptBuf::builder();
+        for _ in 0..20 {
+            builder = builder.push_opcode(OP_DUP);
+        }
+        let description = describe_script(&builder.into_script(), &[]);
+        assert!(description.starts_with("OP_DUP OP_DUP"));
+        assert!(description.ends_with(" OP_DUP ... (20 bytes)"));
+        assert_eq!(description.matches("OP_DUP").count(), 12);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = graph_export().to_dot();
+
+        assert!(dot.starts_with("digraph \"graph\" {\n"));
+        assert!(dot.contains(&format!(
+            "    \"KickOff1\" [label=\"KickOff1\\n{}\\nconfirmed at 100\", {}];\n",
+            Txid::all_zeros(),
+            "style=filled, fillcolor=palegreen"
+        )));
+        assert!(dot.contains(concat!(
+            "    \"Challenge\" ",
+            "[label=\"Challenge\\ntxid known at broadcast\\nunconfirmed\"];\n"
+        )));
+        assert!(dot.contains(concat!(
+            "    \"KickOff1\" -> \"Challenge\" ",
+            "[label=\"ConnectorA:0 leaf 1\\n10000 sats\\n[n-of-n] OP_CHECKSIG\"];\n"
+        )));
+        assert!(dot.contains(concat!(
+            "    \"KickOff1\" -> \"KickOff2\" ",
+            "[label=\"Connector1:1 leaf 0\\n100000 sats\\nafter 6 blocks\\nOP_TRUE\", ",
+            "style=dashed];\n"
+        )));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_to_mermaid() {
+        let mermaid = graph_export().to_mermaid();
+
+        assert!(mermaid.starts_with("---\ntitle: graph (KickedOff1)\n---\nflowchart TD\n"));
+        assert!(mermaid.contains(
+            "    Challenge[\"Challenge<br/>txid known at broadcast<br/>unconfirmed\"]\n"
+        ));
+        assert!(mermaid.contains(concat!(
+            "    KickOff1 -.->",
+            "|\"Connector1:1 leaf 0<br/>100000 sats<br/>after 6 blocks<br/>OP_TRUE\"| ",
+            "KickOff2\n"
+        )));
+        assert!(mermaid.ends_with("    class KickOff1 confirmed\n"));
+    }
+
+    #[test]
+    fn test_export_format_from_str() {
+        assert_eq!("dot".parse::<ExportFormat>(), Ok(ExportFormat::Dot));
+        assert_eq!("mermaid".parse::<ExportFormat>(), Ok(ExportFormat::Mermaid));
+        assert!("svg".parse::<ExportFormat>().is_err());
+    }
+}
@@ -1,3 +1,5 @@
 pub mod base;
+pub mod export;
 pub mod peg_in;
 pub mod peg_out;
+pub mod state;
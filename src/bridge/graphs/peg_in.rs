@@ -3,7 +3,7 @@ use bitcoin::{
     Network, OutPoint, PublicKey, Txid, XOnlyPublicKey,
 };
 use esplora_client::{AsyncClient, Error, TxStatus};
-use musig2::SecNonce;
+use musig2::PubNonce;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -14,17 +14,24 @@ use std::{
 
 use super::{
     super::{
+        connectors::base::ConnectorId,
         contexts::{depositor::DepositorContext, verifier::VerifierContext},
-        graphs::base::get_block_height,
+        params::BridgeParams,
         transactions::{
             base::{validate_transaction, verify_public_nonces_for_tx, BaseTransaction, Input},
             peg_in_confirm::PegInConfirmTransaction,
             peg_in_deposit::PegInDepositTransaction,
             peg_in_refund::PegInRefundTransaction,
             pre_signed::PreSignedTransaction,
+            pre_signed_musig2::{has_musig2_nonces, has_musig2_signatures},
         },
     },
-    base::{get_tx_statuses, verify_if_not_mined, verify_tx_result, BaseGraph, GRAPH_VERSION},
+    base::{
+        is_timelock_elapsed, verify_if_not_mined, verify_tx_result, BaseGraph, GraphChain,
+        GraphTransaction, Timelock, TransactionMatch, GRAPH_VERSION,
+    },
+    export::{connector_edge, ConnectorSpend, GraphExport},
+    state::{GraphOutcome, PegInState, StateMachine},
 };
 
 pub enum PegInDepositorStatus {
@@ -61,6 +68,7 @@ pub enum PegInVerifierStatus {
     PegInWait,     // no action required, wait
     PegInPresign,  // should presign peg-in confirm
     PegInComplete, // peg-in complete
+    PegInFailed,   // peg-in refunded
 }
 
 impl Display for PegInVerifierStatus {
@@ -71,6 +79,7 @@ impl Display for PegInVerifierStatus {
                 write!(f, "Signature required. Presign peg-in confirm transaction?")
             }
             PegInVerifierStatus::PegInComplete => write!(f, "Peg-in complete. Done."),
+            PegInVerifierStatus::PegInFailed => write!(f, "Peg-in refunded. Done."),
         }
     }
 }
@@ -79,6 +88,7 @@ pub enum PegInOperatorStatus {
     PegInWait,             // peg-in not yet complete, no action required yet, wait
     PegInConfirmAvailable, // should execute peg-in confirm
     PegInComplete,         // peg-in complete
+    PegInFailed,           // peg-in refunded
 }
 
 impl Display for PegInOperatorStatus {
@@ -94,6 +104,7 @@ impl Display for PegInOperatorStatus {
                 )
             }
             PegInOperatorStatus::PegInComplete => write!(f, "Peg-in complete. Done."),
+            PegInOperatorStatus::PegInFailed => write!(f, "Peg-in refunded. Done."),
         }
     }
 }
@@ -102,8 +113,13 @@ impl Display for PegInOperatorStatus {
 pub struct PegInGraph {
     version: String,
     network: Network,
+    params: BridgeParams,
     id: String,
 
+    // observed by this client only, kept with its private data rather than shared with peers
+    #[serde(skip)]
+    state: StateMachine<PegInState>,
+
     pub peg_in_deposit_transaction: PegInDepositTransaction,
     pub peg_in_refund_transaction: PegInRefundTransaction,
     pub peg_in_confirm_transaction: PegInConfirmTransaction,
@@ -124,9 +140,13 @@ impl BaseGraph for PegInGraph {
 }
 
 impl PegInGraph {
-    pub fn new(context: &DepositorContext, deposit_input: Input, evm_address: &str) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        deposit_input: Input,
+        evm_address: &str,
+    ) -> Result<Self, String> {
         let peg_in_deposit_transaction =
-            PegInDepositTransaction::new(context, evm_address, deposit_input);
+            PegInDepositTransaction::new(context, evm_address, deposit_input)?;
         let peg_in_deposit_txid = peg_in_deposit_transaction.tx().compute_txid();
 
         let peg_in_refund_vout_0: usize = 0;
@@ -140,7 +160,7 @@ impl PegInGraph {
                 },
                 amount: peg_in_deposit_transaction.tx().output[peg_in_refund_vout_0].value,
             },
-        );
+        )?;
 
         let peg_in_confirm_vout_0: usize = 0;
         let peg_in_confirm_transaction = PegInConfirmTransaction::new(
@@ -153,27 +173,32 @@ impl PegInGraph {
                 },
                 amount: peg_in_deposit_transaction.tx().output[peg_in_confirm_vout_0].value,
             },
-        );
+        )?;
 
-        PegInGraph {
+        Ok(PegInGraph {
             version: GRAPH_VERSION.to_string(),
             network: context.network,
-            id: generate_id(&peg_in_deposit_transaction),
+            params: context.params.clone(),
+            id: generate_id(&peg_in_deposit_transaction, &context.params),
             peg_in_deposit_transaction,
             peg_in_refund_transaction,
             peg_in_confirm_transaction,
+            state: StateMachine::default(),
             n_of_n_presigned: false,
             n_of_n_public_key: context.n_of_n_public_key,
             n_of_n_taproot_public_key: context.n_of_n_taproot_public_key,
             depositor_public_key: context.depositor_public_key,
             depositor_taproot_public_key: context.depositor_taproot_public_key,
             depositor_evm_address: evm_address.to_string(),
-        }
+        })
     }
 
+    pub fn params(&self) -> &BridgeParams { &self.params }
+
     pub fn new_for_validation(&self) -> Self {
         let peg_in_deposit_transaction = PegInDepositTransaction::new_for_validation(
             self.network,
+            &self.params,
             &self.depositor_public_key,
             &self.depositor_taproot_public_key,
             &self.n_of_n_taproot_public_key,
@@ -188,6 +213,7 @@ impl PegInGraph {
         let peg_in_refund_vout_0: usize = 0;
         let peg_in_refund_transaction = PegInRefundTransaction::new_for_validation(
             self.network,
+            &self.params,
             &self.depositor_public_key,
             &self.depositor_taproot_public_key,
             &self.n_of_n_taproot_public_key,
@@ -204,6 +230,7 @@ impl PegInGraph {
         let peg_in_confirm_vout_0: usize = 0;
         let peg_in_confirm_transaction = PegInConfirmTransaction::new_for_validation(
             self.network,
+            &self.params,
             &self.depositor_taproot_public_key,
             &self.n_of_n_taproot_public_key,
             &self.depositor_evm_address,
@@ -219,10 +246,12 @@ impl PegInGraph {
         PegInGraph {
             version: GRAPH_VERSION.to_string(),
             network: self.network,
-            id: generate_id(&peg_in_deposit_transaction),
+            params: self.params.clone(),
+            id: generate_id(&peg_in_deposit_transaction, &self.params),
             peg_in_deposit_transaction,
             peg_in_refund_transaction,
             peg_in_confirm_transaction,
+            state: StateMachine::default(),
             n_of_n_presigned: false,
             n_of_n_public_key: self.n_of_n_public_key,
             n_of_n_taproot_public_key: self.n_of_n_taproot_public_key,
@@ -235,37 +264,117 @@ impl PegInGraph {
     pub fn push_nonces(
         &mut self,
         context: &VerifierContext,
-    ) -> HashMap<Txid, HashMap<usize, SecNonce>> {
-        let mut secret_nonces = HashMap::new();
+    ) -> Result<HashMap<Txid, HashMap<usize, PubNonce>>, String> {
+        let mut nonces = HashMap::new();
 
-        secret_nonces.insert(
+        nonces.insert(
             self.peg_in_confirm_transaction.tx().compute_txid(),
-            self.peg_in_confirm_transaction.push_nonces(context),
+            self.peg_in_confirm_transaction.push_nonces(context)?,
         );
 
-        secret_nonces
+        Ok(nonces)
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
-        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
-    ) {
+        nonces: &HashMap<Txid, HashMap<usize, PubNonce>>,
+    ) -> Result<(), String> {
         self.peg_in_confirm_transaction.pre_sign(
             context,
-            &secret_nonces[&self.peg_in_confirm_transaction.tx().compute_txid()],
-        );
+            &nonces[&self.peg_in_confirm_transaction.tx().compute_txid()],
+        )?;
 
         self.n_of_n_presigned = true; // TODO: set to true after collecting all n of n signatures
+
+        Ok(())
+    }
+
+    /// Whether all MuSig2 transactions have nonces of `public_keys`
+    pub fn has_nonces(&self, public_keys: &[PublicKey]) -> bool {
+        has_musig2_nonces(&self.peg_in_confirm_transaction, public_keys)
+    }
+
+    /// Whether all MuSig2 transactions are pre-signed by `public_keys`
+    pub fn has_signatures(&self, public_keys: &[PublicKey]) -> bool {
+        has_musig2_signatures(&self.peg_in_confirm_transaction, public_keys)
     }
 
     pub fn peg_in_confirm_transaction_ref(&self) -> &PegInConfirmTransaction {
         &self.peg_in_confirm_transaction
     }
 
-    pub async fn verifier_status(&self, client: &AsyncClient) -> PegInVerifierStatus {
+    pub fn state(&self) -> &StateMachine<PegInState> { &self.state }
+
+    pub fn state_mut(&mut self) -> &mut StateMachine<PegInState> { &mut self.state }
+
+    pub fn transaction_matches(&self) -> Vec<(GraphTransaction, TransactionMatch)> {
+        vec![
+            (
+                GraphTransaction::PegInDeposit,
+                TransactionMatch::Txid(self.peg_in_deposit_transaction.tx().compute_txid()),
+            ),
+            (
+                GraphTransaction::PegInRefund,
+                TransactionMatch::Txid(self.peg_in_refund_transaction.tx().compute_txid()),
+            ),
+            (
+                GraphTransaction::PegInConfirm,
+                TransactionMatch::Txid(self.peg_in_confirm_transaction.tx().compute_txid()),
+            ),
+        ]
+    }
+
+    pub fn timelocks(&self) -> Vec<Timelock> {
+        vec![Timelock {
+            transaction: GraphTransaction::PegInRefund,
+            after: GraphTransaction::PegInDeposit,
+            num_blocks: self.peg_in_refund_transaction.num_blocks_timelock_0(),
+        }]
+    }
+
+    pub fn export(&self) -> GraphExport {
+        let signers = [
+            ("n-of-n", self.n_of_n_taproot_public_key),
+            ("depositor", self.depositor_taproot_public_key),
+        ];
+        let edges = vec![
+            connector_edge(
+                &ConnectorSpend::new(
+                    GraphTransaction::PegInRefund,
+                    0,
+                    GraphTransaction::PegInDeposit,
+                    ConnectorId::ConnectorZ,
+                    Some(0),
+                ),
+                &self.peg_in_refund_transaction,
+                &signers,
+            ),
+            connector_edge(
+                &ConnectorSpend::new(
+                    GraphTransaction::PegInConfirm,
+                    0,
+                    GraphTransaction::PegInDeposit,
+                    ConnectorId::ConnectorZ,
+                    Some(1),
+                ),
+                &self.peg_in_confirm_transaction,
+                &signers,
+            ),
+        ];
+
+        GraphExport::new(&self.id, &self.state, &self.transaction_matches(), edges)
+    }
+
+    pub async fn verifier_status(&self, chain: &dyn GraphChain) -> PegInVerifierStatus {
+        match self.state.outcome() {
+            Some(GraphOutcome::Complete) => return PegInVerifierStatus::PegInComplete,
+            Some(GraphOutcome::Failed) => return PegInVerifierStatus::PegInFailed,
+            None => {}
+        }
+
         let (peg_in_deposit_status, peg_in_confirm_status, _) =
-            Self::get_peg_in_statuses(self, client).await;
+            Self::get_peg_in_statuses(self, chain).await;
 
         if peg_in_deposit_status.is_ok_and(|status| status.confirmed) {
             if peg_in_confirm_status.is_ok_and(|status| status.confirmed) {
@@ -286,9 +395,15 @@ impl PegInGraph {
         }
     }
 
-    pub async fn operator_status(&self, client: &AsyncClient) -> PegInOperatorStatus {
+    pub async fn operator_status(&self, chain: &dyn GraphChain) -> PegInOperatorStatus {
+        match self.state.outcome() {
+            Some(GraphOutcome::Complete) => return PegInOperatorStatus::PegInComplete,
+            Some(GraphOutcome::Failed) => return PegInOperatorStatus::PegInFailed,
+            None => {}
+        }
+
         let (peg_in_deposit_status, peg_in_confirm_status, _) =
-            Self::get_peg_in_statuses(self, client).await;
+            Self::get_peg_in_statuses(self, chain).await;
 
         if peg_in_deposit_status.is_ok_and(|status| status.confirmed) {
             if peg_in_confirm_status.is_ok_and(|status| status.confirmed) {
@@ -332,8 +447,11 @@ impl PegInGraph {
                     .unwrap()
                     .block_height
                     .is_some_and(|block_height| {
-                        block_height + self.peg_in_refund_transaction.num_blocks_timelock_0()
-                            <= blockchain_height
+                        is_timelock_elapsed(
+                            block_height,
+                            self.peg_in_refund_transaction.num_blocks_timelock_0(),
+                            blockchain_height,
+                        )
                     })
                 {
                     if peg_in_refund_status
@@ -357,28 +475,21 @@ impl PegInGraph {
         }
     }
 
-    pub async fn depositor_status(&self, client: &AsyncClient) -> PegInDepositorStatus {
-        let tx_statuses = get_tx_statuses(
-            client,
-            &vec![
-                self.peg_in_deposit_transaction.tx().compute_txid(),
-                self.peg_in_confirm_transaction.tx().compute_txid(),
-                self.peg_in_refund_transaction.tx().compute_txid(),
-            ],
-        )
-        .await;
+    pub async fn depositor_status(&self, chain: &dyn GraphChain) -> PegInDepositorStatus {
+        match self.state.outcome() {
+            Some(GraphOutcome::Complete) => return PegInDepositorStatus::PegInConfirmComplete,
+            Some(GraphOutcome::Failed) => return PegInDepositorStatus::PegInRefundComplete,
+            None => {}
+        }
+
         let (peg_in_deposit_status, peg_in_confirm_status, peg_in_refund_status) =
-            match &tx_statuses[..] {
-                [stat1, stat2, stat3, ..] => (stat1, stat2, stat3),
-                // make sure vectors size are the same or will panic
-                _ => unreachable!(),
-            };
-        let blockchain_height = get_block_height(client).await;
+            Self::get_peg_in_statuses(self, chain).await;
+        let blockchain_height = chain.get_block_height().await;
 
         self.interpret_operator_status(
-            peg_in_deposit_status,
-            peg_in_confirm_status,
-            peg_in_refund_status,
+            &peg_in_deposit_status,
+            &peg_in_confirm_status,
+            &peg_in_refund_status,
             blockchain_height,
         )
     }
@@ -440,22 +551,34 @@ impl PegInGraph {
 
     async fn get_peg_in_statuses(
         &self,
-        client: &AsyncClient,
+        chain: &dyn GraphChain,
     ) -> (
         Result<TxStatus, Error>,
         Result<TxStatus, Error>,
         Result<TxStatus, Error>,
     ) {
-        let peg_in_deposit_status = client
-            .get_tx_status(&self.peg_in_deposit_transaction.tx().compute_txid())
+        let peg_in_deposit_status = chain
+            .get_tx_status(
+                &self.id,
+                GraphTransaction::PegInDeposit,
+                &self.peg_in_deposit_transaction.tx().compute_txid(),
+            )
             .await;
 
-        let peg_in_confirm_status = client
-            .get_tx_status(&self.peg_in_confirm_transaction.tx().compute_txid())
+        let peg_in_confirm_status = chain
+            .get_tx_status(
+                &self.id,
+                GraphTransaction::PegInConfirm,
+                &self.peg_in_confirm_transaction.tx().compute_txid(),
+            )
             .await;
 
-        let peg_in_refund_status = client
-            .get_tx_status(&self.peg_in_refund_transaction.tx().compute_txid())
+        let peg_in_refund_status = chain
+            .get_tx_status(
+                &self.id,
+                GraphTransaction::PegInRefund,
+                &self.peg_in_refund_transaction.tx().compute_txid(),
+            )
             .await;
 
         return (
@@ -468,6 +591,9 @@ impl PegInGraph {
     pub fn validate(&self) -> bool {
         let mut ret_val = true;
         let peg_in_graph = self.new_for_validation();
+        if self.id != peg_in_graph.id {
+            ret_val = false;
+        }
         if !validate_transaction(
             self.peg_in_deposit_transaction.tx(),
             peg_in_graph.peg_in_deposit_transaction.tx(),
@@ -500,10 +626,13 @@ impl PegInGraph {
     }
 }
 
-pub fn generate_id(peg_in_deposit_transaction: &PegInDepositTransaction) -> String {
+pub fn generate_id(
+    peg_in_deposit_transaction: &PegInDepositTransaction,
+    params: &BridgeParams,
+) -> String {
     let mut hasher = Sha256::new();
 
-    hasher.update(peg_in_deposit_transaction.tx().compute_txid().to_string());
+    hasher.update(peg_in_deposit_transaction.tx().compute_txid().to_string() + &params.id());
 
     hasher.finalize().to_hex_string(Upper)
 }
@@ -1,11 +1,10 @@
 use bitcoin::{
     hashes::Hash,
     hex::{Case::Upper, DisplayHex},
-    key::Keypair,
-    Amount, Network, OutPoint, PublicKey, ScriptBuf, Txid, XOnlyPublicKey,
+    Network, OutPoint, PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
 };
 use esplora_client::{AsyncClient, Error, TxStatus};
-use musig2::SecNonce;
+use musig2::PubNonce;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -16,30 +15,39 @@ use std::{
 
 use super::{
     super::{
+        assert_program::{assert_program_stacks, sign_assert_stacks, AssertCommitment},
         client::chain::chain::PegOutEvent,
         connectors::{
             base::{BaseConnector, ConnectorId},
             connector_1::Connector1,
             connector_2::Connector2,
             connector_6::Connector6,
+            connector_b::ConnectorB,
+            connector_c::ConnectorC,
         },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
-        superblock::SuperblockMessage,
+        params::BridgeParams,
+        signer::Signer,
+        superblock::{split_superblock_message, SuperblockMessage, SuperblockPeriodScan},
         transactions::{
-            assert::AssertTransaction,
+            assert::{extract_assert_commitment, AssertTransaction},
             base::{
                 validate_transaction, verify_public_nonces_for_tx, BaseTransaction, Input,
                 InputWithScript,
             },
             challenge::ChallengeTransaction,
+            challenge_crowdfunding::ChallengeCrowdfunding,
             disprove::DisproveTransaction,
             disprove_chain::DisproveChainTransaction,
             kick_off_1::KickOff1Transaction,
-            kick_off_2::KickOff2Transaction,
+            kick_off_2::{extract_superblock_message, KickOff2Transaction},
             kick_off_timeout::KickOffTimeoutTransaction,
             peg_out::PegOutTransaction,
             peg_out_confirm::PegOutConfirmTransaction,
             pre_signed::PreSignedTransaction,
+            pre_signed_musig2::{
+                has_musig2_nonces, has_musig2_signatures, PreSignedMusig2Transaction,
+            },
             signing_winternitz::WinternitzSecret,
             start_time::StartTimeTransaction,
             start_time_timeout::StartTimeTimeoutTransaction,
@@ -48,8 +56,13 @@ use super::{
         },
         utils::get_start_time_block,
     },
-    base::{get_block_height, verify_if_not_mined, verify_tx_result, BaseGraph, GRAPH_VERSION},
+    base::{
+        get_block_height, is_timelock_elapsed, match_inputs, verify_if_not_mined, verify_tx_result,
+        BaseGraph, GraphChain, GraphTransaction, Timelock, TransactionMatch, GRAPH_VERSION,
+    },
+    export::{connector_edge, ConnectorSpend, GraphExport},
     peg_in::PegInGraph,
+    state::{GraphOutcome, PegOutState, StateMachine},
 };
 
 pub enum PegOutWithdrawerStatus {
@@ -190,9 +203,12 @@ impl Display for PegOutOperatorStatus {
 pub struct PegOutGraph {
     version: String,
     network: Network,
+    params: BridgeParams,
     id: String,
 
-    // state: State,
+    // observed by this client only, kept with its private data rather than shared with peers
+    #[serde(skip)]
+    state: StateMachine<PegOutState>,
     // n_of_n_pre_signing_state: PreSigningState,
     n_of_n_presigned: bool,
     n_of_n_public_key: PublicKey,
@@ -207,6 +223,8 @@ pub struct PegOutGraph {
     connector_1: Connector1,
     connector_2: Connector2,
     connector_6: Connector6,
+    connector_b: ConnectorB,
+    connector_c: ConnectorC,
 
     peg_out_confirm_transaction: PegOutConfirmTransaction,
     assert_transaction: AssertTransaction,
@@ -239,7 +257,7 @@ impl PegOutGraph {
         context: &OperatorContext,
         peg_in_graph: &PegInGraph,
         peg_out_confirm_input: Input,
-    ) -> (Self, HashMap<ConnectorId, HashMap<u8, WinternitzSecret>>) {
+    ) -> Result<(Self, HashMap<ConnectorId, HashMap<u8, WinternitzSecret>>), String> {
         let peg_in_confirm_transaction = peg_in_graph.peg_in_confirm_transaction_ref();
         let peg_in_confirm_txid = peg_in_confirm_transaction.tx().compute_txid();
 
@@ -247,6 +265,7 @@ impl PegOutGraph {
             context.network,
             &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
+            &context.params,
         );
         let (connector_2, connector_2_winternitz_secrets) = Connector2::new(
             context.network,
@@ -255,15 +274,24 @@ impl PegOutGraph {
         );
         let (connector_6, connector_6_winternitz_secrets) =
             Connector6::new(context.network, &context.operator_taproot_public_key);
+        let (connector_c, connector_c_winternitz_secrets) =
+            ConnectorC::new(context.network, &context.operator_taproot_public_key);
+        let connector_b = ConnectorB::new(
+            context.network,
+            &context.n_of_n_taproot_public_key,
+            &context.params,
+            &connector_c.winternitz_public_keys,
+        );
 
         let winternitz_secrets = HashMap::from([
             (connector_1.id(), connector_1_winternitz_secrets),
             (connector_2.id(), connector_2_winternitz_secrets),
             (connector_6.id(), connector_6_winternitz_secrets),
+            (connector_c.id(), connector_c_winternitz_secrets),
         ]);
 
         let peg_out_confirm_transaction =
-            PegOutConfirmTransaction::new(context, &connector_6, peg_out_confirm_input);
+            PegOutConfirmTransaction::new(context, &connector_6, peg_out_confirm_input)?;
         let peg_out_confirm_txid = peg_out_confirm_transaction.tx().compute_txid();
 
         let kick_off_1_vout_0 = 0;
@@ -321,6 +349,7 @@ impl PegOutGraph {
         let kick_off_2_transaction = KickOff2Transaction::new(
             context,
             &connector_1,
+            &connector_b,
             Input {
                 outpoint: OutPoint {
                     txid: kick_off_1_txid,
@@ -344,7 +373,7 @@ impl PegOutGraph {
             },
         );
 
-        let input_amount_crowdfunding = Amount::from_btc(1.0).unwrap(); // TODO replace placeholder
+        let input_amount_crowdfunding = context.params.challenge_crowdfunding();
         let challenge_vout_0 = 0;
         let challenge_transaction = ChallengeTransaction::new(
             context,
@@ -356,7 +385,7 @@ impl PegOutGraph {
                 amount: kick_off_1_transaction.tx().output[challenge_vout_0].value,
             },
             input_amount_crowdfunding,
-        );
+        )?;
 
         let take_1_vout_0 = 0;
         let take_1_vout_1 = 0;
@@ -364,6 +393,7 @@ impl PegOutGraph {
         let take_1_vout_3 = 1;
         let take_1_transaction = Take1Transaction::new(
             context,
+            &connector_b,
             Input {
                 outpoint: OutPoint {
                     txid: peg_in_confirm_txid,
@@ -392,11 +422,13 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[take_1_vout_3].value,
             },
-        );
+        )?;
 
         let assert_vout_0 = 1;
         let assert_transaction = AssertTransaction::new(
             context,
+            &connector_b,
+            &connector_c,
             Input {
                 outpoint: OutPoint {
                     txid: kick_off_2_txid,
@@ -413,6 +445,7 @@ impl PegOutGraph {
         let take_2_vout_3 = 2;
         let take_2_transaction = Take2Transaction::new(
             context,
+            &connector_c,
             Input {
                 outpoint: OutPoint {
                     txid: peg_in_confirm_txid,
@@ -441,13 +474,14 @@ impl PegOutGraph {
                 },
                 amount: assert_transaction.tx().output[take_2_vout_3].value,
             },
-        );
+        )?;
 
         let script_index = 1; // TODO replace placeholder
         let disprove_vout_0 = 1;
         let disprove_vout_1 = 2;
         let disprove_transaction = DisproveTransaction::new(
             context,
+            &connector_c,
             Input {
                 outpoint: OutPoint {
                     txid: assert_txid,
@@ -468,6 +502,7 @@ impl PegOutGraph {
         let disprove_chain_vout_0 = 1;
         let disprove_chain_transaction = DisproveChainTransaction::new(
             context,
+            &connector_b,
             Input {
                 outpoint: OutPoint {
                     txid: kick_off_2_txid,
@@ -477,11 +512,13 @@ impl PegOutGraph {
             },
         );
 
-        (
+        Ok((
             PegOutGraph {
                 version: GRAPH_VERSION.to_string(),
                 network: context.network,
-                id: generate_id(peg_in_graph, &context.operator_public_key),
+                params: context.params.clone(),
+                id: generate_id(peg_in_graph, &context.operator_public_key, &context.params),
+                state: StateMachine::default(),
                 n_of_n_presigned: false,
                 n_of_n_public_key: context.n_of_n_public_key,
                 n_of_n_taproot_public_key: context.n_of_n_taproot_public_key,
@@ -490,6 +527,8 @@ impl PegOutGraph {
                 connector_1,
                 connector_2,
                 connector_6,
+                connector_b,
+                connector_c,
                 peg_out_confirm_transaction,
                 assert_transaction,
                 challenge_transaction,
@@ -508,9 +547,11 @@ impl PegOutGraph {
                 peg_out_transaction: None,
             },
             winternitz_secrets,
-        )
+        ))
     }
 
+    pub fn params(&self) -> &BridgeParams { &self.params }
+
     pub fn new_for_validation(&self) -> Self {
         let peg_in_confirm_txid = self.take_1_transaction.tx().input[0].previous_output.txid; // Self-referencing
 
@@ -519,6 +560,7 @@ impl PegOutGraph {
             &self.operator_taproot_public_key,
             &self.n_of_n_taproot_public_key,
             &self.connector_1.winternitz_public_keys,
+            &self.params,
         );
         let connector_2 = Connector2::new_for_validation(
             self.network,
@@ -531,10 +573,22 @@ impl PegOutGraph {
             &self.operator_taproot_public_key,
             &self.connector_6.winternitz_public_keys,
         );
+        let connector_c = ConnectorC::new_for_validation(
+            self.network,
+            &self.operator_taproot_public_key,
+            &self.connector_c.winternitz_public_keys,
+        );
+        let connector_b = ConnectorB::new(
+            self.network,
+            &self.n_of_n_taproot_public_key,
+            &self.params,
+            &connector_c.winternitz_public_keys,
+        );
 
         let peg_out_confirm_vout_0 = 0;
         let peg_out_confirm_transaction = PegOutConfirmTransaction::new_for_validation(
             self.network,
+            &self.params,
             &self.operator_public_key,
             &connector_6,
             Input {
@@ -547,6 +601,7 @@ impl PegOutGraph {
         let kick_off_1_vout_0 = 0;
         let kick_off_1_transaction = KickOff1Transaction::new_for_validation(
             self.network,
+            &self.params,
             &self.operator_taproot_public_key,
             &self.n_of_n_taproot_public_key,
             &connector_1,
@@ -562,6 +617,7 @@ impl PegOutGraph {
         let start_time_vout_0 = 2;
         let start_time_transaction = StartTimeTransaction::new_for_validation(
             self.network,
+            &self.params,
             &self.operator_public_key,
             &connector_2,
             Input {
@@ -577,6 +633,7 @@ impl PegOutGraph {
         let start_time_timeout_vout_1 = 1;
         let start_time_timeout_transaction = StartTimeTimeoutTransaction::new_for_validation(
             self.network,
+            &self.params,
             &connector_1,
             &connector_2,
             Input {
@@ -598,9 +655,10 @@ impl PegOutGraph {
         let kick_off_2_vout_0 = 1;
         let kick_off_2_transaction = KickOff2Transaction::new_for_validation(
             self.network,
+            &self.params,
             &self.operator_public_key,
-            &self.n_of_n_taproot_public_key,
             &connector_1,
+            &connector_b,
             Input {
                 outpoint: OutPoint {
                     txid: kick_off_1_txid,
@@ -614,6 +672,7 @@ impl PegOutGraph {
         let kick_off_timeout_vout_0 = 1;
         let kick_off_timeout_transaction = KickOffTimeoutTransaction::new_for_validation(
             self.network,
+            &self.params,
             &connector_1,
             Input {
                 outpoint: OutPoint {
@@ -624,10 +683,11 @@ impl PegOutGraph {
             },
         );
 
-        let input_amount_crowdfunding = Amount::from_btc(1.0).unwrap(); // TODO replace placeholder
+        let input_amount_crowdfunding = self.params.challenge_crowdfunding();
         let challenge_vout_0 = 0;
         let challenge_transaction = ChallengeTransaction::new_for_validation(
             self.network,
+            &self.params,
             &self.operator_public_key,
             &self.operator_taproot_public_key,
             &self.n_of_n_taproot_public_key,
@@ -647,9 +707,11 @@ impl PegOutGraph {
         let take_1_vout_3 = 1;
         let take_1_transaction = Take1Transaction::new_for_validation(
             self.network,
+            &self.params,
             &self.operator_public_key,
             &self.operator_taproot_public_key,
             &self.n_of_n_taproot_public_key,
+            &connector_b,
             Input {
                 outpoint: OutPoint {
                     txid: peg_in_confirm_txid,
@@ -683,9 +745,11 @@ impl PegOutGraph {
         let assert_vout_0 = 1;
         let assert_transaction = AssertTransaction::new_for_validation(
             self.network,
+            &self.params,
             &self.operator_public_key,
-            &self.operator_taproot_public_key,
             &self.n_of_n_taproot_public_key,
+            &connector_b,
+            &connector_c,
             Input {
                 outpoint: OutPoint {
                     txid: kick_off_2_txid,
@@ -702,9 +766,10 @@ impl PegOutGraph {
         let take_2_vout_3 = 2;
         let take_2_transaction = Take2Transaction::new_for_validation(
             self.network,
+            &self.params,
             &self.operator_public_key,
-            &self.operator_taproot_public_key,
             &self.n_of_n_taproot_public_key,
+            &connector_c,
             Input {
                 outpoint: OutPoint {
                     txid: peg_in_confirm_txid,
@@ -740,8 +805,9 @@ impl PegOutGraph {
         let disprove_vout_1 = 2;
         let disprove_transaction = DisproveTransaction::new_for_validation(
             self.network,
-            &self.operator_taproot_public_key,
+            &self.params,
             &self.n_of_n_taproot_public_key,
+            &connector_c,
             Input {
                 outpoint: OutPoint {
                     txid: assert_txid,
@@ -762,7 +828,8 @@ impl PegOutGraph {
         let disprove_chain_vout_0 = 1;
         let disprove_chain_transaction = DisproveChainTransaction::new_for_validation(
             self.network,
-            &self.n_of_n_taproot_public_key,
+            &self.params,
+            &connector_b,
             Input {
                 outpoint: OutPoint {
                     txid: kick_off_2_txid,
@@ -775,7 +842,9 @@ impl PegOutGraph {
         PegOutGraph {
             version: GRAPH_VERSION.to_string(),
             network: self.network,
+            params: self.params.clone(),
             id: self.id.clone(),
+            state: StateMachine::default(),
             n_of_n_presigned: false,
             n_of_n_public_key: self.n_of_n_public_key,
             n_of_n_taproot_public_key: self.n_of_n_taproot_public_key,
@@ -784,6 +853,8 @@ impl PegOutGraph {
             connector_1,
             connector_2,
             connector_6,
+            connector_b,
+            connector_c,
             peg_out_confirm_transaction,
             assert_transaction,
             challenge_transaction,
@@ -803,307 +874,424 @@ impl PegOutGraph {
         }
     }
 
+    pub fn operator_public_key(&self) -> &PublicKey { &self.operator_public_key }
+
     pub fn connector_1_id(&self) -> ConnectorId { self.connector_1.id() }
     pub fn connector_2_id(&self) -> ConnectorId { self.connector_2.id() }
     pub fn connector_6_id(&self) -> ConnectorId { self.connector_6.id() }
+    pub fn connector_c_id(&self) -> ConnectorId { self.connector_c.id() }
+
+    fn musig2_transactions(&self) -> Vec<&dyn PreSignedMusig2Transaction> {
+        vec![
+            &self.assert_transaction,
+            &self.disprove_chain_transaction,
+            &self.disprove_transaction,
+            &self.kick_off_timeout_transaction,
+            &self.start_time_timeout_transaction,
+            &self.take_1_transaction,
+            &self.take_2_transaction,
+        ]
+    }
+
+    /// Whether all MuSig2 transactions have nonces of `public_keys`
+    pub fn has_nonces(&self, public_keys: &[PublicKey]) -> bool {
+        self.musig2_transactions()
+            .into_iter()
+            .all(|tx| has_musig2_nonces(tx, public_keys))
+    }
+
+    /// Whether all MuSig2 transactions are pre-signed by `public_keys`
+    pub fn has_signatures(&self, public_keys: &[PublicKey]) -> bool {
+        self.musig2_transactions()
+            .into_iter()
+            .all(|tx| has_musig2_signatures(tx, public_keys))
+    }
 
     pub fn push_nonces(
         &mut self,
         context: &VerifierContext,
-    ) -> HashMap<Txid, HashMap<usize, SecNonce>> {
-        let mut secret_nonces = HashMap::new();
+    ) -> Result<HashMap<Txid, HashMap<usize, PubNonce>>, String> {
+        let mut nonces = HashMap::new();
 
-        secret_nonces.insert(
+        nonces.insert(
             self.assert_transaction.tx().compute_txid(),
-            self.assert_transaction.push_nonces(context),
+            self.assert_transaction.push_nonces(context)?,
         );
-        secret_nonces.insert(
+        nonces.insert(
             self.disprove_chain_transaction.tx().compute_txid(),
-            self.disprove_chain_transaction.push_nonces(context),
+            self.disprove_chain_transaction.push_nonces(context)?,
         );
-        secret_nonces.insert(
+        nonces.insert(
             self.disprove_transaction.tx().compute_txid(),
-            self.disprove_transaction.push_nonces(context),
+            self.disprove_transaction.push_nonces(context)?,
         );
-        secret_nonces.insert(
+        nonces.insert(
             self.kick_off_timeout_transaction.tx().compute_txid(),
-            self.kick_off_timeout_transaction.push_nonces(context),
+            self.kick_off_timeout_transaction.push_nonces(context)?,
         );
-        secret_nonces.insert(
+        nonces.insert(
             self.start_time_timeout_transaction.tx().compute_txid(),
-            self.start_time_timeout_transaction.push_nonces(context),
+            self.start_time_timeout_transaction.push_nonces(context)?,
         );
-        secret_nonces.insert(
+        nonces.insert(
             self.take_1_transaction.tx().compute_txid(),
-            self.take_1_transaction.push_nonces(context),
+            self.take_1_transaction.push_nonces(context)?,
         );
-        secret_nonces.insert(
+        nonces.insert(
             self.take_2_transaction.tx().compute_txid(),
-            self.take_2_transaction.push_nonces(context),
+            self.take_2_transaction.push_nonces(context)?,
         );
 
-        secret_nonces
+        Ok(nonces)
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
-        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
-    ) {
+        nonces: &HashMap<Txid, HashMap<usize, PubNonce>>,
+    ) -> Result<(), String> {
         self.assert_transaction.pre_sign(
             context,
-            &secret_nonces[&self.assert_transaction.tx().compute_txid()],
-        );
+            &nonces[&self.assert_transaction.tx().compute_txid()],
+        )?;
         self.disprove_chain_transaction.pre_sign(
             context,
-            &secret_nonces[&self.disprove_chain_transaction.tx().compute_txid()],
-        );
+            &nonces[&self.disprove_chain_transaction.tx().compute_txid()],
+        )?;
         self.disprove_transaction.pre_sign(
             context,
-            &secret_nonces[&self.disprove_transaction.tx().compute_txid()],
-        );
+            &nonces[&self.disprove_transaction.tx().compute_txid()],
+        )?;
         self.kick_off_timeout_transaction.pre_sign(
             context,
             &self.connector_1,
-            &secret_nonces[&self.kick_off_timeout_transaction.tx().compute_txid()],
-        );
+            &nonces[&self.kick_off_timeout_transaction.tx().compute_txid()],
+        )?;
         self.start_time_timeout_transaction.pre_sign(
             context,
             &self.connector_1,
             &self.connector_2,
-            &secret_nonces[&self.start_time_timeout_transaction.tx().compute_txid()],
-        );
+            &nonces[&self.start_time_timeout_transaction.tx().compute_txid()],
+        )?;
         self.take_1_transaction.pre_sign(
             context,
-            &secret_nonces[&self.take_1_transaction.tx().compute_txid()],
-        );
+            &nonces[&self.take_1_transaction.tx().compute_txid()],
+        )?;
         self.take_2_transaction.pre_sign(
             context,
-            &secret_nonces[&self.take_2_transaction.tx().compute_txid()],
-        );
+            &nonces[&self.take_2_transaction.tx().compute_txid()],
+        )?;
 
         self.n_of_n_presigned = true; // TODO: set to true after collecting all n of n signatures
+
+        Ok(())
     }
 
-    pub async fn verifier_status(&self, client: &AsyncClient) -> PegOutVerifierStatus {
-        if self.n_of_n_presigned {
-            let (
-                assert_status,
-                challenge_status,
-                disprove_chain_status,
-                disprove_status,
-                _,
-                kick_off_1_status,
-                kick_off_2_status,
-                kick_off_timeout_status,
-                _,
-                start_time_timeout_status,
-                start_time_status,
-                take_1_status,
-                take_2_status,
-            ) = Self::get_peg_out_statuses(self, client).await;
-            let blockchain_height = get_block_height(client).await;
+    pub fn state(&self) -> &StateMachine<PegOutState> { &self.state }
 
-            if kick_off_2_status
-                .as_ref()
-                .is_ok_and(|status| status.confirmed)
-            {
-                if take_1_status.as_ref().is_ok_and(|status| status.confirmed)
-                    || take_2_status.as_ref().is_ok_and(|status| status.confirmed)
-                {
-                    return PegOutVerifierStatus::PegOutComplete;
-                } else if disprove_status
-                    .as_ref()
-                    .is_ok_and(|status| status.confirmed)
-                    || disprove_chain_status
-                        .as_ref()
-                        .is_ok_and(|status| status.confirmed)
-                {
-                    return PegOutVerifierStatus::PegOutFailed; // TODO: can be also `PegOutVerifierStatus::PegOutComplete`
-                } else if assert_status.as_ref().is_ok_and(|status| status.confirmed) {
-                    return PegOutVerifierStatus::PegOutDisproveAvailable;
-                } else {
-                    return PegOutVerifierStatus::PegOutDisproveChainAvailable;
-                }
-            } else if kick_off_1_status
-                .as_ref()
-                .is_ok_and(|status| status.confirmed)
-            {
-                if start_time_timeout_status
-                    .as_ref()
-                    .is_ok_and(|status| status.confirmed)
-                    || kick_off_timeout_status
-                        .as_ref()
-                        .is_ok_and(|status| status.confirmed)
-                {
-                    return PegOutVerifierStatus::PegOutFailed; // TODO: can be also `PegOutVerifierStatus::PegOutComplete`
-                } else if start_time_status
-                    .as_ref()
-                    .is_ok_and(|status| !status.confirmed)
-                {
-                    if kick_off_1_status
-                        .as_ref()
-                        .unwrap()
-                        .block_height
-                        .is_some_and(|block_height| {
-                            block_height + self.connector_1.num_blocks_timelock_leaf_2
-                                > blockchain_height
-                        })
-                    {
-                        return PegOutVerifierStatus::PegOutStartTimeTimeoutAvailable;
-                    } else {
-                        return PegOutVerifierStatus::PegOutWait;
-                    }
-                } else if kick_off_1_status
-                    .as_ref()
-                    .unwrap()
-                    .block_height
-                    .is_some_and(|block_height| {
-                        block_height + self.connector_1.num_blocks_timelock_leaf_1
-                            > blockchain_height
-                    })
-                {
-                    return PegOutVerifierStatus::PegOutKickOffTimeoutAvailable;
-                } else if challenge_status
-                    .as_ref()
-                    .is_ok_and(|status| !status.confirmed)
-                {
-                    return PegOutVerifierStatus::PegOutChallengeAvailable;
+    pub fn state_mut(&mut self) -> &mut StateMachine<PegOutState> { &mut self.state }
+
+    pub fn transaction_matches(&self) -> Vec<(GraphTransaction, TransactionMatch)> {
+        let txid = |tx: &Transaction| TransactionMatch::Txid(tx.compute_txid());
+        let mut matches = vec![
+            (
+                GraphTransaction::PegOutConfirm,
+                txid(self.peg_out_confirm_transaction.tx()),
+            ),
+            (GraphTransaction::KickOff1, txid(self.kick_off_1_transaction.tx())),
+            (GraphTransaction::StartTime, txid(self.start_time_transaction.tx())),
+            (
+                GraphTransaction::StartTimeTimeout,
+                match_inputs(self.start_time_timeout_transaction.tx()),
+            ),
+            (GraphTransaction::KickOff2, txid(self.kick_off_2_transaction.tx())),
+            (
+                GraphTransaction::KickOffTimeout,
+                match_inputs(self.kick_off_timeout_transaction.tx()),
+            ),
+            // input 0 only, the crowdfunded inputs are not known in advance
+            (
+                GraphTransaction::Challenge,
+                TransactionMatch::Inputs(vec![
+                    self.challenge_transaction.tx().input[0].previous_output,
+                ]),
+            ),
+            (GraphTransaction::Assert, txid(self.assert_transaction.tx())),
+            (
+                GraphTransaction::Disprove,
+                match_inputs(self.disprove_transaction.tx()),
+            ),
+            (
+                GraphTransaction::DisproveChain,
+                match_inputs(self.disprove_chain_transaction.tx()),
+            ),
+            (GraphTransaction::Take1, txid(self.take_1_transaction.tx())),
+            (GraphTransaction::Take2, txid(self.take_2_transaction.tx())),
+        ];
+        if let Some(peg_out_transaction) = &self.peg_out_transaction {
+            matches.push((GraphTransaction::PegOut, txid(peg_out_transaction.tx())));
+        }
+
+        matches
+    }
+
+    /// The pre-signed transactions of the graph, to validate the transactions spending its
+    /// outputs against. Inputs and outputs that are only filled in at broadcast are left out.
+    pub fn pre_signed_transactions(&self) -> Vec<(GraphTransaction, Transaction)> {
+        let signed_part = |tx: &Transaction, num_inputs: usize, num_outputs: usize| {
+            let mut tx = tx.clone();
+            tx.input.truncate(num_inputs);
+            tx.output.truncate(num_outputs);
+            tx
+        };
+        let mut transactions = vec![
+            (
+                GraphTransaction::PegOutConfirm,
+                self.peg_out_confirm_transaction.tx().clone(),
+            ),
+            (
+                GraphTransaction::KickOff1,
+                self.kick_off_1_transaction.tx().clone(),
+            ),
+            (
+                GraphTransaction::StartTime,
+                self.start_time_transaction.tx().clone(),
+            ),
+            // reward output script is chosen by the verifier
+            (
+                GraphTransaction::StartTimeTimeout,
+                signed_part(self.start_time_timeout_transaction.tx(), 2, 1),
+            ),
+            (
+                GraphTransaction::KickOff2,
+                self.kick_off_2_transaction.tx().clone(),
+            ),
+            (
+                GraphTransaction::KickOffTimeout,
+                signed_part(self.kick_off_timeout_transaction.tx(), 1, 1),
+            ),
+            // crowdfunded inputs and the refund output are not known in advance
+            (
+                GraphTransaction::Challenge,
+                signed_part(self.challenge_transaction.tx(), 1, 1),
+            ),
+            (GraphTransaction::Assert, self.assert_transaction.tx().clone()),
+            (
+                GraphTransaction::Disprove,
+                signed_part(self.disprove_transaction.tx(), 2, 1),
+            ),
+            (
+                GraphTransaction::DisproveChain,
+                signed_part(self.disprove_chain_transaction.tx(), 1, 1),
+            ),
+            (GraphTransaction::Take1, self.take_1_transaction.tx().clone()),
+            (GraphTransaction::Take2, self.take_2_transaction.tx().clone()),
+        ];
+        if let Some(peg_out_transaction) = &self.peg_out_transaction {
+            transactions.push((GraphTransaction::PegOut, peg_out_transaction.tx().clone()));
+        }
+
+        transactions
+    }
+
+    pub fn timelocks(&self) -> Vec<Timelock> {
+        vec![
+            Timelock {
+                transaction: GraphTransaction::KickOff2,
+                after: GraphTransaction::KickOff1,
+                num_blocks: self.connector_1.num_blocks_timelock_leaf_0,
+            },
+            Timelock {
+                transaction: GraphTransaction::KickOffTimeout,
+                after: GraphTransaction::KickOff1,
+                num_blocks: self.connector_1.num_blocks_timelock_leaf_1,
+            },
+            Timelock {
+                transaction: GraphTransaction::StartTimeTimeout,
+                after: GraphTransaction::KickOff1,
+                num_blocks: self.connector_1.num_blocks_timelock_leaf_2,
+            },
+            Timelock {
+                transaction: GraphTransaction::Take1,
+                after: GraphTransaction::KickOff2,
+                num_blocks: self.take_1_transaction.num_blocks_timelock_2(),
+            },
+            Timelock {
+                transaction: GraphTransaction::Assert,
+                after: GraphTransaction::KickOff2,
+                num_blocks: self.assert_transaction.num_blocks_timelock_0(),
+            },
+            Timelock {
+                transaction: GraphTransaction::Take2,
+                after: GraphTransaction::Assert,
+                num_blocks: self.take_2_transaction.num_blocks_timelock_1(),
+            },
+        ]
+    }
+
+    pub fn export(&self) -> GraphExport {
+        use ConnectorId::*;
+        use GraphTransaction::*;
+
+        let spends = [
+            ConnectorSpend::new(KickOff1, 0, PegOutConfirm, Connector6, Some(0)),
+            ConnectorSpend::new(StartTime, 0, KickOff1, Connector2, Some(0)),
+            ConnectorSpend::new(StartTimeTimeout, 0, KickOff1, Connector2, Some(1)),
+            ConnectorSpend::new(StartTimeTimeout, 1, KickOff1, Connector1, Some(2)),
+            ConnectorSpend::new(KickOff2, 0, KickOff1, Connector1, Some(0)),
+            ConnectorSpend::new(KickOffTimeout, 0, KickOff1, Connector1, Some(1)),
+            ConnectorSpend::new(Challenge, 0, KickOff1, ConnectorA, Some(1)),
+            ConnectorSpend::new(Assert, 0, KickOff2, ConnectorB, Some(1)),
+            ConnectorSpend::new(DisproveChain, 0, KickOff2, ConnectorB, Some(2)),
+            ConnectorSpend::new(Disprove, 0, Assert, Connector5, Some(1)),
+            // the leaf is the disproven script, chosen at broadcast
+            ConnectorSpend::new(Disprove, 1, Assert, ConnectorC, None),
+            ConnectorSpend::new(Take1, 0, PegInConfirm, Connector0, Some(0)),
+            ConnectorSpend::new(Take1, 1, KickOff1, ConnectorA, Some(0)),
+            ConnectorSpend::new(Take1, 2, KickOff2, Connector3, None),
+            ConnectorSpend::new(Take1, 3, KickOff2, ConnectorB, Some(0)),
+            ConnectorSpend::new(Take2, 0, PegInConfirm, Connector0, Some(1)),
+            ConnectorSpend::new(Take2, 1, Assert, Connector4, None),
+            ConnectorSpend::new(Take2, 2, Assert, Connector5, Some(0)),
+            ConnectorSpend::new(Take2, 3, Assert, ConnectorC, Some(0)),
+        ];
+        let transactions: [(GraphTransaction, &dyn PreSignedTransaction); 11] = [
+            (KickOff1, &self.kick_off_1_transaction),
+            (StartTime, &self.start_time_transaction),
+            (StartTimeTimeout, &self.start_time_timeout_transaction),
+            (KickOff2, &self.kick_off_2_transaction),
+            (KickOffTimeout, &self.kick_off_timeout_transaction),
+            (Challenge, &self.challenge_transaction),
+            (Assert, &self.assert_transaction),
+            (Disprove, &self.disprove_transaction),
+            (DisproveChain, &self.disprove_chain_transaction),
+            (Take1, &self.take_1_transaction),
+            (Take2, &self.take_2_transaction),
+        ];
+        let transactions = HashMap::from(transactions);
+        let signers = [
+            ("n-of-n", self.n_of_n_taproot_public_key),
+            ("operator", self.operator_taproot_public_key),
+        ];
+        let edges = spends
+            .iter()
+            .map(|spend| connector_edge(spend, transactions[&spend.transaction], &signers))
+            .collect();
+
+        // peg-in confirm belongs to the peg-in graph, but take 1 and take 2 spend it
+        let mut transaction_matches = vec![(
+            PegInConfirm,
+            TransactionMatch::Txid(self.peg_in_confirm_txid),
+        )];
+        transaction_matches.extend(self.transaction_matches());
+
+        GraphExport::new(&self.id, &self.state, &transaction_matches, edges)
+    }
+
+    pub async fn verifier_status(&self, chain: &dyn GraphChain) -> PegOutVerifierStatus {
+        match self.state.outcome() {
+            Some(GraphOutcome::Complete) => return PegOutVerifierStatus::PegOutComplete,
+            Some(GraphOutcome::Failed) => return PegOutVerifierStatus::PegOutFailed,
+            None => {}
+        }
+        if !self.n_of_n_presigned {
+            return PegOutVerifierStatus::PegOutPresign;
+        }
+
+        let blockchain_height = chain.get_block_height().await;
+        let after_timelock = |transaction, status| {
+            if self.has_timelock_elapsed(transaction, blockchain_height) {
+                status
+            } else {
+                PegOutVerifierStatus::PegOutWait
+            }
+        };
+        match self.state.state() {
+            PegOutState::KickedOff1 {
+                start_time: false, ..
+            } => after_timelock(
+                GraphTransaction::StartTimeTimeout,
+                PegOutVerifierStatus::PegOutStartTimeTimeoutAvailable,
+            ),
+            PegOutState::KickedOff1 { challenged, .. } => {
+                if self.has_timelock_elapsed(GraphTransaction::KickOffTimeout, blockchain_height) {
+                    PegOutVerifierStatus::PegOutKickOffTimeoutAvailable
+                } else if !challenged {
+                    PegOutVerifierStatus::PegOutChallengeAvailable
                 } else {
-                    return PegOutVerifierStatus::PegOutWait;
+                    PegOutVerifierStatus::PegOutWait
                 }
-            } else {
-                return PegOutVerifierStatus::PegOutWait;
             }
-        } else {
-            return PegOutVerifierStatus::PegOutPresign;
+            PegOutState::KickedOff2 { .. } => PegOutVerifierStatus::PegOutDisproveChainAvailable,
+            PegOutState::Asserted { .. } => PegOutVerifierStatus::PegOutDisproveAvailable,
+            _ => PegOutVerifierStatus::PegOutWait,
         }
     }
 
-    pub async fn operator_status(&self, client: &AsyncClient) -> PegOutOperatorStatus {
-        if self.n_of_n_presigned && self.is_peg_out_initiated() {
-            let (
-                assert_status,
-                challenge_status,
-                disprove_chain_status,
-                disprove_status,
-                peg_out_confirm_status,
-                kick_off_1_status,
-                kick_off_2_status,
-                kick_off_timeout_status,
-                peg_out_status,
-                start_time_timeout_status,
-                start_time_status,
-                take_1_status,
-                take_2_status,
-            ) = Self::get_peg_out_statuses(self, client).await;
-            let blockchain_height = get_block_height(client).await;
-
-            if peg_out_status.is_some_and(|status| status.unwrap().confirmed) {
-                if kick_off_2_status
-                    .as_ref()
-                    .is_ok_and(|status| status.confirmed)
-                {
-                    if take_1_status.as_ref().is_ok_and(|status| status.confirmed)
-                        || take_2_status.as_ref().is_ok_and(|status| status.confirmed)
-                    {
-                        return PegOutOperatorStatus::PegOutComplete;
-                    } else if disprove_chain_status
-                        .as_ref()
-                        .is_ok_and(|status| status.confirmed)
-                        || disprove_status
-                            .as_ref()
-                            .is_ok_and(|status| status.confirmed)
-                    {
-                        return PegOutOperatorStatus::PegOutFailed; // TODO: can be also `PegOutOperatorStatus::PegOutComplete`
-                    } else if challenge_status.is_ok_and(|status| status.confirmed) {
-                        if assert_status.as_ref().is_ok_and(|status| status.confirmed) {
-                            if assert_status.as_ref().unwrap().block_height.is_some_and(
-                                |block_height| {
-                                    block_height + self.take_2_transaction.num_blocks_timelock_1()
-                                        <= blockchain_height
-                                },
-                            ) {
-                                return PegOutOperatorStatus::PegOutTake2Available;
-                            } else {
-                                return PegOutOperatorStatus::PegOutWait;
-                            }
-                        } else {
-                            if kick_off_2_status
-                                .as_ref()
-                                .unwrap()
-                                .block_height
-                                .is_some_and(|block_height| {
-                                    block_height + self.assert_transaction.num_blocks_timelock_0()
-                                        <= blockchain_height
-                                })
-                            {
-                                return PegOutOperatorStatus::PegOutAssertAvailable;
-                            } else {
-                                return PegOutOperatorStatus::PegOutWait;
-                            }
-                        }
-                    } else {
-                        if kick_off_2_status
-                            .as_ref()
-                            .unwrap()
-                            .block_height
-                            .is_some_and(|block_height| {
-                                block_height + self.take_1_transaction.num_blocks_timelock_2()
-                                    <= blockchain_height
-                            })
-                        {
-                            return PegOutOperatorStatus::PegOutTake1Available;
-                        } else {
-                            return PegOutOperatorStatus::PegOutWait;
-                        }
-                    }
-                } else if kick_off_1_status
-                    .as_ref()
-                    .is_ok_and(|status| status.confirmed)
-                {
-                    if start_time_timeout_status
-                        .as_ref()
-                        .is_ok_and(|status| status.confirmed)
-                        || kick_off_timeout_status
-                            .as_ref()
-                            .is_ok_and(|status| status.confirmed)
-                    {
-                        return PegOutOperatorStatus::PegOutFailed; // TODO: can be also `PegOutOperatorStatus::PegOutComplete`
-                    } else if start_time_status
-                        .as_ref()
-                        .is_ok_and(|status| status.confirmed)
-                    {
-                        if kick_off_1_status
-                            .as_ref()
-                            .unwrap()
-                            .block_height
-                            .is_some_and(|block_height| {
-                                block_height + self.connector_1.num_blocks_timelock_leaf_0
-                                    <= blockchain_height
-                            })
-                        {
-                            return PegOutOperatorStatus::PegOutKickOff2Available;
-                        } else {
-                            return PegOutOperatorStatus::PegOutWait;
-                        }
-                    } else {
-                        return PegOutOperatorStatus::PegOutStartTimeAvailable;
-                    }
-                } else if peg_out_confirm_status
-                    .as_ref()
-                    .is_ok_and(|status| status.confirmed)
-                {
-                    return PegOutOperatorStatus::PegOutKickOff1Available;
-                } else {
-                    return PegOutOperatorStatus::PegOutPegOutConfirmAvailable;
-                }
+    pub async fn operator_status(&self, chain: &dyn GraphChain) -> PegOutOperatorStatus {
+        match self.state.outcome() {
+            Some(GraphOutcome::Complete) => return PegOutOperatorStatus::PegOutComplete,
+            Some(GraphOutcome::Failed) => return PegOutOperatorStatus::PegOutFailed,
+            None => {}
+        }
+        if !self.n_of_n_presigned || !self.is_peg_out_initiated() {
+            return PegOutOperatorStatus::PegOutWait;
+        }
+
+        let blockchain_height = chain.get_block_height().await;
+        let after_timelock = |transaction, status| {
+            if self.has_timelock_elapsed(transaction, blockchain_height) {
+                status
             } else {
-                return PegOutOperatorStatus::PegOutStartPegOut;
+                PegOutOperatorStatus::PegOutWait
             }
+        };
+        match self.state.state() {
+            PegOutState::Created => PegOutOperatorStatus::PegOutStartPegOut,
+            PegOutState::PegOutSent => PegOutOperatorStatus::PegOutPegOutConfirmAvailable,
+            PegOutState::PegOutConfirmed => PegOutOperatorStatus::PegOutKickOff1Available,
+            PegOutState::KickedOff1 {
+                start_time: false, ..
+            } => PegOutOperatorStatus::PegOutStartTimeAvailable,
+            PegOutState::KickedOff1 {
+                start_time: true, ..
+            } => after_timelock(
+                GraphTransaction::KickOff2,
+                PegOutOperatorStatus::PegOutKickOff2Available,
+            ),
+            PegOutState::KickedOff2 {
+                challenged: false, ..
+            } => after_timelock(
+                GraphTransaction::Take1,
+                PegOutOperatorStatus::PegOutTake1Available,
+            ),
+            PegOutState::KickedOff2 {
+                challenged: true, ..
+            } => after_timelock(
+                GraphTransaction::Assert,
+                PegOutOperatorStatus::PegOutAssertAvailable,
+            ),
+            PegOutState::Asserted { .. } => after_timelock(
+                GraphTransaction::Take2,
+                PegOutOperatorStatus::PegOutTake2Available,
+            ),
+            PegOutState::Complete | PegOutState::Failed => PegOutOperatorStatus::PegOutWait,
         }
+    }
 
-        return PegOutOperatorStatus::PegOutWait;
+    // Whether the timelock `transaction` waits on has elapsed since the transaction it follows
+    // was observed
+    fn has_timelock_elapsed(&self, transaction: GraphTransaction, blockchain_height: u32) -> bool {
+        self.timelocks()
+            .iter()
+            .find(|timelock| timelock.transaction == transaction)
+            .is_some_and(|timelock| {
+                self.state
+                    .height_of(timelock.after)
+                    .is_some_and(|block_height| {
+                        is_timelock_elapsed(block_height, timelock.num_blocks, blockchain_height)
+                    })
+            })
     }
 
     pub fn interpret_operator_status(
@@ -1125,7 +1313,7 @@ impl PegOutGraph {
         }
     }
 
-    pub async fn withdrawer_status(&self, client: &AsyncClient) -> PegOutWithdrawerStatus {
+    pub async fn withdrawer_status(&self, chain: &dyn GraphChain) -> PegOutWithdrawerStatus {
         let peg_out_status = match self.peg_out_transaction {
             Some(_) => {
                 let peg_out_txid = self
@@ -1134,7 +1322,9 @@ impl PegOutGraph {
                     .unwrap()
                     .tx()
                     .compute_txid();
-                let peg_out_status = client.get_tx_status(&peg_out_txid).await;
+                let peg_out_status = chain
+                    .get_tx_status(&self.id, GraphTransaction::PegOut, &peg_out_txid)
+                    .await;
                 Some(peg_out_status)
             }
             None => None,
@@ -1142,7 +1332,12 @@ impl PegOutGraph {
         self.interpret_operator_status(peg_out_status.as_ref())
     }
 
-    pub async fn peg_out(&mut self, client: &AsyncClient, context: &OperatorContext, input: Input) {
+    pub async fn peg_out(
+        &mut self,
+        client: &AsyncClient,
+        context: &OperatorContext,
+        input: Input,
+    ) -> Result<(), String> {
         if !self.is_peg_out_initiated() {
             panic!("Peg out not initiated on L2 chain");
         }
@@ -1157,7 +1352,7 @@ impl PegOutGraph {
             verify_if_not_mined(&client, txid).await;
         } else {
             let event = self.peg_out_chain_event.as_ref().unwrap();
-            let tx = PegOutTransaction::new(context, event, input);
+            let tx = PegOutTransaction::new(context, event, input)?;
             self.peg_out_transaction = Some(tx);
         }
 
@@ -1166,6 +1361,8 @@ impl PegOutGraph {
         let peg_out_result = client.broadcast(&peg_out_tx).await;
 
         verify_tx_result(&peg_out_result);
+
+        Ok(())
     }
 
     pub async fn peg_out_confirm(&mut self, client: &AsyncClient) {
@@ -1202,7 +1399,7 @@ impl PegOutGraph {
         client: &AsyncClient,
         context: &OperatorContext,
         connector_6_winternitz_secrets: &HashMap<u8, WinternitzSecret>,
-    ) {
+    ) -> Result<(), String> {
         verify_if_not_mined(&client, self.kick_off_1_transaction.tx().compute_txid()).await;
 
         let peg_out_confirm_txid = self.peg_out_confirm_transaction.tx().compute_txid();
@@ -1224,7 +1421,7 @@ impl PegOutGraph {
                 source_network_txid.as_byte_array(),
                 destination_network_txid.as_slice(),
                 &connector_6_winternitz_secrets[&connector_6_leaf_index],
-            );
+            )?;
             let kick_off_1_tx = self.kick_off_1_transaction.finalize();
 
             // broadcast kick-off 1 tx
@@ -1235,6 +1432,8 @@ impl PegOutGraph {
         } else {
             panic!("Peg-out-confirm tx has not been confirmed!");
         }
+
+        Ok(())
     }
 
     pub async fn challenge(
@@ -1242,9 +1441,9 @@ impl PegOutGraph {
         client: &AsyncClient,
         context: &dyn BaseContext,
         crowdfundng_inputs: &Vec<InputWithScript<'_>>,
-        keypair: &Keypair,
+        signer: &dyn Signer,
         output_script_pubkey: ScriptBuf,
-    ) {
+    ) -> Result<(), String> {
         verify_if_not_mined(client, self.challenge_transaction.tx().compute_txid()).await;
 
         let kick_off_1_txid = self.kick_off_1_transaction.tx().compute_txid();
@@ -1255,9 +1454,9 @@ impl PegOutGraph {
             self.challenge_transaction.add_inputs_and_output(
                 context,
                 crowdfundng_inputs,
-                keypair,
+                signer,
                 output_script_pubkey,
-            );
+            )?;
             let challenge_tx = self.challenge_transaction.finalize();
 
             // broadcast challenge tx
@@ -1268,6 +1467,57 @@ impl PegOutGraph {
         } else {
             panic!("Kick-off 1 tx has not been confirmed!");
         }
+
+        Ok(())
+    }
+
+    pub fn new_challenge_crowdfunding(&self) -> ChallengeCrowdfunding {
+        ChallengeCrowdfunding::new(&self.id, &self.challenge_transaction, self.params.dust())
+    }
+
+    /// Merge another copy of a crowdfunding of this graph's challenge into `crowdfunding`
+    pub fn merge_challenge_crowdfunding(
+        &self,
+        crowdfunding: &mut ChallengeCrowdfunding,
+        source: &ChallengeCrowdfunding,
+    ) -> Result<(), String> {
+        crowdfunding.merge(source, &self.challenge_transaction)
+    }
+
+    pub fn sign_challenge_crowdfunding(
+        &self,
+        context: &dyn BaseContext,
+        crowdfunding: &mut ChallengeCrowdfunding,
+        signer: &dyn Signer,
+    ) -> Result<(), String> {
+        crowdfunding.sign(context, &self.challenge_transaction, signer)
+    }
+
+    pub async fn crowdfunded_challenge(
+        &mut self,
+        client: &AsyncClient,
+        crowdfunding: &ChallengeCrowdfunding,
+    ) -> Result<(), String> {
+        let challenge_tx = crowdfunding.finalize(&self.challenge_transaction)?;
+        verify_if_not_mined(client, challenge_tx.compute_txid()).await;
+
+        let kick_off_1_txid = self.kick_off_1_transaction.tx().compute_txid();
+        let kick_off_1_status = client.get_tx_status(&kick_off_1_txid).await;
+
+        if kick_off_1_status.is_ok_and(|status| status.confirmed) {
+            // broadcast challenge tx
+            let challenge_result = client.broadcast(&challenge_tx).await;
+
+            // verify challenge tx result
+            verify_tx_result(&challenge_result);
+
+            // track the broadcast tx, its txid depends on the crowdfunded inputs
+            *self.challenge_transaction.tx_mut() = challenge_tx;
+
+            Ok(())
+        } else {
+            Err("Kick-off 1 tx has not been confirmed!".to_string())
+        }
     }
 
     pub async fn start_time(
@@ -1275,7 +1525,7 @@ impl PegOutGraph {
         client: &AsyncClient,
         context: &OperatorContext,
         connector_2_winternitz_secrets: &HashMap<u8, WinternitzSecret>,
-    ) {
+    ) -> Result<(), String> {
         verify_if_not_mined(client, self.start_time_transaction.tx().compute_txid()).await;
 
         let kick_off_1_txid = self.kick_off_1_transaction.tx().compute_txid();
@@ -1284,13 +1534,13 @@ impl PegOutGraph {
         if kick_off_1_status.is_ok_and(|status| status.confirmed) {
             // sign start time tx
             let connector_2_leaf_index = 0;
-            let start_time_block = get_start_time_block();
+            let start_time_block = get_start_time_block(&self.params);
             self.start_time_transaction.sign(
                 context,
                 &self.connector_2,
                 &connector_2_winternitz_secrets[&connector_2_leaf_index],
                 start_time_block,
-            );
+            )?;
 
             // complete start time tx
             let start_time_tx = self.start_time_transaction.finalize();
@@ -1303,6 +1553,8 @@ impl PegOutGraph {
         } else {
             panic!("Kick-off 1 tx has not been confirmed!");
         }
+
+        Ok(())
     }
 
     pub async fn start_time_timeout(
@@ -1330,7 +1582,11 @@ impl PegOutGraph {
                 .unwrap()
                 .block_height
                 .is_some_and(|block_height| {
-                    block_height + self.connector_1.num_blocks_timelock_leaf_2 <= blockchain_height
+                    is_timelock_elapsed(
+                        block_height,
+                        self.connector_1.num_blocks_timelock_leaf_2,
+                        blockchain_height,
+                    )
                 })
             {
                 // complete start time timeout tx
@@ -1357,7 +1613,7 @@ impl PegOutGraph {
         context: &OperatorContext,
         connector_1_winternitz_secrets: &HashMap<u8, WinternitzSecret>,
         sb_message: &SuperblockMessage,
-    ) {
+    ) -> Result<(), String> {
         verify_if_not_mined(client, self.kick_off_2_transaction.tx().compute_txid()).await;
 
         let kick_off_1_txid = self.kick_off_1_transaction.tx().compute_txid();
@@ -1374,7 +1630,11 @@ impl PegOutGraph {
                 .unwrap()
                 .block_height
                 .is_some_and(|block_height| {
-                    block_height + self.connector_1.num_blocks_timelock_leaf_0 <= blockchain_height
+                    is_timelock_elapsed(
+                        block_height,
+                        self.connector_1.num_blocks_timelock_leaf_0,
+                        blockchain_height,
+                    )
                 })
             {
                 // complete kick-off 2 tx
@@ -1384,7 +1644,7 @@ impl PegOutGraph {
                     &self.connector_1,
                     &connector_1_winternitz_secrets[&connector_1_leaf_index],
                     sb_message,
-                );
+                )?;
                 let kick_off_2_tx = self.kick_off_2_transaction.finalize();
 
                 // broadcast kick-off 2 tx
@@ -1398,6 +1658,8 @@ impl PegOutGraph {
         } else {
             panic!("Kick-off 1 tx has not been confirmed!");
         }
+
+        Ok(())
     }
 
     pub async fn kick_off_timeout(
@@ -1425,15 +1687,19 @@ impl PegOutGraph {
                 .unwrap()
                 .block_height
                 .is_some_and(|block_height| {
-                    block_height + self.connector_1.num_blocks_timelock_leaf_1 <= blockchain_height
+                    is_timelock_elapsed(
+                        block_height,
+                        self.connector_1.num_blocks_timelock_leaf_1,
+                        blockchain_height,
+                    )
                 })
             {
                 // complete kick-off timeout tx
+                self.kick_off_timeout_transaction
+                    .add_output(output_script_pubkey);
                 let kick_off_timeout_tx = self.kick_off_timeout_transaction.finalize();
 
                 // broadcast kick-off timeout tx
-                self.kick_off_timeout_transaction
-                    .add_output(output_script_pubkey);
                 let kick_off_timeout_result = client.broadcast(&kick_off_timeout_tx).await;
 
                 // verify kick-off timeout tx result
@@ -1446,7 +1712,11 @@ impl PegOutGraph {
         }
     }
 
-    pub async fn assert(&mut self, client: &AsyncClient) {
+    pub async fn assert(
+        &mut self,
+        client: &AsyncClient,
+        connector_c_winternitz_secrets: &HashMap<u8, WinternitzSecret>,
+    ) {
         verify_if_not_mined(client, self.assert_transaction.tx().compute_txid()).await;
 
         let kick_off_2_txid = self.kick_off_2_transaction.tx().compute_txid();
@@ -1463,12 +1733,17 @@ impl PegOutGraph {
                 .unwrap()
                 .block_height
                 .is_some_and(|block_height| {
-                    block_height + self.assert_transaction.num_blocks_timelock_0()
-                        <= blockchain_height
+                    is_timelock_elapsed(
+                        block_height,
+                        self.assert_transaction.num_blocks_timelock_0(),
+                        blockchain_height,
+                    )
                 })
             {
                 // complete assert tx
-                let assert_tx = self.assert_transaction.finalize();
+                let commitment =
+                    sign_assert_stacks(connector_c_winternitz_secrets, &assert_program_stacks());
+                let assert_tx = self.assert_transaction.finalize_with_commitment(&commitment);
 
                 // broadcast assert tx
                 let assert_result = client.broadcast(&assert_tx).await;
@@ -1483,6 +1758,95 @@ impl PegOutGraph {
         }
     }
 
+    /// The operator's commitment to the assert program, read from the broadcast assert tx
+    async fn get_assert_commitment(
+        &self,
+        client: &AsyncClient,
+    ) -> Result<AssertCommitment, String> {
+        let assert_txid = self.assert_transaction.tx().compute_txid();
+        let assert_tx = client
+            .get_tx(&assert_txid)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or(format!("Assert tx {} not found", assert_txid))?;
+
+        extract_assert_commitment(&assert_tx)
+            .ok_or("Assert tx does not commit to the assert program".to_string())
+    }
+
+    /// Connector C leaf that disproves the operator's assertion, if any
+    pub async fn disprove_script_index(&self, client: &AsyncClient) -> Result<Option<u32>, String> {
+        let commitment = self.get_assert_commitment(client).await?;
+
+        Ok(self
+            .disprove_transaction
+            .find_disprove_script_index(&commitment))
+    }
+
+    /// Whether the superblock committed by the confirmed kick-off 2 tx is not the block at its
+    /// height, lies outside the superblock period, or a heavier block was mined in the period, in
+    /// which case disprove chain can be broadcast. `period_scans` holds the heaviest block hashes
+    /// of the periods checked so far, by the height the period starts at, and is extended with the
+    /// blocks mined since.
+    pub async fn is_superblock_commitment_invalid(
+        &self,
+        client: &AsyncClient,
+        period_scans: &mut HashMap<u32, SuperblockPeriodScan>,
+    ) -> Result<bool, String> {
+        let kick_off_2_txid = self.kick_off_2_transaction.tx().compute_txid();
+        let kick_off_2_tx = client
+            .get_tx(&kick_off_2_txid)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or(format!("Kick-off 2 tx {} not found", kick_off_2_txid))?;
+        let message = extract_superblock_message(&kick_off_2_tx)
+            .ok_or("Kick-off 2 tx does not commit to a superblock".to_string())?;
+        let (superblock, superblock_hash) = split_superblock_message(&message);
+
+        // the period starts at the block confirming the start time tx
+        let start_time_txid = self.start_time_transaction.tx().compute_txid();
+        let period_start = client
+            .get_tx_status(&start_time_txid)
+            .await
+            .map_err(|e| e.to_string())?
+            .block_height
+            .ok_or(format!("Start time tx {} has not been confirmed", start_time_txid))?;
+        let period_end = period_start + self.params.superblock_period;
+        if superblock.height < period_start || superblock.height >= period_end {
+            return Ok(true);
+        }
+
+        let blockchain_height = client.get_height().await.map_err(|e| e.to_string())?;
+        if superblock.height > blockchain_height {
+            return Ok(true);
+        }
+
+        let block_hash = client
+            .get_block_hash(superblock.height)
+            .await
+            .map_err(|e| e.to_string())?;
+        let header = client
+            .get_header_by_hash(&block_hash)
+            .await
+            .map_err(|e| e.to_string())?;
+        if block_hash.to_byte_array() != superblock_hash || header.time != superblock.time {
+            return Ok(true);
+        }
+
+        // the committed block must be the heaviest of the period mined so far
+        let period_scan = period_scans.entry(period_start).or_default();
+        let scan_end = period_end.min(blockchain_height + 1);
+        while period_start + period_scan.num_blocks_scanned < scan_end {
+            let block_hash = client
+                .get_block_hash(period_start + period_scan.num_blocks_scanned)
+                .await
+                .map_err(|e| e.to_string())?;
+            period_scan.scan(&block_hash.to_byte_array());
+        }
+
+        Ok(period_scan.heaviest_hash != Some(superblock_hash))
+    }
+
     pub async fn disprove(
         &mut self,
         client: &AsyncClient,
@@ -1495,9 +1859,17 @@ impl PegOutGraph {
         let assert_status = client.get_tx_status(&assert_txid).await;
 
         if assert_status.is_ok_and(|status| status.confirmed) {
+            let commitment = self
+                .get_assert_commitment(client)
+                .await
+                .unwrap_or_else(|e| panic!("Failed to read assert commitment: {}", e));
+
             // complete disprove tx
-            self.disprove_transaction
-                .add_input_output(input_script_index, output_script_pubkey);
+            self.disprove_transaction.add_input_output(
+                input_script_index,
+                &commitment,
+                output_script_pubkey,
+            );
             let disprove_tx = self.disprove_transaction.finalize();
 
             // broadcast disprove tx
@@ -1560,8 +1932,11 @@ impl PegOutGraph {
                 .unwrap()
                 .block_height
                 .is_some_and(|block_height| {
-                    block_height + self.take_1_transaction.num_blocks_timelock_2()
-                        <= blockchain_height
+                    is_timelock_elapsed(
+                        block_height,
+                        self.take_1_transaction.num_blocks_timelock_2(),
+                        blockchain_height,
+                    )
                 })
             {
                 // complete take 1 tx
@@ -1599,8 +1974,11 @@ impl PegOutGraph {
                 .unwrap()
                 .block_height
                 .is_some_and(|block_height| {
-                    block_height + self.take_2_transaction.num_blocks_timelock_1()
-                        <= blockchain_height
+                    is_timelock_elapsed(
+                        block_height,
+                        self.take_2_transaction.num_blocks_timelock_1(),
+                        blockchain_height,
+                    )
                 })
             {
                 // complete take 2 tx
@@ -1621,131 +1999,10 @@ impl PegOutGraph {
 
     pub fn is_peg_out_initiated(&self) -> bool { return self.peg_out_chain_event.is_some(); }
 
-    pub async fn match_and_set_peg_out_event(
-        &mut self,
-        all_events: &mut Vec<PegOutEvent>,
-    ) -> Result<Option<PegOutEvent>, String> {
-        let mut events: Vec<PegOutEvent> = Vec::new();
-        let mut ids: Vec<usize> = Vec::new();
-        for (i, event) in all_events.iter().enumerate() {
-            if self.peg_in_confirm_txid.eq(&event.source_outpoint.txid)
-                && self.operator_public_key.eq(&event.operator_public_key)
-            {
-                events.push(event.clone());
-                ids.push(i);
-            }
-        }
-        ids.iter().for_each(|x| {
-            all_events.swap_remove(*x);
-        });
-
-        match events.len() {
-            0 => Ok(None),
-            1 => {
-                self.peg_out_chain_event = Some(events[0].clone());
-                Ok(Some(events[0].clone()))
-            }
-            _ => Err(String::from("Event from L2 chain is not unique")),
-        }
-    }
-
-    async fn get_peg_out_statuses(
-        &self,
-        client: &AsyncClient,
-    ) -> (
-        Result<TxStatus, Error>,
-        Result<TxStatus, Error>,
-        Result<TxStatus, Error>,
-        Result<TxStatus, Error>,
-        Result<TxStatus, Error>,
-        Result<TxStatus, Error>,
-        Result<TxStatus, Error>,
-        Result<TxStatus, Error>,
-        Option<Result<TxStatus, Error>>,
-        Result<TxStatus, Error>,
-        Result<TxStatus, Error>,
-        Result<TxStatus, Error>,
-        Result<TxStatus, Error>,
-    ) {
-        let assert_status = client
-            .get_tx_status(&self.assert_transaction.tx().compute_txid())
-            .await;
-
-        let challenge_status = client
-            .get_tx_status(&self.challenge_transaction.tx().compute_txid())
-            .await;
-
-        let disprove_chain_status = client
-            .get_tx_status(&self.disprove_chain_transaction.tx().compute_txid())
-            .await;
-
-        let disprove_status = client
-            .get_tx_status(&self.disprove_transaction.tx().compute_txid())
-            .await;
-
-        let peg_out_confirm_status = client
-            .get_tx_status(&self.peg_out_confirm_transaction.tx().compute_txid())
-            .await;
-
-        let kick_off_1_status = client
-            .get_tx_status(&self.kick_off_1_transaction.tx().compute_txid())
-            .await;
-
-        let kick_off_2_status = client
-            .get_tx_status(&self.kick_off_2_transaction.tx().compute_txid())
-            .await;
-
-        let kick_off_timeout_status = client
-            .get_tx_status(&self.kick_off_timeout_transaction.tx().compute_txid())
-            .await;
-
-        let mut peg_out_status: Option<Result<TxStatus, Error>> = None;
-        if self.peg_out_transaction.is_some() {
-            peg_out_status = Some(
-                client
-                    .get_tx_status(
-                        &self
-                            .peg_out_transaction
-                            .as_ref()
-                            .unwrap()
-                            .tx()
-                            .compute_txid(),
-                    )
-                    .await,
-            );
-        }
-
-        let start_time_timeout_status = client
-            .get_tx_status(&self.start_time_timeout_transaction.tx().compute_txid())
-            .await;
-
-        let start_time_status = client
-            .get_tx_status(&self.start_time_transaction.tx().compute_txid())
-            .await;
-
-        let take_1_status = client
-            .get_tx_status(&self.take_1_transaction.tx().compute_txid())
-            .await;
-
-        let take_2_status = client
-            .get_tx_status(&self.take_2_transaction.tx().compute_txid())
-            .await;
-
-        return (
-            assert_status,
-            challenge_status,
-            disprove_chain_status,
-            disprove_status,
-            peg_out_confirm_status,
-            kick_off_1_status,
-            kick_off_2_status,
-            kick_off_timeout_status,
-            peg_out_status,
-            start_time_timeout_status,
-            start_time_status,
-            take_1_status,
-            take_2_status,
-        );
+    /// Whether `event` asks the operator of this graph to peg out the funds of its peg-in
+    pub fn is_peg_out_event_of(&self, event: &PegOutEvent) -> bool {
+        self.peg_in_confirm_txid.eq(&event.source_outpoint.txid)
+            && self.operator_public_key.eq(&event.operator_public_key)
     }
 
     pub fn validate(&self) -> bool {
@@ -1882,10 +2139,16 @@ impl PegOutGraph {
     }
 }
 
-pub fn generate_id(peg_in_graph: &PegInGraph, operator_public_key: &PublicKey) -> String {
+pub fn generate_id(
+    peg_in_graph: &PegInGraph,
+    operator_public_key: &PublicKey,
+    params: &BridgeParams,
+) -> String {
     let mut hasher = Sha256::new();
 
-    hasher.update(peg_in_graph.id().to_string() + &operator_public_key.to_string());
+    hasher.update(
+        peg_in_graph.id().to_string() + &operator_public_key.to_string() + &params.id(),
+    );
 
     hasher.finalize().to_hex_string(Upper)
 }
@@ -0,0 +1,504 @@
+use bitcoin::Txid;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+use super::base::GraphTransaction;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug)]
+pub enum GraphOutcome {
+    Complete, // the graph ran to its intended end
+    Failed,   // the graph was aborted, e.g. by a refund, timeout or disprove
+}
+
+pub trait GraphState: Copy + Eq + Default + Debug {
+    /// The state after `transaction` was observed, or `None` if it cannot follow this state
+    fn next(self, transaction: GraphTransaction) -> Option<Self>;
+
+    fn outcome(self) -> Option<GraphOutcome>;
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub enum PegInState {
+    #[default]
+    Created,
+    Deposited,
+    Confirmed,
+    Refunded,
+}
+
+impl GraphState for PegInState {
+    fn next(self, transaction: GraphTransaction) -> Option<Self> {
+        match (self, transaction) {
+            (PegInState::Created, GraphTransaction::PegInDeposit) => Some(PegInState::Deposited),
+            (PegInState::Deposited, GraphTransaction::PegInConfirm) => Some(PegInState::Confirmed),
+            (PegInState::Deposited, GraphTransaction::PegInRefund) => Some(PegInState::Refunded),
+            _ => None,
+        }
+    }
+
+    fn outcome(self) -> Option<GraphOutcome> {
+        match self {
+            PegInState::Confirmed => Some(GraphOutcome::Complete),
+            PegInState::Refunded => Some(GraphOutcome::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Start time and challenge spend kick-off 1 outputs nothing else competes for until take 1,
+/// so they can show up at any point after kick-off 1 and are tracked alongside the main path.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub enum PegOutState {
+    #[default]
+    Created,
+    PegOutSent,
+    PegOutConfirmed,
+    KickedOff1 {
+        start_time: bool,
+        challenged: bool,
+    },
+    KickedOff2 {
+        start_time: bool,
+        challenged: bool,
+    },
+    Asserted {
+        start_time: bool,
+        challenged: bool,
+    },
+    // operator reimbursed by take 1 or take 2
+    Complete,
+    // operator slashed by a timeout or disprove
+    Failed,
+}
+
+impl GraphState for PegOutState {
+    fn next(self, transaction: GraphTransaction) -> Option<Self> {
+        use GraphTransaction::*;
+        use PegOutState::*;
+
+        match (self, transaction) {
+            (Created, PegOut) => Some(PegOutSent),
+            (Created | PegOutSent, PegOutConfirm) => Some(PegOutConfirmed),
+            (PegOutConfirmed, KickOff1) => Some(KickedOff1 {
+                start_time: false,
+                challenged: false,
+            }),
+
+            (
+                KickedOff1 {
+                    start_time: false,
+                    challenged,
+                },
+                StartTime,
+            ) => Some(KickedOff1 {
+                start_time: true,
+                challenged,
+            }),
+            (
+                KickedOff1 {
+                    start_time,
+                    challenged: false,
+                },
+                Challenge,
+            ) => Some(KickedOff1 {
+                start_time,
+                challenged: true,
+            }),
+            (
+                KickedOff1 {
+                    start_time: false, ..
+                },
+                StartTimeTimeout,
+            ) => Some(Failed),
+            (KickedOff1 { .. }, KickOffTimeout) => Some(Failed),
+            (
+                KickedOff1 {
+                    start_time,
+                    challenged,
+                },
+                KickOff2,
+            ) => Some(KickedOff2 {
+                start_time,
+                challenged,
+            }),
+
+            (
+                KickedOff2 {
+                    start_time: false,
+                    challenged,
+                },
+                StartTime,
+            ) => Some(KickedOff2 {
+                start_time: true,
+                challenged,
+            }),
+            (
+                KickedOff2 {
+                    start_time,
+                    challenged: false,
+                },
+                Challenge,
+            ) => Some(KickedOff2 {
+                start_time,
+                challenged: true,
+            }),
+            // take 1 spends the output a challenge would spend
+            (
+                KickedOff2 {
+                    challenged: false, ..
+                },
+                Take1,
+            ) => Some(Complete),
+            (
+                KickedOff2 {
+                    start_time,
+                    challenged,
+                },
+                Assert,
+            ) => Some(Asserted {
+                start_time,
+                challenged,
+            }),
+            (KickedOff2 { .. }, DisproveChain) => Some(Failed),
+
+            (
+                Asserted {
+                    start_time: false,
+                    challenged,
+                },
+                StartTime,
+            ) => Some(Asserted {
+                start_time: true,
+                challenged,
+            }),
+            (
+                Asserted {
+                    start_time,
+                    challenged: false,
+                },
+                Challenge,
+            ) => Some(Asserted {
+                start_time,
+                challenged: true,
+            }),
+            (Asserted { .. }, Take2) => Some(Complete),
+            (Asserted { .. }, Disprove) => Some(Failed),
+
+            _ => None,
+        }
+    }
+
+    fn outcome(self) -> Option<GraphOutcome> {
+        match self {
+            PegOutState::Complete => Some(GraphOutcome::Complete),
+            PegOutState::Failed => Some(GraphOutcome::Failed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct Transition<S> {
+    pub from: S,
+    pub to: S,
+    pub transaction: GraphTransaction,
+    pub txid: Txid,
+    pub height: u32,
+}
+
+/// Current state of a graph and the transitions that led to it, in the order the transactions
+/// were observed on chain
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, Default)]
+pub struct StateMachine<S> {
+    state: S,
+    history: Vec<Transition<S>>,
+}
+
+impl<S: GraphState> StateMachine<S> {
+    pub fn state(&self) -> S { self.state }
+
+    pub fn history(&self) -> &Vec<Transition<S>> { &self.history }
+
+    pub fn outcome(&self) -> Option<GraphOutcome> { self.state.outcome() }
+
+    pub fn has_seen(&self, transaction: GraphTransaction) -> bool {
+        self.history
+            .iter()
+            .any(|transition| transition.transaction == transaction)
+    }
+
+    /// Height `transaction` was included at, if it was observed
+    pub fn height_of(&self, transaction: GraphTransaction) -> Option<u32> {
+        self.history
+            .iter()
+            .find(|transition| transition.transaction == transaction)
+            .map(|transition| transition.height)
+    }
+
+    /// Move on with `transaction` included at `height`
+    pub fn apply(
+        &mut self,
+        transaction: GraphTransaction,
+        txid: Txid,
+        height: u32,
+    ) -> Result<S, String> {
+        // seen again, e.g. when rescanning after a restart
+        if self
+            .history
+            .iter()
+            .any(|transition| transition.transaction == transaction && transition.txid == txid)
+        {
+            return Ok(self.state);
+        }
+
+        if self
+            .history
+            .last()
+            .is_some_and(|transition| transition.height > height)
+        {
+            return Err(format!(
+                "{:?} at height {} is older than the last transition",
+                transaction, height
+            ));
+        }
+
+        let next = self.state.next(transaction).ok_or(format!(
+            "{:?} cannot follow state {:?}",
+            transaction, self.state
+        ))?;
+        self.history.push(Transition {
+            from: self.state,
+            to: next,
+            transaction,
+            txid,
+            height,
+        });
+        self.state = next;
+
+        Ok(next)
+    }
+
+    /// Undo `transaction` and the transitions after it, as its block left the best chain
+    pub fn roll_back(&mut self, transaction: GraphTransaction) {
+        if let Some(index) = self
+            .history
+            .iter()
+            .position(|transition| transition.transaction == transaction)
+        {
+            self.state = self.history[index].from;
+            self.history.truncate(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash;
+
+    use super::*;
+    use GraphTransaction::*;
+
+    const PEG_OUT_TRANSACTIONS: [GraphTransaction; 14] = [
+        PegOut,
+        PegOutConfirm,
+        KickOff1,
+        StartTime,
+        StartTimeTimeout,
+        KickOff2,
+        KickOffTimeout,
+        Challenge,
+        Assert,
+        Disprove,
+        DisproveChain,
+        Take1,
+        Take2,
+        PegInConfirm,
+    ];
+
+    // Every sequence of transactions the state machine accepts up to a final state
+    fn legal_paths<S: GraphState>(
+        transactions: &[GraphTransaction],
+        state: S,
+        path: &mut Vec<GraphTransaction>,
+        paths: &mut Vec<(Vec<GraphTransaction>, S)>,
+    ) {
+        let mut is_final = true;
+        for transaction in transactions.iter() {
+            if let Some(next) = state.next(*transaction) {
+                is_final = false;
+                path.push(*transaction);
+                legal_paths(transactions, next, path, paths);
+                path.pop();
+            }
+        }
+        if is_final {
+            paths.push((path.clone(), state));
+        }
+    }
+
+    fn replay<S: GraphState>(path: &[GraphTransaction]) -> StateMachine<S> {
+        let mut state_machine = StateMachine::<S>::default();
+        for (height, transaction) in path.iter().enumerate() {
+            state_machine
+                .apply(*transaction, Txid::all_zeros(), height as u32)
+                .unwrap();
+        }
+        state_machine
+    }
+
+    #[test]
+    fn test_peg_in_paths() {
+        let mut paths = vec![];
+        legal_paths(
+            &[PegInDeposit, PegInConfirm, PegInRefund],
+            PegInState::default(),
+            &mut vec![],
+            &mut paths,
+        );
+
+        assert_eq!(
+            paths,
+            vec![
+                (vec![PegInDeposit, PegInConfirm], PegInState::Confirmed),
+                (vec![PegInDeposit, PegInRefund], PegInState::Refunded),
+            ]
+        );
+        assert_eq!(
+            replay::<PegInState>(&paths[0].0).outcome(),
+            Some(GraphOutcome::Complete)
+        );
+        assert_eq!(
+            replay::<PegInState>(&paths[1].0).outcome(),
+            Some(GraphOutcome::Failed)
+        );
+    }
+
+    #[test]
+    fn test_peg_out_paths() {
+        let mut paths = vec![];
+        legal_paths(
+            &PEG_OUT_TRANSACTIONS,
+            PegOutState::default(),
+            &mut vec![],
+            &mut paths,
+        );
+
+        for (path, state) in paths.iter() {
+            let state_machine = replay::<PegOutState>(path);
+            assert_eq!(state_machine.state(), *state);
+            assert_eq!(state_machine.history().len(), path.len());
+
+            // every path ends in an outcome decided by its last transaction
+            let expected_outcome = match path.last().unwrap() {
+                Take1 | Take2 => GraphOutcome::Complete,
+                StartTimeTimeout | KickOffTimeout | Disprove | DisproveChain => {
+                    GraphOutcome::Failed
+                }
+                transaction => panic!("Path ends with {:?}", transaction),
+            };
+            assert_eq!(state_machine.outcome(), Some(expected_outcome));
+
+            // only side transactions are reordered, the main path always starts the same way
+            let start = path
+                .iter()
+                .position(|transaction| *transaction == KickOff1)
+                .unwrap();
+            assert!(path[..start] == [PegOutConfirm] || path[..start] == [PegOut, PegOutConfirm]);
+
+            // each transaction spends its inputs once
+            for transaction in PEG_OUT_TRANSACTIONS.iter() {
+                assert!(path.iter().filter(|t| *t == transaction).count() <= 1);
+            }
+
+            // take 1 spends the challenge output
+            assert!(!(path.contains(&Take1) && path.contains(&Challenge)));
+            // start time timeout spends the start time output
+            assert!(!(path.contains(&StartTimeTimeout) && path.contains(&StartTime)));
+            // peg-in transactions are not part of a peg-out graph
+            assert!(!path.contains(&PegInConfirm));
+        }
+
+        let count = |last: GraphTransaction| {
+            paths
+                .iter()
+                .filter(|(path, _)| *path.last().unwrap() == last)
+                .count()
+        };
+        // 2 ways to start, times the ways start time and challenge interleave with the main path
+        assert_eq!(count(KickOffTimeout), 2 * 5);
+        assert_eq!(count(StartTimeTimeout), 2 * 2);
+        assert_eq!(count(DisproveChain), 2 * 11);
+        assert_eq!(count(Take1), 2 * 3);
+        assert_eq!(count(Disprove), 2 * 19);
+        assert_eq!(count(Take2), 2 * 19);
+    }
+
+    #[test]
+    fn test_peg_out_rejects_illegal_transitions() {
+        let mut state_machine = StateMachine::<PegOutState>::default();
+        assert!(state_machine.apply(KickOff1, Txid::all_zeros(), 1).is_err());
+
+        state_machine
+            .apply(PegOutConfirm, Txid::all_zeros(), 1)
+            .unwrap();
+        state_machine.apply(KickOff1, Txid::all_zeros(), 2).unwrap();
+        state_machine
+            .apply(Challenge, Txid::all_zeros(), 3)
+            .unwrap();
+        state_machine.apply(KickOff2, Txid::all_zeros(), 4).unwrap();
+        assert!(state_machine.apply(Take1, Txid::all_zeros(), 5).is_err());
+        assert!(state_machine.apply(Assert, Txid::all_zeros(), 3).is_err());
+        assert_eq!(
+            state_machine.state(),
+            PegOutState::KickedOff2 {
+                start_time: false,
+                challenged: true
+            }
+        );
+        assert_eq!(state_machine.outcome(), None);
+        assert_eq!(state_machine.height_of(KickOff2), Some(4));
+        assert_eq!(state_machine.height_of(Assert), None);
+
+        state_machine.apply(Assert, Txid::all_zeros(), 5).unwrap();
+        state_machine.apply(Disprove, Txid::all_zeros(), 6).unwrap();
+        assert_eq!(state_machine.outcome(), Some(GraphOutcome::Failed));
+        assert!(state_machine.apply(Take2, Txid::all_zeros(), 7).is_err());
+
+        // replaying a recorded transition changes nothing
+        assert_eq!(
+            state_machine.apply(KickOff1, Txid::all_zeros(), 2),
+            Ok(PegOutState::Failed)
+        );
+        assert_eq!(state_machine.history().len(), 6);
+    }
+
+    #[test]
+    fn test_roll_back() {
+        let path = [PegOutConfirm, KickOff1, StartTime, KickOff2, Take1];
+        let mut state_machine = replay::<PegOutState>(&path);
+        assert_eq!(state_machine.outcome(), Some(GraphOutcome::Complete));
+
+        // kick-off 2 and take 1 were reorged
+        state_machine.roll_back(KickOff2);
+        assert_eq!(
+            state_machine.state(),
+            PegOutState::KickedOff1 {
+                start_time: true,
+                challenged: false
+            }
+        );
+        assert_eq!(state_machine.history().len(), 3);
+        assert!(!state_machine.has_seen(KickOff2));
+        assert!(!state_machine.has_seen(Take1));
+
+        // a transaction that was not seen leaves the state as it is
+        state_machine.roll_back(Assert);
+        assert_eq!(state_machine.history().len(), 3);
+
+        // the competing transactions of the new best chain apply on top
+        state_machine
+            .apply(KickOffTimeout, Txid::all_zeros(), 4)
+            .unwrap();
+        assert_eq!(state_machine.outcome(), Some(GraphOutcome::Failed));
+    }
+}
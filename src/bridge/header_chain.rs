@@ -0,0 +1,203 @@
+use crate::bigint::BigIntImpl;
+use crate::hash::sha256::sha256d_80bytes;
+use crate::treepp::{script, Script};
+
+// Headers and hashes are laid out as bytes with the first byte on top, as taken by `sha256`.
+// 16-bit limbs let `from_bytes` read the little-endian hashes and targets two bytes per limb.
+type U256 = BigIntImpl<256, 16>;
+
+pub const HEADER_LEN: u32 = 80;
+pub const HASH_LEN: u32 = 32;
+
+// Offsets of the header fields
+const PREV_BLOCKHASH_OFFSET: u32 = 4;
+const BITS_OFFSET: u32 = 72;
+
+/// Expand the compact target `bits` (4 bytes, first byte on top) into a U256 target.
+/// Fails on negative targets and on exponents outside of [3, 32], none of which can be
+/// met by a valid header.
+pub fn bits_to_target() -> Script {
+    script! {
+        // [exponent, m2, m1, m0] -> [m2, m1, m0, exponent - 3]
+        3 OP_ROLL
+        OP_DUP 3 33 OP_WITHIN OP_VERIFY
+        3 OP_SUB
+        3 OP_PICK { 0x80 } OP_LESSTHAN OP_VERIFY
+
+        // Byte i of the target is the mantissa byte i - (exponent - 3), or 0 outside of the mantissa
+        for i in 0..HASH_LEN {
+            OP_DUP { i } OP_SWAP OP_SUB
+            OP_DUP 0 3 OP_WITHIN
+            OP_IF
+                OP_1ADD OP_PICK
+            OP_ELSE
+                OP_DROP 0
+            OP_ENDIF
+            OP_TOALTSTACK
+        }
+        OP_2DROP OP_2DROP
+        for _ in 0..HASH_LEN {
+            OP_FROMALTSTACK
+        }
+        { U256::from_bytes() }
+    }
+}
+
+/// Check the proof of work of the header on top of the stack and replace it with its block hash
+pub fn verify_header_pow() -> Script {
+    script! {
+        // Keep a copy of bits for after hashing
+        for i in 0..4 {
+            { BITS_OFFSET + i } OP_PICK OP_TOALTSTACK
+        }
+        { sha256d_80bytes() }
+
+        // hash <= target, both read as little-endian numbers
+        for _ in 0..HASH_LEN {
+            { HASH_LEN - 1 } OP_PICK
+        }
+        { U256::from_bytes() }
+        for _ in 0..4 {
+            OP_FROMALTSTACK
+        }
+        { bits_to_target() }
+        { U256::lessthanorequal(1, 0) }
+        OP_VERIFY
+    }
+}
+
+/// Check that the prev_blockhash of the header on top of the stack is the block hash below it.
+/// The block hash is consumed, the header is kept.
+pub fn verify_prev_blockhash() -> Script {
+    script! {
+        for i in 0..HASH_LEN {
+            { PREV_BLOCKHASH_OFFSET + i } OP_PICK
+            { HEADER_LEN + 1 } OP_ROLL
+            OP_EQUALVERIFY
+        }
+    }
+}
+
+/// Extend a header chain by one header: check that the header on top of the stack links to
+/// the block hash below it and has valid proof of work, leaving its own block hash.
+/// Applying this to consecutive headers verifies a chain while keeping a single header on the stack.
+pub fn verify_header() -> Script {
+    script! {
+        { verify_prev_blockhash() }
+        { verify_header_pow() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::treepp::*;
+    use bitcoin::block::Header;
+    use bitcoin::blockdata::constants::genesis_block;
+    use bitcoin::consensus::{deserialize, serialize};
+    use bitcoin::hashes::Hash;
+    use bitcoin::{CompactTarget, Network, Target};
+    use num_bigint::BigUint;
+
+    // Mainnet block 1
+    const BLOCK_1: &str = "010000006fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e61bc6649ffff001d01e36299";
+
+    fn push_bytes(bytes: &[u8]) -> Script {
+        script! {
+            for byte in bytes.iter().rev() {
+                { *byte as u32 }
+            }
+        }
+    }
+
+    fn block_hash_equalverify(header: &Header) -> Script {
+        let hash = header.block_hash().to_byte_array();
+        script! {
+            { push_bytes(&hash) }
+            for i in 0..HASH_LEN {
+                { HASH_LEN - i } OP_ROLL OP_EQUALVERIFY
+            }
+        }
+    }
+
+    #[test]
+    fn test_bits_to_target() {
+        for bits in [0x1d00ffff_u32, 0x1b0404cb, 0x17034219, 0x207fffff, 0x03123456] {
+            let target = Target::from_compact(CompactTarget::from_consensus(bits));
+            let target = BigUint::from_bytes_le(&target.to_le_bytes());
+            let script = script! {
+                { push_bytes(&bits.to_le_bytes()) }
+                { bits_to_target() }
+                { U256::push_u32_le(&target.to_u32_digits()) }
+                { U256::equal(1, 0) }
+            };
+            let res = execute_script(script);
+            assert!(res.success, "bits {:#x}", bits);
+        }
+
+        // Negative mantissa
+        let script = script! {
+            { push_bytes(&0x1d80ffff_u32.to_le_bytes()) }
+            { bits_to_target() }
+            { U256::drop() }
+            OP_TRUE
+        };
+        assert!(!execute_script(script).success);
+    }
+
+    #[test]
+    fn test_verify_header_pow() {
+        let header = genesis_block(Network::Bitcoin).header;
+        let script = script! {
+            { push_bytes(&serialize(&header)) }
+            { verify_header_pow() }
+            { block_hash_equalverify(&header) }
+            OP_TRUE
+        };
+        println!("verify_header_pow: {} bytes", script.len());
+        let res = execute_script(script);
+        assert!(res.success);
+        assert_eq!(res.final_stack.len(), 1);
+
+        // Any other nonce misses the target
+        let mut header = header;
+        header.nonce += 1;
+        let script = script! {
+            { push_bytes(&serialize(&header)) }
+            { verify_header_pow() }
+            { block_hash_equalverify(&header) }
+            OP_TRUE
+        };
+        assert!(!execute_script(script).success);
+    }
+
+    #[test]
+    fn test_verify_header_chain() {
+        let genesis = genesis_block(Network::Bitcoin).header;
+        let block_1: Header = deserialize(&hex::decode(BLOCK_1).unwrap()).unwrap();
+
+        let script = script! {
+            { push_bytes(&serialize(&genesis)) }
+            { verify_header_pow() }
+            { push_bytes(&serialize(&block_1)) }
+            { verify_header() }
+            { block_hash_equalverify(&block_1) }
+            OP_TRUE
+        };
+        let res = execute_script(script);
+        assert!(res.success);
+        assert_eq!(res.final_stack.len(), 1);
+
+        // Block 1 does not extend itself
+        let script = script! {
+            { push_bytes(&block_1.block_hash().to_byte_array()) }
+            { push_bytes(&serialize(&block_1)) }
+            { verify_prev_blockhash() }
+            for _ in 0..HEADER_LEN / 2 {
+                OP_2DROP
+            }
+            OP_TRUE
+        };
+        assert!(!execute_script(script).success);
+    }
+}
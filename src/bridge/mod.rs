@@ -1,10 +1,14 @@
+pub mod assert_program;
 pub mod client;
 pub mod connectors;
 pub mod constants;
 pub mod contexts;
 pub mod graphs;
+pub mod header_chain;
+pub mod params;
 pub mod scripts;
 pub mod serialization;
+pub mod signer;
 pub mod superblock;
 pub mod transactions;
 pub mod utils;
@@ -0,0 +1,171 @@
+use bitcoin::{
+    hex::{Case::Upper, DisplayHex},
+    Amount, Network,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, fs};
+
+use super::{
+    constants::{
+        NUM_BLOCKS_PER_2_WEEKS, NUM_BLOCKS_PER_3_DAYS, NUM_BLOCKS_PER_6_HOURS, NUM_BLOCKS_PER_DAY,
+    },
+    graphs::base::{DUST_AMOUNT, FEE_AMOUNT, MESSAGE_COMMITMENT_FEE_AMOUNT},
+    utils::num_blocks_per_network,
+};
+
+// Default min relay fee of kick-off 2, which commits to the superblock
+pub const MIN_RELAY_FEE_AMOUNT: u64 = 52_953;
+
+// Block at which the mainnet superblock measurement period starts
+const MAINNET_START_TIME_BLOCK: u32 = 860033;
+
+/// Protocol parameters all participants of a graph must agree on. Timelocks are in blocks,
+/// amounts in sats.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
+pub struct BridgeParams {
+    /// Superblock measurement period, after which kick-off 2 can spend connector 1
+    pub superblock_period: u32,
+    /// Timelock of kick-off timeout on connector 1
+    pub kick_off_timeout_timelock: u32,
+    /// Window after kick-off 1 in which the operator commits to the start time,
+    /// after which start time timeout can spend connector 1
+    pub start_time_window: u32,
+    /// Block at which the superblock measurement period starts
+    pub start_time_block: u32,
+    /// Timelock of take 1 on connector 3
+    pub take_1_timelock: u32,
+    /// Timelock of take 2 on connector 4
+    pub take_2_timelock: u32,
+    /// Timelock of assert on connector B
+    pub assert_timelock: u32,
+    /// Timelock of peg-in refund on connector Z
+    pub peg_in_refund_timelock: u32,
+
+    pub dust_amount: u64,
+    pub fee_amount: u64,
+    pub message_commitment_fee_amount: u64,
+    pub min_relay_fee_amount: u64,
+    /// Amount verifiers crowdfund for the challenge transaction
+    pub challenge_crowdfunding_amount: u64,
+}
+
+impl BridgeParams {
+    /// Default parameters, with one block timelocks outside of mainnet
+    pub fn for_network(network: Network) -> Self {
+        BridgeParams {
+            superblock_period: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            kick_off_timeout_timelock: num_blocks_per_network(
+                network,
+                NUM_BLOCKS_PER_2_WEEKS + NUM_BLOCKS_PER_DAY,
+            ),
+            start_time_window: num_blocks_per_network(network, NUM_BLOCKS_PER_6_HOURS),
+            start_time_block: MAINNET_START_TIME_BLOCK,
+            take_1_timelock: num_blocks_per_network(network, NUM_BLOCKS_PER_3_DAYS),
+            take_2_timelock: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            assert_timelock: num_blocks_per_network(network, NUM_BLOCKS_PER_3_DAYS),
+            peg_in_refund_timelock: num_blocks_per_network(network, NUM_BLOCKS_PER_2_WEEKS),
+            dust_amount: DUST_AMOUNT,
+            fee_amount: FEE_AMOUNT,
+            message_commitment_fee_amount: MESSAGE_COMMITMENT_FEE_AMOUNT,
+            min_relay_fee_amount: MIN_RELAY_FEE_AMOUNT,
+            challenge_crowdfunding_amount: Amount::ONE_BTC.to_sat(),
+        }
+    }
+
+    /// Parse the parameters of `network` from a TOML document with one table per network,
+    /// named as the network is displayed, e.g. `[bitcoin]` or `[regtest]`
+    pub fn from_toml(toml: &str, network: Network) -> Result<Self, String> {
+        let mut params: HashMap<String, BridgeParams> =
+            toml::from_str(toml).map_err(|e| format!("Invalid bridge params: {}", e))?;
+        params
+            .remove(&network.to_string())
+            .ok_or(format!("No bridge params for network {}", network))
+    }
+
+    pub fn load(path: &str, network: Network) -> Result<Self, String> {
+        let toml = fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path, e))?;
+        Self::from_toml(&toml, network)
+    }
+
+    pub fn to_toml(&self, network: Network) -> String {
+        toml::to_string(&HashMap::from([(network.to_string(), self)]))
+            .expect("Unable to serialize bridge params")
+    }
+
+    /// Hash of the parameters, which graph IDs commit to
+    pub fn id(&self) -> String {
+        let mut hasher = Sha256::new();
+
+        hasher.update(serde_json::to_string(self).expect("Unable to serialize bridge params"));
+
+        hasher.finalize().to_hex_string(Upper)
+    }
+
+    pub fn dust(&self) -> Amount { Amount::from_sat(self.dust_amount) }
+
+    pub fn fee(&self) -> Amount { Amount::from_sat(self.fee_amount) }
+
+    pub fn message_commitment_fee(&self) -> Amount {
+        Amount::from_sat(self.message_commitment_fee_amount)
+    }
+
+    pub fn min_relay_fee(&self) -> Amount { Amount::from_sat(self.min_relay_fee_amount) }
+
+    pub fn challenge_crowdfunding(&self) -> Amount {
+        Amount::from_sat(self.challenge_crowdfunding_amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::{
+        contexts::{
+            base::{generate_keys_from_secret, BaseContext},
+            operator::OperatorContext,
+        },
+        graphs::base::{OPERATOR_SECRET, VERIFIER_0_SECRET, VERIFIER_1_SECRET},
+        signer::local::LocalSigner,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn test_bridge_params_toml() {
+        let params = BridgeParams {
+            superblock_period: 144,
+            ..BridgeParams::for_network(Network::Regtest)
+        };
+        let toml = params.to_toml(Network::Regtest);
+        assert_eq!(BridgeParams::from_toml(&toml, Network::Regtest), Ok(params.clone()));
+        assert!(BridgeParams::from_toml(&toml, Network::Bitcoin).is_err());
+
+        // Graphs built with different parameters get different IDs
+        assert_ne!(params.id(), BridgeParams::for_network(Network::Regtest).id());
+    }
+
+    #[test]
+    fn test_bridge_params_load_into_context() {
+        let params = BridgeParams {
+            take_1_timelock: 6,
+            ..BridgeParams::for_network(Network::Regtest)
+        };
+        let path = std::env::temp_dir().join("bitvm_test_bridge_params.toml");
+        fs::write(&path, params.to_toml(Network::Regtest)).unwrap();
+        let loaded = BridgeParams::load(path.to_str().unwrap(), Network::Regtest);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, Ok(params.clone()));
+
+        let n_of_n_public_keys = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+            .iter()
+            .map(|secret| generate_keys_from_secret(Network::Regtest, secret).2)
+            .collect::<Vec<_>>();
+        let context = OperatorContext::new_with_params(
+            Network::Regtest,
+            loaded.unwrap(),
+            Arc::new(LocalSigner::from_secret(OPERATOR_SECRET).unwrap()),
+            &n_of_n_public_keys,
+        );
+        assert_eq!(context.params(), &params);
+    }
+}
@@ -0,0 +1,239 @@
+use bitcoin::{
+    key::{Keypair, Secp256k1},
+    secp256k1::{ecdsa, schnorr, All, Message},
+    PublicKey,
+};
+use musig2::{
+    secp::Point, sign_partial, AggNonce, BinaryEncoding, KeyAggContext, PartialSignature, PubNonce,
+    SecNonce,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use super::{super::transactions::signing_musig2::generate_nonce, Signer};
+
+// Hex encoded public nonce -> secret nonce, removed once used
+type SecretNonces = HashMap<String, SecNonce>;
+
+/// Signer holding the secret key in memory
+pub struct LocalSigner {
+    secp: Secp256k1<All>,
+    keypair: Keypair,
+    secret_nonces: Mutex<SecretNonces>,
+    // Backing store of `secret_nonces`, so that published nonces survive a restart
+    nonce_file: Option<PathBuf>,
+}
+
+impl LocalSigner {
+    pub fn new(keypair: Keypair) -> Self {
+        LocalSigner {
+            secp: Secp256k1::new(),
+            keypair,
+            secret_nonces: Mutex::new(HashMap::new()),
+            nonce_file: None,
+        }
+    }
+
+    pub fn from_secret(secret: &str) -> Result<Self, String> {
+        let secp = Secp256k1::new();
+        let keypair = Keypair::from_seckey_str(&secp, secret)
+            .map_err(|e| format!("Invalid secret key: {}", e))?;
+
+        Ok(Self::new(keypair))
+    }
+
+    /// Keep the secret MuSig2 nonces in `nonce_file` instead of memory only. The file is read
+    /// before and written after every nonce operation, and a nonce is removed from it before
+    /// its partial signature is returned.
+    pub fn with_nonce_file(mut self, nonce_file: impl Into<PathBuf>) -> Self {
+        self.nonce_file = Some(nonce_file.into());
+        self
+    }
+
+    fn update_secret_nonces<R>(
+        &self,
+        update: impl FnOnce(&mut SecretNonces) -> R,
+    ) -> Result<R, String> {
+        let mut secret_nonces = self.secret_nonces.lock().map_err(|e| e.to_string())?;
+        if let Some(nonce_file) = &self.nonce_file {
+            *secret_nonces = read_secret_nonces(nonce_file)?;
+        }
+        let result = update(&mut secret_nonces);
+        if let Some(nonce_file) = &self.nonce_file {
+            write_secret_nonces(nonce_file, &secret_nonces)?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// Add `secret_nonces` to the nonce file of a `LocalSigner`, e.g. to migrate nonces that were
+/// kept elsewhere
+pub fn store_secret_nonces(
+    nonce_file: &Path,
+    secret_nonces: impl IntoIterator<Item = SecNonce>,
+) -> Result<(), String> {
+    let mut stored_secret_nonces = read_secret_nonces(nonce_file)?;
+    for secret_nonce in secret_nonces {
+        stored_secret_nonces.insert(nonce_key(&secret_nonce.public_nonce()), secret_nonce);
+    }
+
+    write_secret_nonces(nonce_file, &stored_secret_nonces)
+}
+
+fn nonce_key(public_nonce: &PubNonce) -> String { hex::encode(public_nonce.to_bytes()) }
+
+fn read_secret_nonces(nonce_file: &Path) -> Result<SecretNonces, String> {
+    if !nonce_file.exists() {
+        return Ok(HashMap::new());
+    }
+    let json = fs::read_to_string(nonce_file)
+        .map_err(|e| format!("Unable to read {}: {}", nonce_file.display(), e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Invalid secret nonces in {}: {}", nonce_file.display(), e))
+}
+
+// Write to a temporary file first, so that a crash cannot leave a truncated nonce file
+fn write_secret_nonces(nonce_file: &Path, secret_nonces: &SecretNonces) -> Result<(), String> {
+    if let Some(directory) = nonce_file.parent() {
+        fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string(secret_nonces).map_err(|e| e.to_string())?;
+    let temporary_file = nonce_file.with_extension("tmp");
+    fs::write(&temporary_file, json)
+        .map_err(|e| format!("Unable to write {}: {}", temporary_file.display(), e))?;
+    fs::rename(&temporary_file, nonce_file)
+        .map_err(|e| format!("Unable to write {}: {}", nonce_file.display(), e))
+}
+
+impl Signer for LocalSigner {
+    fn public_key(&self) -> PublicKey { PublicKey::new(self.keypair.public_key()) }
+
+    fn sign_ecdsa(&self, message: &Message) -> Result<ecdsa::Signature, String> {
+        Ok(self.secp.sign_ecdsa(message, &self.keypair.secret_key()))
+    }
+
+    fn sign_schnorr(&self, message: &Message) -> Result<schnorr::Signature, String> {
+        Ok(self.secp.sign_schnorr_no_aux_rand(message, &self.keypair))
+    }
+
+    fn generate_musig2_nonce(&self) -> Result<PubNonce, String> {
+        let secret_nonce = generate_nonce();
+        let public_nonce = secret_nonce.public_nonce();
+        self.update_secret_nonces(|secret_nonces| {
+            secret_nonces.insert(nonce_key(&public_nonce), secret_nonce)
+        })?;
+
+        Ok(public_nonce)
+    }
+
+    fn sign_musig2_partial(
+        &self,
+        public_keys: &[PublicKey],
+        public_nonce: &PubNonce,
+        aggregated_nonce: &AggNonce,
+        message: &Message,
+    ) -> Result<PartialSignature, String> {
+        let secret_nonce = self
+            .update_secret_nonces(|secret_nonces| secret_nonces.remove(&nonce_key(public_nonce)))?
+            .ok_or("Unknown or already used nonce".to_string())?;
+
+        let points: Vec<Point> = public_keys
+            .iter()
+            .map(|&public_key| public_key.inner.into())
+            .collect();
+        let key_agg_ctx = KeyAggContext::new(points).map_err(|e| e.to_string())?;
+        let message: [u8; 32] = *message.as_ref();
+
+        sign_partial(
+            &key_agg_ctx,
+            self.keypair.secret_key(),
+            secret_nonce,
+            aggregated_nonce,
+            message,
+        )
+        .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use musig2::{aggregate_partial_signatures, LiftedSignature};
+
+    use super::*;
+
+    #[test]
+    fn test_nonce_is_used_once() {
+        let signers = [
+            LocalSigner::from_secret(&"01".repeat(32)).unwrap(),
+            LocalSigner::from_secret(&"02".repeat(32)).unwrap(),
+        ];
+        let public_keys: Vec<PublicKey> = signers.iter().map(|s| s.public_key()).collect();
+        let digest = [7; 32];
+        let message = Message::from_digest(digest);
+
+        let nonces: Vec<PubNonce> = signers
+            .iter()
+            .map(|s| s.generate_musig2_nonce().unwrap())
+            .collect();
+        let aggregated_nonce = AggNonce::sum(&nonces);
+        let partial_signatures: Vec<PartialSignature> = signers
+            .iter()
+            .zip(&nonces)
+            .map(|(s, nonce)| {
+                s.sign_musig2_partial(&public_keys, nonce, &aggregated_nonce, &message)
+                    .unwrap()
+            })
+            .collect();
+
+        let points: Vec<Point> = public_keys.iter().map(|&k| k.inner.into()).collect();
+        let signature: Result<LiftedSignature, _> = aggregate_partial_signatures(
+            &KeyAggContext::new(points).unwrap(),
+            &aggregated_nonce,
+            partial_signatures,
+            digest,
+        );
+        assert!(signature.is_ok());
+
+        // Signing again with the same nonce would leak the secret key
+        assert!(signers[0]
+            .sign_musig2_partial(&public_keys, &nonces[0], &aggregated_nonce, &message)
+            .is_err());
+    }
+
+    #[test]
+    fn test_nonce_file_survives_restart() {
+        let nonce_file = std::env::temp_dir().join("bitvm_test_secret_nonces.json");
+        let _ = fs::remove_file(&nonce_file);
+        let secret = "04".repeat(32);
+        let signer = LocalSigner::from_secret(&secret)
+            .unwrap()
+            .with_nonce_file(&nonce_file);
+        let public_keys = [signer.public_key()];
+        let message = Message::from_digest([9; 32]);
+
+        let nonce = signer.generate_musig2_nonce().unwrap();
+        let aggregated_nonce = AggNonce::sum([nonce.clone()]);
+        drop(signer);
+
+        // A new signer process picks the published nonce up, and uses it only once
+        let signer = LocalSigner::from_secret(&secret)
+            .unwrap()
+            .with_nonce_file(&nonce_file);
+        assert!(signer
+            .sign_musig2_partial(&public_keys, &nonce, &aggregated_nonce, &message)
+            .is_ok());
+        let signer = LocalSigner::from_secret(&secret)
+            .unwrap()
+            .with_nonce_file(&nonce_file);
+        assert!(signer
+            .sign_musig2_partial(&public_keys, &nonce, &aggregated_nonce, &message)
+            .is_err());
+
+        fs::remove_file(&nonce_file).unwrap();
+    }
+}
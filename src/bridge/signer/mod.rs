@@ -0,0 +1,34 @@
+use bitcoin::{
+    secp256k1::{ecdsa, schnorr, Message},
+    PublicKey, XOnlyPublicKey,
+};
+use musig2::{AggNonce, PartialSignature, PubNonce};
+
+pub mod local;
+pub mod remote;
+
+/// Signing operations of a bridge participant. Implementations hold the secret key, so that
+/// contexts and transactions only ever see the public key.
+pub trait Signer: Send + Sync {
+    fn public_key(&self) -> PublicKey;
+
+    fn taproot_public_key(&self) -> XOnlyPublicKey { XOnlyPublicKey::from(self.public_key()) }
+
+    fn sign_ecdsa(&self, message: &Message) -> Result<ecdsa::Signature, String>;
+
+    fn sign_schnorr(&self, message: &Message) -> Result<schnorr::Signature, String>;
+
+    /// Generate a MuSig2 nonce. The signer keeps the secret nonce until `sign_musig2_partial`
+    /// consumes it, so every nonce signs at most one message.
+    fn generate_musig2_nonce(&self) -> Result<PubNonce, String>;
+
+    /// Partially sign `message` for the aggregate of `public_keys` with the secret nonce of
+    /// `public_nonce`
+    fn sign_musig2_partial(
+        &self,
+        public_keys: &[PublicKey],
+        public_nonce: &PubNonce,
+        aggregated_nonce: &AggNonce,
+        message: &Message,
+    ) -> Result<PartialSignature, String>;
+}
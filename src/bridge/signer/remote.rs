@@ -0,0 +1,391 @@
+// JSON-RPC 2.0 over TCP, one request and one response per line. Keys, messages and signatures
+// are hex encoded, MuSig2 nonces and partial signatures use their serde encoding.
+//
+// Every request carries an HMAC-SHA256 of its id, method and params under a key shared by the
+// signer and its clients, and the signer refuses requests without a valid one. The channel is not
+// encrypted: digests and signatures end up on chain anyway, the key only keeps whoever can reach
+// the port from having arbitrary digests signed.
+
+use bitcoin::{
+    hashes::{hmac, sha256, Hash, HashEngine},
+    secp256k1::{ecdsa, schnorr, Message},
+    PublicKey,
+};
+use musig2::{AggNonce, PartialSignature, PubNonce};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use super::Signer;
+
+#[derive(Serialize, Deserialize)]
+struct Request {
+    jsonrpc: String,
+    id: u64,
+    method: String,
+    params: Value,
+    #[serde(default)]
+    mac: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Response {
+    jsonrpc: String,
+    id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ResponseError>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+// Error codes defined by JSON-RPC 2.0
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+// Signing failed, e.g. because of an unknown nonce
+const SIGNING_ERROR: i64 = -32000;
+// The request is not authenticated with the shared key
+const UNAUTHORIZED: i64 = -32001;
+
+#[derive(Serialize, Deserialize)]
+struct MessageParams {
+    message: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Musig2PartialParams {
+    public_keys: Vec<String>,
+    public_nonce: PubNonce,
+    aggregated_nonce: AggNonce,
+    message: String,
+}
+
+fn request(auth_key: &[u8], id: u64, method: &str, params: Value) -> Request {
+    Request {
+        jsonrpc: "2.0".to_string(),
+        id,
+        method: method.to_string(),
+        mac: request_mac(auth_key, id, method, &params),
+        params,
+    }
+}
+
+fn request_mac(auth_key: &[u8], id: u64, method: &str, params: &Value) -> String {
+    let mut engine = hmac::HmacEngine::<sha256::Hash>::new(auth_key);
+    engine.input(json!([id, method, params]).to_string().as_bytes());
+    hmac::Hmac::<sha256::Hash>::from_engine(engine).to_string()
+}
+
+// Compare in constant time, so that the response time does not reveal a valid MAC
+fn is_authenticated(auth_key: &[u8], request: &Request) -> bool {
+    let mac = request_mac(auth_key, request.id, &request.method, &request.params);
+    mac.len() == request.mac.len()
+        && mac
+            .bytes()
+            .zip(request.mac.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+fn parse_message(message: &str) -> Result<Message, String> {
+    let bytes = hex::decode(message).map_err(|e| format!("Invalid message: {}", e))?;
+    Message::from_digest_slice(&bytes).map_err(|e| format!("Invalid message: {}", e))
+}
+
+fn parse_result<T: DeserializeOwned>(result: Value) -> Result<T, String> {
+    serde_json::from_value(result).map_err(|e| format!("Invalid signer response: {}", e))
+}
+
+fn encode_message(message: &Message) -> String {
+    let digest: &[u8; 32] = message.as_ref();
+    hex::encode(digest)
+}
+
+fn send(address: &str, request: &Request) -> Result<Value, String> {
+    let mut stream = TcpStream::connect(address)
+        .map_err(|e| format!("Unable to connect to signer at {}: {}", address, e))?;
+    let mut line = serde_json::to_string(request).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    let response: Response =
+        serde_json::from_str(&line).map_err(|e| format!("Invalid signer response: {}", e))?;
+    if response.id != request.id {
+        return Err(format!(
+            "Signer answered request {} instead of {}",
+            response.id, request.id
+        ));
+    }
+
+    match (response.result, response.error) {
+        (_, Some(error)) => Err(format!("Signer error {}: {}", error.code, error.message)),
+        (Some(result), None) => Ok(result),
+        (None, None) => Err("Empty signer response".to_string()),
+    }
+}
+
+/// Signer forwarding every operation to a signer process, see `serve`
+pub struct RemoteSigner {
+    address: String,
+    auth_key: Vec<u8>,
+    public_key: PublicKey,
+    next_id: AtomicU64,
+}
+
+impl RemoteSigner {
+    /// Connect to the signer listening on `address`, e.g. "127.0.0.1:9100", that was started
+    /// with `auth_key`
+    pub fn connect(address: &str, auth_key: &[u8]) -> Result<Self, String> {
+        let request = request(auth_key, 0, "public_key", json!({}));
+        let public_key: String = parse_result(send(address, &request)?)?;
+
+        Ok(RemoteSigner {
+            address: address.to_string(),
+            auth_key: auth_key.to_vec(),
+            public_key: PublicKey::from_str(&public_key)
+                .map_err(|e| format!("Invalid public key: {}", e))?,
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        send(&self.address, &request(&self.auth_key, id, method, params))
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> PublicKey { self.public_key }
+
+    fn sign_ecdsa(&self, message: &Message) -> Result<ecdsa::Signature, String> {
+        let params = MessageParams {
+            message: encode_message(message),
+        };
+        let signature: String = parse_result(self.call("sign_ecdsa", json!(params))?)?;
+        let bytes = hex::decode(signature).map_err(|e| e.to_string())?;
+        ecdsa::Signature::from_der(&bytes).map_err(|e| format!("Invalid signature: {}", e))
+    }
+
+    fn sign_schnorr(&self, message: &Message) -> Result<schnorr::Signature, String> {
+        let params = MessageParams {
+            message: encode_message(message),
+        };
+        let signature: String = parse_result(self.call("sign_schnorr", json!(params))?)?;
+        let bytes = hex::decode(signature).map_err(|e| e.to_string())?;
+        schnorr::Signature::from_slice(&bytes).map_err(|e| format!("Invalid signature: {}", e))
+    }
+
+    fn generate_musig2_nonce(&self) -> Result<PubNonce, String> {
+        parse_result(self.call("generate_musig2_nonce", json!({}))?)
+    }
+
+    fn sign_musig2_partial(
+        &self,
+        public_keys: &[PublicKey],
+        public_nonce: &PubNonce,
+        aggregated_nonce: &AggNonce,
+        message: &Message,
+    ) -> Result<PartialSignature, String> {
+        let params = Musig2PartialParams {
+            public_keys: public_keys.iter().map(|key| key.to_string()).collect(),
+            public_nonce: public_nonce.clone(),
+            aggregated_nonce: aggregated_nonce.clone(),
+            message: encode_message(message),
+        };
+        parse_result(self.call("sign_musig2_partial", json!(params))?)
+    }
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|e| (INVALID_PARAMS, e.to_string()))
+}
+
+fn dispatch(
+    signer: &dyn Signer,
+    method: &str,
+    request_params: Value,
+) -> Result<Value, (i64, String)> {
+    let signing_error = |e: String| (SIGNING_ERROR, e);
+    match method {
+        "public_key" => Ok(json!(signer.public_key().to_string())),
+        "sign_ecdsa" => {
+            let p: MessageParams = params(request_params)?;
+            let message = parse_message(&p.message).map_err(|e| (INVALID_PARAMS, e))?;
+            let signature = signer.sign_ecdsa(&message).map_err(signing_error)?;
+            Ok(json!(hex::encode(signature.serialize_der().to_vec())))
+        }
+        "sign_schnorr" => {
+            let p: MessageParams = params(request_params)?;
+            let message = parse_message(&p.message).map_err(|e| (INVALID_PARAMS, e))?;
+            let signature = signer.sign_schnorr(&message).map_err(signing_error)?;
+            Ok(json!(hex::encode(signature.serialize())))
+        }
+        "generate_musig2_nonce" => {
+            let nonce = signer.generate_musig2_nonce().map_err(signing_error)?;
+            Ok(json!(nonce))
+        }
+        "sign_musig2_partial" => {
+            let p: Musig2PartialParams = params(request_params)?;
+            let public_keys = p
+                .public_keys
+                .iter()
+                .map(|key| key.parse::<PublicKey>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| (INVALID_PARAMS, format!("Invalid public key: {}", e)))?;
+            let message = parse_message(&p.message).map_err(|e| (INVALID_PARAMS, e))?;
+            let signature = signer
+                .sign_musig2_partial(&public_keys, &p.public_nonce, &p.aggregated_nonce, &message)
+                .map_err(signing_error)?;
+            Ok(json!(signature))
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method {}", method))),
+    }
+}
+
+/// Answer a single request line of a `RemoteSigner` sharing `auth_key`
+pub fn handle_request(signer: &dyn Signer, auth_key: &[u8], line: &str) -> String {
+    let response = match serde_json::from_str::<Request>(line) {
+        Ok(request) => {
+            let result = if is_authenticated(auth_key, &request) {
+                dispatch(signer, &request.method, request.params)
+            } else {
+                Err((UNAUTHORIZED, "Invalid request MAC".to_string()))
+            };
+            let (result, error) = match result {
+                Ok(result) => (Some(result), None),
+                Err((code, message)) => (None, Some(ResponseError { code, message })),
+            };
+            Response {
+                jsonrpc: "2.0".to_string(),
+                id: request.id,
+                result,
+                error,
+            }
+        }
+        Err(e) => Response {
+            jsonrpc: "2.0".to_string(),
+            id: 0,
+            result: None,
+            error: Some(ResponseError {
+                code: PARSE_ERROR,
+                message: e.to_string(),
+            }),
+        },
+    };
+
+    serde_json::to_string(&response).expect("Unable to serialize signer response")
+}
+
+/// Serve `signer` to `RemoteSigner`s connecting to `listener` with `auth_key`, one connection at a
+/// time
+pub fn serve(listener: TcpListener, signer: &dyn Signer, auth_key: &[u8]) -> Result<(), String> {
+    for stream in listener.incoming() {
+        let mut stream = stream.map_err(|e| e.to_string())?;
+        let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+        let mut line = String::new();
+        while reader.read_line(&mut line).map_err(|e| e.to_string())? > 0 {
+            let mut response = handle_request(signer, auth_key, line.trim_end());
+            response.push('\n');
+            // The client may hang up after its response, which should not stop the signer
+            if stream.write_all(response.as_bytes()).is_err() {
+                break;
+            }
+            line.clear();
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::key::Secp256k1;
+    use std::thread;
+
+    use super::{super::local::LocalSigner, *};
+
+    const AUTH_KEY: &[u8] = b"test signer key";
+
+    #[test]
+    fn test_remote_signer() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            let signer = LocalSigner::from_secret(&"03".repeat(32)).unwrap();
+            serve(listener, &signer, AUTH_KEY).unwrap();
+        });
+
+        // Clients without the shared key are refused
+        assert!(RemoteSigner::connect(&address, b"another key").is_err());
+
+        let local = LocalSigner::from_secret(&"03".repeat(32)).unwrap();
+        let remote = RemoteSigner::connect(&address, AUTH_KEY).unwrap();
+        assert_eq!(remote.public_key(), local.public_key());
+
+        let message = Message::from_digest([5; 32]);
+        // Both signature schemes are deterministic
+        assert_eq!(remote.sign_ecdsa(&message), local.sign_ecdsa(&message));
+        assert_eq!(remote.sign_schnorr(&message), local.sign_schnorr(&message));
+
+        let secp = Secp256k1::new();
+        let signature = remote.sign_schnorr(&message).unwrap();
+        assert!(secp
+            .verify_schnorr(&signature, &message, &remote.taproot_public_key())
+            .is_ok());
+
+        // The secret nonce stays in the signer process
+        let nonce = remote.generate_musig2_nonce().unwrap();
+        let aggregated_nonce = AggNonce::sum([nonce.clone()]);
+        let public_keys = [remote.public_key()];
+        assert!(remote
+            .sign_musig2_partial(&public_keys, &nonce, &aggregated_nonce, &message)
+            .is_ok());
+        assert!(local
+            .sign_musig2_partial(&public_keys, &nonce, &aggregated_nonce, &message)
+            .is_err());
+    }
+
+    #[test]
+    fn test_handle_request_errors() {
+        let signer = LocalSigner::from_secret(&"03".repeat(32)).unwrap();
+        let handle = |request: &str| -> Response {
+            serde_json::from_str(&handle_request(&signer, AUTH_KEY, request)).unwrap()
+        };
+
+        let unknown_method = request(AUTH_KEY, 1, "sign_secret", json!({}));
+        let response = handle(&serde_json::to_string(&unknown_method).unwrap());
+        assert_eq!(response.error.unwrap().code, METHOD_NOT_FOUND);
+
+        let response = handle("{");
+        assert_eq!(response.error.unwrap().code, PARSE_ERROR);
+
+        // A request without a MAC, or with one for other params, is not signed
+        let message = json!({ "message": encode_message(&Message::from_digest([5; 32])) });
+        let without_mac = r#"{"jsonrpc":"2.0","id":1,"method":"sign_schnorr","params":"#.to_string()
+            + &message.to_string()
+            + "}";
+        assert_eq!(handle(&without_mac).error.unwrap().code, UNAUTHORIZED);
+
+        let mut other_params = request(AUTH_KEY, 1, "sign_schnorr", json!({}));
+        other_params.params = message;
+        let response = handle(&serde_json::to_string(&other_params).unwrap());
+        assert_eq!(response.error.unwrap().code, UNAUTHORIZED);
+    }
+}
@@ -43,6 +43,41 @@ pub fn get_superblock_message(sb: &Superblock, sb_hash: &SuperblockHash) -> Supe
     buffer
 }
 
+pub fn split_superblock_message(message: &SuperblockMessage) -> (Superblock, SuperblockHash) {
+    let sb = deserialize_superblock(message[..size_of::<Superblock>()].try_into().unwrap());
+    let sb_hash = message[size_of::<Superblock>()..].try_into().unwrap();
+
+    (sb, sb_hash)
+}
+
+/// Whether the block with hash `hash` is heavier than the one with hash `other`, i.e. its hash is
+/// the smaller number. Hashes are in their internal, little-endian byte order.
+pub fn is_heavier_superblock_hash(hash: &SuperblockHash, other: &SuperblockHash) -> bool {
+    hash.iter().rev().lt(other.iter().rev())
+}
+
+/// The heaviest block of a superblock period among its first `num_blocks_scanned` blocks. Kept
+/// across checks so a period is only scanned once, block by block as it is mined.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SuperblockPeriodScan {
+    pub num_blocks_scanned: u32,
+    pub heaviest_hash: Option<SuperblockHash>,
+}
+
+impl SuperblockPeriodScan {
+    /// Records the hash of the next block of the period
+    pub fn scan(&mut self, hash: &SuperblockHash) {
+        let is_heaviest = match &self.heaviest_hash {
+            Some(heaviest_hash) => is_heavier_superblock_hash(hash, heaviest_hash),
+            None => true,
+        };
+        if is_heaviest {
+            self.heaviest_hash = Some(*hash);
+        }
+        self.num_blocks_scanned += 1;
+    }
+}
+
 pub fn find_superblock() -> (Superblock, SuperblockHash) { todo!() }
 
 #[cfg(test)]
@@ -62,4 +97,49 @@ mod tests {
 
         assert_eq!(sb, deserialized_sb);
     }
+
+    #[test]
+    fn test_get_split_superblock_message() {
+        let sb = Superblock {
+            height: 850000,
+            time: 1719000000,
+            weight: 3993000,
+        };
+        let sb_hash = [7u8; SHA256_DIGEST_LENGTH_IN_BYTES];
+
+        let message = get_superblock_message(&sb, &sb_hash);
+        let (split_sb, split_sb_hash) = split_superblock_message(&message);
+
+        assert_eq!(sb, split_sb);
+        assert_eq!(sb_hash, split_sb_hash);
+    }
+
+    #[test]
+    fn test_is_heavier_superblock_hash() {
+        let mut light = [0u8; SHA256_DIGEST_LENGTH_IN_BYTES];
+        light[0] = 0xff;
+        light[31] = 0x01;
+        let mut heavy = [0u8; SHA256_DIGEST_LENGTH_IN_BYTES];
+        heavy[30] = 0xff;
+
+        assert!(is_heavier_superblock_hash(&heavy, &light));
+        assert!(!is_heavier_superblock_hash(&light, &heavy));
+        assert!(!is_heavier_superblock_hash(&heavy, &heavy));
+    }
+
+    #[test]
+    fn test_superblock_period_scan() {
+        let mut light = [0u8; SHA256_DIGEST_LENGTH_IN_BYTES];
+        light[31] = 0x02;
+        let mut heavy = [0u8; SHA256_DIGEST_LENGTH_IN_BYTES];
+        heavy[31] = 0x01;
+
+        let mut scan = SuperblockPeriodScan::default();
+        scan.scan(&light);
+        assert_eq!(scan.heaviest_hash, Some(light));
+        scan.scan(&heavy);
+        scan.scan(&light);
+        assert_eq!(scan.heaviest_hash, Some(heavy));
+        assert_eq!(scan.num_blocks_scanned, 3);
+    }
 }
@@ -1,19 +1,22 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, taproot::TaprootSpendInfo, Network, PublicKey, ScriptBuf, TapSighashType,
+    Transaction, TxOut, Witness, XOnlyPublicKey,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::signatures::winternitz;
+
 use super::{
     super::{
+        assert_program::{assert_chunks, AssertCommitment},
         connectors::{
             base::*, connector_4::Connector4, connector_5::Connector5, connector_b::ConnectorB,
             connector_c::ConnectorC,
         },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
-        graphs::base::{DUST_AMOUNT, FEE_AMOUNT},
+        params::BridgeParams,
     },
     base::*,
     pre_signed::*,
@@ -42,6 +45,10 @@ impl PreSignedTransaction for AssertTransaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> {
+        HashMap::from([(0, self.connector_b.generate_taproot_spend_info())])
+    }
 }
 
 impl PreSignedMusig2Transaction for AssertTransaction {
@@ -65,48 +72,58 @@ impl PreSignedMusig2Transaction for AssertTransaction {
     ) -> &mut HashMap<usize, HashMap<PublicKey, PartialSignature>> {
         &mut self.musig2_signatures
     }
+    fn musig2_sighash_types(&self) -> HashMap<usize, TapSighashType> {
+        HashMap::from([(0, TapSighashType::All)])
+    }
 }
 
 impl AssertTransaction {
-    pub fn new(context: &OperatorContext, input_0: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        connector_b: &ConnectorB,
+        connector_c: &ConnectorC,
+        input_0: Input,
+    ) -> Self {
         Self::new_for_validation(
             context.network,
+            &context.params,
             &context.operator_public_key,
-            &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
+            connector_b,
+            connector_c,
             input_0,
         )
     }
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         operator_public_key: &PublicKey,
-        operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
+        connector_b: &ConnectorB,
+        connector_c: &ConnectorC,
         input_0: Input,
     ) -> Self {
-        let connector_4 = Connector4::new(network, operator_public_key);
+        let connector_4 = Connector4::new(network, operator_public_key, params);
         let connector_5 = Connector5::new(network, n_of_n_taproot_public_key);
-        let connector_b = ConnectorB::new(network, n_of_n_taproot_public_key);
-        let connector_c = ConnectorC::new(network, operator_taproot_public_key);
 
         let input_0_leaf = 1;
         let _input_0 = connector_b.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(FEE_AMOUNT);
+        let total_output_amount = input_0.amount - params.fee();
 
         let _output_0 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
+            value: params.dust(),
             script_pubkey: connector_4.generate_address().script_pubkey(),
         };
 
         let _output_1 = TxOut {
-            value: total_output_amount - Amount::from_sat(DUST_AMOUNT) * 2,
+            value: total_output_amount - params.dust() * 2,
             script_pubkey: connector_5.generate_taproot_address().script_pubkey(),
         };
 
         let _output_2 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
+            value: params.dust(),
             script_pubkey: connector_c.generate_taproot_address().script_pubkey(),
         };
 
@@ -122,7 +139,7 @@ impl AssertTransaction {
                 script_pubkey: connector_b.generate_taproot_address().script_pubkey(),
             }],
             prev_scripts: vec![connector_b.generate_taproot_leaf_script(input_0_leaf)],
-            connector_b,
+            connector_b: connector_b.clone(),
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
             musig2_signatures: HashMap::new(),
@@ -131,23 +148,19 @@ impl AssertTransaction {
 
     pub fn num_blocks_timelock_0(&self) -> u32 { self.connector_b.num_blocks_timelock_1 }
 
-    fn sign_input_0(&mut self, context: &VerifierContext, secret_nonce: &SecNonce) {
+    fn sign_input_0(&mut self, context: &VerifierContext, nonce: &PubNonce) -> Result<(), String> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, nonce)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context);
+            self.finalize_input_0(context)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext) {
+    fn finalize_input_0(&mut self, context: &dyn BaseContext) -> Result<(), String> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -155,32 +168,77 @@ impl AssertTransaction {
             input_index,
             TapSighashType::All,
             self.connector_b.generate_taproot_spend_info(),
-        );
+        )
     }
 
-    pub fn push_nonces(&mut self, context: &VerifierContext) -> HashMap<usize, SecNonce> {
-        let mut secret_nonces = HashMap::new();
+    pub fn push_nonces(
+        &mut self,
+        context: &VerifierContext,
+    ) -> Result<HashMap<usize, PubNonce>, String> {
+        let mut nonces = HashMap::new();
 
         let input_index = 0;
-        let secret_nonce = push_nonce(self, context, input_index);
-        secret_nonces.insert(input_index, secret_nonce);
+        let nonce = push_nonce(self, context, input_index)?;
+        nonces.insert(input_index, nonce);
 
-        secret_nonces
+        Ok(nonces)
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        nonces: &HashMap<usize, PubNonce>,
+    ) -> Result<(), String> {
         let input_index = 0;
-        self.sign_input_0(context, &secret_nonces[&input_index]);
+        self.sign_input_0(context, &nonces[&input_index])
     }
 
     pub fn merge(&mut self, assert: &AssertTransaction) {
         merge_transactions(&mut self.tx, &assert.tx);
         merge_musig2_nonces_and_signatures(self, assert);
     }
+
+    /// The pre-signed assert tx with the operator's commitment to the outputs of the assert
+    /// program's chunks added to the witness of input 0
+    pub fn finalize_with_commitment(&self, commitment: &AssertCommitment) -> Transaction {
+        let input_index = 0;
+        let mut tx = self.finalize();
+        let witness = tx.input[input_index].witness.to_vec();
+        if witness.len() != 3 {
+            panic!("Assert tx is missing the n-of-n signature. Pre-sign it before finalizing");
+        }
+
+        // The n-of-n signature, the commitments with the first chunk on top, the script and the
+        // control block
+        let mut unlock_data = vec![witness[0].clone()];
+        for chunk_signature in commitment.iter().rev() {
+            unlock_data.extend(chunk_signature.iter().cloned());
+        }
+        unlock_data.extend(witness[1..].iter().cloned());
+        tx.input[input_index].witness = Witness::from_slice(&unlock_data);
+
+        tx
+    }
+}
+
+/// Reads the operator's commitment to the outputs of the assert program's chunks from input 0 of
+/// a broadcast assert transaction
+pub fn extract_assert_commitment(tx: &Transaction) -> Option<AssertCommitment> {
+    let witness = tx.input.first()?.witness.to_vec();
+    let num_chunks = assert_chunks().len();
+    let signature_len = 2 * winternitz::N as usize;
+    if witness.len() != num_chunks * signature_len + 3 {
+        return None;
+    }
+
+    // Skip the n-of-n signature, the last chunk comes first
+    let mut commitment: AssertCommitment = witness[1..witness.len() - 2]
+        .chunks(signature_len)
+        .map(|chunk_signature| chunk_signature.to_vec())
+        .collect();
+    commitment.reverse();
+
+    Some(commitment)
 }
 
 impl BaseTransaction for AssertTransaction {
@@ -1,15 +1,17 @@
 use bitcoin::{
-    absolute, consensus, key::Keypair, Amount, Network, PublicKey, ScriptBuf, Sequence,
+    absolute, consensus, taproot::TaprootSpendInfo, Amount, Network, PublicKey, ScriptBuf, Sequence,
     TapSighashType, Transaction, TxIn, TxOut, Witness, XOnlyPublicKey,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use super::{
     super::{
         connectors::{base::*, connector_a::ConnectorA},
         contexts::{base::BaseContext, operator::OperatorContext},
-        graphs::base::FEE_AMOUNT,
+        params::BridgeParams,
         scripts::*,
+        signer::Signer,
     },
     base::*,
     pre_signed::*,
@@ -35,6 +37,10 @@ impl PreSignedTransaction for ChallengeTransaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> {
+        HashMap::from([(0, self.connector_a.generate_taproot_spend_info())])
+    }
 }
 
 impl ChallengeTransaction {
@@ -42,9 +48,10 @@ impl ChallengeTransaction {
         context: &OperatorContext,
         input_0: Input,
         input_amount_crowdfunding: Amount,
-    ) -> Self {
+    ) -> Result<Self, String> {
         let mut this = Self::new_for_validation(
             context.network,
+            &context.params,
             &context.operator_public_key,
             &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
@@ -52,13 +59,14 @@ impl ChallengeTransaction {
             input_amount_crowdfunding,
         );
 
-        this.sign_input_0(context);
+        this.sign_input_0(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         operator_public_key: &PublicKey,
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
@@ -75,7 +83,7 @@ impl ChallengeTransaction {
         let _input_0 = connector_a.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
         let total_output_amount =
-            input_0.amount + input_amount_crowdfunding - Amount::from_sat(FEE_AMOUNT);
+            input_0.amount + input_amount_crowdfunding - params.fee();
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -106,15 +114,15 @@ impl ChallengeTransaction {
         }
     }
 
-    fn sign_input_0(&mut self, context: &OperatorContext) {
+    fn sign_input_0(&mut self, context: &OperatorContext) -> Result<(), String> {
         pre_sign_taproot_input_default(
             self,
             context,
             0,
             TapSighashType::SinglePlusAnyoneCanPay,
             self.connector_a.generate_taproot_spend_info(),
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 
     // allows for aggregating multiple inputs and one refund output
@@ -122,11 +130,11 @@ impl ChallengeTransaction {
         &mut self,
         context: &dyn BaseContext,
         inputs: &Vec<InputWithScript>,
-        keypair: &Keypair,
+        signer: &dyn Signer,
         output_script_pubkey: ScriptBuf,
-    ) {
+    ) -> Result<(), String> {
         if self.tx.input.len() > 1 {
-            panic!("Cannot add any more inputs or outputs.");
+            return Err("Cannot add any more inputs or outputs.".to_string());
         }
 
         // check total input amount
@@ -135,7 +143,7 @@ impl ChallengeTransaction {
             total_input_amount += input.amount;
         }
         if total_input_amount < self.input_amount_crowdfunding {
-            panic!("Total input amount too low. Add additional input.");
+            return Err("Total input amount too low. Add additional input.".to_string());
         } else if total_input_amount > self.input_amount_crowdfunding {
             // add refund output
             let _output = TxOut {
@@ -165,13 +173,17 @@ impl ChallengeTransaction {
                 sighash_type,
                 input.script,
                 input.amount,
-                &vec![&keypair],
-            );
+                &vec![signer],
+            )?;
 
             input_index += 1;
         }
+
+        Ok(())
     }
 
+    pub fn input_amount_crowdfunding(&self) -> Amount { self.input_amount_crowdfunding }
+
     pub fn merge(&mut self, challenge: &ChallengeTransaction) {
         merge_transactions(&mut self.tx, &challenge.tx);
     }
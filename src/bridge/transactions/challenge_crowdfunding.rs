@@ -0,0 +1,789 @@
+use bitcoin::{
+    consensus::serialize,
+    hashes::{sha256, Hash, HashEngine},
+    secp256k1::{ecdsa, Message, Secp256k1},
+    sighash::SighashCache,
+    Amount, EcdsaSighashType, OutPoint, PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut,
+    Witness,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    super::{contexts::base::BaseContext, scripts::generate_pay_to_pubkey_script, signer::Signer},
+    challenge::ChallengeTransaction,
+    pre_signed::PreSignedTransaction,
+    signing::populate_p2wsh_witness,
+};
+
+// Domain separation of the message a contributor signs its pledge with
+const PLEDGE_TAG: &[u8] = b"bitvm/challenge-crowdfunding-pledge";
+
+// Crowdfunded inputs are signed with this sighash type, so that their signatures do not depend
+// on the other inputs
+const CROWDFUNDING_SIGHASH_TYPE: EcdsaSighashType = EcdsaSighashType::AllPlusAnyoneCanPay;
+
+/// A P2WSH output a contributor funds the challenge with. Its script must be the pay to pubkey
+/// script of the contributor.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct CrowdfundingInput {
+    pub outpoint: OutPoint,
+    pub amount: Amount,
+    pub script: ScriptBuf,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct CrowdfundingPledge {
+    pub contributor_public_key: PublicKey,
+    pub inputs: Vec<CrowdfundingInput>,
+    /// Most the contributor is willing to contribute, the rest of its inputs is refunded
+    pub amount: Amount,
+    pub refund_script_pubkey: ScriptBuf,
+    /// Signature of the contributor over the pledge and the peg-out graph it is for
+    authorization: ecdsa::Signature,
+    /// Witnesses of `inputs`, which commit to the refund outputs they were signed for
+    witnesses: Vec<Witness>,
+    signed_refund_outputs: Vec<TxOut>,
+}
+
+impl CrowdfundingPledge {
+    /// Pledge of the owner of `signer` to the challenge of the peg-out graph `peg_out_graph_id`
+    pub fn new(
+        peg_out_graph_id: &str,
+        inputs: Vec<CrowdfundingInput>,
+        amount: Amount,
+        refund_script_pubkey: ScriptBuf,
+        signer: &dyn Signer,
+    ) -> Result<Self, String> {
+        let contributor_public_key = signer.public_key();
+        let message = Self::message(
+            peg_out_graph_id,
+            &contributor_public_key,
+            &inputs,
+            amount,
+            &refund_script_pubkey,
+        );
+
+        Ok(CrowdfundingPledge {
+            contributor_public_key,
+            inputs,
+            amount,
+            refund_script_pubkey,
+            authorization: signer.sign_ecdsa(&message)?,
+            witnesses: vec![],
+            signed_refund_outputs: vec![],
+        })
+    }
+
+    pub fn input_amount(&self) -> Amount { self.inputs.iter().map(|input| input.amount).sum() }
+
+    // Whether `other` is the same pledge, whatever signatures either one carries
+    fn is_same_pledge(&self, other: &CrowdfundingPledge) -> bool {
+        self.contributor_public_key == other.contributor_public_key
+            && self.inputs == other.inputs
+            && self.amount == other.amount
+            && self.refund_script_pubkey == other.refund_script_pubkey
+            && self.authorization == other.authorization
+    }
+
+    fn spends_input_of(&self, other: &CrowdfundingPledge) -> bool {
+        self.inputs
+            .iter()
+            .any(|input| other.inputs.iter().any(|i| i.outpoint == input.outpoint))
+    }
+
+    fn message(
+        peg_out_graph_id: &str,
+        contributor_public_key: &PublicKey,
+        inputs: &[CrowdfundingInput],
+        amount: Amount,
+        refund_script_pubkey: &ScriptBuf,
+    ) -> Message {
+        let prevouts: Vec<TxOut> = inputs
+            .iter()
+            .map(|input| TxOut {
+                value: input.amount,
+                script_pubkey: input.script.clone(),
+            })
+            .collect();
+        let refund = TxOut {
+            value: amount,
+            script_pubkey: refund_script_pubkey.clone(),
+        };
+
+        let mut engine = sha256::Hash::engine();
+        engine.input(PLEDGE_TAG);
+        engine.input(&serialize(&peg_out_graph_id.to_string()));
+        engine.input(&contributor_public_key.to_bytes());
+        engine.input(&serialize(&prevouts));
+        for input in inputs.iter() {
+            engine.input(&serialize(&input.outpoint));
+        }
+        engine.input(&serialize(&refund));
+        Message::from_digest(sha256::Hash::from_engine(engine).to_byte_array())
+    }
+
+    /// Check that the contributor made the pledge for `peg_out_graph_id` and owns its inputs
+    fn verify(&self, peg_out_graph_id: &str) -> Result<(), String> {
+        Secp256k1::verification_only()
+            .verify_ecdsa(
+                &Self::message(
+                    peg_out_graph_id,
+                    &self.contributor_public_key,
+                    &self.inputs,
+                    self.amount,
+                    &self.refund_script_pubkey,
+                ),
+                &self.authorization,
+                &self.contributor_public_key.inner,
+            )
+            .map_err(|_| format!("Invalid pledge signature of {}", self.contributor_public_key))?;
+
+        let script = generate_pay_to_pubkey_script(&self.contributor_public_key);
+        if self.inputs.iter().any(|input| input.script != script) {
+            return Err(format!(
+                "{} pledged inputs it cannot sign",
+                self.contributor_public_key
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check the witnesses of the pledged inputs of `tx` against their pay to pubkey scripts
+    fn verify_witnesses(&self, tx: &Transaction) -> Result<(), String> {
+        let secp = Secp256k1::verification_only();
+        for (input, witness) in self.inputs.iter().zip(self.witnesses.iter()) {
+            let input_index = tx
+                .input
+                .iter()
+                .position(|tx_in| tx_in.previous_output == input.outpoint)
+                .ok_or(format!("Input {} is not spent", input.outpoint))?;
+            let invalid = || {
+                format!(
+                    "Invalid signature of {} for input {}",
+                    self.contributor_public_key, input_index
+                )
+            };
+
+            if witness.len() != 2 || witness.nth(1) != Some(input.script.as_bytes()) {
+                return Err(invalid());
+            }
+            let signature = bitcoin::ecdsa::Signature::from_slice(witness.nth(0).unwrap())
+                .map_err(|_| invalid())?;
+            if signature.sighash_type != CROWDFUNDING_SIGHASH_TYPE {
+                return Err(invalid());
+            }
+            let sighash = SighashCache::new(tx)
+                .p2wsh_signature_hash(
+                    input_index,
+                    &input.script,
+                    input.amount,
+                    signature.sighash_type,
+                )
+                .map_err(|e| e.to_string())?;
+            secp.verify_ecdsa(
+                &Message::from(sighash),
+                &signature.signature,
+                &self.contributor_public_key.inner,
+            )
+            .map_err(|_| invalid())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Collects the pledges of several verifiers for the crowdfunded inputs of a challenge
+/// transaction. Pledges are taken in the order of the contributors' public keys until the
+/// crowdfunding amount is reached, skipping the ones that spend an input taken before, so
+/// every client assembles the same transaction whatever order it got the pledges in.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct ChallengeCrowdfunding {
+    peg_out_graph_id: String,
+    required_amount: Amount,
+    /// Refunds below this amount are left to the fee
+    dust_amount: Amount,
+    pledges: Vec<CrowdfundingPledge>,
+}
+
+impl ChallengeCrowdfunding {
+    pub fn new(
+        peg_out_graph_id: &str,
+        challenge_transaction: &ChallengeTransaction,
+        dust_amount: Amount,
+    ) -> Self {
+        ChallengeCrowdfunding {
+            peg_out_graph_id: peg_out_graph_id.to_string(),
+            required_amount: challenge_transaction.input_amount_crowdfunding(),
+            dust_amount,
+            pledges: vec![],
+        }
+    }
+
+    pub fn peg_out_graph_id(&self) -> &String { &self.peg_out_graph_id }
+
+    pub fn required_amount(&self) -> Amount { self.required_amount }
+
+    pub fn pledged_amount(&self) -> Amount {
+        self.usable_pledges()
+            .iter()
+            .map(|pledge| pledge.amount)
+            .sum()
+    }
+
+    pub fn is_funded(&self) -> bool { self.pledged_amount() >= self.required_amount }
+
+    pub fn pledges(&self) -> &Vec<CrowdfundingPledge> { &self.pledges }
+
+    pub fn pledge(&mut self, pledge: CrowdfundingPledge) -> Result<(), String> {
+        self.check_pledge(&pledge)?;
+        if self.is_funded() {
+            return Err("The challenge is already fully funded".to_string());
+        }
+        for other in self.pledges.iter() {
+            if other.contributor_public_key == pledge.contributor_public_key {
+                return Err(format!("{} already pledged", pledge.contributor_public_key));
+            }
+            if other.spends_input_of(&pledge) {
+                return Err("An input is already pledged".to_string());
+            }
+        }
+
+        self.pledges.push(pledge);
+        self.pledges
+            .sort_by_key(|pledge| pledge.contributor_public_key.to_bytes());
+
+        Ok(())
+    }
+
+    // Checks a pledge, wherever it comes from, has to pass. They do not depend on the other
+    // pledges, so that merged copies end up with the same pledges.
+    fn check_pledge(&self, pledge: &CrowdfundingPledge) -> Result<(), String> {
+        pledge.verify(&self.peg_out_graph_id)?;
+        if pledge.amount == Amount::ZERO || pledge.amount > pledge.input_amount() {
+            return Err(format!(
+                "Pledged {} but the inputs hold {}",
+                pledge.amount,
+                pledge.input_amount()
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Pledges in the order of their contributors, without the ones spending an input of a
+    // pledge before them
+    fn usable_pledges(&self) -> Vec<&CrowdfundingPledge> {
+        let mut pledges: Vec<&CrowdfundingPledge> = vec![];
+        for pledge in self.pledges.iter() {
+            if !pledges.iter().any(|other| other.spends_input_of(pledge)) {
+                pledges.push(pledge);
+            }
+        }
+
+        pledges
+    }
+
+    /// Pledges in the challenge transaction and the amount each one contributes
+    fn contributions(&self) -> Vec<(&CrowdfundingPledge, Amount)> {
+        let mut remaining = self.required_amount;
+        let mut contributions = vec![];
+        for pledge in self.usable_pledges() {
+            if remaining == Amount::ZERO {
+                break;
+            }
+            let contribution = pledge.amount.min(remaining);
+            remaining -= contribution;
+            contributions.push((pledge, contribution));
+        }
+
+        contributions
+    }
+
+    /// Refund outputs of the contributors, after output 0 of the challenge transaction
+    fn refund_outputs(&self) -> Vec<TxOut> {
+        let mut outputs = vec![];
+        for (pledge, contribution) in self.contributions() {
+            let refund = pledge.input_amount() - contribution;
+            if refund >= self.dust_amount {
+                outputs.push(TxOut {
+                    value: refund,
+                    script_pubkey: pledge.refund_script_pubkey.clone(),
+                });
+            }
+        }
+
+        outputs
+    }
+
+    fn is_signed(pledge: &CrowdfundingPledge, refund_outputs: &Vec<TxOut>) -> bool {
+        pledge.witnesses.len() == pledge.inputs.len()
+            && pledge.signed_refund_outputs == *refund_outputs
+    }
+
+    /// Contributors who still have to sign their inputs
+    pub fn missing_signatures(&self) -> Vec<PublicKey> {
+        let refund_outputs = self.refund_outputs();
+        self.contributions()
+            .iter()
+            .filter(|(pledge, _)| !Self::is_signed(pledge, &refund_outputs))
+            .map(|(pledge, _)| pledge.contributor_public_key)
+            .collect()
+    }
+
+    fn assemble(&self, challenge_transaction: &ChallengeTransaction) -> Transaction {
+        let mut tx = challenge_transaction.tx().clone();
+        tx.input.truncate(1);
+        tx.output.truncate(1);
+        tx.output.extend(self.refund_outputs());
+        for (pledge, _) in self.contributions() {
+            for (i, input) in pledge.inputs.iter().enumerate() {
+                tx.input.push(TxIn {
+                    previous_output: input.outpoint,
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::MAX,
+                    witness: pledge.witnesses.get(i).cloned().unwrap_or_default(),
+                });
+            }
+        }
+
+        tx
+    }
+
+    /// Sign the inputs pledged by `signer` once the challenge is fully funded
+    pub fn sign(
+        &mut self,
+        context: &dyn BaseContext,
+        challenge_transaction: &ChallengeTransaction,
+        signer: &dyn Signer,
+    ) -> Result<(), String> {
+        if !self.is_funded() {
+            return Err(format!(
+                "Only {} of {} pledged",
+                self.pledged_amount(),
+                self.required_amount
+            ));
+        }
+
+        let public_key = signer.public_key();
+        if !self
+            .contributions()
+            .iter()
+            .any(|(pledge, _)| pledge.contributor_public_key == public_key)
+        {
+            return Err(format!("{} has no pledge in the challenge", public_key));
+        }
+
+        let refund_outputs = self.refund_outputs();
+        let mut tx = self.assemble(challenge_transaction);
+        let pledge = self
+            .pledges
+            .iter_mut()
+            .find(|pledge| pledge.contributor_public_key == public_key)
+            .unwrap();
+        let mut witnesses = vec![];
+        for input in pledge.inputs.iter() {
+            let input_index = tx
+                .input
+                .iter()
+                .position(|tx_in| tx_in.previous_output == input.outpoint)
+                .unwrap();
+            tx.input[input_index].witness.clear();
+            populate_p2wsh_witness(
+                context,
+                &mut tx,
+                input_index,
+                CROWDFUNDING_SIGHASH_TYPE,
+                &input.script,
+                input.amount,
+                &vec![signer],
+            )?;
+            witnesses.push(tx.input[input_index].witness.clone());
+        }
+        pledge.witnesses = witnesses;
+        pledge.signed_refund_outputs = refund_outputs;
+
+        Ok(())
+    }
+
+    /// The challenge transaction with the crowdfunded inputs, once every contributor signed
+    pub fn finalize(
+        &self,
+        challenge_transaction: &ChallengeTransaction,
+    ) -> Result<Transaction, String> {
+        if !self.is_funded() {
+            return Err(format!(
+                "Only {} of {} pledged",
+                self.pledged_amount(),
+                self.required_amount
+            ));
+        }
+        let missing_signatures = self.missing_signatures();
+        if !missing_signatures.is_empty() {
+            return Err(format!("Missing signatures of {:?}", missing_signatures));
+        }
+
+        let tx = self.assemble(challenge_transaction);
+        for (pledge, _) in self.contributions() {
+            pledge.verify_witnesses(&tx)?;
+        }
+
+        Ok(tx)
+    }
+
+    /// Merge the pledges and signatures of `source`, e.g. from another verifier's copy of the
+    /// crowdfunding. Every pledge that passes the checks of `pledge` on its own is kept, so the
+    /// copies agree on the pledges whatever order they are merged in. Signatures are only taken
+    /// if they are valid for the challenge transaction of this crowdfunding. The rest of
+    /// `source` is merged even if some of it is rejected, and the rejections are returned.
+    pub fn merge(
+        &mut self,
+        source: &ChallengeCrowdfunding,
+        challenge_transaction: &ChallengeTransaction,
+    ) -> Result<(), String> {
+        let mut errors = vec![];
+        for pledge in source.pledges.iter() {
+            let mut pledge = pledge.clone();
+            pledge.witnesses.clear();
+            pledge.signed_refund_outputs.clear();
+            if let Err(e) = self.check_pledge(&pledge) {
+                errors.push(format!(
+                    "Rejected pledge of {}: {}",
+                    pledge.contributor_public_key, e
+                ));
+                continue;
+            }
+            match self
+                .pledges
+                .iter_mut()
+                .find(|p| p.contributor_public_key == pledge.contributor_public_key)
+            {
+                Some(own) if own.is_same_pledge(&pledge) => {}
+                // A contributor that pledged twice keeps the pledge with the lowest
+                // authorization, so that the copies keep the same one
+                Some(own) => {
+                    if pledge.authorization.serialize_compact()
+                        < own.authorization.serialize_compact()
+                    {
+                        *own = pledge;
+                    }
+                }
+                None => self.pledges.push(pledge),
+            }
+        }
+        self.pledges
+            .sort_by_key(|pledge| pledge.contributor_public_key.to_bytes());
+
+        // Keep the signatures made for the outputs of the merged pledges
+        let refund_outputs = self.refund_outputs();
+        let tx = self.assemble(challenge_transaction);
+        for pledge in source.pledges.iter() {
+            let own = match self.pledges.iter_mut().find(|p| p.is_same_pledge(pledge)) {
+                Some(own) => own,
+                None => continue,
+            };
+            if Self::is_signed(own, &refund_outputs) || !Self::is_signed(pledge, &refund_outputs) {
+                continue;
+            }
+            match pledge.verify_witnesses(&tx) {
+                Ok(()) => {
+                    own.witnesses = pledge.witnesses.clone();
+                    own.signed_refund_outputs = pledge.signed_refund_outputs.clone();
+                }
+                Err(e) => errors.push(format!(
+                    "Rejected signatures of {}: {}",
+                    pledge.contributor_public_key, e
+                )),
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, Network, Txid};
+
+    use super::{
+        super::{
+            super::{
+                contexts::{
+                    base::generate_keys_from_secret, operator::OperatorContext,
+                    verifier::VerifierContext,
+                },
+                graphs::base::{
+                    DUST_AMOUNT, INITIAL_AMOUNT, OPERATOR_SECRET, VERIFIER_0_SECRET,
+                    VERIFIER_1_SECRET,
+                },
+            },
+            base::Input,
+        },
+        *,
+    };
+
+    fn pledge(
+        context: &VerifierContext,
+        vout: u32,
+        amount: u64,
+        pledged: u64,
+    ) -> CrowdfundingPledge {
+        let script = generate_pay_to_pubkey_script(&context.verifier_public_key);
+        CrowdfundingPledge::new(
+            "graph",
+            vec![CrowdfundingInput {
+                outpoint: OutPoint {
+                    txid: Txid::all_zeros(),
+                    vout,
+                },
+                amount: Amount::from_sat(amount),
+                script: script.clone(),
+            }],
+            Amount::from_sat(pledged),
+            script.to_p2wsh(),
+            context.verifier_signer.as_ref(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_challenge_crowdfunding() {
+        let (_, _, verifier_0_public_key) =
+            generate_keys_from_secret(Network::Regtest, VERIFIER_0_SECRET);
+        let (_, _, verifier_1_public_key) =
+            generate_keys_from_secret(Network::Regtest, VERIFIER_1_SECRET);
+        let n_of_n_public_keys = vec![verifier_0_public_key, verifier_1_public_key];
+        let operator_context =
+            OperatorContext::new(Network::Regtest, OPERATOR_SECRET, &n_of_n_public_keys);
+        let verifier_0_context =
+            VerifierContext::new(Network::Regtest, VERIFIER_0_SECRET, &n_of_n_public_keys);
+        let verifier_1_context =
+            VerifierContext::new(Network::Regtest, VERIFIER_1_SECRET, &n_of_n_public_keys);
+
+        let challenge_transaction = ChallengeTransaction::new(
+            &operator_context,
+            Input {
+                outpoint: OutPoint::null(),
+                amount: Amount::from_sat(DUST_AMOUNT),
+            },
+            Amount::from_sat(INITIAL_AMOUNT),
+        )
+        .unwrap();
+
+        let mut crowdfunding_0 = ChallengeCrowdfunding::new(
+            "graph",
+            &challenge_transaction,
+            Amount::from_sat(DUST_AMOUNT),
+        );
+        crowdfunding_0
+            .pledge(pledge(
+                &verifier_0_context,
+                0,
+                INITIAL_AMOUNT,
+                INITIAL_AMOUNT / 2,
+            ))
+            .unwrap();
+        assert!(!crowdfunding_0.is_funded());
+        assert!(crowdfunding_0
+            .sign(
+                &verifier_0_context,
+                &challenge_transaction,
+                verifier_0_context.verifier_signer.as_ref()
+            )
+            .is_err());
+
+        // The other verifier pledges through its own copy, e.g. after syncing
+        let mut crowdfunding_1 = crowdfunding_0.clone();
+        assert!(crowdfunding_1
+            .pledge(pledge(
+                &verifier_1_context,
+                0,
+                INITIAL_AMOUNT,
+                INITIAL_AMOUNT
+            ))
+            .is_err());
+        crowdfunding_1
+            .pledge(pledge(
+                &verifier_1_context,
+                1,
+                INITIAL_AMOUNT,
+                INITIAL_AMOUNT / 2,
+            ))
+            .unwrap();
+        assert!(crowdfunding_1.is_funded());
+        assert!(crowdfunding_1
+            .pledge(pledge(
+                &verifier_0_context,
+                2,
+                INITIAL_AMOUNT,
+                INITIAL_AMOUNT
+            ))
+            .is_err());
+
+        // Pledges from another copy are checked like the ones pledged directly
+        let mut forged = crowdfunding_1.clone();
+        forged
+            .pledges
+            .iter_mut()
+            .find(|pledge| pledge.contributor_public_key == verifier_1_context.verifier_public_key)
+            .unwrap()
+            .amount = Amount::from_sat(INITIAL_AMOUNT);
+        let mut crowdfunding = crowdfunding_0.clone();
+        assert!(crowdfunding.merge(&forged, &challenge_transaction).is_err());
+        assert_eq!(crowdfunding, crowdfunding_0);
+        // Of two pledges spending the same input, only the first one contributes
+        let mut double_pledged = crowdfunding_0.clone();
+        double_pledged.pledges = vec![pledge(
+            &verifier_1_context,
+            0,
+            INITIAL_AMOUNT,
+            INITIAL_AMOUNT / 2,
+        )];
+        crowdfunding
+            .merge(&double_pledged, &challenge_transaction)
+            .unwrap();
+        assert_eq!(crowdfunding.pledges().len(), 2);
+        assert_eq!(crowdfunding.contributions().len(), 1);
+        assert_eq!(
+            crowdfunding.pledged_amount(),
+            crowdfunding_0.pledged_amount()
+        );
+
+        crowdfunding_0
+            .merge(&crowdfunding_1, &challenge_transaction)
+            .unwrap();
+        assert_eq!(crowdfunding_0, crowdfunding_1);
+
+        crowdfunding_0
+            .sign(
+                &verifier_0_context,
+                &challenge_transaction,
+                verifier_0_context.verifier_signer.as_ref(),
+            )
+            .unwrap();
+        assert!(crowdfunding_0.finalize(&challenge_transaction).is_err());
+        crowdfunding_1
+            .sign(
+                &verifier_1_context,
+                &challenge_transaction,
+                verifier_1_context.verifier_signer.as_ref(),
+            )
+            .unwrap();
+        // A signature that does not verify is not taken over
+        let mut forged = crowdfunding_0.clone();
+        let verifier_0_pledge = forged
+            .pledges
+            .iter_mut()
+            .find(|pledge| pledge.contributor_public_key == verifier_0_context.verifier_public_key)
+            .unwrap();
+        verifier_0_pledge.witnesses = crowdfunding_1
+            .pledges
+            .iter()
+            .find(|pledge| pledge.contributor_public_key == verifier_1_context.verifier_public_key)
+            .unwrap()
+            .witnesses
+            .clone();
+        let mut crowdfunding = crowdfunding_1.clone();
+        assert!(crowdfunding.merge(&forged, &challenge_transaction).is_err());
+        assert_eq!(
+            crowdfunding.missing_signatures(),
+            vec![verifier_0_context.verifier_public_key]
+        );
+
+        crowdfunding_1
+            .merge(&crowdfunding_0, &challenge_transaction)
+            .unwrap();
+        assert!(crowdfunding_1.missing_signatures().is_empty());
+
+        let tx = crowdfunding_1.finalize(&challenge_transaction).unwrap();
+        assert_eq!(tx.input.len(), 3);
+        // Every crowdfunded input carries a valid signature of its contributor
+        let secp = Secp256k1::verification_only();
+        for (input_index, input) in tx.input.iter().enumerate().skip(1) {
+            let contributor_public_key = [&verifier_0_context, &verifier_1_context]
+                .iter()
+                .map(|context| context.verifier_public_key)
+                .find(|public_key| {
+                    input.witness.nth(1)
+                        == Some(generate_pay_to_pubkey_script(public_key).as_bytes())
+                })
+                .unwrap();
+            let signature =
+                bitcoin::ecdsa::Signature::from_slice(input.witness.nth(0).unwrap()).unwrap();
+            assert_eq!(signature.sighash_type, CROWDFUNDING_SIGHASH_TYPE);
+            let sighash = SighashCache::new(&tx)
+                .p2wsh_signature_hash(
+                    input_index,
+                    &generate_pay_to_pubkey_script(&contributor_public_key),
+                    Amount::from_sat(INITIAL_AMOUNT),
+                    signature.sighash_type,
+                )
+                .unwrap();
+            secp.verify_ecdsa(
+                &Message::from(sighash),
+                &signature.signature,
+                &contributor_public_key.inner,
+            )
+            .unwrap();
+        }
+        // Each verifier is refunded what the challenge did not need
+        let refunds: Amount = tx.output.iter().skip(1).map(|output| output.value).sum();
+        assert_eq!(refunds, Amount::from_sat(INITIAL_AMOUNT));
+        assert_eq!(tx.output[0], challenge_transaction.tx().output[0]);
+    }
+    #[test]
+    fn test_challenge_crowdfunding_merge_order() {
+        let (_, _, verifier_0_public_key) =
+            generate_keys_from_secret(Network::Regtest, VERIFIER_0_SECRET);
+        let (_, _, verifier_1_public_key) =
+            generate_keys_from_secret(Network::Regtest, VERIFIER_1_SECRET);
+        let n_of_n_public_keys = vec![verifier_0_public_key, verifier_1_public_key];
+        let operator_context =
+            OperatorContext::new(Network::Regtest, OPERATOR_SECRET, &n_of_n_public_keys);
+        let verifier_contexts = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+            .map(|secret| VerifierContext::new(Network::Regtest, secret, &n_of_n_public_keys));
+
+        let challenge_transaction = ChallengeTransaction::new(
+            &operator_context,
+            Input {
+                outpoint: OutPoint::null(),
+                amount: Amount::from_sat(DUST_AMOUNT),
+            },
+            Amount::from_sat(INITIAL_AMOUNT),
+        )
+        .unwrap();
+
+        // Each verifier fully funds the challenge on its own copy before seeing the other one
+        let crowdfundings: Vec<ChallengeCrowdfunding> = verifier_contexts
+            .iter()
+            .enumerate()
+            .map(|(vout, context)| {
+                let mut crowdfunding = ChallengeCrowdfunding::new(
+                    "graph",
+                    &challenge_transaction,
+                    Amount::from_sat(DUST_AMOUNT),
+                );
+                crowdfunding
+                    .pledge(pledge(context, vout as u32, INITIAL_AMOUNT, INITIAL_AMOUNT))
+                    .unwrap();
+                crowdfunding
+            })
+            .collect();
+
+        let mut merged_0 = crowdfundings[0].clone();
+        merged_0
+            .merge(&crowdfundings[1], &challenge_transaction)
+            .unwrap();
+        let mut merged_1 = crowdfundings[1].clone();
+        merged_1
+            .merge(&crowdfundings[0], &challenge_transaction)
+            .unwrap();
+        assert_eq!(merged_0, merged_1);
+        assert_eq!(merged_0.pledges().len(), 2);
+        assert_eq!(merged_0.contributions().len(), 1);
+        assert_eq!(merged_0.contributions()[0].0, &merged_0.pledges()[0]);
+    }
+}
@@ -1,22 +1,23 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, taproot::TaprootSpendInfo, Amount, Network, PublicKey, ScriptBuf,
+    TapSighashType, Transaction, TxOut, XOnlyPublicKey,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::{
     super::{
+        assert_program::AssertCommitment,
         connectors::{base::*, connector_5::Connector5, connector_c::ConnectorC},
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
-        graphs::base::FEE_AMOUNT,
+        params::BridgeParams,
         scripts::*,
     },
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
-    signing::push_taproot_leaf_script_and_control_block_to_witness,
+    signing::populate_taproot_input_witness,
 };
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -43,6 +44,13 @@ impl PreSignedTransaction for DisproveTransaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> {
+        HashMap::from([
+            (0, self.connector_5.generate_taproot_spend_info()),
+            (1, self.connector_c.generate_taproot_spend_info()),
+        ])
+    }
 }
 
 impl PreSignedMusig2Transaction for DisproveTransaction {
@@ -66,19 +74,24 @@ impl PreSignedMusig2Transaction for DisproveTransaction {
     ) -> &mut HashMap<usize, HashMap<PublicKey, PartialSignature>> {
         &mut self.musig2_signatures
     }
+    fn musig2_sighash_types(&self) -> HashMap<usize, TapSighashType> {
+        HashMap::from([(0, TapSighashType::Single)])
+    }
 }
 
 impl DisproveTransaction {
     pub fn new(
         context: &OperatorContext,
+        connector_c: &ConnectorC,
         input_0: Input,
         input_1: Input,
         script_index: u32,
     ) -> Self {
         Self::new_for_validation(
             context.network,
-            &context.operator_taproot_public_key,
+            &context.params,
             &context.n_of_n_taproot_public_key,
+            connector_c,
             input_0,
             input_1,
             script_index,
@@ -87,14 +100,14 @@ impl DisproveTransaction {
 
     pub fn new_for_validation(
         network: Network,
-        operator_taproot_public_key: &XOnlyPublicKey,
+        params: &BridgeParams,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
+        connector_c: &ConnectorC,
         input_0: Input,
         input_1: Input,
         script_index: u32,
     ) -> Self {
         let connector_5 = Connector5::new(network, &n_of_n_taproot_public_key);
-        let connector_c = ConnectorC::new(network, &operator_taproot_public_key);
 
         let input_0_leaf = 1;
         let _input_0 = connector_5.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
@@ -102,7 +115,7 @@ impl DisproveTransaction {
         let input_1_leaf = script_index;
         let _input_1 = connector_c.generate_taproot_leaf_tx_in(input_1_leaf, &input_1);
 
-        let total_output_amount = input_0.amount + input_1.amount - Amount::from_sat(FEE_AMOUNT);
+        let total_output_amount = input_0.amount + input_1.amount - params.fee();
 
         let _output_0 = TxOut {
             value: total_output_amount / 2,
@@ -137,7 +150,7 @@ impl DisproveTransaction {
                 connector_c.generate_taproot_leaf_script(input_1_leaf),
             ],
             connector_5,
-            connector_c,
+            connector_c: connector_c.clone(),
             reward_output_amount,
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
@@ -145,23 +158,19 @@ impl DisproveTransaction {
         }
     }
 
-    fn sign_input_0(&mut self, context: &VerifierContext, secret_nonce: &SecNonce) {
+    fn sign_input_0(&mut self, context: &VerifierContext, nonce: &PubNonce) -> Result<(), String> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::Single,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::Single, nonce)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context);
+            self.finalize_input_0(context)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext) {
+    fn finalize_input_0(&mut self, context: &dyn BaseContext) -> Result<(), String> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -169,54 +178,67 @@ impl DisproveTransaction {
             input_index,
             TapSighashType::Single,
             self.connector_5.generate_taproot_spend_info(),
-        );
+        )
     }
 
-    pub fn push_nonces(&mut self, context: &VerifierContext) -> HashMap<usize, SecNonce> {
-        let mut secret_nonces = HashMap::new();
+    pub fn push_nonces(
+        &mut self,
+        context: &VerifierContext,
+    ) -> Result<HashMap<usize, PubNonce>, String> {
+        let mut nonces = HashMap::new();
 
         let input_index = 0;
-        let secret_nonce = push_nonce(self, context, input_index);
-        secret_nonces.insert(input_index, secret_nonce);
+        let nonce = push_nonce(self, context, input_index)?;
+        nonces.insert(input_index, nonce);
 
-        secret_nonces
+        Ok(nonces)
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        nonces: &HashMap<usize, PubNonce>,
+    ) -> Result<(), String> {
         let input_index = 0;
-        self.sign_input_0(context, &secret_nonces[&input_index]);
+        self.sign_input_0(context, &nonces[&input_index])
     }
 
-    pub fn add_input_output(&mut self, input_script_index: u32, output_script_pubkey: ScriptBuf) {
+    /// Spends leaf `input_script_index` of connector C, disproving the operator's `commitment`
+    /// from the broadcast assert tx
+    pub fn add_input_output(
+        &mut self,
+        input_script_index: u32,
+        commitment: &AssertCommitment,
+        output_script_pubkey: ScriptBuf,
+    ) {
         // Add output
         let output_index = 1;
         self.tx.output[output_index].script_pubkey = output_script_pubkey;
 
         let input_index = 1;
-
-        // Push the unlocking witness
-        let unlock_witness = self
+        let unlock_data = self
             .connector_c
-            .generate_taproot_leaf_script_witness(input_script_index);
-        self.tx.input[input_index].witness.push(unlock_witness);
-
-        // Push script + control block
+            .generate_taproot_leaf_script_witness(input_script_index, commitment);
         let script = self
             .connector_c
             .generate_taproot_leaf_script(input_script_index);
         let taproot_spend_info = self.connector_c.generate_taproot_spend_info();
-        push_taproot_leaf_script_and_control_block_to_witness(
+        populate_taproot_input_witness(
             &mut self.tx,
             input_index,
             &taproot_spend_info,
             &script,
+            unlock_data,
         );
     }
 
+    /// Connector C leaf that disproves the operator's `commitment`, if any
+    pub fn find_disprove_script_index(&self, commitment: &AssertCommitment) -> Option<u32> {
+        let input_index = 1;
+        self.connector_c
+            .find_disprove_leaf(&self.tx, input_index, &self.prev_outs, commitment)
+    }
+
     pub fn merge(&mut self, disprove: &DisproveTransaction) {
         merge_transactions(&mut self.tx, &disprove.tx);
         merge_musig2_nonces_and_signatures(self, disprove);
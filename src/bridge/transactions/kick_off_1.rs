@@ -1,8 +1,9 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, taproot::TaprootSpendInfo, Network, ScriptBuf, TapSighashType, Transaction,
+    TxOut, XOnlyPublicKey,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use super::{
     super::{
@@ -11,7 +12,7 @@ use super::{
             connector_a::ConnectorA,
         },
         contexts::operator::OperatorContext,
-        graphs::base::{DUST_AMOUNT, FEE_AMOUNT, MESSAGE_COMMITMENT_FEE_AMOUNT},
+        params::BridgeParams,
     },
     base::*,
     pre_signed::*,
@@ -26,6 +27,7 @@ pub struct KickOff1Transaction {
     #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
     prev_outs: Vec<TxOut>,
     prev_scripts: Vec<ScriptBuf>,
+    connector_6: Connector6,
 }
 
 impl PreSignedTransaction for KickOff1Transaction {
@@ -36,6 +38,10 @@ impl PreSignedTransaction for KickOff1Transaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> {
+        HashMap::from([(0, self.connector_6.generate_taproot_spend_info())])
+    }
 }
 
 impl KickOff1Transaction {
@@ -48,6 +54,7 @@ impl KickOff1Transaction {
     ) -> Self {
         let this = Self::new_for_validation(
             context.network,
+            &context.params,
             &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
             connector_1,
@@ -61,6 +68,7 @@ impl KickOff1Transaction {
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         connector_1: &Connector1,
@@ -78,20 +86,20 @@ impl KickOff1Transaction {
         let _input_0 = connector_6.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
         let total_output_amount =
-            input_0.amount - Amount::from_sat(MESSAGE_COMMITMENT_FEE_AMOUNT * 2 + FEE_AMOUNT);
+            input_0.amount - (params.message_commitment_fee() * 2 + params.fee());
 
         let _output_0 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
+            value: params.dust(),
             script_pubkey: connector_a.generate_taproot_address().script_pubkey(),
         };
 
         let _output_1 = TxOut {
-            value: total_output_amount - Amount::from_sat(DUST_AMOUNT) * 2,
+            value: total_output_amount - params.dust() * 2,
             script_pubkey: connector_1.generate_taproot_address().script_pubkey(),
         };
 
         let _output_2 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
+            value: params.dust(),
             script_pubkey: connector_2.generate_taproot_address().script_pubkey(),
         };
 
@@ -107,6 +115,7 @@ impl KickOff1Transaction {
                 script_pubkey: connector_6.generate_taproot_address().script_pubkey(), // TODO: Add address of Commit y
             }],
             prev_scripts: vec![connector_6.generate_taproot_leaf_script(input_0_leaf)],
+            connector_6: connector_6.clone(),
         }
     }
 
@@ -117,7 +126,7 @@ impl KickOff1Transaction {
         source_network_txid: &[u8],
         destination_network_txid: &[u8],
         winternitz_secret: &WinternitzSecret,
-    ) {
+    ) -> Result<(), String> {
         let input_index = 0;
         let script = &self.prev_scripts()[input_index].clone();
         let prev_outs = &self.prev_outs().clone();
@@ -132,8 +141,8 @@ impl KickOff1Transaction {
             input_index,
             TapSighashType::All,
             script,
-            &context.operator_keypair,
-        );
+            context.operator_signer.as_ref(),
+        )?;
         unlock_data.push(schnorr_signature.to_vec());
 
         // get winternitz signature for source network txid
@@ -164,6 +173,8 @@ impl KickOff1Transaction {
             script,
             unlock_data,
         );
+
+        Ok(())
     }
 
     pub fn sign(
@@ -173,14 +184,14 @@ impl KickOff1Transaction {
         source_network_txid: &[u8],
         destination_network_txid: &[u8],
         winternitz_secret: &WinternitzSecret,
-    ) {
+    ) -> Result<(), String> {
         self.sign_input_0(
             context,
             connector_6,
             source_network_txid,
             destination_network_txid,
             winternitz_secret,
-        );
+        )
     }
 }
 
@@ -1,8 +1,9 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, taproot::TaprootSpendInfo, Network, PublicKey, ScriptBuf, TapSighashType,
+    Transaction, TxOut,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::bridge::{
     connectors::base::{CommitmentConnector, P2wshConnector, TaprootConnector},
@@ -13,15 +14,14 @@ use super::{
     super::{
         connectors::{connector_1::Connector1, connector_3::Connector3, connector_b::ConnectorB},
         contexts::operator::OperatorContext,
-        graphs::base::DUST_AMOUNT,
+        params::BridgeParams,
+        superblock::{SuperblockMessage, SUPERBLOCK_MESSAGE_DIGITS_LENGTH},
     },
     base::*,
     pre_signed::*,
     signing::{generate_taproot_leaf_schnorr_signature, populate_taproot_input_witness},
 };
 
-const MIN_RELAY_FEE_AMOUNT: u64 = 52_953;
-
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct KickOff2Transaction {
     #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
@@ -29,6 +29,7 @@ pub struct KickOff2Transaction {
     #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
     prev_outs: Vec<TxOut>,
     prev_scripts: Vec<ScriptBuf>,
+    connector_1: Connector1,
 }
 
 impl PreSignedTransaction for KickOff2Transaction {
@@ -39,41 +40,51 @@ impl PreSignedTransaction for KickOff2Transaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> {
+        HashMap::from([(0, self.connector_1.generate_taproot_spend_info())])
+    }
 }
 
 impl KickOff2Transaction {
-    pub fn new(context: &OperatorContext, connector_1: &Connector1, input_0: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        connector_1: &Connector1,
+        connector_b: &ConnectorB,
+        input_0: Input,
+    ) -> Self {
         Self::new_for_validation(
             context.network,
+            &context.params,
             &context.operator_public_key,
-            &context.n_of_n_taproot_public_key,
             connector_1,
+            connector_b,
             input_0,
         )
     }
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         operator_public_key: &PublicKey,
-        n_of_n_taproot_public_key: &XOnlyPublicKey,
         connector_1: &Connector1,
+        connector_b: &ConnectorB,
         input_0: Input,
     ) -> Self {
-        let connector_3 = Connector3::new(network, operator_public_key);
-        let connector_b = ConnectorB::new(network, n_of_n_taproot_public_key);
+        let connector_3 = Connector3::new(network, operator_public_key, params);
 
         let input_0_leaf = 0;
         let _input_0 = connector_1.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(MIN_RELAY_FEE_AMOUNT);
+        let total_output_amount = input_0.amount - params.min_relay_fee();
 
         let _output_0 = TxOut {
-            value: Amount::from_sat(DUST_AMOUNT),
+            value: params.dust(),
             script_pubkey: connector_3.generate_address().script_pubkey(),
         };
 
         let _output_1 = TxOut {
-            value: total_output_amount - Amount::from_sat(DUST_AMOUNT),
+            value: total_output_amount - params.dust(),
             script_pubkey: connector_b.generate_taproot_address().script_pubkey(),
         };
 
@@ -89,6 +100,7 @@ impl KickOff2Transaction {
                 script_pubkey: connector_1.generate_taproot_address().script_pubkey(),
             }],
             prev_scripts: vec![connector_1.generate_taproot_leaf_script(input_0_leaf)],
+            connector_1: connector_1.clone(),
         }
     }
 
@@ -98,7 +110,7 @@ impl KickOff2Transaction {
         connector_1: &Connector1,
         winternitz_secret: &WinternitzSecret,
         message: &[u8],
-    ) {
+    ) -> Result<(), String> {
         let input_index = 0;
         let prev_outs = &self.prev_outs().clone();
         let script = &self.prev_scripts()[input_index].clone();
@@ -112,8 +124,8 @@ impl KickOff2Transaction {
             input_index,
             TapSighashType::All,
             script,
-            &context.operator_keypair,
-        );
+            context.operator_signer.as_ref(),
+        )?;
         unlock_data.push(schnorr_signature.to_vec());
 
         let leaf_index = 0;
@@ -130,9 +142,37 @@ impl KickOff2Transaction {
             script,
             unlock_data,
         );
+
+        Ok(())
     }
 }
 
+/// Reads the superblock message the operator committed to in input 0 of a broadcast kick-off 2
+/// transaction. The witness holds the schnorr signature followed by the message digits in
+/// reverse order.
+pub fn extract_superblock_message(tx: &Transaction) -> Option<SuperblockMessage> {
+    let witness = &tx.input.first()?.witness;
+    if witness.len() <= SUPERBLOCK_MESSAGE_DIGITS_LENGTH {
+        return None;
+    }
+
+    let mut digits = Vec::with_capacity(SUPERBLOCK_MESSAGE_DIGITS_LENGTH);
+    for i in (1..=SUPERBLOCK_MESSAGE_DIGITS_LENGTH).rev() {
+        match witness.nth(i)? {
+            [] => digits.push(0),
+            [digit] if *digit < 16 => digits.push(*digit),
+            _ => return None,
+        }
+    }
+
+    let mut message = [0u8; size_of::<SuperblockMessage>()];
+    for (byte, pair) in message.iter_mut().zip(digits.chunks(2)) {
+        *byte = pair[0] | (pair[1] << 4);
+    }
+
+    Some(message)
+}
+
 impl BaseTransaction for KickOff2Transaction {
     fn finalize(&self) -> Transaction { self.tx.clone() }
 }
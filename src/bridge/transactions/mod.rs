@@ -1,6 +1,7 @@
 pub mod assert;
 pub mod base;
 pub mod challenge;
+pub mod challenge_crowdfunding;
 pub mod disprove;
 pub mod disprove_chain;
 pub mod kick_off_1;
@@ -13,6 +14,7 @@ pub mod peg_out;
 pub mod peg_out_confirm;
 pub mod pre_signed;
 pub mod pre_signed_musig2;
+pub mod psbt;
 pub mod signing;
 pub mod signing_musig2;
 pub mod signing_winternitz;
@@ -1,8 +1,8 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, taproot::TaprootSpendInfo, Network, PublicKey, ScriptBuf, TapSighashType,
+    Transaction, TxOut, XOnlyPublicKey,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -10,7 +10,7 @@ use super::{
     super::{
         connectors::{base::*, connector_0::Connector0, connector_z::ConnectorZ},
         contexts::{base::BaseContext, depositor::DepositorContext, verifier::VerifierContext},
-        graphs::base::FEE_AMOUNT,
+        params::BridgeParams,
     },
     base::*,
     pre_signed::*,
@@ -40,6 +40,10 @@ impl PreSignedTransaction for PegInConfirmTransaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> {
+        HashMap::from([(0, self.connector_z.generate_taproot_spend_info())])
+    }
 }
 
 impl PreSignedMusig2Transaction for PegInConfirmTransaction {
@@ -63,25 +67,34 @@ impl PreSignedMusig2Transaction for PegInConfirmTransaction {
     ) -> &mut HashMap<usize, HashMap<PublicKey, PartialSignature>> {
         &mut self.musig2_signatures
     }
+    fn musig2_sighash_types(&self) -> HashMap<usize, TapSighashType> {
+        HashMap::from([(0, TapSighashType::All)])
+    }
 }
 
 impl PegInConfirmTransaction {
-    pub fn new(context: &DepositorContext, evm_address: &str, input_0: Input) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        evm_address: &str,
+        input_0: Input,
+    ) -> Result<Self, String> {
         let mut this = Self::new_for_validation(
             context.network,
+            &context.params,
             &context.depositor_taproot_public_key,
             &context.n_of_n_taproot_public_key,
             evm_address,
             input_0,
         );
 
-        this.push_depositor_signature_input_0(context);
+        this.push_depositor_signature_input_0(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         depositor_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
         evm_address: &str,
@@ -93,12 +106,13 @@ impl PegInConfirmTransaction {
             evm_address,
             depositor_taproot_public_key,
             n_of_n_taproot_public_key,
+            params,
         );
 
         let input_0_leaf = 1;
         let _input_0 = connector_z.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(FEE_AMOUNT);
+        let total_output_amount = input_0.amount - params.fee();
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -124,7 +138,10 @@ impl PegInConfirmTransaction {
         }
     }
 
-    fn push_depositor_signature_input_0(&mut self, context: &DepositorContext) {
+    fn push_depositor_signature_input_0(
+        &mut self,
+        context: &DepositorContext,
+    ) -> Result<(), String> {
         let input_index = 0;
         let mut unlock_data: Vec<Vec<u8>> = Vec::new();
 
@@ -135,34 +152,32 @@ impl PegInConfirmTransaction {
             input_index,
             TapSighashType::All,
             &self.prev_scripts[input_index],
-            &context.depositor_keypair,
-        );
+            context.depositor_signer.as_ref(),
+        )?;
         unlock_data.push(schnorr_signature.to_vec());
 
         push_taproot_leaf_unlock_data_to_witness(&mut self.tx, input_index, unlock_data);
+
+        Ok(())
     }
 
     fn push_verifier_signature_input_0(
         &mut self,
         context: &VerifierContext,
-        secret_nonce: &SecNonce,
-    ) {
+        nonce: &PubNonce,
+    ) -> Result<(), String> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, nonce)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context);
+            self.finalize_input_0(context)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext) {
+    fn finalize_input_0(&mut self, context: &dyn BaseContext) -> Result<(), String> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -170,26 +185,29 @@ impl PegInConfirmTransaction {
             input_index,
             TapSighashType::All,
             self.connector_z.generate_taproot_spend_info(),
-        );
+        )
     }
 
-    pub fn push_nonces(&mut self, context: &VerifierContext) -> HashMap<usize, SecNonce> {
-        let mut secret_nonces = HashMap::new();
+    pub fn push_nonces(
+        &mut self,
+        context: &VerifierContext,
+    ) -> Result<HashMap<usize, PubNonce>, String> {
+        let mut nonces = HashMap::new();
 
         let input_index = 0;
-        let secret_nonce = push_nonce(self, context, input_index);
-        secret_nonces.insert(input_index, secret_nonce);
+        let nonce = push_nonce(self, context, input_index)?;
+        nonces.insert(input_index, nonce);
 
-        secret_nonces
+        Ok(nonces)
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        nonces: &HashMap<usize, PubNonce>,
+    ) -> Result<(), String> {
         let input_index = 0;
-        self.push_verifier_signature_input_0(context, &secret_nonces[&input_index]);
+        self.push_verifier_signature_input_0(context, &nonces[&input_index])
     }
 
     pub fn merge(&mut self, peg_in_confirm: &PegInConfirmTransaction) {
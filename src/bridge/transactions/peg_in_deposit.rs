@@ -1,14 +1,15 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Transaction,
-    TxOut, XOnlyPublicKey,
+    absolute, consensus, taproot::TaprootSpendInfo, EcdsaSighashType, Network, PublicKey, ScriptBuf,
+    Transaction, TxOut, XOnlyPublicKey,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use super::{
     super::{
         connectors::{base::*, connector_z::ConnectorZ},
         contexts::depositor::DepositorContext,
-        graphs::base::FEE_AMOUNT,
+        params::BridgeParams,
         scripts::*,
     },
     base::*,
@@ -32,12 +33,19 @@ impl PreSignedTransaction for PegInDepositTransaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> { HashMap::new() }
 }
 
 impl PegInDepositTransaction {
-    pub fn new(context: &DepositorContext, evm_address: &str, input_0: Input) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        evm_address: &str,
+        input_0: Input,
+    ) -> Result<Self, String> {
         let mut this = Self::new_for_validation(
             context.network,
+            &context.params,
             &context.depositor_public_key,
             &context.depositor_taproot_public_key,
             &context.n_of_n_taproot_public_key,
@@ -45,13 +53,14 @@ impl PegInDepositTransaction {
             input_0,
         );
 
-        this.sign_input_0(context);
+        this.sign_input_0(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         depositor_public_key: &PublicKey,
         depositor_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
@@ -63,11 +72,12 @@ impl PegInDepositTransaction {
             evm_address,
             depositor_taproot_public_key,
             n_of_n_taproot_public_key,
+            params,
         );
 
         let _input_0 = generate_default_tx_in(&input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(FEE_AMOUNT);
+        let total_output_amount = input_0.amount - params.fee();
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -90,15 +100,15 @@ impl PegInDepositTransaction {
         }
     }
 
-    fn sign_input_0(&mut self, context: &DepositorContext) {
+    fn sign_input_0(&mut self, context: &DepositorContext) -> Result<(), String> {
         let input_index = 0;
         pre_sign_p2wsh_input(
             self,
             context,
             input_index,
             EcdsaSighashType::All,
-            &vec![&context.depositor_keypair],
-        );
+            &vec![context.depositor_signer.as_ref()],
+        )
     }
 }
 
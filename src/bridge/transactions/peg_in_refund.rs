@@ -1,14 +1,15 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
-    XOnlyPublicKey,
+    absolute, consensus, taproot::TaprootSpendInfo, Network, PublicKey, ScriptBuf, TapSighashType,
+    Transaction, TxOut, XOnlyPublicKey,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use super::{
     super::{
         connectors::{base::*, connector_z::ConnectorZ},
         contexts::depositor::DepositorContext,
-        graphs::base::FEE_AMOUNT,
+        params::BridgeParams,
         scripts::*,
     },
     base::*,
@@ -33,12 +34,21 @@ impl PreSignedTransaction for PegInRefundTransaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> {
+        HashMap::from([(0, self.connector_z.generate_taproot_spend_info())])
+    }
 }
 
 impl PegInRefundTransaction {
-    pub fn new(context: &DepositorContext, evm_address: &str, input_0: Input) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        evm_address: &str,
+        input_0: Input,
+    ) -> Result<Self, String> {
         let mut this = Self::new_for_validation(
             context.network,
+            &context.params,
             &context.depositor_public_key,
             &context.depositor_taproot_public_key,
             &context.n_of_n_taproot_public_key,
@@ -46,13 +56,14 @@ impl PegInRefundTransaction {
             input_0,
         );
 
-        this.sign_input_0(context);
+        this.sign_input_0(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         depositor_public_key: &PublicKey,
         depositor_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
@@ -64,12 +75,13 @@ impl PegInRefundTransaction {
             evm_address,
             depositor_taproot_public_key,
             n_of_n_taproot_public_key,
+            params,
         );
 
         let input_0_leaf = 0;
         let _input_0 = connector_z.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(FEE_AMOUNT);
+        let total_output_amount = input_0.amount - params.fee();
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -95,15 +107,15 @@ impl PegInRefundTransaction {
 
     pub fn num_blocks_timelock_0(&self) -> u32 { self.connector_z.num_blocks_timelock_0 }
 
-    fn sign_input_0(&mut self, context: &DepositorContext) {
+    fn sign_input_0(&mut self, context: &DepositorContext) -> Result<(), String> {
         pre_sign_taproot_input_default(
             self,
             context,
             0,
             TapSighashType::All,
             self.connector_z.generate_taproot_spend_info(),
-            &vec![&context.depositor_keypair],
-        );
+            &vec![context.depositor_signer.as_ref()],
+        )
     }
 }
 
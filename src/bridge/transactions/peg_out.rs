@@ -1,13 +1,14 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Sequence,
-    Transaction, TxIn, TxOut, Witness,
+    absolute, consensus, taproot::TaprootSpendInfo, EcdsaSighashType, Network, PublicKey, ScriptBuf,
+    Sequence, Transaction, TxIn, TxOut, Witness,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::bridge::client::chain::chain::PegOutEvent;
 
 use super::{
-    super::{contexts::operator::OperatorContext, graphs::base::FEE_AMOUNT, scripts::*},
+    super::{contexts::operator::OperatorContext, params::BridgeParams, scripts::*},
     base::*,
     pre_signed::*,
 };
@@ -29,24 +30,32 @@ impl PreSignedTransaction for PegOutTransaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> { HashMap::new() }
 }
 
 impl PegOutTransaction {
-    pub fn new(context: &OperatorContext, peg_out_event: &PegOutEvent, input_0: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        peg_out_event: &PegOutEvent,
+        input_0: Input,
+    ) -> Result<Self, String> {
         let mut this = Self::new_for_validation(
             context.network,
+            &context.params,
             &context.operator_public_key,
             peg_out_event,
             input_0,
         );
 
-        this.sign_input_0(context);
+        this.sign_input_0(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         operator_public_key: &PublicKey,
         peg_out_event: &PegOutEvent,
         input_0: Input,
@@ -58,7 +67,7 @@ impl PegOutTransaction {
             witness: Witness::default(),
         };
 
-        let total_output_amount = input_0.amount - Amount::from_sat(FEE_AMOUNT);
+        let total_output_amount = input_0.amount - params.fee();
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -87,15 +96,15 @@ impl PegOutTransaction {
         }
     }
 
-    fn sign_input_0(&mut self, context: &OperatorContext) {
+    fn sign_input_0(&mut self, context: &OperatorContext) -> Result<(), String> {
         let input_index = 0;
         pre_sign_p2wsh_input(
             self,
             context,
             input_index,
             EcdsaSighashType::All,
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 }
 
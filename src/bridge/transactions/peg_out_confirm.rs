@@ -1,14 +1,15 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, Transaction,
-    TxOut,
+    absolute, consensus, taproot::TaprootSpendInfo, EcdsaSighashType, Network, PublicKey, ScriptBuf,
+    Transaction, TxOut,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use super::{
     super::{
         connectors::{base::*, connector_6::Connector6},
         contexts::operator::OperatorContext,
-        graphs::base::FEE_AMOUNT,
+        params::BridgeParams,
         scripts::*,
     },
     base::*,
@@ -32,31 +33,39 @@ impl PreSignedTransaction for PegOutConfirmTransaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> { HashMap::new() }
 }
 
 impl PegOutConfirmTransaction {
-    pub fn new(context: &OperatorContext, connector_6: &Connector6, input_0: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        connector_6: &Connector6,
+        input_0: Input,
+    ) -> Result<Self, String> {
         let mut this = Self::new_for_validation(
             context.network,
+            &context.params,
             &context.operator_public_key,
             connector_6,
             input_0,
         );
 
-        this.sign_input_0(context);
+        this.sign_input_0(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         operator_public_key: &PublicKey,
         connector_6: &Connector6,
         input_0: Input,
     ) -> Self {
         let _input_0 = generate_default_tx_in(&input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(FEE_AMOUNT);
+        let total_output_amount = input_0.amount - params.fee();
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -79,15 +88,15 @@ impl PegOutConfirmTransaction {
         }
     }
 
-    fn sign_input_0(&mut self, context: &OperatorContext) {
+    fn sign_input_0(&mut self, context: &OperatorContext) -> Result<(), String> {
         let input_index = 0;
         pre_sign_p2wsh_input(
             self,
             context,
             input_index,
             EcdsaSighashType::All,
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 }
 
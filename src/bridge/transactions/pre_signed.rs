@@ -1,10 +1,12 @@
+use std::collections::HashMap;
+
 use bitcoin::{
-    key::Keypair, taproot::TaprootSpendInfo, EcdsaSighashType, PublicKey, ScriptBuf,
-    TapSighashType, Transaction, TxOut,
+    taproot::TaprootSpendInfo, EcdsaSighashType, PublicKey, ScriptBuf, TapSighashType, Transaction,
+    TxOut,
 };
 
 use super::{
-    super::contexts::base::BaseContext,
+    super::{contexts::base::BaseContext, signer::Signer},
     signing::{
         populate_p2wpkh_witness, populate_p2wsh_witness, populate_taproot_input_witness_default,
     },
@@ -15,6 +17,8 @@ pub trait PreSignedTransaction {
     fn tx_mut(&mut self) -> &mut Transaction;
     fn prev_outs(&self) -> &Vec<TxOut>;
     fn prev_scripts(&self) -> &Vec<ScriptBuf>;
+    /// Spend info of the connector spent by each taproot input, keyed by input index
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo>;
 }
 
 pub fn pre_sign_p2wsh_input<T: PreSignedTransaction>(
@@ -22,8 +26,8 @@ pub fn pre_sign_p2wsh_input<T: PreSignedTransaction>(
     context: &dyn BaseContext,
     input_index: usize,
     sighash_type: EcdsaSighashType,
-    keypairs: &Vec<&Keypair>,
-) {
+    signers: &Vec<&dyn Signer>,
+) -> Result<(), String> {
     let script = &tx.prev_scripts()[input_index].clone();
     let value = tx.prev_outs()[input_index].value;
 
@@ -34,8 +38,8 @@ pub fn pre_sign_p2wsh_input<T: PreSignedTransaction>(
         sighash_type,
         script,
         value,
-        keypairs,
-    );
+        signers,
+    )
 }
 
 pub fn pre_sign_p2wpkh_input<T: PreSignedTransaction>(
@@ -44,8 +48,8 @@ pub fn pre_sign_p2wpkh_input<T: PreSignedTransaction>(
     input_index: usize,
     sighash_type: EcdsaSighashType,
     public_key: &PublicKey,
-    keypair: &Keypair,
-) {
+    signer: &dyn Signer,
+) -> Result<(), String> {
     let value = tx.prev_outs()[input_index].value;
 
     populate_p2wpkh_witness(
@@ -55,8 +59,8 @@ pub fn pre_sign_p2wpkh_input<T: PreSignedTransaction>(
         sighash_type,
         value,
         public_key,
-        keypair,
-    );
+        signer,
+    )
 }
 
 pub fn pre_sign_taproot_input_default<T: PreSignedTransaction>(
@@ -65,8 +69,8 @@ pub fn pre_sign_taproot_input_default<T: PreSignedTransaction>(
     input_index: usize,
     sighash_type: TapSighashType,
     taproot_spend_info: TaprootSpendInfo,
-    keypairs: &Vec<&Keypair>,
-) {
+    signers: &Vec<&dyn Signer>,
+) -> Result<(), String> {
     let prev_outs = &tx.prev_outs().clone();
     let script = &tx.prev_scripts()[input_index].clone();
 
@@ -78,6 +82,6 @@ pub fn pre_sign_taproot_input_default<T: PreSignedTransaction>(
         sighash_type,
         &taproot_spend_info,
         script,
-        keypairs,
-    );
+        signers,
+    )
 }
@@ -4,7 +4,7 @@ use bitcoin::{
 use musig2::{
     secp::MaybeScalar,
     secp256k1::{schnorr::Signature, Message},
-    BinaryEncoding, PartialSignature, PubNonce, SecNonce,
+    BinaryEncoding, PartialSignature, PubNonce,
 };
 use std::collections::HashMap;
 
@@ -13,7 +13,7 @@ use super::{
     pre_signed::PreSignedTransaction,
     signing::push_taproot_leaf_script_and_control_block_to_witness,
     signing_musig2::{
-        generate_aggregated_nonce, generate_nonce, generate_taproot_aggregated_signature,
+        generate_aggregated_nonce, generate_taproot_aggregated_signature,
         generate_taproot_partial_signature,
     },
 };
@@ -28,24 +28,26 @@ pub trait PreSignedMusig2Transaction {
     fn musig2_signatures_mut(
         &mut self,
     ) -> &mut HashMap<usize, HashMap<PublicKey, PartialSignature>>;
+    /// Sighash type the n-of-n signs each MuSig2 input with, keyed by input index
+    fn musig2_sighash_types(&self) -> HashMap<usize, TapSighashType>;
 }
 
 pub fn push_nonce<T: PreSignedTransaction + PreSignedMusig2Transaction>(
     tx: &mut T,
     context: &VerifierContext,
     input_index: usize,
-) -> SecNonce {
-    // Push nonce
+) -> Result<PubNonce, String> {
+    // Push nonce, the signer keeps its secret part
     let musig2_nonces = tx.musig2_nonces_mut();
     if musig2_nonces.get(&input_index).is_none() {
         musig2_nonces.insert(input_index, HashMap::new());
     }
 
-    let secret_nonce = generate_nonce();
+    let nonce = context.verifier_signer.generate_musig2_nonce()?;
     musig2_nonces
         .get_mut(&input_index)
         .unwrap()
-        .insert(context.verifier_public_key, secret_nonce.public_nonce());
+        .insert(context.verifier_public_key, nonce.clone());
 
     // Sign the nonce and push the signature
     let musig2_nonce_signatures = tx.musig2_nonce_signatures_mut();
@@ -53,17 +55,40 @@ pub fn push_nonce<T: PreSignedTransaction + PreSignedMusig2Transaction>(
         musig2_nonce_signatures.insert(input_index, HashMap::new());
     }
 
-    let nonce_signature = context.secp.sign_schnorr(
-        &get_nonce_message(&secret_nonce.public_nonce()),
-        &context.verifier_keypair,
-    );
+    let nonce_signature = context
+        .verifier_signer
+        .sign_schnorr(&get_nonce_message(&nonce))?;
 
     musig2_nonce_signatures
         .get_mut(&input_index)
         .unwrap()
         .insert(context.verifier_public_key, nonce_signature);
 
-    secret_nonce
+    Ok(nonce)
+}
+
+/// Whether every MuSig2 input of `tx` has a nonce of each of `public_keys`
+pub fn has_musig2_nonces(tx: &dyn PreSignedMusig2Transaction, public_keys: &[PublicKey]) -> bool {
+    !tx.musig2_nonces().is_empty()
+        && tx
+            .musig2_nonces()
+            .values()
+            .all(|nonces| public_keys.iter().all(|key| nonces.contains_key(key)))
+}
+
+/// Whether every MuSig2 input of `tx` has a partial signature of each of `public_keys`
+pub fn has_musig2_signatures(
+    tx: &dyn PreSignedMusig2Transaction,
+    public_keys: &[PublicKey],
+) -> bool {
+    !tx.musig2_nonces().is_empty()
+        && tx.musig2_nonces().keys().all(|input_index| {
+            tx.musig2_signatures()
+                .get(input_index)
+                .is_some_and(|signatures| {
+                    public_keys.iter().all(|key| signatures.contains_key(key))
+                })
+        })
 }
 
 pub fn get_nonce_message(nonce: &PubNonce) -> Message {
@@ -89,8 +114,8 @@ pub fn pre_sign_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
     context: &VerifierContext,
     input_index: usize,
     sighash_type: TapSighashType,
-    secret_nonce: &SecNonce,
-) {
+    nonce: &PubNonce,
+) -> Result<(), String> {
     // TODO validate nonces first
 
     let prev_outs = &tx.prev_outs().clone();
@@ -103,14 +128,13 @@ pub fn pre_sign_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
     let partial_signature = generate_taproot_partial_signature(
         context,
         tx.tx_mut(),
-        secret_nonce,
+        nonce,
         &generate_aggregated_nonce(musig2_nonces),
         input_index,
         prev_outs,
         script,
         sighash_type,
-    )
-    .unwrap(); // TODO: Add error handling.
+    )?;
 
     let musig2_signatures = tx.musig2_signatures_mut();
     if musig2_signatures.get(&input_index).is_none() {
@@ -120,6 +144,8 @@ pub fn pre_sign_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
         .get_mut(&input_index)
         .unwrap()
         .insert(context.verifier_public_key, partial_signature);
+
+    Ok(())
 }
 
 pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Transaction>(
@@ -128,7 +154,7 @@ pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
     input_index: usize,
     sighash_type: TapSighashType,
     taproot_spend_info: TaprootSpendInfo,
-) {
+) -> Result<(), String> {
     // TODO: Verify we have partial signatures from all verifiers.
     // TODO: Verify each signature against the signers public key.
     // See example here: https://github.com/conduition/musig2/blob/c39bfce58098d337a3ec38b54d93def8306d9953/src/signing.rs#L358C1-L366C65
@@ -147,7 +173,7 @@ pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
 
     // Aggregate signature
     let signature = generate_taproot_aggregated_signature(
-        context,
+        context.n_of_n_public_keys(),
         tx_mut,
         &generate_aggregated_nonce(musig2_nonces),
         input_index,
@@ -156,7 +182,12 @@ pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
         sighash_type,
         musig2_signatures, // TODO: Is there a more elegant way of doing this?
     )
-    .unwrap(); // TODO: Add error handling.
+    .map_err(|e| {
+        format!(
+            "Unable to aggregate signature of input {}: {}",
+            input_index, e
+        )
+    })?;
 
     let final_signature = bitcoin::taproot::Signature {
         signature: signature.into(),
@@ -175,4 +206,6 @@ pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
         &taproot_spend_info,
         script,
     );
+
+    Ok(())
 }
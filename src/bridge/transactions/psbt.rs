@@ -0,0 +1,874 @@
+use bitcoin::{
+    consensus, ecdsa,
+    hashes::Hash,
+    hex::DisplayHex,
+    opcodes::all::{OP_CHECKSIG, OP_CHECKSIGADD, OP_CHECKSIGVERIFY},
+    psbt::{raw, Input as PsbtInput, Psbt},
+    script::Instruction,
+    secp256k1::{Message, Secp256k1, XOnlyPublicKey},
+    sighash::{Prevouts, SighashCache},
+    taproot::{self, ControlBlock, LeafVersion},
+    PublicKey, Script, ScriptBuf, TapLeafHash, TapSighashType, Witness,
+};
+use musig2::{BinaryEncoding, PartialSignature, PubNonce};
+use std::cmp::Reverse;
+
+use super::{
+    super::contexts::base::generate_n_of_n_public_key,
+    pre_signed::PreSignedTransaction,
+    pre_signed_musig2::PreSignedMusig2Transaction,
+    signing_musig2::{generate_aggregated_nonce, generate_taproot_aggregated_signature},
+};
+
+// MuSig2 input fields of BIP-373
+const PSBT_IN_MUSIG2_PARTICIPANT_PUBKEYS: u8 = 0x1a;
+const PSBT_IN_MUSIG2_PUB_NONCE: u8 = 0x1b;
+const PSBT_IN_MUSIG2_PARTIAL_SIG: u8 = 0x1c;
+
+// Proprietary input field holding the unlock data of a taproot leaf that are not signatures
+const PSBT_PROPRIETARY_PREFIX: &[u8] = b"bitvm";
+const PSBT_IN_UNLOCK_DATA: u8 = 0x00;
+
+/// Export `tx` as a PSBT carrying its prevouts, witness scripts and taproot leaves.
+/// Inputs already signed in-process are exported as finalized.
+pub fn export_psbt<T: PreSignedTransaction>(tx: &T) -> Psbt {
+    let mut unsigned_tx = tx.tx().clone();
+    for input in unsigned_tx.input.iter_mut() {
+        input.witness = Witness::default();
+    }
+    let mut psbt = Psbt::from_unsigned_tx(unsigned_tx).expect("Unable to create PSBT");
+    let taproot_spend_infos = tx.taproot_spend_infos();
+
+    for (input_index, psbt_input) in psbt.inputs.iter_mut().enumerate() {
+        let prev_out = &tx.prev_outs()[input_index];
+        let script = &tx.prev_scripts()[input_index];
+
+        psbt_input.witness_utxo = Some(prev_out.clone());
+        if prev_out.script_pubkey.is_p2wsh() {
+            psbt_input.witness_script = Some(script.clone());
+        } else if prev_out.script_pubkey.is_p2tr() {
+            if let Some(taproot_spend_info) = taproot_spend_infos.get(&input_index) {
+                let leaf = (script.clone(), LeafVersion::TapScript);
+                let control_block = taproot_spend_info
+                    .control_block(&leaf)
+                    .expect("Unable to create Control block");
+                psbt_input.tap_scripts.insert(control_block, leaf);
+                psbt_input.tap_internal_key = Some(taproot_spend_info.internal_key());
+                psbt_input.tap_merkle_root = taproot_spend_info.merkle_root();
+            }
+        }
+
+        let witness = &tx.tx().input[input_index].witness;
+        if !witness.is_empty() {
+            psbt_input.final_script_witness = Some(witness.clone());
+        }
+    }
+
+    psbt
+}
+
+/// Export `tx` as in `export_psbt`, adding the sighash type, MuSig2 participants, public nonces
+/// and partial signatures of its n-of-n inputs as BIP-373 fields
+pub fn export_musig2_psbt<T: PreSignedTransaction + PreSignedMusig2Transaction>(
+    tx: &T,
+    n_of_n_public_keys: &Vec<PublicKey>,
+) -> Psbt {
+    let mut psbt = export_psbt(tx);
+    let (n_of_n_public_key, _) = generate_n_of_n_public_key(n_of_n_public_keys);
+
+    for (input_index, sighash_type) in tx.musig2_sighash_types() {
+        let psbt_input = &mut psbt.inputs[input_index];
+        psbt_input.sighash_type = Some(sighash_type.into());
+        psbt_input.unknown.insert(
+            raw::Key {
+                type_value: PSBT_IN_MUSIG2_PARTICIPANT_PUBKEYS,
+                key: n_of_n_public_key.to_bytes(),
+            },
+            n_of_n_public_keys
+                .iter()
+                .flat_map(|public_key| public_key.to_bytes())
+                .collect(),
+        );
+
+        let nonces = tx.musig2_nonces().get(&input_index);
+        let signatures = tx.musig2_signatures().get(&input_index);
+        let leaf_hash =
+            TapLeafHash::from_script(&tx.prev_scripts()[input_index], LeafVersion::TapScript);
+        for (public_key, nonce) in nonces.into_iter().flatten() {
+            psbt_input.unknown.insert(
+                musig2_key(PSBT_IN_MUSIG2_PUB_NONCE, public_key, &n_of_n_public_key, &leaf_hash),
+                nonce.to_bytes().to_vec(),
+            );
+        }
+        for (public_key, signature) in signatures.into_iter().flatten() {
+            psbt_input.unknown.insert(
+                musig2_key(PSBT_IN_MUSIG2_PARTIAL_SIG, public_key, &n_of_n_public_key, &leaf_hash),
+                signature.serialize().to_vec(),
+            );
+        }
+    }
+
+    psbt
+}
+
+/// Attach to input `input_index` of `psbt` the elements of its taproot leaf witness that are not
+/// signatures, such as Winternitz signatures or preimages. Finalizing the input pushes them after
+/// its signatures, in the given order.
+pub fn set_unlock_data(psbt: &mut Psbt, input_index: usize, unlock_data: &[Vec<u8>]) {
+    psbt.inputs[input_index].proprietary.insert(
+        unlock_data_key(),
+        consensus::serialize(&Witness::from_slice(unlock_data)),
+    );
+}
+
+fn unlock_data_key() -> raw::ProprietaryKey {
+    raw::ProprietaryKey {
+        prefix: PSBT_PROPRIETARY_PREFIX.to_vec(),
+        subtype: PSBT_IN_UNLOCK_DATA,
+        key: vec![],
+    }
+}
+
+fn unlock_data(input_index: usize, psbt_input: &PsbtInput) -> Result<Witness, String> {
+    match psbt_input.proprietary.get(&unlock_data_key()) {
+        Some(value) => consensus::deserialize(value)
+            .map_err(|e| format!("Invalid unlock data in input {}: {}", input_index, e)),
+        None => Ok(Witness::new()),
+    }
+}
+
+// Key of a per-participant BIP-373 field: participant key, aggregate key and leaf hash
+fn musig2_key(
+    type_value: u8,
+    participant: &PublicKey,
+    aggregate: &PublicKey,
+    leaf_hash: &TapLeafHash,
+) -> raw::Key {
+    raw::Key {
+        type_value,
+        key: [
+            participant.to_bytes(),
+            aggregate.to_bytes(),
+            leaf_hash.to_byte_array().to_vec(),
+        ]
+        .concat(),
+    }
+}
+
+/// Finalize the inputs of `tx` signed in `psbt` into the witness layouts the connectors expect.
+/// Inputs without signatures or unlock data in `psbt` are left as they are. Signatures are
+/// checked against the sighashes of `tx`, and a rejected PSBT leaves `tx` unchanged.
+pub fn import_psbt<T: PreSignedTransaction>(tx: &mut T, psbt: &Psbt) -> Result<(), String> {
+    check_txid(&*tx, psbt)?;
+
+    let mut witnesses = vec![];
+    for (input_index, psbt_input) in psbt.inputs.iter().enumerate() {
+        if let Some(witness) = finalize_input(&*tx, input_index, psbt_input)? {
+            witnesses.push((input_index, witness));
+        }
+    }
+    for (input_index, witness) in witnesses {
+        tx.tx_mut().input[input_index].witness = witness;
+    }
+
+    Ok(())
+}
+
+/// Import `psbt` as in `import_psbt`, also collecting the BIP-373 public nonces and partial
+/// signatures of the n-of-n inputs. Once every key of `n_of_n_public_keys` has a nonce and a
+/// partial signature for an input, they are aggregated into its n-of-n witness. Nonce signatures
+/// are not part of BIP-373 and are not imported. A rejected PSBT leaves `tx` unchanged.
+pub fn import_musig2_psbt<T: PreSignedTransaction + PreSignedMusig2Transaction + Clone>(
+    tx: &mut T,
+    psbt: &Psbt,
+    n_of_n_public_keys: &Vec<PublicKey>,
+) -> Result<(), String> {
+    check_txid(&*tx, psbt)?;
+
+    // Nonces and partial signatures are collected before they can be checked, so work on a copy
+    let mut imported = tx.clone();
+
+    for (input_index, psbt_input) in psbt.inputs.iter().enumerate() {
+        for (key, value) in psbt_input.unknown.iter() {
+            if key.type_value != PSBT_IN_MUSIG2_PUB_NONCE
+                && key.type_value != PSBT_IN_MUSIG2_PARTIAL_SIG
+            {
+                continue;
+            }
+            let participant = key
+                .key
+                .get(..33)
+                .and_then(|bytes| PublicKey::from_slice(bytes).ok())
+                .ok_or(format!("Invalid MuSig2 participant in input {}", input_index))?;
+
+            if key.type_value == PSBT_IN_MUSIG2_PUB_NONCE {
+                let nonce = PubNonce::from_bytes(value).map_err(|e| {
+                    format!("Invalid MuSig2 nonce in input {}: {:?}", input_index, e)
+                })?;
+                imported
+                    .musig2_nonces_mut()
+                    .entry(input_index)
+                    .or_default()
+                    .insert(participant, nonce);
+            } else {
+                let signature = PartialSignature::from_slice(value).map_err(|e| {
+                    format!("Invalid MuSig2 partial signature in input {}: {:?}", input_index, e)
+                })?;
+                imported
+                    .musig2_signatures_mut()
+                    .entry(input_index)
+                    .or_default()
+                    .insert(participant, signature);
+            }
+        }
+    }
+
+    let sighash_types = imported.musig2_sighash_types();
+    for (input_index, psbt_input) in psbt.inputs.iter().enumerate() {
+        let witness = match sighash_types.get(&input_index) {
+            Some(&sighash_type) if psbt_input.final_script_witness.is_none() => {
+                finalize_musig2_input(
+                    &imported,
+                    input_index,
+                    psbt_input,
+                    sighash_type,
+                    n_of_n_public_keys,
+                )?
+            }
+            _ => finalize_input(&imported, input_index, psbt_input)?,
+        };
+        if let Some(witness) = witness {
+            imported.tx_mut().input[input_index].witness = witness;
+        }
+    }
+    *tx = imported;
+
+    Ok(())
+}
+
+fn check_txid<T: PreSignedTransaction>(tx: &T, psbt: &Psbt) -> Result<(), String> {
+    let txid = tx.tx().compute_txid();
+    if psbt.unsigned_tx.compute_txid() != txid {
+        return Err(format!(
+            "PSBT is for transaction {}, not {}",
+            psbt.unsigned_tx.compute_txid(),
+            txid
+        ));
+    }
+
+    Ok(())
+}
+
+fn finalize_input<T: PreSignedTransaction>(
+    tx: &T,
+    input_index: usize,
+    psbt_input: &PsbtInput,
+) -> Result<Option<Witness>, String> {
+    if let Some(witness) = &psbt_input.final_script_witness {
+        verify_final_witness(tx, input_index, witness)?;
+        return Ok(Some(witness.clone()));
+    }
+
+    let script = &tx.prev_scripts()[input_index];
+    let script_pubkey = &tx.prev_outs()[input_index].script_pubkey;
+    let mut witness = Witness::new();
+
+    if script_pubkey.is_p2wsh() {
+        if psbt_input.partial_sigs.is_empty() {
+            return Ok(None);
+        }
+        // The signature of the first key in the script goes on top
+        let mut signatures = vec![];
+        for (public_key, signature) in psbt_input.partial_sigs.iter() {
+            verify_ecdsa_signature(tx, input_index, public_key, signature)?;
+            signatures.push((key_position(script, &public_key.to_bytes())?, signature));
+        }
+        signatures.sort_by_key(|(position, _)| Reverse(*position));
+        for (_, signature) in signatures {
+            witness.push_ecdsa_signature(signature);
+        }
+        witness.push(script.as_bytes());
+    } else if script_pubkey.is_p2wpkh() {
+        match psbt_input.partial_sigs.iter().next() {
+            Some((public_key, signature)) => {
+                verify_ecdsa_signature(tx, input_index, public_key, signature)?;
+                witness.push_ecdsa_signature(signature);
+                witness.push(public_key.to_bytes());
+            }
+            None => return Ok(None),
+        }
+    } else if script_pubkey.is_p2tr() {
+        let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
+        let mut signatures = vec![];
+        for ((public_key, signature_leaf_hash), signature) in psbt_input.tap_script_sigs.iter() {
+            if *signature_leaf_hash == leaf_hash {
+                verify_schnorr_signature(tx, input_index, public_key, signature)?;
+                signatures.push((key_position(script, &public_key.serialize())?, signature));
+            }
+        }
+        let unlock_data = unlock_data(input_index, psbt_input)?;
+        if signatures.is_empty() && unlock_data.is_empty() {
+            return Ok(None);
+        }
+
+        signatures.sort_by_key(|(position, _)| Reverse(*position));
+        for (_, signature) in signatures {
+            witness.push(signature.to_vec());
+        }
+        push_leaf(
+            &mut witness,
+            &unlock_data,
+            script,
+            control_block(input_index, psbt_input, script_pubkey, script)?,
+        );
+    } else {
+        return Err(format!("Unsupported prevout of input {}", input_index));
+    }
+
+    Ok(Some(witness))
+}
+
+// Aggregate the partial signatures of an n-of-n input, once all participants have signed
+fn finalize_musig2_input<T: PreSignedTransaction + PreSignedMusig2Transaction>(
+    tx: &T,
+    input_index: usize,
+    psbt_input: &PsbtInput,
+    sighash_type: TapSighashType,
+    n_of_n_public_keys: &Vec<PublicKey>,
+) -> Result<Option<Witness>, String> {
+    let (Some(nonces), Some(signatures)) = (
+        tx.musig2_nonces().get(&input_index),
+        tx.musig2_signatures().get(&input_index),
+    ) else {
+        return Ok(None);
+    };
+    let mut musig2_nonces = vec![];
+    let mut musig2_signatures = vec![];
+    for public_key in n_of_n_public_keys {
+        match (nonces.get(public_key), signatures.get(public_key)) {
+            (Some(nonce), Some(&signature)) => {
+                musig2_nonces.push(nonce.clone());
+                musig2_signatures.push(signature);
+            }
+            _ => return Ok(None),
+        }
+    }
+
+    let script = &tx.prev_scripts()[input_index];
+    let script_pubkey = &tx.prev_outs()[input_index].script_pubkey;
+    let signature = generate_taproot_aggregated_signature(
+        n_of_n_public_keys,
+        tx.tx(),
+        &generate_aggregated_nonce(&musig2_nonces),
+        input_index,
+        tx.prev_outs(),
+        script,
+        sighash_type,
+        musig2_signatures,
+    )
+    .map_err(|e| format!("Invalid MuSig2 partial signatures in input {}: {:?}", input_index, e))?;
+
+    let mut witness = Witness::new();
+    witness.push(
+        taproot::Signature {
+            signature: signature.into(),
+            sighash_type,
+        }
+        .serialize(),
+    );
+    push_leaf(
+        &mut witness,
+        &unlock_data(input_index, psbt_input)?,
+        script,
+        control_block(input_index, psbt_input, script_pubkey, script)?,
+    );
+
+    Ok(Some(witness))
+}
+
+fn control_block<'a>(
+    input_index: usize,
+    psbt_input: &'a PsbtInput,
+    script_pubkey: &Script,
+    script: &Script,
+) -> Result<&'a ControlBlock, String> {
+    let control_block = psbt_input
+        .tap_scripts
+        .iter()
+        .find(|(_, (leaf_script, _))| leaf_script.as_script() == script)
+        .map(|(control_block, _)| control_block)
+        .ok_or(format!("Missing control block of input {}", input_index))?;
+    verify_control_block(input_index, control_block, script_pubkey, script)?;
+
+    Ok(control_block)
+}
+
+fn verify_control_block(
+    input_index: usize,
+    control_block: &ControlBlock,
+    script_pubkey: &Script,
+    script: &Script,
+) -> Result<(), String> {
+    let output_key = XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..])
+        .map_err(|e| format!("Invalid taproot output of input {}: {}", input_index, e))?;
+    let secp = Secp256k1::verification_only();
+    if !control_block.verify_taproot_commitment(&secp, output_key, script) {
+        return Err(format!("Invalid control block of input {}", input_index));
+    }
+
+    Ok(())
+}
+
+// Check a witness finalized by the signer. Its signatures must be laid out as `finalize_input`
+// lays them out, the signature of the first key in the script on top.
+fn verify_final_witness<T: PreSignedTransaction>(
+    tx: &T,
+    input_index: usize,
+    witness: &Witness,
+) -> Result<(), String> {
+    let script = &tx.prev_scripts()[input_index];
+    let script_pubkey = &tx.prev_outs()[input_index].script_pubkey;
+    let invalid = || format!("Invalid final witness of input {}", input_index);
+    let elements = witness.to_vec();
+
+    if script_pubkey.is_p2wsh() {
+        let (witness_script, signatures) = elements.split_last().ok_or_else(invalid)?;
+        let keys = signature_keys(script);
+        if witness_script != script.as_bytes() || signatures.len() != keys.len() {
+            return Err(invalid());
+        }
+        for (key, signature) in keys.iter().rev().zip(signatures) {
+            let public_key = PublicKey::from_slice(key).map_err(|_| invalid())?;
+            let signature = ecdsa::Signature::from_slice(signature).map_err(|_| invalid())?;
+            verify_ecdsa_signature(tx, input_index, &public_key, &signature)?;
+        }
+    } else if script_pubkey.is_p2wpkh() {
+        let [signature, public_key] = elements.as_slice() else {
+            return Err(invalid());
+        };
+        let public_key = PublicKey::from_slice(public_key).map_err(|_| invalid())?;
+        let signature = ecdsa::Signature::from_slice(signature).map_err(|_| invalid())?;
+        verify_ecdsa_signature(tx, input_index, &public_key, &signature)?;
+    } else if script_pubkey.is_p2tr() {
+        let [unlock_data @ .., leaf_script, control_block] = elements.as_slice() else {
+            return Err(invalid());
+        };
+        let keys = signature_keys(script);
+        if leaf_script != script.as_bytes() || unlock_data.len() < keys.len() {
+            return Err(invalid());
+        }
+        let control_block = ControlBlock::decode(control_block).map_err(|_| invalid())?;
+        verify_control_block(input_index, &control_block, script_pubkey, script)?;
+        // Unlock data that are not signatures are checked by the script itself
+        for (key, signature) in keys.iter().rev().zip(unlock_data) {
+            let public_key = XOnlyPublicKey::from_slice(key).map_err(|_| invalid())?;
+            let signature = taproot::Signature::from_slice(signature).map_err(|_| invalid())?;
+            verify_schnorr_signature(tx, input_index, &public_key, &signature)?;
+        }
+    } else {
+        return Err(format!("Unsupported prevout of input {}", input_index));
+    }
+
+    Ok(())
+}
+
+fn verify_ecdsa_signature<T: PreSignedTransaction>(
+    tx: &T,
+    input_index: usize,
+    public_key: &PublicKey,
+    signature: &ecdsa::Signature,
+) -> Result<(), String> {
+    let invalid = || {
+        format!(
+            "Invalid signature of {} for input {}",
+            public_key, input_index
+        )
+    };
+    let prev_out = &tx.prev_outs()[input_index];
+    let mut sighash_cache = SighashCache::new(tx.tx());
+    let sighash = if prev_out.script_pubkey.is_p2wpkh() {
+        let wpubkey_hash = public_key.wpubkey_hash().map_err(|_| invalid())?;
+        if ScriptBuf::new_p2wpkh(&wpubkey_hash) != prev_out.script_pubkey {
+            return Err(invalid());
+        }
+        sighash_cache
+            .p2wpkh_signature_hash(
+                input_index,
+                &prev_out.script_pubkey,
+                prev_out.value,
+                signature.sighash_type,
+            )
+            .map_err(|e| e.to_string())?
+    } else {
+        sighash_cache
+            .p2wsh_signature_hash(
+                input_index,
+                &tx.prev_scripts()[input_index],
+                prev_out.value,
+                signature.sighash_type,
+            )
+            .map_err(|e| e.to_string())?
+    };
+
+    Secp256k1::verification_only()
+        .verify_ecdsa(
+            &Message::from(sighash),
+            &signature.signature,
+            &public_key.inner,
+        )
+        .map_err(|_| invalid())
+}
+
+fn verify_schnorr_signature<T: PreSignedTransaction>(
+    tx: &T,
+    input_index: usize,
+    public_key: &XOnlyPublicKey,
+    signature: &taproot::Signature,
+) -> Result<(), String> {
+    let leaf_hash =
+        TapLeafHash::from_script(&tx.prev_scripts()[input_index], LeafVersion::TapScript);
+    let sighash = SighashCache::new(tx.tx())
+        .taproot_script_spend_signature_hash(
+            input_index,
+            &Prevouts::All(tx.prev_outs()),
+            leaf_hash,
+            signature.sighash_type,
+        )
+        .map_err(|e| e.to_string())?;
+
+    Secp256k1::verification_only()
+        .verify_schnorr(&signature.signature, &Message::from(sighash), public_key)
+        .map_err(|_| {
+            format!(
+                "Invalid signature of {} for input {}",
+                public_key, input_index
+            )
+        })
+}
+
+// Keys checked by the signature opcodes of `script`, in script order
+fn signature_keys(script: &Script) -> Vec<Vec<u8>> {
+    let instructions: Vec<_> = script.instructions().filter_map(Result::ok).collect();
+    instructions
+        .windows(2)
+        .filter_map(|pair| match pair {
+            [Instruction::PushBytes(key), Instruction::Op(op)]
+                if [OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CHECKSIGADD].contains(op) =>
+            {
+                Some(key.as_bytes().to_vec())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// Complete a taproot script path witness after its signatures
+fn push_leaf(
+    witness: &mut Witness,
+    unlock_data: &Witness,
+    script: &Script,
+    control_block: &ControlBlock,
+) {
+    for element in unlock_data.iter() {
+        witness.push(element);
+    }
+    witness.push(script.as_bytes());
+    witness.push(control_block.serialize());
+}
+
+fn key_position(script: &Script, key: &[u8]) -> Result<usize, String> {
+    script
+        .as_bytes()
+        .windows(key.len())
+        .position(|window| window == key)
+        .ok_or(format!("Key {} is not in the script", key.to_lower_hex_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{psbt::PsbtSighashType, Amount, EcdsaSighashType, Network, OutPoint};
+
+    use crate::execute_leaf;
+
+    use super::{
+        super::{
+            super::{
+                connectors::{
+                    base::{CommitmentConnector, TaprootConnector},
+                    connector_1::Connector1,
+                    connector_b::ConnectorB,
+                    connector_c::ConnectorC,
+                },
+                contexts::{
+                    base::generate_keys_from_secret, depositor::DepositorContext,
+                    operator::OperatorContext, verifier::VerifierContext,
+                },
+                graphs::base::{
+                    DEPOSITOR_EVM_ADDRESS, DEPOSITOR_SECRET, FEE_AMOUNT, INITIAL_AMOUNT,
+                    OPERATOR_SECRET, VERIFIER_0_SECRET, VERIFIER_1_SECRET,
+                },
+                superblock::{get_superblock_message, Superblock},
+            },
+            base::Input,
+            kick_off_2::{extract_superblock_message, KickOff2Transaction},
+            kick_off_timeout::KickOffTimeoutTransaction,
+            peg_in_deposit::PegInDepositTransaction,
+        },
+        *,
+    };
+
+    fn n_of_n_public_keys() -> Vec<PublicKey> {
+        [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+            .iter()
+            .map(|secret| generate_keys_from_secret(Network::Regtest, secret).2)
+            .collect()
+    }
+
+    #[test]
+    fn test_p2wsh_psbt_round_trip() {
+        let (_, _, verifier_public_key) =
+            generate_keys_from_secret(Network::Regtest, VERIFIER_0_SECRET);
+        let context =
+            DepositorContext::new(Network::Regtest, DEPOSITOR_SECRET, &vec![verifier_public_key]);
+        let input = || Input {
+            outpoint: OutPoint::null(),
+            amount: Amount::from_sat(INITIAL_AMOUNT + FEE_AMOUNT),
+        };
+
+        let signed =
+            PegInDepositTransaction::new(&context, DEPOSITOR_EVM_ADDRESS, input()).unwrap();
+        let mut unsigned = PegInDepositTransaction::new_for_validation(
+            context.network,
+            &context.params,
+            &context.depositor_public_key,
+            &context.depositor_taproot_public_key,
+            &context.n_of_n_taproot_public_key,
+            DEPOSITOR_EVM_ADDRESS,
+            input(),
+        );
+
+        let mut psbt = export_psbt(&unsigned);
+        let script = psbt.inputs[0].witness_script.clone().unwrap();
+        assert_eq!(script, unsigned.prev_scripts()[0]);
+
+        // Sign as an external signer would
+        let sighash = SighashCache::new(&psbt.unsigned_tx)
+            .p2wsh_signature_hash(
+                0,
+                &script,
+                psbt.inputs[0].witness_utxo.as_ref().unwrap().value,
+                EcdsaSighashType::All,
+            )
+            .unwrap();
+        let signature = context
+            .depositor_signer
+            .sign_ecdsa(&Message::from(sighash))
+            .unwrap();
+        psbt.inputs[0].partial_sigs.insert(
+            context.depositor_public_key,
+            ecdsa::Signature {
+                signature,
+                sighash_type: EcdsaSighashType::All,
+            },
+        );
+
+        let psbt = Psbt::deserialize(&psbt.serialize()).unwrap();
+
+        // Signatures are checked against the sighash and a rejected PSBT leaves the input as it is
+        let mut tampered = psbt.clone();
+        for signature in tampered.inputs[0].partial_sigs.values_mut() {
+            signature.sighash_type = EcdsaSighashType::None;
+        }
+        assert!(import_psbt(&mut unsigned, &tampered).is_err());
+        assert!(unsigned.tx().input[0].witness.is_empty());
+
+        import_psbt(&mut unsigned, &psbt).unwrap();
+        assert_eq!(unsigned.tx(), signed.tx());
+
+        // So are the signatures of a witness finalized by the signer
+        let mut finalized = export_psbt(&signed);
+        let mut witness = signed.tx().input[0].witness.to_vec();
+        *witness[0].last_mut().unwrap() = EcdsaSighashType::None.to_u32() as u8;
+        finalized.inputs[0].final_script_witness = Some(Witness::from_slice(&witness));
+        assert!(import_psbt(&mut unsigned, &finalized).is_err());
+        assert_eq!(unsigned.tx(), signed.tx());
+
+        // A PSBT of another transaction is rejected
+        let mut other = PegInDepositTransaction::new_for_validation(
+            context.network,
+            &context.params,
+            &context.depositor_public_key,
+            &context.depositor_taproot_public_key,
+            &context.n_of_n_taproot_public_key,
+            DEPOSITOR_EVM_ADDRESS,
+            Input {
+                outpoint: OutPoint::null(),
+                amount: Amount::from_sat(INITIAL_AMOUNT),
+            },
+        );
+        assert!(import_psbt(&mut other, &psbt).is_err());
+    }
+
+    #[test]
+    fn test_taproot_psbt_unlock_data() {
+        let context =
+            OperatorContext::new(Network::Regtest, OPERATOR_SECRET, &n_of_n_public_keys());
+        let (connector_1, winternitz_secrets) = Connector1::new(
+            context.network,
+            &context.operator_taproot_public_key,
+            &context.n_of_n_taproot_public_key,
+            &context.params,
+        );
+        let (connector_c, _) =
+            ConnectorC::new(context.network, &context.operator_taproot_public_key);
+        let connector_b = ConnectorB::new(
+            context.network,
+            &context.n_of_n_taproot_public_key,
+            &context.params,
+            &connector_c.winternitz_public_keys,
+        );
+        let mut kick_off_2 = KickOff2Transaction::new(
+            &context,
+            &connector_1,
+            &connector_b,
+            Input {
+                outpoint: OutPoint::null(),
+                amount: Amount::from_sat(INITIAL_AMOUNT),
+            },
+        );
+
+        let mut psbt = export_psbt(&kick_off_2);
+        let script = kick_off_2.prev_scripts()[0].clone();
+        assert!(psbt.inputs[0]
+            .tap_scripts
+            .values()
+            .any(|(leaf_script, _)| *leaf_script == script));
+
+        // Sign and commit to a superblock as an external signer would
+        let leaf_hash = TapLeafHash::from_script(&script, LeafVersion::TapScript);
+        let sighash = SighashCache::new(&psbt.unsigned_tx)
+            .taproot_script_spend_signature_hash(
+                0,
+                &Prevouts::All(kick_off_2.prev_outs()),
+                leaf_hash,
+                TapSighashType::All,
+            )
+            .unwrap();
+        let signature = context
+            .operator_signer
+            .sign_schnorr(&Message::from(sighash))
+            .unwrap();
+        let signature = taproot::Signature {
+            signature,
+            sighash_type: TapSighashType::All,
+        };
+        psbt.inputs[0]
+            .tap_script_sigs
+            .insert((context.operator_taproot_public_key, leaf_hash), signature);
+        let message = get_superblock_message(
+            &Superblock {
+                height: 123,
+                time: 45678,
+                weight: 9012345,
+            },
+            &[0xf0; 32],
+        );
+        set_unlock_data(
+            &mut psbt,
+            0,
+            &connector_1.generate_commitment_witness(0, &winternitz_secrets[&0], &message),
+        );
+
+        let psbt = Psbt::deserialize(&psbt.serialize()).unwrap();
+
+        // A signature that does not match its sighash type is rejected
+        let mut tampered = psbt.clone();
+        for signature in tampered.inputs[0].tap_script_sigs.values_mut() {
+            signature.sighash_type = TapSighashType::None;
+        }
+        assert!(import_psbt(&mut kick_off_2, &tampered).is_err());
+        assert!(kick_off_2.tx().input[0].witness.is_empty());
+
+        import_psbt(&mut kick_off_2, &psbt).unwrap();
+
+        // The signature goes below the Winternitz signatures and the leaf accepts the witness
+        let witness = kick_off_2.tx().input[0].witness.to_vec();
+        let (unlock_witness, leaf) = witness.split_at(witness.len() - 2);
+        assert_eq!(leaf[0], script.to_bytes());
+        assert_eq!(unlock_witness[0], signature.to_vec());
+        assert!(
+            execute_leaf(
+                kick_off_2.tx(),
+                0,
+                kick_off_2.prev_outs(),
+                &script,
+                unlock_witness.to_vec()
+            )
+            .success
+        );
+        assert_eq!(extract_superblock_message(kick_off_2.tx()), Some(message));
+    }
+
+    #[test]
+    fn test_musig2_psbt_round_trip() {
+        let n_of_n_public_keys = n_of_n_public_keys();
+        let verifier_contexts = [VERIFIER_0_SECRET, VERIFIER_1_SECRET]
+            .map(|secret| VerifierContext::new(Network::Regtest, secret, &n_of_n_public_keys));
+        let context = OperatorContext::new(Network::Regtest, OPERATOR_SECRET, &n_of_n_public_keys);
+        let (connector_1, _) = Connector1::new(
+            context.network,
+            &context.operator_taproot_public_key,
+            &context.n_of_n_taproot_public_key,
+            &context.params,
+        );
+        let input = || Input {
+            outpoint: OutPoint::null(),
+            amount: Amount::from_sat(INITIAL_AMOUNT),
+        };
+
+        let mut signed = KickOffTimeoutTransaction::new(&context, &connector_1, input());
+        let nonces: Vec<_> = verifier_contexts
+            .iter()
+            .map(|verifier_context| signed.push_nonces(verifier_context).unwrap())
+            .collect();
+        for (verifier_context, nonces) in verifier_contexts.iter().zip(nonces.iter()) {
+            signed
+                .pre_sign(verifier_context, &connector_1, nonces)
+                .unwrap();
+        }
+
+        // Keep only the BIP-373 fields, as a coordinator collecting them from the verifiers would
+        let mut psbt = export_musig2_psbt(&signed, &n_of_n_public_keys);
+        psbt.inputs[0].final_script_witness = None;
+        let psbt = Psbt::deserialize(&psbt.serialize()).unwrap();
+        assert_eq!(
+            psbt.inputs[0].sighash_type,
+            Some(PsbtSighashType::from(TapSighashType::Single))
+        );
+
+        let mut unsigned = KickOffTimeoutTransaction::new_for_validation(
+            context.network,
+            &context.params,
+            &connector_1,
+            input(),
+        );
+
+        // Partial signatures that do not aggregate into a valid signature are rejected, and none
+        // of the nonces and partial signatures of the PSBT are kept
+        let mut tampered = psbt.clone();
+        let (_, value) = tampered.inputs[0]
+            .unknown
+            .iter_mut()
+            .find(|(key, _)| key.type_value == PSBT_IN_MUSIG2_PARTIAL_SIG)
+            .unwrap();
+        *value = vec![1; 32];
+        assert!(import_musig2_psbt(&mut unsigned, &tampered, &n_of_n_public_keys).is_err());
+        assert!(unsigned.musig2_nonces().is_empty());
+        assert!(unsigned.musig2_signatures().is_empty());
+        assert!(unsigned.tx().input[0].witness.is_empty());
+
+        import_musig2_psbt(&mut unsigned, &psbt, &n_of_n_public_keys).unwrap();
+        assert_eq!(unsigned.musig2_signatures(), signed.musig2_signatures());
+        assert_eq!(unsigned.tx(), signed.tx());
+    }
+}
@@ -1,5 +1,4 @@
 use bitcoin::{
-    key::Keypair,
     secp256k1::Message,
     sighash::{Prevouts, SighashCache},
     taproot::{LeafVersion, TaprootSpendInfo},
@@ -7,7 +6,7 @@ use bitcoin::{
     Transaction, TxOut,
 };
 
-use super::super::{contexts::base::BaseContext, scripts::generate_p2wpkh_address};
+use super::super::{contexts::base::BaseContext, scripts::generate_p2wpkh_address, signer::Signer};
 
 pub fn generate_p2wsh_schnorr_signature(
     context: &dyn BaseContext,
@@ -16,22 +15,33 @@ pub fn generate_p2wsh_schnorr_signature(
     sighash_type: EcdsaSighashType,
     script: &Script,
     value: Amount,
-    keypair: &Keypair,
-) -> bitcoin::ecdsa::Signature {
+    signer: &dyn Signer,
+) -> Result<bitcoin::ecdsa::Signature, String> {
     let mut sighash_cache = SighashCache::new(tx);
 
     let sighash = sighash_cache
         .p2wsh_signature_hash(input_index, script, value, sighash_type)
         .expect("Failed to construct sighash");
 
-    let signature = context
+    let message = Message::from(sighash);
+    let signature = signer
+        .sign_ecdsa(&message)
+        .map_err(|e| format!("Unable to sign input {}: {}", input_index, e))?;
+    // The signer may be a remote process, so check what it returned
+    context
         .secp()
-        .sign_ecdsa(&Message::from(sighash), &keypair.secret_key());
+        .verify_ecdsa(&message, &signature, &signer.public_key().inner)
+        .map_err(|e| {
+            format!(
+                "Invalid signature from signer for input {}: {}",
+                input_index, e
+            )
+        })?;
 
-    bitcoin::ecdsa::Signature {
+    Ok(bitcoin::ecdsa::Signature {
         signature,
         sighash_type,
-    }
+    })
 }
 
 pub fn push_p2wsh_signature_to_witness(
@@ -41,8 +51,8 @@ pub fn push_p2wsh_signature_to_witness(
     sighash_type: EcdsaSighashType,
     script: &Script,
     value: Amount,
-    keypair: &Keypair,
-) {
+    signer: &dyn Signer,
+) -> Result<(), String> {
     let signature = generate_p2wsh_schnorr_signature(
         context,
         tx,
@@ -50,12 +60,14 @@ pub fn push_p2wsh_signature_to_witness(
         sighash_type,
         script,
         value,
-        keypair,
-    );
+        signer,
+    )?;
 
     tx.input[input_index]
         .witness
         .push_ecdsa_signature(&signature);
+
+    Ok(())
 }
 
 pub fn push_p2wsh_script_to_witness(tx: &mut Transaction, input_index: usize, script: &Script) {
@@ -69,9 +81,9 @@ pub fn populate_p2wsh_witness(
     sighash_type: EcdsaSighashType,
     script: &Script,
     value: Amount,
-    keypairs: &Vec<&Keypair>,
-) {
-    for keypair in keypairs {
+    signers: &Vec<&dyn Signer>,
+) -> Result<(), String> {
+    for signer in signers {
         push_p2wsh_signature_to_witness(
             context,
             tx,
@@ -79,10 +91,12 @@ pub fn populate_p2wsh_witness(
             sighash_type,
             script,
             value,
-            keypair,
-        );
+            *signer,
+        )?;
     }
     push_p2wsh_script_to_witness(tx, input_index, script);
+
+    Ok(())
 }
 
 pub fn generate_p2wpkh_schnorr_signature(
@@ -92,8 +106,8 @@ pub fn generate_p2wpkh_schnorr_signature(
     sighash_type: EcdsaSighashType,
     value: Amount,
     public_key: &PublicKey,
-    keypair: &Keypair,
-) -> bitcoin::ecdsa::Signature {
+    signer: &dyn Signer,
+) -> Result<bitcoin::ecdsa::Signature, String> {
     let mut sighash_cache = SighashCache::new(tx);
 
     let sighash = sighash_cache
@@ -105,14 +119,24 @@ pub fn generate_p2wpkh_schnorr_signature(
         )
         .expect("Failed to construct sighash");
 
-    let signature = context
+    let message = Message::from(sighash);
+    let signature = signer
+        .sign_ecdsa(&message)
+        .map_err(|e| format!("Unable to sign input {}: {}", input_index, e))?;
+    context
         .secp()
-        .sign_ecdsa(&Message::from(sighash), &keypair.secret_key());
+        .verify_ecdsa(&message, &signature, &signer.public_key().inner)
+        .map_err(|e| {
+            format!(
+                "Invalid signature from signer for input {}: {}",
+                input_index, e
+            )
+        })?;
 
-    bitcoin::ecdsa::Signature {
+    Ok(bitcoin::ecdsa::Signature {
         signature,
         sighash_type,
-    }
+    })
 }
 
 pub fn push_p2wpkh_signature_to_witness(
@@ -122,8 +146,8 @@ pub fn push_p2wpkh_signature_to_witness(
     sighash_type: EcdsaSighashType,
     value: Amount,
     public_key: &PublicKey,
-    keypair: &Keypair,
-) {
+    signer: &dyn Signer,
+) -> Result<(), String> {
     let signature = generate_p2wpkh_schnorr_signature(
         context,
         tx,
@@ -131,12 +155,14 @@ pub fn push_p2wpkh_signature_to_witness(
         sighash_type,
         value,
         public_key,
-        keypair,
-    );
+        signer,
+    )?;
 
     tx.input[input_index]
         .witness
         .push_ecdsa_signature(&signature);
+
+    Ok(())
 }
 
 pub fn push_p2wpkh_public_key_to_witness(
@@ -154,8 +180,8 @@ pub fn populate_p2wpkh_witness(
     sighash_type: EcdsaSighashType,
     value: Amount,
     public_key: &PublicKey,
-    keypair: &Keypair,
-) {
+    signer: &dyn Signer,
+) -> Result<(), String> {
     push_p2wpkh_signature_to_witness(
         context,
         tx,
@@ -163,9 +189,11 @@ pub fn populate_p2wpkh_witness(
         sighash_type,
         value,
         public_key,
-        keypair,
-    );
+        signer,
+    )?;
     push_p2wpkh_public_key_to_witness(tx, input_index, public_key);
+
+    Ok(())
 }
 
 pub fn generate_taproot_leaf_schnorr_signature(
@@ -175,8 +203,8 @@ pub fn generate_taproot_leaf_schnorr_signature(
     input_index: usize,
     sighash_type: TapSighashType,
     script: &Script,
-    keypair: &Keypair,
-) -> bitcoin::taproot::Signature {
+    signer: &dyn Signer,
+) -> Result<bitcoin::taproot::Signature, String> {
     let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
 
     let sighash;
@@ -203,14 +231,24 @@ pub fn generate_taproot_leaf_schnorr_signature(
             .expect("Failed to construct sighash");
     }
 
-    let signature = context
+    let message = Message::from(sighash);
+    let signature = signer
+        .sign_schnorr(&message)
+        .map_err(|e| format!("Unable to sign input {}: {}", input_index, e))?;
+    context
         .secp()
-        .sign_schnorr_no_aux_rand(&Message::from(sighash), keypair);
+        .verify_schnorr(&signature, &message, &signer.taproot_public_key())
+        .map_err(|e| {
+            format!(
+                "Invalid signature from signer for input {}: {}",
+                input_index, e
+            )
+        })?;
 
-    bitcoin::taproot::Signature {
+    Ok(bitcoin::taproot::Signature {
         signature,
         sighash_type,
-    }
+    })
 }
 
 pub fn push_taproot_leaf_unlock_data_to_witness(
@@ -276,10 +314,10 @@ pub fn populate_taproot_input_witness_default(
     sighash_type: TapSighashType,
     taproot_spend_info: &TaprootSpendInfo,
     script: &Script,
-    keypairs: &Vec<&Keypair>,
-) {
+    signers: &Vec<&dyn Signer>,
+) -> Result<(), String> {
     let mut unlock_data: Vec<Vec<u8>> = Vec::new();
-    for keypair in keypairs {
+    for signer in signers {
         let schnorr_signature = generate_taproot_leaf_schnorr_signature(
             context,
             tx,
@@ -287,8 +325,8 @@ pub fn populate_taproot_input_witness_default(
             input_index,
             sighash_type,
             script,
-            &keypair,
-        );
+            *signer,
+        )?;
         unlock_data.push(schnorr_signature.to_vec());
     }
 
@@ -299,4 +337,6 @@ pub fn populate_taproot_input_witness_default(
         taproot_spend_info,
         script,
     );
+
+    Ok(())
 }
@@ -1,16 +1,15 @@
 use bitcoin::{
+    secp256k1::Message,
     sighash::{Prevouts, SighashCache},
     taproot::LeafVersion,
-    Script, TapLeafHash, TapSighashType, Transaction, TxOut,
+    PublicKey, Script, TapLeafHash, TapSighashType, Transaction, TxOut,
 };
 use musig2::{
-    aggregate_partial_signatures,
-    errors::{SigningError, VerifyError},
-    secp::{MaybeScalar, Point},
-    sign_partial, AggNonce, KeyAggContext, LiftedSignature, PartialSignature, PubNonce, SecNonce,
+    aggregate_partial_signatures, errors::VerifyError, secp::Point, AggNonce, KeyAggContext,
+    LiftedSignature, PartialSignature, PubNonce, SecNonce,
 };
 
-use super::super::contexts::{base::BaseContext, verifier::VerifierContext};
+use super::super::contexts::verifier::VerifierContext;
 
 pub fn generate_nonce() -> SecNonce { SecNonce::build(&mut rand::rngs::OsRng).build() }
 
@@ -19,21 +18,13 @@ pub fn generate_aggregated_nonce(nonces: &Vec<PubNonce>) -> AggNonce { AggNonce:
 pub fn generate_taproot_partial_signature(
     context: &VerifierContext,
     tx: &Transaction,
-    secret_nonce: &SecNonce,
+    public_nonce: &PubNonce,
     aggregated_nonce: &AggNonce,
     input_index: usize,
     prevouts: &Vec<TxOut>,
     script: &Script,
     sighash_type: TapSighashType,
-) -> Result<MaybeScalar, SigningError> {
-    let pubkeys: Vec<Point> = Vec::from_iter(
-        context
-            .n_of_n_public_keys
-            .iter()
-            .map(|&public_key| public_key.inner.into()),
-    ); // TODO: The tests will reveal whether this conversion works as expected.
-    let key_agg_ctx = KeyAggContext::new(pubkeys).unwrap();
-
+) -> Result<PartialSignature, String> {
     let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
     let sighash = SighashCache::new(tx)
         .taproot_script_spend_signature_hash(
@@ -44,17 +35,17 @@ pub fn generate_taproot_partial_signature(
         )
         .expect("Failed to construct sighash");
 
-    sign_partial(
-        &key_agg_ctx,
-        context.verifier_keypair.secret_key(),
-        secret_nonce.clone(),
-        &aggregated_nonce,
-        sighash,
+    // The signer consumes the secret nonce of `public_nonce`
+    context.verifier_signer.sign_musig2_partial(
+        &context.n_of_n_public_keys,
+        public_nonce,
+        aggregated_nonce,
+        &Message::from(sighash),
     )
 }
 
 pub fn generate_taproot_aggregated_signature(
-    context: &dyn BaseContext,
+    n_of_n_public_keys: &Vec<PublicKey>,
     tx: &Transaction,
     aggregated_nonce: &AggNonce,
     input_index: usize,
@@ -63,12 +54,8 @@ pub fn generate_taproot_aggregated_signature(
     sighash_type: TapSighashType,
     partial_signatures: Vec<PartialSignature>,
 ) -> Result<LiftedSignature, VerifyError> {
-    let pubkeys: Vec<Point> = Vec::from_iter(
-        context
-            .n_of_n_public_keys()
-            .iter()
-            .map(|&public_key| public_key.inner.into()),
-    );
+    let pubkeys: Vec<Point> =
+        Vec::from_iter(n_of_n_public_keys.iter().map(|&public_key| public_key.inner.into()));
     let key_agg_ctx = KeyAggContext::new(pubkeys).unwrap();
 
     let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
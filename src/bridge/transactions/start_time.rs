@@ -1,5 +1,6 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
+    absolute, consensus, taproot::TaprootSpendInfo, Network, PublicKey, ScriptBuf, TapSighashType,
+    Transaction, TxOut,
 };
 use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
@@ -11,7 +12,7 @@ use crate::bridge::connectors::{
 };
 
 use super::{
-    super::{contexts::operator::OperatorContext, graphs::base::FEE_AMOUNT, scripts::*},
+    super::{contexts::operator::OperatorContext, params::BridgeParams, scripts::*},
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
@@ -26,6 +27,7 @@ pub struct StartTimeTransaction {
     #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
     prev_outs: Vec<TxOut>,
     prev_scripts: Vec<ScriptBuf>,
+    connector_2: Connector2,
 
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
     musig2_nonce_signatures: HashMap<usize, HashMap<PublicKey, Signature>>,
@@ -40,6 +42,10 @@ impl PreSignedTransaction for StartTimeTransaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> {
+        HashMap::from([(0, self.connector_2.generate_taproot_spend_info())])
+    }
 }
 
 impl PreSignedMusig2Transaction for StartTimeTransaction {
@@ -63,12 +69,14 @@ impl PreSignedMusig2Transaction for StartTimeTransaction {
     ) -> &mut HashMap<usize, HashMap<PublicKey, PartialSignature>> {
         &mut self.musig2_signatures
     }
+    fn musig2_sighash_types(&self) -> HashMap<usize, TapSighashType> { HashMap::new() }
 }
 
 impl StartTimeTransaction {
     pub fn new(context: &OperatorContext, connector_2: &Connector2, input_0: Input) -> Self {
         let this = Self::new_for_validation(
             context.network,
+            &context.params,
             &context.operator_public_key,
             connector_2,
             input_0,
@@ -79,6 +87,7 @@ impl StartTimeTransaction {
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         operator_public_key: &PublicKey,
         connector_2: &Connector2,
         input_0: Input,
@@ -86,7 +95,7 @@ impl StartTimeTransaction {
         let input_0_leaf = 0;
         let _input_0 = connector_2.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
 
-        let total_output_amount = input_0.amount - Amount::from_sat(FEE_AMOUNT);
+        let total_output_amount = input_0.amount - params.fee();
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -106,6 +115,7 @@ impl StartTimeTransaction {
                 script_pubkey: connector_2.generate_taproot_address().script_pubkey(),
             }],
             prev_scripts: vec![connector_2.generate_taproot_leaf_script(input_0_leaf)],
+            connector_2: connector_2.clone(),
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
             musig2_signatures: HashMap::new(),
@@ -118,7 +128,7 @@ impl StartTimeTransaction {
         connector_2: &Connector2,
         winternitz_secret: &WinternitzSecret,
         start_time_block: u32,
-    ) {
+    ) -> Result<(), String> {
         let input_index = 0;
         let script = &self.prev_scripts()[input_index].clone();
         let prev_outs = &self.prev_outs().clone();
@@ -133,8 +143,8 @@ impl StartTimeTransaction {
             input_index,
             TapSighashType::All,
             script,
-            &context.operator_keypair,
-        );
+            context.operator_signer.as_ref(),
+        )?;
         unlock_data.push(schnorr_signature.to_vec());
 
         // get winternitz signature
@@ -151,6 +161,8 @@ impl StartTimeTransaction {
             script,
             unlock_data,
         );
+
+        Ok(())
     }
 
     pub fn sign(
@@ -159,10 +171,10 @@ impl StartTimeTransaction {
         connector_2: &Connector2,
         winternitz_secret: &WinternitzSecret,
         start_time_block: u32,
-    ) {
+    ) -> Result<(), String> {
         self.tx_mut().lock_time = absolute::LockTime::from_height(start_time_block)
             .expect("Failed to set lock time from block.");
-        self.sign_input_0(context, connector_2, winternitz_secret, start_time_block);
+        self.sign_input_0(context, connector_2, winternitz_secret, start_time_block)
     }
 
     pub fn merge(&mut self, burn: &StartTimeTransaction) {
@@ -1,7 +1,8 @@
 use bitcoin::{
-    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
+    absolute, consensus, taproot::TaprootSpendInfo, Amount, Network, PublicKey, ScriptBuf,
+    TapSighashType, Transaction, TxOut,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -9,7 +10,7 @@ use super::{
     super::{
         connectors::{base::*, connector_1::Connector1, connector_2::Connector2},
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
-        graphs::base::FEE_AMOUNT,
+        params::BridgeParams,
         scripts::*,
     },
     base::*,
@@ -24,6 +25,8 @@ pub struct StartTimeTimeoutTransaction {
     #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
     prev_outs: Vec<TxOut>,
     prev_scripts: Vec<ScriptBuf>,
+    connector_1: Connector1,
+    connector_2: Connector2,
     reward_output_amount: Amount,
 
     musig2_nonces: HashMap<usize, HashMap<PublicKey, PubNonce>>,
@@ -39,6 +42,13 @@ impl PreSignedTransaction for StartTimeTimeoutTransaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> {
+        HashMap::from([
+            (0, self.connector_2.generate_taproot_spend_info()),
+            (1, self.connector_1.generate_taproot_spend_info()),
+        ])
+    }
 }
 
 impl PreSignedMusig2Transaction for StartTimeTimeoutTransaction {
@@ -62,6 +72,9 @@ impl PreSignedMusig2Transaction for StartTimeTimeoutTransaction {
     ) -> &mut HashMap<usize, HashMap<PublicKey, PartialSignature>> {
         &mut self.musig2_signatures
     }
+    fn musig2_sighash_types(&self) -> HashMap<usize, TapSighashType> {
+        HashMap::from([(0, TapSighashType::Single), (1, TapSighashType::None)])
+    }
 }
 
 impl StartTimeTimeoutTransaction {
@@ -74,6 +87,7 @@ impl StartTimeTimeoutTransaction {
     ) -> Self {
         Self::new_for_validation(
             context.network,
+            &context.params,
             &connector_1,
             &connector_2,
             input_0,
@@ -83,6 +97,7 @@ impl StartTimeTimeoutTransaction {
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         connector_1: &Connector1,
         connector_2: &Connector2,
         input_0: Input,
@@ -94,7 +109,7 @@ impl StartTimeTimeoutTransaction {
         let input_1_leaf = 2;
         let _input_1 = connector_1.generate_taproot_leaf_tx_in(input_1_leaf, &input_1);
 
-        let total_output_amount = input_0.amount + input_1.amount - Amount::from_sat(FEE_AMOUNT);
+        let total_output_amount = input_0.amount + input_1.amount - params.fee();
 
         // Output[0]: value=V*2%*95% to burn
         let _output_0 = TxOut {
@@ -129,6 +144,8 @@ impl StartTimeTimeoutTransaction {
                 connector_2.generate_taproot_leaf_script(input_0_leaf),
                 connector_1.generate_taproot_leaf_script(input_1_leaf),
             ],
+            connector_1: connector_1.clone(),
+            connector_2: connector_2.clone(),
             reward_output_amount,
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
@@ -140,24 +157,24 @@ impl StartTimeTimeoutTransaction {
         &mut self,
         context: &VerifierContext,
         connector_2: &Connector2,
-        secret_nonce: &SecNonce,
-    ) {
+        nonce: &PubNonce,
+    ) -> Result<(), String> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::Single,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::Single, nonce)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_2);
+            self.finalize_input_0(context, connector_2)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_2: &Connector2) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_2: &Connector2,
+    ) -> Result<(), String> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -165,31 +182,31 @@ impl StartTimeTimeoutTransaction {
             input_index,
             TapSighashType::Single,
             connector_2.generate_taproot_spend_info(),
-        );
+        )
     }
 
     fn sign_input_1(
         &mut self,
         context: &VerifierContext,
         connector_1: &Connector1,
-        secret_nonce: &SecNonce,
-    ) {
+        nonce: &PubNonce,
+    ) -> Result<(), String> {
         let input_index = 1;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::None,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::None, nonce)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_1(context, connector_1);
+            self.finalize_input_1(context, connector_1)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_1(&mut self, context: &dyn BaseContext, connector_1: &Connector1) {
+    fn finalize_input_1(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_1: &Connector1,
+    ) -> Result<(), String> {
         let input_index = 1;
         finalize_musig2_taproot_input(
             self,
@@ -197,21 +214,24 @@ impl StartTimeTimeoutTransaction {
             input_index,
             TapSighashType::None,
             connector_1.generate_taproot_spend_info(),
-        );
+        )
     }
 
-    pub fn push_nonces(&mut self, context: &VerifierContext) -> HashMap<usize, SecNonce> {
-        let mut secret_nonces = HashMap::new();
+    pub fn push_nonces(
+        &mut self,
+        context: &VerifierContext,
+    ) -> Result<HashMap<usize, PubNonce>, String> {
+        let mut nonces = HashMap::new();
 
         let input_index = 0;
-        let secret_nonce = push_nonce(self, context, input_index);
-        secret_nonces.insert(input_index, secret_nonce);
+        let nonce = push_nonce(self, context, input_index)?;
+        nonces.insert(input_index, nonce);
 
         let input_index = 1;
-        let secret_nonce = push_nonce(self, context, input_index);
-        secret_nonces.insert(input_index, secret_nonce);
+        let nonce = push_nonce(self, context, input_index)?;
+        nonces.insert(input_index, nonce);
 
-        secret_nonces
+        Ok(nonces)
     }
 
     pub fn pre_sign(
@@ -219,13 +239,13 @@ impl StartTimeTimeoutTransaction {
         context: &VerifierContext,
         connector_1: &Connector1,
         connector_2: &Connector2,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        nonces: &HashMap<usize, PubNonce>,
+    ) -> Result<(), String> {
         let input_index = 0;
-        self.sign_input_0(context, connector_2, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_2, &nonces[&input_index])?;
 
         let input_index = 1;
-        self.sign_input_1(context, connector_1, &secret_nonces[&input_index]);
+        self.sign_input_1(context, connector_1, &nonces[&input_index])
     }
 
     pub fn add_output(&mut self, output_script_pubkey: ScriptBuf) {
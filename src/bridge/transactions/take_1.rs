@@ -1,8 +1,8 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, TapSighashType,
-    Transaction, TxOut, XOnlyPublicKey,
+    absolute, consensus, taproot::TaprootSpendInfo, EcdsaSighashType, Network, PublicKey, ScriptBuf,
+    TapSighashType, Transaction, TxOut, XOnlyPublicKey,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -13,7 +13,7 @@ use super::{
             connector_b::ConnectorB,
         },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
-        graphs::base::FEE_AMOUNT,
+        params::BridgeParams,
         scripts::*,
     },
     base::*,
@@ -46,6 +46,14 @@ impl PreSignedTransaction for Take1Transaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> {
+        HashMap::from([
+            (0, self.connector_0.generate_taproot_spend_info()),
+            (1, self.connector_a.generate_taproot_spend_info()),
+            (3, self.connector_b.generate_taproot_spend_info()),
+        ])
+    }
 }
 
 impl PreSignedMusig2Transaction for Take1Transaction {
@@ -69,51 +77,58 @@ impl PreSignedMusig2Transaction for Take1Transaction {
     ) -> &mut HashMap<usize, HashMap<PublicKey, PartialSignature>> {
         &mut self.musig2_signatures
     }
+    fn musig2_sighash_types(&self) -> HashMap<usize, TapSighashType> {
+        HashMap::from([(0, TapSighashType::All), (3, TapSighashType::All)])
+    }
 }
 
 impl Take1Transaction {
     pub fn new(
         context: &OperatorContext,
+        connector_b: &ConnectorB,
         input_0: Input,
         input_1: Input,
         input_2: Input,
         input_3: Input,
-    ) -> Self {
+    ) -> Result<Self, String> {
         let mut this = Self::new_for_validation(
             context.network,
+            &context.params,
             &context.operator_public_key,
             &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
+            connector_b,
             input_0,
             input_1,
             input_2,
             input_3,
         );
 
-        this.sign_input_1(context);
-        this.sign_input_2(context);
+        this.sign_input_1(context)?;
+        this.sign_input_2(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         operator_public_key: &PublicKey,
         operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
+        connector_b: &ConnectorB,
         input_0: Input,
         input_1: Input,
         input_2: Input,
         input_3: Input,
     ) -> Self {
         let connector_0 = Connector0::new(network, n_of_n_taproot_public_key);
-        let connector_3 = Connector3::new(network, operator_public_key);
+        let connector_3 = Connector3::new(network, operator_public_key, params);
         let connector_a = ConnectorA::new(
             network,
             operator_taproot_public_key,
             n_of_n_taproot_public_key,
         );
-        let connector_b = ConnectorB::new(network, n_of_n_taproot_public_key);
 
         let input_0_leaf = 0;
         let _input_0 = connector_0.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
@@ -127,7 +142,7 @@ impl Take1Transaction {
         let _input_3 = connector_b.generate_taproot_leaf_tx_in(input_3_leaf, &input_3);
 
         let total_output_amount = input_0.amount + input_1.amount + input_2.amount + input_3.amount
-            - Amount::from_sat(FEE_AMOUNT);
+            - params.fee();
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -169,7 +184,7 @@ impl Take1Transaction {
             connector_0,
             connector_3,
             connector_a,
-            connector_b,
+            connector_b: connector_b.clone(),
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
             musig2_signatures: HashMap::new(),
@@ -178,23 +193,19 @@ impl Take1Transaction {
 
     pub fn num_blocks_timelock_2(&self) -> u32 { self.connector_3.num_blocks_timelock }
 
-    fn sign_input_0(&mut self, context: &VerifierContext, secret_nonce: &SecNonce) {
+    fn sign_input_0(&mut self, context: &VerifierContext, nonce: &PubNonce) -> Result<(), String> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, nonce)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context);
+            self.finalize_input_0(context)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext) {
+    fn finalize_input_0(&mut self, context: &dyn BaseContext) -> Result<(), String> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -202,10 +213,10 @@ impl Take1Transaction {
             input_index,
             TapSighashType::All,
             self.connector_0.generate_taproot_spend_info(),
-        );
+        )
     }
 
-    fn sign_input_1(&mut self, context: &OperatorContext) {
+    fn sign_input_1(&mut self, context: &OperatorContext) -> Result<(), String> {
         let input_index = 1;
         pre_sign_taproot_input_default(
             self,
@@ -213,38 +224,34 @@ impl Take1Transaction {
             input_index,
             TapSighashType::All,
             self.connector_a.generate_taproot_spend_info(),
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 
-    fn sign_input_2(&mut self, context: &OperatorContext) {
+    fn sign_input_2(&mut self, context: &OperatorContext) -> Result<(), String> {
         let input_index = 2;
         pre_sign_p2wsh_input(
             self,
             context,
             input_index,
             EcdsaSighashType::All,
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 
-    fn sign_input_3(&mut self, context: &VerifierContext, secret_nonce: &SecNonce) {
+    fn sign_input_3(&mut self, context: &VerifierContext, nonce: &PubNonce) -> Result<(), String> {
         let input_index = 3;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, nonce)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_3(context);
+            self.finalize_input_3(context)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_3(&mut self, context: &dyn BaseContext) {
+    fn finalize_input_3(&mut self, context: &dyn BaseContext) -> Result<(), String> {
         let input_index = 3;
         finalize_musig2_taproot_input(
             self,
@@ -252,33 +259,36 @@ impl Take1Transaction {
             input_index,
             TapSighashType::All,
             self.connector_b.generate_taproot_spend_info(),
-        );
+        )
     }
 
-    pub fn push_nonces(&mut self, context: &VerifierContext) -> HashMap<usize, SecNonce> {
-        let mut secret_nonces = HashMap::new();
+    pub fn push_nonces(
+        &mut self,
+        context: &VerifierContext,
+    ) -> Result<HashMap<usize, PubNonce>, String> {
+        let mut nonces = HashMap::new();
 
         let input_index = 0;
-        let secret_nonce = push_nonce(self, context, input_index);
-        secret_nonces.insert(input_index, secret_nonce);
+        let nonce = push_nonce(self, context, input_index)?;
+        nonces.insert(input_index, nonce);
 
         let input_index = 3;
-        let secret_nonce = push_nonce(self, context, input_index);
-        secret_nonces.insert(input_index, secret_nonce);
+        let nonce = push_nonce(self, context, input_index)?;
+        nonces.insert(input_index, nonce);
 
-        secret_nonces
+        Ok(nonces)
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        nonces: &HashMap<usize, PubNonce>,
+    ) -> Result<(), String> {
         let input_index = 0;
-        self.sign_input_0(context, &secret_nonces[&input_index]);
+        self.sign_input_0(context, &nonces[&input_index])?;
 
         let input_index = 3;
-        self.sign_input_3(context, &secret_nonces[&input_index]);
+        self.sign_input_3(context, &nonces[&input_index])
     }
 
     pub fn merge(&mut self, take_1: &Take1Transaction) {
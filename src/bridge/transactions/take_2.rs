@@ -1,8 +1,8 @@
 use bitcoin::{
-    absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, TapSighashType,
-    Transaction, TxOut, XOnlyPublicKey,
+    absolute, consensus, taproot::TaprootSpendInfo, EcdsaSighashType, Network, PublicKey, ScriptBuf,
+    TapSighashType, Transaction, TxOut, XOnlyPublicKey,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -13,7 +13,7 @@ use super::{
             connector_c::ConnectorC,
         },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
-        graphs::base::FEE_AMOUNT,
+        params::BridgeParams,
         scripts::*,
     },
     base::*,
@@ -46,6 +46,14 @@ impl PreSignedTransaction for Take2Transaction {
     fn prev_outs(&self) -> &Vec<TxOut> { &self.prev_outs }
 
     fn prev_scripts(&self) -> &Vec<ScriptBuf> { &self.prev_scripts }
+
+    fn taproot_spend_infos(&self) -> HashMap<usize, TaprootSpendInfo> {
+        HashMap::from([
+            (0, self.connector_0.generate_taproot_spend_info()),
+            (2, self.connector_5.generate_taproot_spend_info()),
+            (3, self.connector_c.generate_taproot_spend_info()),
+        ])
+    }
 }
 
 impl PreSignedMusig2Transaction for Take2Transaction {
@@ -69,47 +77,52 @@ impl PreSignedMusig2Transaction for Take2Transaction {
     ) -> &mut HashMap<usize, HashMap<PublicKey, PartialSignature>> {
         &mut self.musig2_signatures
     }
+    fn musig2_sighash_types(&self) -> HashMap<usize, TapSighashType> {
+        HashMap::from([(0, TapSighashType::All), (2, TapSighashType::All)])
+    }
 }
 
 impl Take2Transaction {
     pub fn new(
         context: &OperatorContext,
+        connector_c: &ConnectorC,
         input_0: Input,
         input_1: Input,
         input_2: Input,
         input_3: Input,
-    ) -> Self {
+    ) -> Result<Self, String> {
         let mut this = Self::new_for_validation(
             context.network,
+            &context.params,
             &context.operator_public_key,
-            &context.operator_taproot_public_key,
             &context.n_of_n_taproot_public_key,
+            connector_c,
             input_0,
             input_1,
             input_2,
             input_3,
         );
 
-        this.sign_input_1(context);
-        this.sign_input_3(context);
+        this.sign_input_1(context)?;
+        this.sign_input_3(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
         network: Network,
+        params: &BridgeParams,
         operator_public_key: &PublicKey,
-        operator_taproot_public_key: &XOnlyPublicKey,
         n_of_n_taproot_public_key: &XOnlyPublicKey,
+        connector_c: &ConnectorC,
         input_0: Input,
         input_1: Input,
         input_2: Input,
         input_3: Input,
     ) -> Self {
         let connector_0 = Connector0::new(network, n_of_n_taproot_public_key);
-        let connector_4 = Connector4::new(network, operator_public_key);
+        let connector_4 = Connector4::new(network, operator_public_key, params);
         let connector_5 = Connector5::new(network, n_of_n_taproot_public_key);
-        let connector_c = ConnectorC::new(network, operator_taproot_public_key);
 
         let input_0_leaf = 1;
         let _input_0 = connector_0.generate_taproot_leaf_tx_in(input_0_leaf, &input_0);
@@ -123,7 +136,7 @@ impl Take2Transaction {
         let _input_3 = connector_c.generate_taproot_leaf_tx_in(input_3_leaf, &input_3);
 
         let total_output_amount = input_0.amount + input_1.amount + input_2.amount + input_3.amount
-            - Amount::from_sat(FEE_AMOUNT);
+            - params.fee();
 
         let _output_0 = TxOut {
             value: total_output_amount,
@@ -165,7 +178,7 @@ impl Take2Transaction {
             connector_0,
             connector_4,
             connector_5,
-            connector_c,
+            connector_c: connector_c.clone(),
             musig2_nonces: HashMap::new(),
             musig2_nonce_signatures: HashMap::new(),
             musig2_signatures: HashMap::new(),
@@ -174,23 +187,19 @@ impl Take2Transaction {
 
     pub fn num_blocks_timelock_1(&self) -> u32 { self.connector_4.num_blocks_timelock }
 
-    fn sign_input_0(&mut self, context: &VerifierContext, secret_nonce: &SecNonce) {
+    fn sign_input_0(&mut self, context: &VerifierContext, nonce: &PubNonce) -> Result<(), String> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, nonce)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context);
+            self.finalize_input_0(context)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext) {
+    fn finalize_input_0(&mut self, context: &dyn BaseContext) -> Result<(), String> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -198,37 +207,33 @@ impl Take2Transaction {
             input_index,
             TapSighashType::All,
             self.connector_0.generate_taproot_spend_info(),
-        );
+        )
     }
 
-    fn sign_input_1(&mut self, context: &OperatorContext) {
+    fn sign_input_1(&mut self, context: &OperatorContext) -> Result<(), String> {
         let input_index = 1;
         pre_sign_p2wsh_input(
             self,
             context,
             input_index,
             EcdsaSighashType::All,
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 
-    fn sign_input_2(&mut self, context: &VerifierContext, secret_nonce: &SecNonce) {
+    fn sign_input_2(&mut self, context: &VerifierContext, nonce: &PubNonce) -> Result<(), String> {
         let input_index = 2;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, nonce)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_2(context);
+            self.finalize_input_2(context)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_2(&mut self, context: &dyn BaseContext) {
+    fn finalize_input_2(&mut self, context: &dyn BaseContext) -> Result<(), String> {
         let input_index = 2;
         finalize_musig2_taproot_input(
             self,
@@ -236,10 +241,10 @@ impl Take2Transaction {
             input_index,
             TapSighashType::All,
             self.connector_5.generate_taproot_spend_info(),
-        );
+        )
     }
 
-    fn sign_input_3(&mut self, context: &OperatorContext) {
+    fn sign_input_3(&mut self, context: &OperatorContext) -> Result<(), String> {
         let input_index = 3;
         pre_sign_taproot_input_default(
             self,
@@ -247,34 +252,37 @@ impl Take2Transaction {
             input_index,
             TapSighashType::All,
             self.connector_c.generate_taproot_spend_info(),
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 
-    pub fn push_nonces(&mut self, context: &VerifierContext) -> HashMap<usize, SecNonce> {
-        let mut secret_nonces = HashMap::new();
+    pub fn push_nonces(
+        &mut self,
+        context: &VerifierContext,
+    ) -> Result<HashMap<usize, PubNonce>, String> {
+        let mut nonces = HashMap::new();
 
         let input_index = 0;
-        let secret_nonce = push_nonce(self, context, input_index);
-        secret_nonces.insert(input_index, secret_nonce);
+        let nonce = push_nonce(self, context, input_index)?;
+        nonces.insert(input_index, nonce);
 
         let input_index = 2;
-        let secret_nonce = push_nonce(self, context, input_index);
-        secret_nonces.insert(input_index, secret_nonce);
+        let nonce = push_nonce(self, context, input_index)?;
+        nonces.insert(input_index, nonce);
 
-        secret_nonces
+        Ok(nonces)
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        nonces: &HashMap<usize, PubNonce>,
+    ) -> Result<(), String> {
         let input_index = 0;
-        self.sign_input_0(context, &secret_nonces[&input_index]);
+        self.sign_input_0(context, &nonces[&input_index])?;
 
         let input_index = 2;
-        self.sign_input_2(context, &secret_nonces[&input_index]);
+        self.sign_input_2(context, &nonces[&input_index])
     }
 
     pub fn merge(&mut self, take_2: &Take2Transaction) {
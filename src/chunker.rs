@@ -0,0 +1,332 @@
+//
+// Stack-aware chunker
+//
+// Splits a script into chunks that fit into tapleaves, cutting only where the live stack is small
+// and the altstack is empty. Every chunk hands its output stack to the next one through a
+// commitment: the blake3-160 hash of the stack, signed with a Winternitz signature.
+//
+
+use bitcoin::ScriptBuf;
+
+use crate::cost::trace_stack_heights;
+use crate::hash::blake3::blake3_160_var_length;
+use crate::signatures::winternitz::{self, checksig_verify, PublicKey};
+use crate::signatures::winternitz_hash::check_hash_sig;
+use crate::treepp::{script, Script};
+
+/// Number of stack elements of a Winternitz signature
+const SIGNATURE_LEN: u32 = 2 * winternitz::N;
+const MESSAGE_HASH_LEN: u32 = 20;
+
+/// The largest stack blake3 can commit to, at 4 bytes per element
+pub const MAX_TRANSFER: usize = 512 / 4;
+
+pub struct Chunk {
+    pub script: ScriptBuf,
+    /// Number of stack elements the chunk starts with
+    pub input_len: usize,
+    /// Number of stack elements the chunk leaves for the next one
+    pub output_len: usize,
+}
+
+pub struct Chunker {
+    target_size: usize,
+    tolerance: usize,
+    max_transfer: usize,
+}
+
+impl Chunker {
+    /// Chunks of at most `target_size` bytes, cut within the last `tolerance` bytes at the smallest
+    /// live stack, and never with more than `max_transfer` stack elements
+    pub fn new(target_size: usize, tolerance: usize, max_transfer: usize) -> Self {
+        assert!(tolerance <= target_size);
+        assert!(max_transfer <= MAX_TRANSFER, "cannot commit to more than {} elements", MAX_TRANSFER);
+        Chunker { target_size, tolerance, max_transfer }
+    }
+
+    /// Split `script`, which starts with `input_len` elements on the stack and an empty altstack
+    pub fn chunk(&self, script: Script, input_len: usize) -> Vec<Chunk> {
+        let compiled = script.compile();
+        let heights = trace_stack_heights(&compiled);
+        if let Some(last) = heights.last() {
+            assert!(last.exact, "the output stack depends on the execution path");
+        }
+        let output_len = input_len as i64 + heights.last().map_or(0, |h| h.stack);
+
+        // Offsets between top-level instructions with an empty altstack, with the live stack there.
+        // After branches of different heights the live stack is not known, so no cuts there.
+        let mut cuts = vec![];
+        for h in heights {
+            let live = input_len as i64 + h.stack;
+            assert!(live >= 0, "script consumes more than {} elements", input_len);
+            let top_level = h.branch_depth == 0 && h.exact && h.altstack == 0;
+            if top_level && live as usize <= self.max_transfer {
+                cuts.push((h.offset, live as usize));
+            }
+        }
+
+        let mut chunks = vec![];
+        let (mut start, mut start_len) = (0, input_len);
+        while start < compiled.len() {
+            let (end, end_len) = if compiled.len() - start <= self.target_size {
+                (compiled.len(), output_len as usize)
+            } else {
+                let candidates = cuts
+                    .iter()
+                    .filter(|(offset, _)| *offset > start && *offset - start <= self.target_size)
+                    .collect::<Vec<_>>();
+                let window = candidates
+                    .iter()
+                    .filter(|(offset, _)| *offset - start + self.tolerance >= self.target_size);
+                // Prefer the smallest stack within the tolerance, otherwise the longest chunk
+                match window.min_by_key(|(offset, len)| (*len, usize::MAX - *offset)) {
+                    Some(cut) => **cut,
+                    None => **candidates.last().unwrap_or_else(|| {
+                        panic!("no cut point with a live stack of at most {} elements after byte {}",
+                               self.max_transfer, start)
+                    }),
+                }
+            };
+            chunks.push(Chunk {
+                script: ScriptBuf::from_bytes(compiled.as_bytes()[start..end].to_vec()),
+                input_len: start_len,
+                output_len: end_len,
+            });
+            (start, start_len) = (end, end_len);
+        }
+        chunks
+    }
+}
+
+/// Number of stack elements transferred between consecutive chunks
+pub fn transfer_sizes(chunks: &[Chunk]) -> Vec<usize> {
+    chunks[..chunks.len().saturating_sub(1)].iter().map(|chunk| chunk.output_len).collect()
+}
+
+/// Native counterpart of `stack_to_bytes`, for the elements listed from the bottom of the stack
+pub fn stack_bytes(elements: &[u32]) -> Vec<u8> {
+    elements.iter().rev().flat_map(|element| element.to_le_bytes()).collect()
+}
+
+/// Replace the top `num_elements` elements, each in [0, 2^31), with their 4 little-endian bytes.
+/// The top element comes first, its least significant byte on top.
+pub fn stack_to_bytes(num_elements: usize) -> Script {
+    script! {
+        for _ in 0..num_elements {
+            for byte in (0..4).rev() {
+                0 OP_SWAP
+                for bit in (0..8).rev() {
+                    if 8 * byte + bit < 31 {
+                        OP_DUP { 1 << (8 * byte + bit) } OP_GREATERTHANOREQUAL
+                        OP_IF
+                            { 1 << (8 * byte + bit) } OP_SUB
+                            OP_SWAP { 1 << bit } OP_ADD OP_SWAP
+                        OP_ENDIF
+                    }
+                }
+            }
+            // Nothing is left of elements out of range
+            OP_NOT OP_VERIFY
+            for _ in 0..4 {
+                OP_TOALTSTACK
+            }
+        }
+        for _ in 0..4 * num_elements {
+            OP_FROMALTSTACK
+        }
+    }
+}
+
+impl Chunk {
+    /// Fail unless the input signature signs the hash of the `input_len` elements below it,
+    /// which are kept. The output signature on top of the witness moves to the altstack.
+    fn verify_input_commitment(&self, input_key: &PublicKey) -> Script {
+        script! {
+            for _ in 0..SIGNATURE_LEN {
+                OP_TOALTSTACK
+            }
+            if self.input_len > 0 {
+                for _ in 0..SIGNATURE_LEN {
+                    OP_TOALTSTACK
+                }
+                for _ in 0..self.input_len {
+                    { self.input_len - 1 } OP_PICK
+                }
+                { stack_to_bytes(self.input_len) }
+                for _ in 0..SIGNATURE_LEN {
+                    OP_FROMALTSTACK
+                }
+                { check_hash_sig(input_key, 4 * self.input_len) }
+            }
+        }
+    }
+
+    /// Succeed if the output stack differs from the one signed by the signature on the altstack
+    fn output_differs(&self, output_key: &PublicKey) -> Script {
+        script! {
+            { stack_to_bytes(self.output_len) }
+            for _ in 0..SIGNATURE_LEN {
+                OP_FROMALTSTACK
+            }
+            { checksig_verify(output_key) }
+            for _ in 0..MESSAGE_HASH_LEN {
+                OP_TOALTSTACK
+            }
+            { blake3_160_var_length(4 * self.output_len) }
+
+            // Compare the hashes as in `check_hash_sig`, accumulating any difference
+            0
+            for _ in 0..MESSAGE_HASH_LEN / 4 {
+                for j in 0..4 {
+                    { 4 - j } OP_ROLL
+                    OP_FROMALTSTACK
+                    OP_EQUAL OP_NOT OP_BOOLOR
+                }
+            }
+        }
+    }
+
+    /// Leaf of connector C disproving an operator who signed an input and an output stack of this
+    /// chunk that do not match. Witness: the input stack, the input signature (unless the chunk
+    /// has no input) and the output signature.
+    pub fn disprove_leaf(&self, input_key: &PublicKey, output_key: &PublicKey) -> ScriptBuf {
+        assert!(self.output_len > 0, "the chunk has no output to disprove");
+        let mut leaf = self.verify_input_commitment(input_key).compile().to_bytes();
+        leaf.extend(self.script.as_bytes());
+        leaf.extend(self.output_differs(output_key).compile().as_bytes());
+        ScriptBuf::from_bytes(leaf)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn254::fp254impl::Fp254Impl;
+    use crate::bn254::fq::Fq;
+    use crate::execute_script_buf;
+    use crate::signatures::winternitz::generate_public_key;
+    use crate::signatures::winternitz_hash::sign_hash;
+    use crate::treepp::*;
+    use ark_std::UniformRand;
+    use num_bigint::BigUint;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn push_signature(secret_key: &str, elements: &[u32]) -> Script {
+        script! {
+            for signature in sign_hash(secret_key, &stack_bytes(elements)) {
+                { signature.hash_bytes }
+                { signature.message_digit }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stack_to_bytes() {
+        let elements = [0, 1, 0x1234_5678, 0x7fff_ffff];
+        let bytes = stack_bytes(&elements);
+        let script = script! {
+            for element in elements {
+                { element }
+            }
+            { stack_to_bytes(elements.len()) }
+            for byte in bytes.iter().rev() {
+                { *byte as u32 }
+            }
+            for i in 0..bytes.len() {
+                { bytes.len() - i } OP_ROLL OP_EQUALVERIFY
+            }
+            OP_TRUE
+        };
+        let res = execute_script(script);
+        assert!(res.success);
+        assert_eq!(res.final_stack.len(), 1);
+
+        assert!(!execute_script(script! { { -1 } { stack_to_bytes(1) } OP_TRUE }).success);
+    }
+
+    fn fq_chain(a: ark_bn254::Fq, b: ark_bn254::Fq) -> Script {
+        script! {
+            { Fq::push_u32_le(&BigUint::from(a).to_u32_digits()) }
+            { Fq::push_u32_le(&BigUint::from(b).to_u32_digits()) }
+            { Fq::mul() }
+            { Fq::copy(0) }
+            { Fq::square() }
+            { Fq::mul() }
+            { Fq::copy(0) }
+            { Fq::square() }
+            { Fq::add(1, 0) }
+        }
+    }
+
+    #[test]
+    fn test_chunker() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let a = ark_bn254::Fq::rand(&mut prng);
+        let b = ark_bn254::Fq::rand(&mut prng);
+
+        let script = fq_chain(a, b);
+        let size = script.len();
+        let chunks = Chunker::new(size / 2, size / 2, 32).chunk(script.clone(), 0);
+        println!("chunk sizes: {:?}", chunks.iter().map(|c| c.script.len()).collect::<Vec<_>>());
+        println!("transfer sizes: {:?}", transfer_sizes(&chunks));
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.last().unwrap().output_len, Fq::N_LIMBS as usize);
+
+        // The chunks put together are the script, and every transfer is small
+        let bytes = chunks.iter().flat_map(|c| c.script.to_bytes()).collect::<Vec<_>>();
+        assert_eq!(bytes, script.compile().to_bytes());
+        for (chunk, next) in chunks.iter().zip(chunks.iter().skip(1)) {
+            assert!(chunk.output_len <= 32);
+            assert_eq!(chunk.output_len, next.input_len);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the output stack depends on the execution path")]
+    fn test_chunker_rejects_unbalanced_branches() {
+        let mut prng = ChaCha20Rng::seed_from_u64(1);
+        let a = ark_bn254::Fq::rand(&mut prng);
+        let b = ark_bn254::Fq::rand(&mut prng);
+
+        // Duplicating the top limb on one path only, the live stack is unknown from there on
+        let script = script! {
+            { fq_chain(a, b) }
+            OP_DUP
+            OP_IF
+                OP_DUP
+            OP_ENDIF
+        };
+        let size = script.len();
+        Chunker::new(size / 2, size / 2, 32).chunk(script, 0);
+    }
+
+    #[test]
+    fn test_disprove_leaf() {
+        let input_key = "b138982ce17ac813d505b5b40b665d404e9528e7";
+        let output_key = "a138982ce17ac813d505b5b40b665d404e9528e7";
+
+        // [x, y] -> [x + y, 2 * x]
+        let chunk = Chunk {
+            script: script! { OP_2DUP OP_ADD OP_TOALTSTACK OP_DROP OP_DUP OP_ADD OP_FROMALTSTACK OP_SWAP }.compile(),
+            input_len: 2,
+            output_len: 2,
+        };
+        let leaf = chunk.disprove_leaf(&generate_public_key(input_key), &generate_public_key(output_key));
+
+        let input = [3, 5];
+        for (output, disproved) in [([8, 6], false), ([8, 7], true)] {
+            let witness = script! {
+                for element in input {
+                    { element }
+                }
+                { push_signature(input_key, &input) }
+                { push_signature(output_key, &output) }
+            };
+            let mut script = witness.compile().to_bytes();
+            script.extend(leaf.as_bytes());
+            let res = execute_script_buf(ScriptBuf::from_bytes(script));
+            assert_eq!(res.success, disproved);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//
+// Static cost model of scripts
+//
+// Gadgets are deterministic scripts, so their cost can be derived from the compiled opcodes
+// without executing them. Stack depths are relative to the stack at the start of the gadget.
+//
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+
+use bitcoin::script::Instruction;
+use bitcoin::{Opcode, ScriptBuf};
+
+use crate::treepp::Script;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Cost {
+    /// Size of the compiled script in bytes
+    pub script_size: usize,
+    /// Number of opcodes, not counting pushes (OP_0 to OP_16 and data pushes)
+    pub opcode_count: usize,
+    /// Largest number of elements pushed onto the stack on top of the initial stack
+    pub max_stack: usize,
+    /// Largest number of elements pushed onto the altstack on top of the initial altstack
+    pub max_altstack: usize,
+    /// Change in the number of stack elements once the gadget is done
+    pub stack_delta: i64,
+}
+
+// Elements popped from and pushed to the stack by an opcode, for the opcodes used in tapscript.
+// OP_PICK and OP_ROLL are counted without the element they reach down to.
+fn stack_effect(opcode: Opcode) -> (i64, i64) {
+    match opcode.to_u8() {
+        // OP_1NEGATE, OP_1 to OP_16
+        0x4f | 0x51..=0x60 => (0, 1),
+        // OP_IFDUP is counted as if it always duplicates
+        0x73 => (1, 2),
+        // OP_DEPTH, OP_SIZE
+        0x74 => (0, 1),
+        0x82 => (1, 2),
+        // OP_VERIFY, OP_DROP
+        0x69 | 0x75 => (1, 0),
+        // OP_2DROP
+        0x6d => (2, 0),
+        // OP_2DUP, OP_3DUP, OP_2OVER
+        0x6e => (2, 4),
+        0x6f => (3, 6),
+        0x70 => (4, 6),
+        // OP_2ROT, OP_2SWAP
+        0x71 => (6, 6),
+        0x72 => (4, 4),
+        // OP_DUP, OP_NIP, OP_OVER, OP_PICK, OP_ROLL, OP_ROT, OP_SWAP, OP_TUCK
+        0x76 => (1, 2),
+        0x77 => (2, 1),
+        0x78 => (2, 3),
+        0x79 => (1, 1),
+        0x7a => (2, 1),
+        0x7b => (3, 3),
+        0x7c => (2, 2),
+        0x7d => (2, 3),
+        // OP_EQUAL, OP_EQUALVERIFY
+        0x87 => (2, 1),
+        0x88 => (2, 0),
+        // Unary arithmetic: OP_1ADD, OP_1SUB, OP_NEGATE, OP_ABS, OP_NOT, OP_0NOTEQUAL
+        0x8b | 0x8c | 0x8f..=0x92 => (1, 1),
+        // Binary arithmetic and comparisons, OP_NUMEQUALVERIFY, OP_WITHIN
+        0x93 | 0x94 | 0x9a..=0x9c | 0x9e..=0xa4 => (2, 1),
+        0x9d => (2, 0),
+        0xa5 => (3, 1),
+        // Hashes
+        0xa6..=0xaa => (1, 1),
+        // OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CHECKSIGADD
+        0xac => (2, 1),
+        0xad => (2, 0),
+        0xba => (3, 1),
+        // OP_NOP, OP_CODESEPARATOR, OP_CLTV, OP_CSV and the other NOPs
+        _ => (0, 0),
+    }
+}
+
+/// Stack heights after an instruction, relative to the start of the script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackHeights {
+    /// Byte offset right after the instruction
+    pub offset: usize,
+    pub stack: i64,
+    pub altstack: i64,
+    /// Number of enclosing conditionals
+    pub branch_depth: usize,
+    /// Whether every execution path gets here with these heights. Once the branches of a
+    /// conditional leave different heights, the heights are only upper bounds from there on.
+    pub exact: bool,
+}
+
+/// Stack heights after every instruction of a compiled script. Both branches of a conditional
+/// are accounted for, taking the larger height when they join.
+pub fn trace_stack_heights(compiled: &ScriptBuf) -> Vec<StackHeights> {
+    let mut heights = vec![];
+    let (mut stack, mut altstack, mut exact) = (0_i64, 0_i64, true);
+    // Stack heights at OP_IF and at the end of the first branch, if any
+    let mut branches: Vec<((i64, i64, bool), Option<(i64, i64, bool)>)> = vec![];
+
+    let instructions = compiled.instruction_indices().collect::<Result<Vec<_>, _>>().expect("invalid script");
+    for (i, (_, instruction)) in instructions.iter().enumerate() {
+        let offset = instructions.get(i + 1).map_or(compiled.len(), |(next, _)| *next);
+        match instruction {
+            Instruction::PushBytes(_) => stack += 1,
+            Instruction::Op(opcode) => match opcode.to_u8() {
+                // OP_IF, OP_NOTIF
+                0x63 | 0x64 => {
+                    stack -= 1;
+                    branches.push(((stack, altstack, exact), None));
+                }
+                // OP_ELSE
+                0x67 => {
+                    let branch = branches.last_mut().expect("OP_ELSE without OP_IF");
+                    branch.1 = Some((stack, altstack, exact));
+                    (stack, altstack, exact) = branch.0;
+                }
+                // OP_ENDIF
+                0x68 => {
+                    let (at_if, first) = branches.pop().expect("OP_ENDIF without OP_IF");
+                    let (other_stack, other_altstack, other_exact) = first.unwrap_or(at_if);
+                    exact &= other_exact && stack == other_stack && altstack == other_altstack;
+                    stack = stack.max(other_stack);
+                    altstack = altstack.max(other_altstack);
+                }
+                // OP_TOALTSTACK, OP_FROMALTSTACK
+                0x6b => {
+                    stack -= 1;
+                    altstack += 1;
+                }
+                0x6c => {
+                    stack += 1;
+                    altstack -= 1;
+                }
+                _ => {
+                    let (pops, pushes) = stack_effect(*opcode);
+                    stack += pushes - pops;
+                }
+            },
+        }
+        heights.push(StackHeights { offset, stack, altstack, branch_depth: branches.len(), exact });
+    }
+    assert!(branches.is_empty(), "unbalanced conditional");
+    heights
+}
+
+impl Cost {
+    /// Cost of a script, derived from its compiled opcodes. Both branches of a conditional
+    /// are accounted for, so depths are upper bounds for every execution path.
+    pub fn of(script: Script) -> Cost {
+        let compiled = script.compile();
+        let heights = trace_stack_heights(&compiled);
+        Cost {
+            script_size: compiled.len(),
+            // OP_1NEGATE and OP_1 to OP_16 are pushes
+            opcode_count: compiled
+                .instructions()
+                .filter(|instruction| matches!(instruction, Ok(Instruction::Op(opcode)) if opcode.to_u8() > 0x60))
+                .count(),
+            max_stack: heights.iter().map(|h| h.stack).max().unwrap_or(0).max(0) as usize,
+            max_altstack: heights.iter().map(|h| h.altstack).max().unwrap_or(0).max(0) as usize,
+            stack_delta: heights.last().map_or(0, |h| h.stack),
+        }
+    }
+
+    /// Cost of a deterministic gadget, computed once per name
+    pub fn cached(name: &'static str, script: impl FnOnce() -> Script) -> Cost {
+        static CACHE: OnceLock<Mutex<HashMap<&'static str, Cost>>> = OnceLock::new();
+        let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+        if let Some(cost) = cache.lock().unwrap().get(name) {
+            return *cost;
+        }
+        // Compile without holding the lock, gadgets may be built from other cached gadgets
+        let cost = Cost::of(script());
+        cache.lock().unwrap().insert(name, cost);
+        cost
+    }
+}
+
+impl fmt::Display for Cost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} bytes, {} opcodes, max stack {}, max altstack {}, stack delta {}",
+            self.script_size, self.opcode_count, self.max_stack, self.max_altstack, self.stack_delta
+        )
+    }
+}
+
+/// Costs of the gadgets we budget tapleaves with, as a markdown table
+pub fn report() -> String {
+    use crate::bn254::ell_coeffs::G2Prepared;
+    use crate::bn254::fp254impl::Fp254Impl;
+    use crate::bn254::fq::Fq;
+    use crate::bn254::fq12::Fq12;
+    use crate::bn254::pairing::Pairing;
+    use crate::hash::blake3::blake3;
+    use crate::signatures::winternitz;
+    use ark_ff::UniformRand;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    let mut prng = ChaCha20Rng::seed_from_u64(0);
+    let constants = (0..4)
+        .map(|_| G2Prepared::from_affine(ark_bn254::G2Affine::rand(&mut prng)))
+        .collect::<Vec<_>>();
+    let public_key = winternitz::generate_public_key("b138982ce17ac813d505b5b40b665d404e9528e7");
+
+    let gadgets = [
+        ("Fq::mul", Cost::cached("Fq::mul", Fq::mul)),
+        ("Fq12::mul", Cost::cached("Fq12::mul", || Fq12::mul(12, 0))),
+        (
+            "Pairing::quad_miller_loop_with_c_wi",
+            Cost::cached("Pairing::quad_miller_loop_with_c_wi", || {
+                Pairing::quad_miller_loop_with_c_wi(constants)
+            }),
+        ),
+        ("blake3", Cost::cached("blake3", blake3)),
+        (
+            "winternitz::checksig_verify",
+            Cost::cached("winternitz::checksig_verify", || winternitz::checksig_verify(&public_key)),
+        ),
+    ];
+
+    let mut report = String::from(
+        "| gadget | bytes | opcodes | max stack | max altstack | stack delta |\n|---|---|---|---|---|---|\n",
+    );
+    for (name, cost) in gadgets {
+        report += &format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            name, cost.script_size, cost.opcode_count, cost.max_stack, cost.max_altstack, cost.stack_delta
+        );
+    }
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::treepp::*;
+
+    #[test]
+    fn test_cost() {
+        let cost = Cost::of(script! {
+            1 2
+            OP_TOALTSTACK
+            OP_DUP
+            OP_IF
+                3 4 OP_2DROP
+            OP_ELSE
+                5 OP_DROP
+            OP_ENDIF
+            OP_FROMALTSTACK
+        });
+        assert_eq!(
+            cost,
+            Cost { script_size: 13, opcode_count: 8, max_stack: 3, max_altstack: 1, stack_delta: 2 }
+        );
+
+        // Consuming inputs
+        let cost = Cost::of(script! { OP_ADD OP_ADD 7 });
+        assert_eq!(cost.max_stack, 0);
+        assert_eq!(cost.stack_delta, -1);
+    }
+
+    #[test]
+    fn test_trace_unbalanced_branches() {
+        let compiled = script! {
+            1
+            OP_IF
+                2 OP_DROP
+            OP_ELSE
+                3 4 OP_2DROP
+            OP_ENDIF
+            7
+            OP_IF
+                5
+            OP_ENDIF
+            6
+        }
+        .compile();
+        let heights = trace_stack_heights(&compiled);
+        assert_eq!(heights.len(), 14);
+        // The first conditional leaves the same height on both paths
+        assert!(heights[..=11].iter().all(|h| h.exact));
+        assert_eq!(heights[8].stack, 0);
+        // The second one pushes on one path only
+        assert!(!heights[12].exact && !heights[13].exact);
+        assert_eq!((heights[12].stack, heights[13].stack), (1, 2));
+    }
+
+    #[test]
+    fn test_cost_report() {
+        let report = report();
+        println!("{}", report);
+        assert_eq!(report.lines().count(), 2 + 5);
+
+        // Cached costs are not recomputed
+        let cost = Cost::cached("Fq::mul", || unreachable!());
+        assert!(cost.script_size > 0);
+    }
+}
@@ -1,2227 +1,2394 @@
-#[allow(non_snake_case)]
-#[cfg(test)]
-mod test {
-    use crate::bn254::curves::G1Affine;
-    use crate::bn254::curves::G1Projective;
-    use crate::bn254::ell_coeffs::G2Prepared;
-    use crate::bn254::fp254impl::Fp254Impl;
-    use crate::bn254::fq::Fq;
-    use crate::bn254::fq12::Fq12;
-    use crate::bn254::fr::Fr;
-    use crate::bn254::pairing::Pairing;
-
-    use crate::bn254::utils;
-    use crate::execute_script_as_chunks;
-    use crate::hash::blake3::blake3_var_length;
-    use crate::treepp::*;
-    use ark_bn254::Bn254;
-    use ark_ec::pairing::Pairing as ArkPairing;
-    use ark_ec::CurveGroup;
-    use ark_ff::{Field, One};
-    use ark_std::UniformRand;
-    use num_bigint::BigUint;
-    use num_traits::{Num, ToPrimitive};
-    use rand::SeedableRng;
-    use rand_chacha::ChaCha20Rng;
-    use std::str::FromStr;
-
-    fn fq12_push(element: ark_bn254::Fq12) -> Script {
-        script! {
-            for elem in element.to_base_prime_field_elements() {
-                { Fq::push_u32_le(&BigUint::from(elem).to_u32_digits()) }
-           }
-        }
-    }
-
-    /// compute challenges
-    fn compute_challenges_beta(
-        hash: &Script,
-        c0_x: &str,
-        c0_y: &str,
-        c1_x: &str,
-        c1_y: &str,
-        inp_1: &str,
-        inp_2: &str,
-    ) -> Script {
-        script! {
-            // push C0
-            {
-                Fq::push_dec(
-                    c0_x
-                )
-            }
-            {
-                Fq::push_dec(
-                    c0_y
-                )
-            }
-            // push the public input
-            {
-                Fq::push_dec(inp_1)
-            }
-            {
-                Fq::push_dec(inp_2)
-            }
-            // push C1
-            {
-                Fq::push_dec(
-                    c1_x
-                )
-            }
-            {
-                Fq::push_dec(
-                    c1_y
-                )
-            }
-
-            // send C0 to altstack
-            {
-                Fq::roll(4)
-            }
-            {
-                Fq::toaltstack()
-            }
-            {
-                Fq::roll(4)
-            }
-            {
-                Fq::toaltstack()
-            }
-
-            // send the public input to altstack
-            {
-                Fq::roll(3)
-            }
-            {
-                Fq::toaltstack()
-            }
-            {
-                Fq::roll(2)
-            }
-            {
-                Fq::toaltstack()
-            }
-
-            // convert C1 into bytes
-            {
-                G1Affine::convert_to_compressed()
-            }
-
-            // convert the public input into bytes
-            {
-                Fq::fromaltstack()
-            }
-            {
-                Fq::convert_to_be_bytes()
-            }
-            {
-                Fq::fromaltstack()
-            }
-            {
-                Fq::convert_to_be_bytes()
-            }
-
-            // convert C0 into bytes
-            {
-                Fq::fromaltstack()
-            }
-            {
-                Fq::fromaltstack()
-            }
-            {
-                G1Affine::convert_to_compressed()
-            }
-
-            // compute the hash
-            {
-                hash.clone()
-            }
-            {
-                Fr::from_hash()
-            }
-        }
-    }
-
-    fn compute_challenges_gamma(hash: &Script) -> Script {
-        script! {
-         { Fr::copy(0) }
-         { Fr::convert_to_be_bytes() }
-         { hash.clone() }
-         { Fr::from_hash() }
-        }
-    }
-
-    fn compute_challenges_alpha(
-        hash: &Script,
-        xi: &str,
-        ql: &str,
-        qr: &str,
-        qm: &str,
-        qo: &str,
-        qc: &str,
-        s1: &str,
-        s2: &str,
-        s3: &str,
-        a: &str,
-        b: &str,
-        c: &str,
-        z: &str,
-        zw: &str,
-        t1w: &str,
-        t2w: &str,
-    ) -> Script {
-        script! {
-            // push xi seed
-            { Fr::push_dec(xi) }
-
-            // push the polynomial evaluations
-
-            // ql
-            { Fr::push_dec(ql) }
-
-            // qr
-            { Fr::push_dec(qr) }
-
-            // qm
-            { Fr::push_dec(qm) }
-
-            // qo
-            { Fr::push_dec(qo) }
-
-            // qc
-            { Fr::push_dec(qc) }
-
-            // s1
-            { Fr::push_dec(s1) }
-
-            // s2
-            { Fr::push_dec(s2) }
-
-            // s3
-            { Fr::push_dec(s3) }
-
-            // a
-            { Fr::push_dec(a) }
-
-            // b
-            { Fr::push_dec(b) }
-
-            // c
-            { Fr::push_dec(c) }
-
-            // z
-            { Fr::push_dec(z) }
-
-            // zw
-            { Fr::push_dec(zw) }
-
-            // t1w
-            { Fr::push_dec(t1w) }
-
-            // t2w
-            { Fr::push_dec(t2w) }
-
-            for i in 1..16 {
-                { Fr::roll(16 - i) } { Fr::toaltstack() }
-            }
-
-            { Fr::convert_to_be_bytes() }
-
-            for _ in 0..15 {
-                { Fr::fromaltstack() } { Fr::convert_to_be_bytes() }
-            }
-
-            {hash.clone()}
-            { Fr::from_hash() }
-        }
-    }
-
-    // [beta, gamma, alpha]
-    fn compute_challenges_y(hash: &Script, w1_x: &str, w1_y: &str) -> Script {
-        script! {
-            // alpha
-            { Fr::copy(0) }
-            // W1
-            { Fq::push_dec(w1_x) }
-            { Fq::push_dec(w1_y) }
-
-            { Fr::roll(2) }
-            { Fr::toaltstack() }
-
-            { G1Affine::convert_to_compressed() }
-            { Fr::fromaltstack() }
-            { Fr::convert_to_be_bytes() }
-
-            {hash.clone()}
-            { Fr::from_hash() }
-        }
-    }
-
-    // [beta, gamma, alpha, y]
-    fn compute_challenges_xiseed(hash: &Script, c2_x: &str, c2_y: &str) -> Script {
-        script! {
-            { Fr::copy(2) }
-            // C2
-            { Fq::push_dec(c2_x) }
-            { Fq::push_dec(c2_y) }
-
-            { Fr::roll(2) }
-            { Fr::toaltstack() }
-
-            { G1Affine::convert_to_compressed() }
-            { Fr::fromaltstack() }
-            { Fr::convert_to_be_bytes() }
-
-            {hash.clone()}
-            { Fr::from_hash() }
-        }
-    }
-
-    // [beta, gamma, alpha, y, xiseed]
-    fn compute_challenges_xin(
-        w8_1: &str,
-        w8_2: &str,
-        w8_3: &str,
-        w8_4: &str,
-        w8_5: &str,
-        w8_6: &str,
-        w8_7: &str,
-        w3: &str,
-        w3_2: &str,
-        w4: &str,
-        w4_2: &str,
-        w4_3: &str,
-        wr: &str,
-    ) -> Script {
-        script! {
-            // push xiseed
-            // { Fr::copy(0) }
-            // compute xiseed^2
-            { Fr::copy(0) }
-            { Fr::square() }
-            { Fr::copy(0) }
-            { Fr::toaltstack() }
-
-            // pH0w8_0 = xiseed^3
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0]
-
-            // pH0w8_1
-            { Fr::copy(0) }
-            // push constant w8_1
-            { Fr::push_dec(w8_1) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1]
-
-            // pH0w8_2
-            // { Fr::copy(0) }
-            { Fr::copy(1) }
-            // push constant w8_2
-            { Fr::push_dec(w8_2) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2]
-
-            // pH0w8_3
-            // { Fr::copy(0) }
-            { Fr::copy(2) }
-            // push constant w8_3
-            { Fr::push_dec(w8_3) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3]
-
-            // pH0w8_4
-            { Fr::copy(3) }
-            // push constant w8_4
-            { Fr::push_dec(w8_4) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4]
-
-            // pH0w8_5
-            { Fr::copy(4) }
-            // push constant w8_5
-            { Fr::push_dec(w8_5) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5]
-
-            // pH0w8_6
-            { Fr::copy(5) }
-            // push constant w8_6
-            { Fr::push_dec(w8_6) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6]
-
-            // pH0w8_7
-            { Fr::copy(6) }
-            // push constant w8_7
-            { Fr::push_dec(w8_7) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7]
-
-            // pH1w4_0 = xiseed^6
-            { Fr::copy(7) }
-            { Fr::square() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, ]
-
-            // pH1w4_1
-            { Fr::copy(0) }
-            // push constant w4
-            { Fr::push_dec(w4) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1]
-
-            // pH1w4_2
-            { Fr::copy(1) }
-            // push constant w4_1
-            { Fr::push_dec(w4_2) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1, pH1w4_2]
-
-            // pH1w4_3
-            { Fr::copy(2) }
-            // push constant w4_2
-            { Fr::push_dec(w4_3) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3]
-
-            // pH2w3_0 = xiseed^8
-            { Fr::copy(3) }
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            // { Fr::copy(0) }
-            // { Fr::toaltstack() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0]
-
-            // pH2w3_1
-            { Fr::copy(0) }
-            // push constant w3
-            { Fr::push_dec(w3) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1]
-
-            // pH2w3_2
-            { Fr::copy(1) }
-            // push constant w3_2
-            { Fr::push_dec(w3_2) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2]
-
-            // pH3w3_0 = xiseed^8 * ω^{1/3}
-            { Fr::copy(2) }
-            { Fr::push_dec(wr) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0]
-
-            // pH3w3_1
-            { Fr::copy(0) }
-            // push constant w3
-            { Fr::push_dec(w3) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1]
-
-            // pH2w3_2
-            // push constant w3_2
-            { Fr::copy(1) }
-            { Fr::push_dec(w3_2) }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2]
-
-            // { Fr::fromaltstack() }
-            { Fr::copy(5) }
-
-            // xi = xi_seeder^24
-            { Fr::copy(0) }
-            { Fr::square() }
-            { Fr::mul() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi]
-
-            // xiN
-            { Fr::copy(0) }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::square() }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, xiN]
-
-            // zh
-            { Fr::push_one() }
-            { Fr::sub(1, 0) }
-            // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-            // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh]
-        }
-    }
-    // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-    // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh]
-    // pH3w3_0(3), pH2w3_0(6), pH1w4_0(10), pH0w8_0(18)
-
-    /// compute inversions
-    fn compute_inversions(w: &str, inv: &str) -> Script {
-        script! {
-            // push Z_H
-            { Fr::copy(0) }
-            { Fr::toaltstack() }
-
-            // push y
-            { Fr::copy(20) }
-            // push H1w4_0, H1w4_1, H1w4_2, H1w4_3
-            { Fr::copy(12) }
-            { Fr::copy(12) }
-            { Fr::copy(12) }
-            { Fr::copy(12) }
-            // [..., xi, zh, y, pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3 | Z_H]
-
-            { Fr::copy(4) }
-            { Fr::sub(0, 1) }
-            // [xi, zh, y, pH1w4_0, pH1w4_1, pH1w4_2, y - pH1w4_3 | Z_H]
-            { Fr::copy(4) }
-            { Fr::sub(0, 2) }
-            // [xi, zh, y, pH1w4_0, pH1w4_1, y - pH1w4_3, y - pH1w4_2 | Z_H]
-            { Fr::copy(4) }
-            { Fr::sub(0, 3) }
-            // [xi, zh, y, pH1w4_0, y - pH1w4_3, y - pH1w4_2, y - pH1w4_1 | Z_H]
-            { Fr::copy(4) }
-            { Fr::sub(0, 4) }
-            // [xi, zh, y, y - pH1w4_3, y - pH1w4_2, y - pH1w4_1, y - pH1w4_0 | Z_H]
-
-            { Fr::mul() }
-            { Fr::mul() }
-            { Fr::mul() }
-            // [y, (y - pH1w4_3) * (y - pH1w4_2) * (y - pH1w4_1) * (y - pH1w4_0)]
-            { Fr::toaltstack() }
-            // [y | Z_H, (y - pH1w4_3) * (y - pH1w4_2) * (y - pH1w4_1) * (y - pH1w4_0)]
-
-            // push H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, H3w3_2
-            { Fr::copy(8) }
-            { Fr::copy(8) }
-            { Fr::copy(8) }
-            { Fr::copy(8) }
-            { Fr::copy(8) }
-            { Fr::copy(8) }
-            // [y, H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, H3w3_2 | Z_H, prod_1]
-
-            { Fr::copy(6) }
-            { Fr::sub(0, 1) }
-            // [y, H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, y -  H3w3_2]
-            { Fr::copy(6) }
-            { Fr::sub(0, 2) }
-            // [y, H2w3_0, H2w3_1, H2w3_2, H3w3_0, y -  H3w3_2, y - H3w3_1]
-            { Fr::copy(6) }
-            { Fr::sub(0, 3) }
-            // [y, H2w3_0, H2w3_1, H2w3_2, y -  H3w3_2, y - H3w3_1, y - H3w3_0]
-            { Fr::copy(6) }
-            { Fr::sub(0, 4) }
-            // [y, H2w3_0, H2w3_1, y -  H3w3_2, y - H3w3_1, y - H3w3_0, y - H2w3_2]
-            { Fr::copy(6) }
-            { Fr::sub(0, 5) }
-            // [y, H2w3_0, y -  H3w3_2, y - H3w3_1, y - H3w3_0, y - H2w3_2, y - H2w3_1]
-            { Fr::copy(6) }
-            { Fr::sub(0, 6) }
-            // [y, y -  H3w3_2, y - H3w3_1, y - H3w3_0, y - H2w3_2, y - H2w3_1, y - H2w3_0]
-
-            { Fr::mul() }
-            { Fr::mul() }
-            { Fr::mul() }
-            { Fr::mul() }
-            { Fr::mul() }
-            // [y, (y -  H3w3_2) * (y - H3w3_1) * (y - H3w3_0) * (y - H2w3_2) * (y - H2w3_1) * (y - H2w3_0)]
-            { Fr::toaltstack() }
-            // [y | Z_H, prod_1, prod_2]
-
-            // push H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7
-            { Fr::copy(20) }
-            { Fr::copy(20) }
-            { Fr::copy(20) }
-            { Fr::copy(20) }
-            { Fr::copy(20) }
-            { Fr::copy(20) }
-            { Fr::copy(20) }
-            { Fr::copy(20) }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2]
-
-            // den1 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 8 * H0w8_0 ^ 6
-            { Fr::copy(7) }
-            { Fr::square() }
-            { Fr::copy(0) }
-            { Fr::square() }
-            { Fr::mul() }
-            { Fr::double(0) }
-            { Fr::double(0) }
-            { Fr::double(0) }
-            { Fr::toaltstack() }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, den1]
-
-            // den2 = roots[7 * 0 % 8] = roots[0]
-            { Fr::copy(7) }
-            { Fr::toaltstack() }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | prod_1, prod_2, den1, den2]
-
-            // den3 = x - roots[0]
-            { Fr::copy(8) }
-            { Fr::toaltstack() }
-            { Fr::copy(7) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7, y - H0w8_0 | Z_H, prod_1, prod_2, den1, den2]
-
-            // LiS0_1 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, den1]
-
-            // den2 = roots[7 * 1 % 8] = roots[7]
-            { Fr::copy(0) }
-            { Fr::toaltstack() }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, den1, H0w8_7]
-
-            // den3 = x - roots[1]
-            { Fr::copy(8) }
-            { Fr::toaltstack() }
-            { Fr::copy(6) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7, y - H0w8_1 | Z_H, prod_1, prod_2, LiS0_1, den1, den2]
-
-            // LiS0_2 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, den1]
-
-            // den2 = roots[7 * 2 % 8] = roots[6]
-            { Fr::copy(1) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[2]
-            { Fr::copy(8) }
-            { Fr::toaltstack() }
-            { Fr::copy(5) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS0_3 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, den1]
-
-            // den2 = roots[7 * 3 % 8] = roots[5]
-            { Fr::copy(2) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[3]
-            { Fr::copy(8) }
-            { Fr::toaltstack() }
-            { Fr::copy(4) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS0_4 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, den1]
-
-            // den2 = roots[7 * 4 % 8] = roots[4]
-            { Fr::copy(3) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[4]
-            { Fr::copy(8) }
-            { Fr::toaltstack() }
-            { Fr::copy(3) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS0_5 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, den1]
-
-            // den2 = roots[7 * 5 % 8] = roots[3]
-            { Fr::copy(4) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[5]
-            { Fr::copy(8) }
-            { Fr::toaltstack() }
-            { Fr::copy(2) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS0_6 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, den1]
-
-            // den2 = roots[7 * 6 % 8] = roots[2]
-            { Fr::copy(5) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[6]
-            { Fr::copy(8) }
-            { Fr::toaltstack() }
-            { Fr::copy(1) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS0_7 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-           // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, den1]
-
-            // den2 = roots[7 * 7 % 8] = roots[1]
-            { Fr::copy(6) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[7]
-            { Fr::copy(8) }
-            { Fr::toaltstack() }
-            { Fr::copy(0) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS0_8 = den1 * den2 * den3, remove den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-           // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8]
-
-            // drop H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7
-            { Fr::drop() }
-            { Fr::drop() }
-            { Fr::drop() }
-            { Fr::drop() }
-            { Fr::drop() }
-            { Fr::drop() }
-            { Fr::drop() }
-            { Fr::drop() }
-           // [y | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8]
-
-            // push H1w4_0, H1w4_1, H1w4_2, H1w4_3
-            { Fr::copy(12) }
-            { Fr::copy(12) }
-            { Fr::copy(12) }
-            { Fr::copy(12) }
-
-            // den1 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 4 * H0w8_0 ^ 2
-            { Fr::copy(3) }
-            { Fr::square() }
-            { Fr::double(0) }
-            { Fr::double(0) }
-            { Fr::toaltstack() }
-           // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, den1]
-
-            // den2 = roots[3 * 0 % 4] = roots[0]
-            { Fr::copy(3) }
-            { Fr::toaltstack() }
-           // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, den1, den2]
-
-            // den3 = x - roots[0]
-            { Fr::copy(4) }
-            { Fr::toaltstack() }
-            { Fr::copy(3) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-           // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3, den3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, den1, den2]
-
-            // LiS1_1 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-           // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, den1]
-
-            // den2 = roots[3 * 1 % 4] = roots[3]
-            { Fr::copy(0) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[1]
-            { Fr::copy(4) }
-            { Fr::toaltstack() }
-            { Fr::copy(2) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS1_2 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-           // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, den1]
-
-            // den2 = roots[3 * 2 % 4] = roots[2]
-            { Fr::copy(1) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[2]
-            { Fr::copy(4) }
-            { Fr::toaltstack() }
-            { Fr::copy(1) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS1_3 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-           // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, den1]
-
-            // den2 = roots[3 * 3 % 4] = roots[1]
-            { Fr::copy(2) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[3]
-            { Fr::copy(4) }
-            { Fr::toaltstack() }
-            { Fr::copy(0) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS1_4 = den1 * den2 * den3, remove den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-           // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
-
-            // drop H1w4_0, H1w4_1, H1w4_2, H1w4_3
-            { Fr::drop() }
-            { Fr::drop() }
-            { Fr::drop() }
-            { Fr::drop() }
-           // [y | prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
-
-            // push H2w3_0, H2w3_1, H2w3_2
-            { Fr::copy(8) }
-            { Fr::copy(8) }
-            { Fr::copy(8) }
-            // [y, H2w3_0, H2w3_1, H2w3_2 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
-
-            // push xi
-            // { Fr::push_dec("14814634099415170872937750660683266261347419959225231219985478027287965492246") }
-            { Fr::copy(5) }
-            // [y, H2w3_0, H2w3_1, H2w3_2, xi | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
-
-            // compute xiw
-            { Fr::copy(0) }
-            { Fr::push_dec(w) }
-            { Fr::mul() }
-            // [y, H2w3_0, H2w3_1, H2w3_2, xi, xiw | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
-
-            // compute xi - xiw
-            { Fr::sub(1, 0) }
-            { Fr::copy(0) }
-            { Fr::toaltstack() }
-            // [y, H2w3_0, H2w3_1, H2w3_2, xi - xiw | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, xi - xiw]
-
-            // move xi - xiw to before y
-            { Fr::roll(4) }
-            { Fr::roll(4) }
-            { Fr::roll(4) }
-            { Fr::roll(4) }
-            // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, xi - xiw]
-
-            // _3h2 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 3 * H2w3_0
-            { Fr::copy(2) }
-            { Fr::copy(0) }
-            { Fr::double(0) }
-            { Fr::add(1, 0) }
-
-            // compute den1 = _3h2 * (xi - xiw)
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, den1]
-
-            // den2 = roots[2 * 0 % 3] = roots[0]
-            { Fr::copy(2) }
-            { Fr::toaltstack() }
-            // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, den1, den2]
-
-            // den3 = x - roots[0]
-            { Fr::copy(3) }
-            { Fr::toaltstack() }
-            { Fr::copy(2) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-            // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2, y - H2w3_0 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, den1, den2]
-
-            // LiS2_1 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, den1]
-
-            // den2 = roots[2 * 1 % 3] = roots[2]
-            { Fr::copy(0) }
-            { Fr::toaltstack() }
-            // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, den1, den2]
-
-            // den3 = x - roots[1]
-            { Fr::copy(3) }
-            { Fr::toaltstack() }
-            { Fr::copy(1) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS2_2 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, den1]
-
-            // den2 = roots[2 * 2 % 3] = roots[1]
-            { Fr::copy(1) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[2]
-            { Fr::copy(3) }
-            { Fr::toaltstack() }
-            { Fr::copy(0) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS2_3 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3]
-
-            // drop H2w3_0, H2w3_1, H2w3_2
-            { Fr::drop() }
-            { Fr::drop() }
-            { Fr::drop() }
-            // [xi - xiw, y |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3]
-
-            // push H3w3_0, H3w3_1, H3w3_2
-            { Fr::copy(6) }
-            { Fr::copy(6) }
-            { Fr::copy(6) }
-            // [xi - xiw, y, H3w3_0, H3w3_1, H3w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3]
-
-            // obtain xiw - xi
-            { Fr::neg(4) }
-            { Fr::toaltstack() }
-            // [y, H3w3_0, H3w3_1, H3w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, xiw - xi]
-
-            // _3h2 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 3 * H3w3_0
-            { Fr::copy(2) }
-            { Fr::copy(0) }
-            { Fr::double(0) }
-            { Fr::add(1, 0) }
-
-            // compute den1 = _3h2 * (xiw - xi)
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            // [y, H3w3_0, H3w3_1, H3w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, den1]
-
-            // den2 = roots[2 * 0 % 3] = roots[0]
-            { Fr::copy(2) }
-            { Fr::toaltstack() }
-            // [y, H3w3_0, H3w3_1, H3w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, den1, den2]
-
-            // den3 = x - roots[0]
-            { Fr::copy(3) }
-            { Fr::toaltstack() }
-            { Fr::copy(2) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-            // [y, H3w3_0, H3w3_1, H3w3_2, den3 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, den1, den2]
-
-            // LiS3_1 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            // [y, H3w3_0, H3w3_1, H3w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, den1]
-
-            // den2 = roots[2 * 1 % 3] = roots[2]
-            { Fr::copy(0) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[1]
-            { Fr::copy(3) }
-            { Fr::toaltstack() }
-            { Fr::copy(1) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS3_2 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::copy(0) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            // [y, H3w3_0, H3w3_1, H3w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, den1]
-
-            // den2 = roots[2 * 2 % 3] = roots[1]
-            { Fr::copy(1) }
-            { Fr::toaltstack() }
-
-            // den3 = x - roots[2]
-            { Fr::copy(3) }
-            { Fr::toaltstack() }
-            { Fr::copy(0) }
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // LiS3_3 = den1 * den2 * den3, keep den1 in the altstack
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            // [y, H3w3_0, H3w3_1, H3w3_2 |
-            // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3]
-
-            // drop H3w3_0, H3w3_1, H3w3_2
-            { Fr::drop() }
-            { Fr::drop() }
-            { Fr::drop() }
-
-            // drop y
-            { Fr::drop() }
-            // [ xi, zh | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3]
-
-            // push xi again
-            // { Fr::push_dec("14814634099415170872937750660683266261347419959225231219985478027287965492246") }
-            { Fr::copy(1) }
-            // [ xi | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3]
-
-            // Li_1 = 262144 * (xi - 1)
-            { Fr::copy(0) }
-            { Fr::push_one() }
-            { Fr::sub(1, 0) }
-            { Fr::push_dec("262144") }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            // [ xi | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1]
-
-            // Li_2 = 262144 * (xi - w1)
-            { Fr::copy(0) }
-            { Fr::push_dec(w) }
-            { Fr::sub(1, 0) }
-            { Fr::push_dec("262144") }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-            // [ xi | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2]
-
-
-            // Get all the elements back to the stack
-            for _ in 0..23 {
-                { Fr::fromaltstack() }
-            }
-            // [ xi , Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2]
-
-            // build up the accumulator
-            { Fr::copy(0) }
-            for i in 1..23 {
-                { Fr::copy(0) }
-                // [ xi, Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
-                // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, Li_2, Li_2]
-                { Fr::copy(i + 1 + i) }
-                { Fr::mul() }
-                // [ xi, Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
-                // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, Li_2, Li_2 * Li_1]
-            }
-
-            // push the inv from the proof and verify the inv
-            { Fr::copy(0) }
-            { Fr::push_dec(inv) }
-            { Fr::copy(0) } { Fr::toaltstack() }
-            { Fr::mul() }
-            { Fr::is_one_keep_element(0) }
-            OP_VERIFY
-            { Fr::drop() } // is_one does not consume the input
-
-            // current stack:
-            //   inputs (Li_2 down to ZH)
-            //   accumulators (ZH down to prod of all)
-            // altstack:
-            //   inv
-
-            // compute the inverses now
-            { Fr::drop() }
-            { Fr::fromaltstack() }
-            // [ Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
-            // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, Li_2, Li_2 * Li_1, Li_2 * Li_1 * LiS3_3, ...
-            // inv ]
-
-            for i in 0..22 {
-                { Fr::copy(0) }
-                { Fr::roll(2) }
-                { Fr::mul() }
-                { Fr::toaltstack() }
-                { Fr::roll(23 - 1 - i + 23 - 1 - i) }
-                { Fr::mul() }
-            }
-            { Fr::roll(1) }
-            { Fr::drop() }
-            { Fr::roll(1) }
-            { Fr::drop() }
-            // [ZH | ..., LiS0_3, LiS0_2, LiS0_1, DenH2, DenH1]
-
-            for _ in 0..22 {
-                { Fr::fromaltstack() }
-            }
-            // [..., xi, ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, ...]
+use crate::bn254::curves::{G1Affine, G1Projective};
+use crate::bn254::ell_coeffs::G2Prepared;
+use crate::bn254::fp254impl::Fp254Impl;
+use crate::bn254::fq::Fq;
+use crate::bn254::fq12::Fq12;
+use crate::bn254::fr::Fr;
+use crate::bn254::pairing::Pairing;
+use crate::bn254::utils;
+use crate::bn254::utils::fq12_push;
+use crate::groth16::constants::{LAMBDA, P_POW3};
+use crate::groth16::loader::{parse_dec, snarkjs_g1, snarkjs_g2};
+use crate::groth16::offchain_checker::compute_c_wi;
+use crate::hash::script_hasher::Blake3;
+use crate::hash::transcript::{NativeTranscript, Transcript};
+use crate::treepp::*;
+use ark_ec::pairing::Pairing as ArkPairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, One, PrimeField, Zero};
+use num_bigint::BigUint;
+use serde::Deserialize;
+
+fn fq_push(x: ark_bn254::Fq) -> Script { Fq::push_u32_le(&BigUint::from(x).to_u32_digits()) }
+
+fn fr_push(x: ark_bn254::Fr) -> Script { Fr::push_u32_le(&BigUint::from(x).to_u32_digits()) }
+
+/// compute challenges with the transcript of snarkjs, same as `Verifier::compute_challenges`
+// [] -> [beta, gamma, alpha, y, xiseed]
+fn compute_challenges(
+    c0: ark_bn254::G1Affine,
+    proof: &Proof,
+    public_inputs: &[ark_bn254::Fr],
+) -> Script {
+    let ev = &proof.evaluations;
+    let mut transcript = Transcript::<Blake3>::new();
+
+    script! {
+        // beta = H(C0, public inputs, C1)
+        { fq_push(c0.x) }
+        { fq_push(c0.y) }
+        { transcript.absorb_g1() }
+        for input in public_inputs {
+            { fr_push(*input) }
+            { transcript.absorb_fr() }
         }
-    }
-
-    /// compute lagranges
-    // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-    // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
-    // ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
-    // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2]
-    fn compute_lagranges(w1: &str) -> Script {
-        script! {
-            // push zh
-            { Fr::copy(23) }
-            { Fr::copy(0) }
-            { Fr::toaltstack() }
-
-            // push the inverse of Li_1
-            { Fr::copy(2) }
-            { Fr::mul() }
-
-            // push the inverse of Li_2
-            { Fr::copy(1)}
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::push_dec(w1) }
-            { Fr::mul() }
+        { fq_push(proof.c1.x) }
+        { fq_push(proof.c1.y) }
+        { transcript.absorb_g1() }
+        { transcript.squeeze_challenge() }
+        // [beta]
+
+        // gamma = H(beta)
+        { Fr::copy(0) }
+        { transcript.absorb_fr() }
+        { transcript.squeeze_challenge() }
+        // [beta, gamma]
+
+        // xiseed = H(gamma, C2)
+        { Fr::copy(0) }
+        { transcript.absorb_fr() }
+        { fq_push(proof.c2.x) }
+        { fq_push(proof.c2.y) }
+        { transcript.absorb_g1() }
+        { transcript.squeeze_challenge() }
+        // [beta, gamma, xiseed]
+
+        // alpha = H(xiseed, evaluations)
+        { Fr::copy(0) }
+        { transcript.absorb_fr() }
+        for eval in [
+            ev.ql, ev.qr, ev.qm, ev.qo, ev.qc, ev.s1, ev.s2, ev.s3, ev.a, ev.b, ev.c, ev.z, ev.zw,
+            ev.t1w, ev.t2w,
+        ] {
+            { fr_push(eval) }
+            { transcript.absorb_fr() }
         }
+        { transcript.squeeze_challenge() }
+        // [beta, gamma, xiseed, alpha]
+
+        // y = H(alpha, W1)
+        { Fr::copy(0) }
+        { transcript.absorb_fr() }
+        { fq_push(proof.w1.x) }
+        { fq_push(proof.w1.y) }
+        { transcript.absorb_g1() }
+        { transcript.squeeze_challenge() }
+        // [beta, gamma, xiseed, alpha, y]
+
+        { Fr::roll(2) }
     }
+}
 
-    /// compute pi {48 elements}
-    // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-    // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi,
-    // ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
-    // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2]
-    fn compute_pi(input1: &str, input2: &str) -> Script {
-        script! {
-
-            { Fr::copy(1)}
-            { Fr::copy(1)}
-            { Fr::push_dec(input1) }
-            { Fr::push_dec(input2) }
-            { Fr::roll(2) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::add(1, 0) }
-            { Fr::neg(0) }
-        }
-    }
+/// Roots of unity of the verifying key as decimal strings, `w8` holds w8^1 to w8^7
+#[derive(Clone, Copy)]
+struct DecRoots<'a> {
+    w8: [&'a str; 7],
+    w3: &'a str,
+    w3_2: &'a str,
+    w4: &'a str,
+    w4_2: &'a str,
+    w4_3: &'a str,
+    wr: &'a str,
+}
 
-    /// compute R0 {50 elements} ql, qr, qo, qm, qc, s1, s2, s3
-    // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-    // pH1w4_0(37), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
-    // ZH(25), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
-    // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI]
-    fn compute_r0(
-        ql: &str,
-        qr: &str,
-        qo: &str,
-        qm: &str,
-        qc: &str,
-        s1: &str,
-        s2: &str,
-        s3: &str,
-    ) -> Script {
-        script! {
-            // push ql, qr, qo, qm, qc, s1, s2, s3
-            { Fr::push_dec(ql) }
-            { Fr::push_dec(qr) }
-            { Fr::push_dec(qo) }
-            { Fr::push_dec(qm) }
-            { Fr::push_dec(qc) }
-            { Fr::push_dec(s1) }
-            { Fr::push_dec(s2) }
-            { Fr::push_dec(s3) }
-
-            // push H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7
-            { Fr::copy(53) }
-            { Fr::copy(53) }
-            { Fr::copy(53) }
-            { Fr::copy(53) }
-            { Fr::copy(53) }
-            { Fr::copy(53) }
-            { Fr::copy(53) }
-            { Fr::copy(53) }
-
-            // push lis0_1_inv, ...
-            { Fr::copy(38) }
-            { Fr::copy(38) }
-            { Fr::copy(38) }
-            { Fr::copy(38) }
-            { Fr::copy(38) }
-            { Fr::copy(38) }
-            { Fr::copy(38) }
-            { Fr::copy(38) }
-
-            // push y, xi
-            { Fr::copy(70) }
-            { Fr::copy(52) }
-
-            // compute num = y^8 - xi, push to altstack
-            { Fr::roll(1) }
-            { Fr::square() }
+// [beta, gamma, alpha, y, xiseed]
+fn compute_challenges_xin(roots: &DecRoots, power: u32) -> Script {
+    let DecRoots {
+        w8: [w8_1, w8_2, w8_3, w8_4, w8_5, w8_6, w8_7],
+        w3,
+        w3_2,
+        w4,
+        w4_2,
+        w4_3,
+        wr,
+    } = *roots;
+    script! {
+        // push xiseed
+        // { Fr::copy(0) }
+        // compute xiseed^2
+        { Fr::copy(0) }
+        { Fr::square() }
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+
+        // pH0w8_0 = xiseed^3
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0]
+
+        // pH0w8_1
+        { Fr::copy(0) }
+        // push constant w8_1
+        { Fr::push_dec(w8_1) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1]
+
+        // pH0w8_2
+        // { Fr::copy(0) }
+        { Fr::copy(1) }
+        // push constant w8_2
+        { Fr::push_dec(w8_2) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2]
+
+        // pH0w8_3
+        // { Fr::copy(0) }
+        { Fr::copy(2) }
+        // push constant w8_3
+        { Fr::push_dec(w8_3) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3]
+
+        // pH0w8_4
+        { Fr::copy(3) }
+        // push constant w8_4
+        { Fr::push_dec(w8_4) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4]
+
+        // pH0w8_5
+        { Fr::copy(4) }
+        // push constant w8_5
+        { Fr::push_dec(w8_5) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5]
+
+        // pH0w8_6
+        { Fr::copy(5) }
+        // push constant w8_6
+        { Fr::push_dec(w8_6) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6]
+
+        // pH0w8_7
+        { Fr::copy(6) }
+        // push constant w8_7
+        { Fr::push_dec(w8_7) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7]
+
+        // pH1w4_0 = xiseed^6
+        { Fr::copy(7) }
+        { Fr::square() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, ]
+
+        // pH1w4_1
+        { Fr::copy(0) }
+        // push constant w4
+        { Fr::push_dec(w4) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1]
+
+        // pH1w4_2
+        { Fr::copy(1) }
+        // push constant w4_1
+        { Fr::push_dec(w4_2) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2]
+
+        // pH1w4_3
+        { Fr::copy(2) }
+        // push constant w4_2
+        { Fr::push_dec(w4_3) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3]
+
+        // pH2w3_0 = xiseed^8
+        { Fr::copy(3) }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        // { Fr::copy(0) }
+        // { Fr::toaltstack() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0]
+
+        // pH2w3_1
+        { Fr::copy(0) }
+        // push constant w3
+        { Fr::push_dec(w3) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1]
+
+        // pH2w3_2
+        { Fr::copy(1) }
+        // push constant w3_2
+        { Fr::push_dec(w3_2) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2]
+
+        // pH3w3_0 = xiseed^8 * ω^{1/3}
+        { Fr::copy(2) }
+        { Fr::push_dec(wr) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0]
+
+        // pH3w3_1
+        { Fr::copy(0) }
+        // push constant w3
+        { Fr::push_dec(w3) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1]
+
+        // pH2w3_2
+        // push constant w3_2
+        { Fr::copy(1) }
+        { Fr::push_dec(w3_2) }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2]
+
+        // { Fr::fromaltstack() }
+        { Fr::copy(5) }
+
+        // xi = xi_seeder^24
+        { Fr::copy(0) }
+        { Fr::square() }
+        { Fr::mul() }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi]
+
+        // xiN
+        { Fr::copy(0) }
+        for _ in 0..power {
             { Fr::square() }
-            { Fr::square() }
-            { Fr::sub(0, 1) }
-            { Fr::toaltstack() }
-            // [ql, qr, ...., H0w8_0, H0w8_1, ..., lis0_1_inv, lis0_2_inv, ... | num]
-
-            // pick H0w8_0, ..., H0w8_7 and compute the corresponding c0Value
-            for i in 0..8 {
-                { Fr::copy(8 + 7 - i) }
-
-                { Fr::copy(0) } { Fr::copy(1) } { Fr::mul() }
-                { Fr::copy(0) } { Fr::copy(2) } { Fr::mul() }
-                { Fr::copy(0) } { Fr::copy(3) } { Fr::mul() }
-                { Fr::copy(0) } { Fr::copy(4) } { Fr::mul() }
-                { Fr::copy(0) } { Fr::copy(5) } { Fr::mul() }
-                { Fr::copy(0) } { Fr::copy(6) } { Fr::mul() }
-                // H0w8_0, H0w8_0^2, H0w8_0^3, ...
-
-                for _ in 0..7 {
-                    { Fr::toaltstack() }
-                }
-
-                // c0Value starts with ql
-                { Fr::copy(16 + 7) }
-                { Fr::copy(16 + 6 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-                { Fr::copy(16 + 5 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-                { Fr::copy(16 + 4 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-                { Fr::copy(16 + 3 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-                { Fr::copy(16 + 2 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-                { Fr::copy(16 + 1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-                { Fr::copy(16 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-
-                // push this c0Value to the altstack
-                { Fr::toaltstack() }
-            }
-
-            // get all the c0Values out
-            for _ in 0..8 {
-                { Fr::fromaltstack() }
-            }
-
-            // multiply the corresponding LiS0Inv
-            for i in 0..8 {
-                { Fr::roll(8 - i + 7 - i) }
-                { Fr::mul() }
-                { Fr::toaltstack() }
-            }
-
-            // drop all the intermediate values
-            for _ in 0..16 {
-                { Fr::drop() }
-            }
-
-            // add all the c0Values together
-            { Fr::fromaltstack() }
-            for _ in 1..8 {
-                { Fr::fromaltstack() }
-                { Fr::add(1, 0) }
-            }
-
-            // multiply by the num
-            { Fr::fromaltstack() }
-            { Fr::mul() }
         }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, xiN]
+
+        // zh
+        { Fr::push_one() }
+        { Fr::sub(1, 0) }
+        // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+        // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh]
     }
-
-    /// compute R1 {51 elements} ql, qr, qo, qm, qc, a, b, c
-    // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-    // pH1w4_0(38), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
-    // ZH(26), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
-    // LiS1_1(15), LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI, r0]
-    fn compute_r1(
-        ql: &str,
-        qr: &str,
-        qm: &str,
-        qo: &str,
-        qc: &str,
-        a: &str,
-        b: &str,
-        c: &str,
-    ) -> Script {
-        script! {
-
-            { Fr::push_dec(ql) }
-            { Fr::push_dec(qr) }
-            { Fr::push_dec(qm) }
-            { Fr::push_dec(qo) }
-            { Fr::push_dec(qc) }
-            { Fr::push_dec(a) }
-            { Fr::push_dec(b) }
-            { Fr::push_dec(c) }
-            // pi, zh
-            { Fr::copy(9)}
-            { Fr::copy(35)}
-            // pH1w4_0->3
-            { Fr::copy(48)}
-            { Fr::copy(48)}
-            { Fr::copy(48)}
-            { Fr::copy(48)}
-            // LiS1_1 -> 4
-            { Fr::copy(29)}
-            { Fr::copy(29)}
-            { Fr::copy(29)}
-            { Fr::copy(29)}
-            // y, xi
-            { Fr::copy(65)}
-            { Fr::copy(47)}
-            // compute num = y^4 - xi, push to altstack
-            { Fr::roll(1) }
-            { Fr::square() }
-            { Fr::square() }
-            { Fr::sub(0, 1) }
-            { Fr::toaltstack() }
-
-            // compute t0
-
-            // ql * evalA
-            { Fr::copy(10 + 7) }
-            { Fr::copy(10 + 2 + 1) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-
-            // qr * evalB
-            { Fr::copy(10 + 6) }
-            { Fr::copy(10 + 1 + 1) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-
-            // qm * evalA * evalB
-            { Fr::copy(10 + 5) }
-            { Fr::copy(10 + 2 + 1) }
-            { Fr::mul() }
-            { Fr::copy(10 + 1 + 1) }
-            { Fr::mul() }
-            { Fr::toaltstack() }
-
-            // qo * evalC
-            { Fr::copy(10 + 4) }
-            { Fr::copy(10 + 1) }
-            { Fr::mul() }
-
-            // t0 := ql * evalA + qr * evalB + qm * evalA * evalB + qo * evalC + qc + pi
-            { Fr::fromaltstack() }
-            { Fr::add(1, 0) }
-            { Fr::fromaltstack() }
-            { Fr::add(1, 0) }
-            { Fr::fromaltstack() }
-            { Fr::add(1, 0) }
-            { Fr::copy(10 + 3 + 1) }
-            { Fr::add(1, 0) }
-            { Fr::copy(8 + 1 + 1) }
-            { Fr::add(1, 0) }
-
-            // t0 := t0 * zhInv
-            { Fr::copy(8 + 1) }
-            { Fr::mul() }
-
-            // the stack should look like:
-            //    ql, qr, qm, qo, qc, a, b, c
-            //    pi, zhInv
-            //    H1w4_0, H1w4_1, H1w4_2, H1w4_3
-            //    LiS1Inv 1-4
-            //    t0
-            //
-            // altstack: num
-
-            // pick H1w4_0, ..., H1w4_3 and compute the corresponding c1Value
-            for i in 0..4 {
-                { Fr::copy(1 + 4 + 3 - i) }
-
-                { Fr::copy(0) } { Fr::copy(1) } { Fr::mul() }
-                { Fr::copy(0) } { Fr::copy(2) } { Fr::mul() }
-
-                for _ in 0..3 {
-                    { Fr::toaltstack() }
-                }
-
-                // c1Value starts with a
-                { Fr::copy(1 + 4 + 4 + 2 + 2) }
-                { Fr::copy(1 + 4 + 4 + 2 + 1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-                { Fr::copy(1 + 4 + 4 + 2 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-                { Fr::copy(1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-
-                // push this c1Value to the altstack
-                { Fr::toaltstack() }
-            }
-
-            // get all the c1Values out
-            for _ in 0..4 {
-                { Fr::fromaltstack() }
-            }
-
-            // multiply the corresponding LiS1Inv
-            for i in 0..4 {
-                { Fr::roll(4 - i + 1 + 3 - i) }
-                { Fr::mul() }
-                { Fr::toaltstack() }
-            }
-
-            // drop all the intermediate values
-            for _ in 0..(1 + 4 + 2 + 8) {
-                { Fr::drop() }
-            }
-
-            // add all the c0Values together
-            { Fr::fromaltstack() }
-            for _ in 1..4 {
-                { Fr::fromaltstack() }
-                { Fr::add(1, 0) }
-            }
-
-            // multiply by the num
+}
+// [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh]
+// pH3w3_0(3), pH2w3_0(6), pH1w4_0(10), pH0w8_0(18)
+
+/// compute inversions
+fn compute_inversions(w: &str, inv: &str, n: &str) -> Script {
+    script! {
+        // push Z_H
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+
+        // push y
+        { Fr::copy(20) }
+        // push H1w4_0, H1w4_1, H1w4_2, H1w4_3
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+        // [..., xi, zh, y, pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3 | Z_H]
+
+        { Fr::copy(4) }
+        { Fr::sub(0, 1) }
+        // [xi, zh, y, pH1w4_0, pH1w4_1, pH1w4_2, y - pH1w4_3 | Z_H]
+        { Fr::copy(4) }
+        { Fr::sub(0, 2) }
+        // [xi, zh, y, pH1w4_0, pH1w4_1, y - pH1w4_3, y - pH1w4_2 | Z_H]
+        { Fr::copy(4) }
+        { Fr::sub(0, 3) }
+        // [xi, zh, y, pH1w4_0, y - pH1w4_3, y - pH1w4_2, y - pH1w4_1 | Z_H]
+        { Fr::copy(4) }
+        { Fr::sub(0, 4) }
+        // [xi, zh, y, y - pH1w4_3, y - pH1w4_2, y - pH1w4_1, y - pH1w4_0 | Z_H]
+
+        { Fr::mul() }
+        { Fr::mul() }
+        { Fr::mul() }
+        // [y, (y - pH1w4_3) * (y - pH1w4_2) * (y - pH1w4_1) * (y - pH1w4_0)]
+        { Fr::toaltstack() }
+        // [y | Z_H, (y - pH1w4_3) * (y - pH1w4_2) * (y - pH1w4_1) * (y - pH1w4_0)]
+
+        // push H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, H3w3_2
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        // [y, H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, H3w3_2 | Z_H, prod_1]
+
+        { Fr::copy(6) }
+        { Fr::sub(0, 1) }
+        // [y, H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, y -  H3w3_2]
+        { Fr::copy(6) }
+        { Fr::sub(0, 2) }
+        // [y, H2w3_0, H2w3_1, H2w3_2, H3w3_0, y -  H3w3_2, y - H3w3_1]
+        { Fr::copy(6) }
+        { Fr::sub(0, 3) }
+        // [y, H2w3_0, H2w3_1, H2w3_2, y -  H3w3_2, y - H3w3_1, y - H3w3_0]
+        { Fr::copy(6) }
+        { Fr::sub(0, 4) }
+        // [y, H2w3_0, H2w3_1, y -  H3w3_2, y - H3w3_1, y - H3w3_0, y - H2w3_2]
+        { Fr::copy(6) }
+        { Fr::sub(0, 5) }
+        // [y, H2w3_0, y -  H3w3_2, y - H3w3_1, y - H3w3_0, y - H2w3_2, y - H2w3_1]
+        { Fr::copy(6) }
+        { Fr::sub(0, 6) }
+        // [y, y -  H3w3_2, y - H3w3_1, y - H3w3_0, y - H2w3_2, y - H2w3_1, y - H2w3_0]
+
+        { Fr::mul() }
+        { Fr::mul() }
+        { Fr::mul() }
+        { Fr::mul() }
+        { Fr::mul() }
+        // [y, (y -  H3w3_2) * (y - H3w3_1) * (y - H3w3_0) * (y - H2w3_2) * (y - H2w3_1) * (y - H2w3_0)]
+        { Fr::toaltstack() }
+        // [y | Z_H, prod_1, prod_2]
+
+        // push H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        { Fr::copy(20) }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2]
+
+        // den1 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 8 * H0w8_0 ^ 6
+        { Fr::copy(7) }
+        { Fr::square() }
+        { Fr::copy(0) }
+        { Fr::square() }
+        { Fr::mul() }
+        { Fr::double(0) }
+        { Fr::double(0) }
+        { Fr::double(0) }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, den1]
+
+        // den2 = roots[7 * 0 % 8] = roots[0]
+        { Fr::copy(7) }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | prod_1, prod_2, den1, den2]
+
+        // den3 = x - roots[0]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(7) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7, y - H0w8_0 | Z_H, prod_1, prod_2, den1, den2]
+
+        // LiS0_1 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, den1]
+
+        // den2 = roots[7 * 1 % 8] = roots[7]
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, den1, H0w8_7]
+
+        // den3 = x - roots[1]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(6) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7, y - H0w8_1 | Z_H, prod_1, prod_2, LiS0_1, den1, den2]
+
+        // LiS0_2 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, den1]
+
+        // den2 = roots[7 * 2 % 8] = roots[6]
+        { Fr::copy(1) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[2]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(5) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_3 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, den1]
+
+        // den2 = roots[7 * 3 % 8] = roots[5]
+        { Fr::copy(2) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[3]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(4) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_4 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, den1]
+
+        // den2 = roots[7 * 4 % 8] = roots[4]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[4]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(3) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_5 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, den1]
+
+        // den2 = roots[7 * 5 % 8] = roots[3]
+        { Fr::copy(4) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[5]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(2) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_6 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, den1]
+
+        // den2 = roots[7 * 6 % 8] = roots[2]
+        { Fr::copy(5) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[6]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(1) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_7 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+       // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, den1]
+
+        // den2 = roots[7 * 7 % 8] = roots[1]
+        { Fr::copy(6) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[7]
+        { Fr::copy(8) }
+        { Fr::toaltstack() }
+        { Fr::copy(0) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS0_8 = den1 * den2 * den3, remove den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+       // [y, H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8]
+
+        // drop H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+       // [y | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8]
+
+        // push H1w4_0, H1w4_1, H1w4_2, H1w4_3
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+        { Fr::copy(12) }
+
+        // den1 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 4 * H0w8_0 ^ 2
+        { Fr::copy(3) }
+        { Fr::square() }
+        { Fr::double(0) }
+        { Fr::double(0) }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, den1]
+
+        // den2 = roots[3 * 0 % 4] = roots[0]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, den1, den2]
+
+        // den3 = x - roots[0]
+        { Fr::copy(4) }
+        { Fr::toaltstack() }
+        { Fr::copy(3) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3, den3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, den1, den2]
+
+        // LiS1_1 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, den1]
+
+        // den2 = roots[3 * 1 % 4] = roots[3]
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[1]
+        { Fr::copy(4) }
+        { Fr::toaltstack() }
+        { Fr::copy(2) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS1_2 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, den1]
+
+        // den2 = roots[3 * 2 % 4] = roots[2]
+        { Fr::copy(1) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[2]
+        { Fr::copy(4) }
+        { Fr::toaltstack() }
+        { Fr::copy(1) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS1_3 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, den1]
+
+        // den2 = roots[3 * 3 % 4] = roots[1]
+        { Fr::copy(2) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[3]
+        { Fr::copy(4) }
+        { Fr::toaltstack() }
+        { Fr::copy(0) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS1_4 = den1 * den2 * den3, remove den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+       // [y, H1w4_0, H1w4_1, H1w4_2, H1w4_3 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
+
+        // drop H1w4_0, H1w4_1, H1w4_2, H1w4_3
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+       // [y | prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
+
+        // push H2w3_0, H2w3_1, H2w3_2
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        { Fr::copy(8) }
+        // [y, H2w3_0, H2w3_1, H2w3_2 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
+
+        // push xi
+        // { Fr::push_dec("14814634099415170872937750660683266261347419959225231219985478027287965492246") }
+        { Fr::copy(5) }
+        // [y, H2w3_0, H2w3_1, H2w3_2, xi | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
+
+        // compute xiw
+        { Fr::copy(0) }
+        { Fr::push_dec(w) }
+        { Fr::mul() }
+        // [y, H2w3_0, H2w3_1, H2w3_2, xi, xiw | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4]
+
+        // compute xi - xiw
+        { Fr::sub(1, 0) }
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+        // [y, H2w3_0, H2w3_1, H2w3_2, xi - xiw | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, xi - xiw]
+
+        // move xi - xiw to before y
+        { Fr::roll(4) }
+        { Fr::roll(4) }
+        { Fr::roll(4) }
+        { Fr::roll(4) }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, xi - xiw]
+
+        // _3h2 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 3 * H2w3_0
+        { Fr::copy(2) }
+        { Fr::copy(0) }
+        { Fr::double(0) }
+        { Fr::add(1, 0) }
+
+        // compute den1 = _3h2 * (xi - xiw)
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, den1]
+
+        // den2 = roots[2 * 0 % 3] = roots[0]
+        { Fr::copy(2) }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, den1, den2]
+
+        // den3 = x - roots[0]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(2) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2, y - H2w3_0 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, den1, den2]
+
+        // LiS2_1 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, den1]
+
+        // den2 = roots[2 * 1 % 3] = roots[2]
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, den1, den2]
+
+        // den3 = x - roots[1]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(1) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS2_2 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, den1]
+
+        // den2 = roots[2 * 2 % 3] = roots[1]
+        { Fr::copy(1) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[2]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(0) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS2_3 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        // [xi - xiw, y, H2w3_0, H2w3_1, H2w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3]
+
+        // drop H2w3_0, H2w3_1, H2w3_2
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+        // [xi - xiw, y |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3]
+
+        // push H3w3_0, H3w3_1, H3w3_2
+        { Fr::copy(6) }
+        { Fr::copy(6) }
+        { Fr::copy(6) }
+        // [xi - xiw, y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3]
+
+        // obtain xiw - xi
+        { Fr::neg(4) }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, xiw - xi]
+
+        // _3h2 = Fr.mul(Fr.e(len), Fr.exp(roots[0], len - 2)) = = 3 * H3w3_0
+        { Fr::copy(2) }
+        { Fr::copy(0) }
+        { Fr::double(0) }
+        { Fr::add(1, 0) }
+
+        // compute den1 = _3h2 * (xiw - xi)
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, den1]
+
+        // den2 = roots[2 * 0 % 3] = roots[0]
+        { Fr::copy(2) }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, den1, den2]
+
+        // den3 = x - roots[0]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(2) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+        // [y, H3w3_0, H3w3_1, H3w3_2, den3 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, den1, den2]
+
+        // LiS3_1 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, den1]
+
+        // den2 = roots[2 * 1 % 3] = roots[2]
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[1]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(1) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS3_2 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::copy(0) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, den1]
+
+        // den2 = roots[2 * 2 % 3] = roots[1]
+        { Fr::copy(1) }
+        { Fr::toaltstack() }
+
+        // den3 = x - roots[2]
+        { Fr::copy(3) }
+        { Fr::toaltstack() }
+        { Fr::copy(0) }
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // LiS3_3 = den1 * den2 * den3, keep den1 in the altstack
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        // [y, H3w3_0, H3w3_1, H3w3_2 |
+        // Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3]
+
+        // drop H3w3_0, H3w3_1, H3w3_2
+        { Fr::drop() }
+        { Fr::drop() }
+        { Fr::drop() }
+
+        // drop y
+        { Fr::drop() }
+        // [ xi, zh | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3]
+
+        // push xi again
+        // { Fr::push_dec("14814634099415170872937750660683266261347419959225231219985478027287965492246") }
+        { Fr::copy(1) }
+        // [ xi | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3]
+
+        // Li_1 = n * (xi - 1)
+        { Fr::copy(0) }
+        { Fr::push_one() }
+        { Fr::sub(1, 0) }
+        { Fr::push_dec(n) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        // [ xi | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1]
+
+        // Li_2 = n * (xi - w1)
+        { Fr::copy(0) }
+        { Fr::push_dec(w) }
+        { Fr::sub(1, 0) }
+        { Fr::push_dec(n) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+        // [ xi | Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2]
+
+
+        // Get all the elements back to the stack
+        for _ in 0..23 {
             { Fr::fromaltstack() }
-            { Fr::mul() }
         }
-    }
-
-    /// compute R2 {52 elements} a, b, c, z, zw, s1, s2, s3, t1w, t2w
-    // [beta(51), gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-    // pH1w4_0(39), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
-    // ZH(27), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
-    // LiS1_1(16), LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI, r0, r1]
-    fn compute_r2(
-        a: &str,
-        b: &str,
-        c: &str,
-        z: &str,
-        zw: &str,
-        s1: &str,
-        s2: &str,
-        s3: &str,
-        t1w: &str,
-        t2w: &str,
-        w1: &str,
-    ) -> Script {
-        script! {
-
-            { Fr::push_dec(a) }
-            { Fr::push_dec(b) }
-            { Fr::push_dec(c) }
-            { Fr::push_dec(z) }
-            { Fr::push_dec(zw) }
-            { Fr::push_dec(s1) }
-            { Fr::push_dec(s2) }
-            { Fr::push_dec(s3) }
-            { Fr::push_dec(t1w) }
-            { Fr::push_dec(t2w) }
-            // beta, y, xi, gamma, zhinv, L[1]
-            { Fr::copy(61)}
-            { Fr::copy(59)}
-            { Fr::copy(41)}
-            { Fr::copy(63)}
-            { Fr::copy(41)}
-            { Fr::copy(19)}
-            // todo push from stack
-            //{ Fr::push_dec("19264250262515049392118907974032894668050943806280011767302681470321758079402") }
-            //  H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, H3w3_2 (6 elements)
-            { Fr::copy(51)}
-            { Fr::copy(51)}
-            { Fr::copy(51)}
-            { Fr::copy(51)}
-            { Fr::copy(51)}
-            { Fr::copy(51)}
-            // LiS2Inv 1-6 (6 elements)
-            { Fr::copy(34)}
-            { Fr::copy(34)}
-            { Fr::copy(34)}
-            { Fr::copy(34)}
-            { Fr::copy(34)}
-            { Fr::copy(34)}
-
-            // compute num2 := y^3
-            { Fr::copy(6 + 6 + 4) }
-            { Fr::copy(0) }
-            { Fr::square() }
-            { Fr::mul() }
-
-            // compute num := num2^2 = y^6
-            { Fr::copy(0) }
-            { Fr::square() }
-
-            // compute xi * w1 + xi = xi * (w1 + 1)
-            { Fr::copy(6 + 6 + 3 + 2) }
-            // { Fr::push_dec("11699596668367776675346610687704220591435078791727316319397053191800576917728") }
-            { Fr::push_dec(w1) }
-            { Fr::push_one() }
-            { Fr::add(1, 0) }
-            { Fr::mul() }
-
-            // compute num2 := num2 * (xi * (w1 + 1))
-            { Fr::roll(2) }
-            { Fr::mul() }
-
-            // compute num := num - num2
-            { Fr::sub(1, 0) }
-
-            // compute xi^2 * w1
-            { Fr::copy(6 + 6 + 3 + 1) }
-            { Fr::square() }
-            // { Fr::push_dec("11699596668367776675346610687704220591435078791727316319397053191800576917728") }
-            { Fr::push_dec(w1) }
-            { Fr::mul() }
-
-            // compute num := num +  xi^2 * w1 and move to altstack
-            { Fr::add(1, 0) }
-            { Fr::toaltstack() }
-
-            // compute betaxi
-            { Fr::copy(6 + 6 + 5) }
-            { Fr::copy(6 + 6 + 3 + 1) }
-            { Fr::mul() }
-
-            // compute betaxi + gamma
-            { Fr::copy(0) }
-            { Fr::copy(6 + 6 + 2 + 2) }
-            { Fr::add(1, 0) }
-
-            // compute a + betaxi + gamma and send to altstack
-            { Fr::copy(6 + 6 + 6 + 9 + 2) }
-            { Fr::add(1, 0) }
-            { Fr::toaltstack() }
-
-            // compute betaxi * k1 + gamma for k1 = 2
-            { Fr::copy(0) }
-            { Fr::double(0) }
-            { Fr::copy(6 + 6 + 2 + 2) }
-            { Fr::add(1, 0) }
-
-            // compute b + betaxi * k1 + gamma and send to altstack
-            { Fr::copy(6 + 6 + 6 + 8 + 2) }
-            { Fr::add(1, 0) }
-            { Fr::toaltstack() }
+        // [ xi , Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8, LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2]
 
-            // compute betaxi * k2 + gamma for k2 = 3
+        // build up the accumulator
+        { Fr::copy(0) }
+        for i in 1..23 {
             { Fr::copy(0) }
-            { Fr::double(0) }
-            { Fr::add(1, 0) }
-            { Fr::copy(6 + 6 + 2 + 1) }
-            { Fr::add(1, 0) }
-
-            // compute c + betaxi * k2 + gamma and send to altstack
-            { Fr::copy(6 + 6 + 6 + 7 + 1) }
-            { Fr::add(1, 0) }
-
-            // compute t2 = (a + betaxi + gamma) * (b + betaxi * k1 + gamma) * (c + betaxi * k2 + gamma) * z
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::copy(6 + 6 + 6 + 6 + 1) }
-            { Fr::mul() }
-
-            // send t2 to the altstack
-            { Fr::toaltstack() }
-
-            // compute beta * s1 + gamma + a
-            { Fr::copy(6 + 6 + 5) }
-            { Fr::copy(6 + 6 + 6 + 4 + 1) }
-            { Fr::mul() }
-            { Fr::copy(6 + 6 + 2 + 1) }
-            { Fr::add(1, 0) }
-            { Fr::copy(6 + 6 + 6 + 9 + 1) }
-            { Fr::add(1, 0) }
-            { Fr::toaltstack() }
-
-            // compute beta * s2 + gamma + b
-            { Fr::copy(6 + 6 + 5) }
-            { Fr::copy(6 + 6 + 6 + 3 + 1) }
-            { Fr::mul() }
-            { Fr::copy(6 + 6 + 2 + 1) }
-            { Fr::add(1, 0) }
-            { Fr::copy(6 + 6 + 6 + 8 + 1) }
-            { Fr::add(1, 0) }
-            { Fr::toaltstack() }
-
-            // compute beta * s3 + gamma + c
-            { Fr::copy(6 + 6 + 5) }
-            { Fr::copy(6 + 6 + 6 + 2 + 1) }
-            { Fr::mul() }
-            { Fr::copy(6 + 6 + 2 + 1) }
-            { Fr::add(1, 0) }
-            { Fr::copy(6 + 6 + 6 + 7 + 1) }
-            { Fr::add(1, 0) }
-
-            // compute t2' = (beta * s1 + gamma + a) * (beta * s2 + gamma + b) * (beta * s3 + gamma + c) * zw
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::fromaltstack() }
-            { Fr::mul() }
-            { Fr::copy(6 + 6 + 6 + 5 + 1) }
-            { Fr::mul() }
-
-            // compute t2 := t2 - t2'
-            { Fr::fromaltstack() }
-            { Fr::sub(0, 1) }
-
-            // compute t2 := t2 * zhinv
-            { Fr::copy(6 + 6 + 1 + 1) }
+            // [ xi, Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+            // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, Li_2, Li_2]
+            { Fr::copy(i + 1 + i) }
             { Fr::mul() }
+            // [ xi, Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+            // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, Li_2, Li_2 * Li_1]
+        }
 
-            // send the updated t2 to the altstack
-            { Fr::toaltstack() }
-
-            // compute t1 = (z - 1) * L[1] * zhinv
-            { Fr::copy(6 + 6 + 6 + 6) }
-            { Fr::push_one() }
-            { Fr::sub(1, 0) }
-            { Fr::copy(6 + 6 + 1) }
+        // push the inv from the proof and verify the inv
+        { Fr::copy(0) }
+        { Fr::push_dec(inv) }
+        { Fr::copy(0) } { Fr::toaltstack() }
+        { Fr::mul() }
+        { Fr::is_one_keep_element(0) }
+        OP_VERIFY
+        { Fr::drop() } // is_one does not consume the input
+
+        // current stack:
+        //   inputs (Li_2 down to ZH)
+        //   accumulators (ZH down to prod of all)
+        // altstack:
+        //   inv
+
+        // compute the inverses now
+        { Fr::drop() }
+        { Fr::fromaltstack() }
+        // [ Z_H, prod_1, prod_2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+        // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, Li_2, Li_2 * Li_1, Li_2 * Li_1 * LiS3_3, ...
+        // inv ]
+
+        for i in 0..22 {
+            { Fr::copy(0) }
+            { Fr::roll(2) }
             { Fr::mul() }
-            { Fr::copy(6 + 6 + 1 + 1) }
+            { Fr::toaltstack() }
+            { Fr::roll(23 - 1 - i + 23 - 1 - i) }
             { Fr::mul() }
+        }
+        { Fr::roll(1) }
+        { Fr::drop() }
+        { Fr::roll(1) }
+        { Fr::drop() }
+        // [ZH | ..., LiS0_3, LiS0_2, LiS0_1, DenH2, DenH1]
 
-            // pull t2 from the altstack
+        for _ in 0..22 {
             { Fr::fromaltstack() }
+        }
+        // [..., xi, ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, ...]
+    }
+}
 
-            // the stack now looks:
-            //   10 + 6 + 6 + 6 Fr elements
-            //   t1
-            //   t2
-            // altstack: num
+/// compute lagranges
+// [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
+// ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+// LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2]
+fn compute_lagranges(w1: &str) -> Script {
+    script! {
+        // push zh
+        { Fr::copy(23) }
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+
+        // push the inverse of Li_1
+        { Fr::copy(2) }
+        { Fr::mul() }
+
+        // push the inverse of Li_2
+        { Fr::copy(1)}
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::push_dec(w1) }
+        { Fr::mul() }
+    }
+}
 
-            // pick H2w3_0, ..., H2w3_2 and compute the corresponding c2Value
-            for i in 0..3 {
-                { Fr::copy(2 + 6 + 5 - i) }
+/// compute pi {48 elements}
+// [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi,
+// ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+// LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2]
+fn compute_pi(input1: &str, input2: &str) -> Script {
+    script! {
+
+        { Fr::copy(1)}
+        { Fr::copy(1)}
+        { Fr::push_dec(input1) }
+        { Fr::push_dec(input2) }
+        { Fr::roll(2) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::add(1, 0) }
+        { Fr::neg(0) }
+    }
+}
 
-                { Fr::copy(0) } { Fr::square() }
-                { Fr::toaltstack() } { Fr::toaltstack() }
+/// Evaluations of the proof as decimal strings, as pushed by the r0, r1 and r2 scripts
+#[derive(Clone, Copy)]
+struct DecEvaluations<'a> {
+    ql: &'a str,
+    qr: &'a str,
+    qm: &'a str,
+    qo: &'a str,
+    qc: &'a str,
+    s1: &'a str,
+    s2: &'a str,
+    s3: &'a str,
+    a: &'a str,
+    b: &'a str,
+    c: &'a str,
+    z: &'a str,
+    zw: &'a str,
+    t1w: &'a str,
+    t2w: &'a str,
+}
 
-                // c2Value starts with z
-                { Fr::copy(2 + 6 + 6 + 6 + 6) }
-                { Fr::copy(1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-                { Fr::copy(1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+/// compute R0 {50 elements} ql, qr, qo, qm, qc, s1, s2, s3
+// [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0(37), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
+// ZH(25), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+// LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI]
+fn compute_r0(evaluations: &DecEvaluations) -> Script {
+    let DecEvaluations {
+        ql,
+        qr,
+        qo,
+        qm,
+        qc,
+        s1,
+        s2,
+        s3,
+        ..
+    } = *evaluations;
+    script! {
+        // push ql, qr, qo, qm, qc, s1, s2, s3
+        { Fr::push_dec(ql) }
+        { Fr::push_dec(qr) }
+        { Fr::push_dec(qo) }
+        { Fr::push_dec(qm) }
+        { Fr::push_dec(qc) }
+        { Fr::push_dec(s1) }
+        { Fr::push_dec(s2) }
+        { Fr::push_dec(s3) }
+
+        // push H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+        { Fr::copy(53) }
+
+        // push lis0_1_inv, ...
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+        { Fr::copy(38) }
+
+        // push y, xi
+        { Fr::copy(70) }
+        { Fr::copy(52) }
+
+        // compute num = y^8 - xi, push to altstack
+        { Fr::roll(1) }
+        { Fr::square() }
+        { Fr::square() }
+        { Fr::square() }
+        { Fr::sub(0, 1) }
+        { Fr::toaltstack() }
+        // [ql, qr, ...., H0w8_0, H0w8_1, ..., lis0_1_inv, lis0_2_inv, ... | num]
+
+        // pick H0w8_0, ..., H0w8_7 and compute the corresponding c0Value
+        for i in 0..8 {
+            { Fr::copy(8 + 7 - i) }
+
+            { Fr::copy(0) } { Fr::copy(1) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(2) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(3) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(4) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(5) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(6) } { Fr::mul() }
+            // H0w8_0, H0w8_0^2, H0w8_0^3, ...
 
-                // push this c2Value to the altstack
+            for _ in 0..7 {
                 { Fr::toaltstack() }
             }
 
-            // pick H3w3_0, ..., H3w3_2 and compute the corresponding c2Value
-            for i in 0..3 {
-                { Fr::copy(2 + 6 + 2 - i) }
+            // c0Value starts with ql
+            { Fr::copy(16 + 7) }
+            { Fr::copy(16 + 6 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 5 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 4 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 3 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 2 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(16 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
 
-                { Fr::copy(0) } { Fr::square() }
-                { Fr::toaltstack() } { Fr::toaltstack() }
+            // push this c0Value to the altstack
+            { Fr::toaltstack() }
+        }
 
-                // c2Value starts with zw
-                { Fr::copy(2 + 6 + 6 + 6 + 5) }
-                { Fr::copy(2 + 6 + 6 + 6 + 1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
-                { Fr::copy(2 + 6 + 6 + 6 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+        // get all the c0Values out
+        for _ in 0..8 {
+            { Fr::fromaltstack() }
+        }
 
-                // push this c2Value to the altstack
-                { Fr::toaltstack() }
-            }
+        // multiply the corresponding LiS0Inv
+        for i in 0..8 {
+            { Fr::roll(8 - i + 7 - i) }
+            { Fr::mul() }
+            { Fr::toaltstack() }
+        }
 
-            // get all the c1Values out
-            for _ in 0..6 {
-                { Fr::fromaltstack() }
-            }
+        // drop all the intermediate values
+        for _ in 0..16 {
+            { Fr::drop() }
+        }
+
+        // add all the c0Values together
+        { Fr::fromaltstack() }
+        for _ in 1..8 {
+            { Fr::fromaltstack() }
+            { Fr::add(1, 0) }
+        }
+
+        // multiply by the num
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+    }
+}
 
-            // multiply the corresponding LiS1Inv
-            for i in 0..6 {
-                { Fr::roll(6 - i + 2 + 5 - i) }
-                { Fr::mul() }
+/// compute R1 {51 elements} ql, qr, qo, qm, qc, a, b, c
+// [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0(38), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
+// ZH(26), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+// LiS1_1(15), LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI, r0]
+fn compute_r1(evaluations: &DecEvaluations) -> Script {
+    let DecEvaluations {
+        ql,
+        qr,
+        qm,
+        qo,
+        qc,
+        a,
+        b,
+        c,
+        ..
+    } = *evaluations;
+    script! {
+
+        { Fr::push_dec(ql) }
+        { Fr::push_dec(qr) }
+        { Fr::push_dec(qm) }
+        { Fr::push_dec(qo) }
+        { Fr::push_dec(qc) }
+        { Fr::push_dec(a) }
+        { Fr::push_dec(b) }
+        { Fr::push_dec(c) }
+        // pi, zh
+        { Fr::copy(9)}
+        { Fr::copy(35)}
+        // pH1w4_0->3
+        { Fr::copy(48)}
+        { Fr::copy(48)}
+        { Fr::copy(48)}
+        { Fr::copy(48)}
+        // LiS1_1 -> 4
+        { Fr::copy(29)}
+        { Fr::copy(29)}
+        { Fr::copy(29)}
+        { Fr::copy(29)}
+        // y, xi
+        { Fr::copy(65)}
+        { Fr::copy(47)}
+        // compute num = y^4 - xi, push to altstack
+        { Fr::roll(1) }
+        { Fr::square() }
+        { Fr::square() }
+        { Fr::sub(0, 1) }
+        { Fr::toaltstack() }
+
+        // compute t0
+
+        // ql * evalA
+        { Fr::copy(10 + 7) }
+        { Fr::copy(10 + 2 + 1) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+
+        // qr * evalB
+        { Fr::copy(10 + 6) }
+        { Fr::copy(10 + 1 + 1) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+
+        // qm * evalA * evalB
+        { Fr::copy(10 + 5) }
+        { Fr::copy(10 + 2 + 1) }
+        { Fr::mul() }
+        { Fr::copy(10 + 1 + 1) }
+        { Fr::mul() }
+        { Fr::toaltstack() }
+
+        // qo * evalC
+        { Fr::copy(10 + 4) }
+        { Fr::copy(10 + 1) }
+        { Fr::mul() }
+
+        // t0 := ql * evalA + qr * evalB + qm * evalA * evalB + qo * evalC + qc + pi
+        { Fr::fromaltstack() }
+        { Fr::add(1, 0) }
+        { Fr::fromaltstack() }
+        { Fr::add(1, 0) }
+        { Fr::fromaltstack() }
+        { Fr::add(1, 0) }
+        { Fr::copy(10 + 3 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::copy(8 + 1 + 1) }
+        { Fr::add(1, 0) }
+
+        // t0 := t0 * zhInv
+        { Fr::copy(8 + 1) }
+        { Fr::mul() }
+
+        // the stack should look like:
+        //    ql, qr, qm, qo, qc, a, b, c
+        //    pi, zhInv
+        //    H1w4_0, H1w4_1, H1w4_2, H1w4_3
+        //    LiS1Inv 1-4
+        //    t0
+        //
+        // altstack: num
+
+        // pick H1w4_0, ..., H1w4_3 and compute the corresponding c1Value
+        for i in 0..4 {
+            { Fr::copy(1 + 4 + 3 - i) }
+
+            { Fr::copy(0) } { Fr::copy(1) } { Fr::mul() }
+            { Fr::copy(0) } { Fr::copy(2) } { Fr::mul() }
+
+            for _ in 0..3 {
                 { Fr::toaltstack() }
             }
 
-            // drop all the intermediate values
-            for _ in 0..(2 + 6 + 6 + 10) {
-                { Fr::drop() }
-            }
+            // c1Value starts with a
+            { Fr::copy(1 + 4 + 4 + 2 + 2) }
+            { Fr::copy(1 + 4 + 4 + 2 + 1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(1 + 4 + 4 + 2 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
 
-            // add all the c0Values together
-            { Fr::fromaltstack() }
-            for _ in 1..6 {
-                { Fr::fromaltstack() }
-                { Fr::add(1, 0) }
-            }
+            // push this c1Value to the altstack
+            { Fr::toaltstack() }
+        }
 
-            // multiply by the num
+        // get all the c1Values out
+        for _ in 0..4 {
             { Fr::fromaltstack() }
-            { Fr::mul() }
         }
-    }
 
-    /// compute fej {53 elements}
-    // [beta(52), gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
-    // pH1w4_0(40), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
-    // ZH(28), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
-    // LiS1_1(17), LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI, r0, r1, r2]
-    fn compute_fej() -> Script {
-        script! {
-
-            // push alpha, denh1, denh2, y (4 elements)
-            { Fr::copy(50)}
-            { Fr::copy(28)}
-            { Fr::copy(28)}
-            { Fr::copy(52)}
-
-            // push R0, R1, R2 (3 elements)
-            { Fr::copy(6)}
-            { Fr::copy(6)}
-            { Fr::copy(6)}
-
-            // push H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 (8 elements)
-            { Fr::copy(55)}
-            { Fr::copy(55)}
-            { Fr::copy(55)}
-            { Fr::copy(55)}
-            { Fr::copy(55)}
-            { Fr::copy(55)}
-            { Fr::copy(55)}
-            { Fr::copy(55)}
-
-            // roll y
-            { Fr::roll(8 + 3) }
-
-            // compute numerator entries
-            for i in 0..8 {
-                { Fr::copy(0) }
-                { Fr::roll(7 - i + 2) }
-                { Fr::sub(1, 0) }
-                { Fr::toaltstack() }
-            }
+        // multiply the corresponding LiS1Inv
+        for i in 0..4 {
+            { Fr::roll(4 - i + 1 + 3 - i) }
+            { Fr::mul() }
+            { Fr::toaltstack() }
+        }
 
-            // drop y
+        // drop all the intermediate values
+        for _ in 0..(1 + 4 + 2 + 8) {
             { Fr::drop() }
+        }
 
-            // compute numerator
+        // add all the c0Values together
+        { Fr::fromaltstack() }
+        for _ in 1..4 {
             { Fr::fromaltstack() }
-            for _ in 0..7 {
-                { Fr::fromaltstack() }
-                { Fr::mul() }
-            }
+            { Fr::add(1, 0) }
+        }
 
-            // copy the numerator in the altstack
-            { Fr::copy(0) }
+        // multiply by the num
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+    }
+}
+
+/// compute R2 {52 elements} a, b, c, z, zw, s1, s2, s3, t1w, t2w
+// [beta(51), gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0(39), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
+// ZH(27), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+// LiS1_1(16), LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI, r0, r1]
+fn compute_r2(evaluations: &DecEvaluations, w1: &str) -> Script {
+    let DecEvaluations {
+        a,
+        b,
+        c,
+        z,
+        zw,
+        s1,
+        s2,
+        s3,
+        t1w,
+        t2w,
+        ..
+    } = *evaluations;
+    script! {
+
+        { Fr::push_dec(a) }
+        { Fr::push_dec(b) }
+        { Fr::push_dec(c) }
+        { Fr::push_dec(z) }
+        { Fr::push_dec(zw) }
+        { Fr::push_dec(s1) }
+        { Fr::push_dec(s2) }
+        { Fr::push_dec(s3) }
+        { Fr::push_dec(t1w) }
+        { Fr::push_dec(t2w) }
+        // beta, y, xi, gamma, zhinv, L[1]
+        { Fr::copy(61)}
+        { Fr::copy(59)}
+        { Fr::copy(41)}
+        { Fr::copy(63)}
+        { Fr::copy(41)}
+        { Fr::copy(19)}
+        // todo push from stack
+        //{ Fr::push_dec("19264250262515049392118907974032894668050943806280011767302681470321758079402") }
+        //  H2w3_0, H2w3_1, H2w3_2, H3w3_0, H3w3_1, H3w3_2 (6 elements)
+        { Fr::copy(51)}
+        { Fr::copy(51)}
+        { Fr::copy(51)}
+        { Fr::copy(51)}
+        { Fr::copy(51)}
+        { Fr::copy(51)}
+        // LiS2Inv 1-6 (6 elements)
+        { Fr::copy(34)}
+        { Fr::copy(34)}
+        { Fr::copy(34)}
+        { Fr::copy(34)}
+        { Fr::copy(34)}
+        { Fr::copy(34)}
+
+        // compute num2 := y^3
+        { Fr::copy(6 + 6 + 4) }
+        { Fr::copy(0) }
+        { Fr::square() }
+        { Fr::mul() }
+
+        // compute num := num2^2 = y^6
+        { Fr::copy(0) }
+        { Fr::square() }
+
+        // compute xi * w1 + xi = xi * (w1 + 1)
+        { Fr::copy(6 + 6 + 3 + 2) }
+        // { Fr::push_dec("11699596668367776675346610687704220591435078791727316319397053191800576917728") }
+        { Fr::push_dec(w1) }
+        { Fr::push_one() }
+        { Fr::add(1, 0) }
+        { Fr::mul() }
+
+        // compute num2 := num2 * (xi * (w1 + 1))
+        { Fr::roll(2) }
+        { Fr::mul() }
+
+        // compute num := num - num2
+        { Fr::sub(1, 0) }
+
+        // compute xi^2 * w1
+        { Fr::copy(6 + 6 + 3 + 1) }
+        { Fr::square() }
+        // { Fr::push_dec("11699596668367776675346610687704220591435078791727316319397053191800576917728") }
+        { Fr::push_dec(w1) }
+        { Fr::mul() }
+
+        // compute num := num +  xi^2 * w1 and move to altstack
+        { Fr::add(1, 0) }
+        { Fr::toaltstack() }
+
+        // compute betaxi
+        { Fr::copy(6 + 6 + 5) }
+        { Fr::copy(6 + 6 + 3 + 1) }
+        { Fr::mul() }
+
+        // compute betaxi + gamma
+        { Fr::copy(0) }
+        { Fr::copy(6 + 6 + 2 + 2) }
+        { Fr::add(1, 0) }
+
+        // compute a + betaxi + gamma and send to altstack
+        { Fr::copy(6 + 6 + 6 + 9 + 2) }
+        { Fr::add(1, 0) }
+        { Fr::toaltstack() }
+
+        // compute betaxi * k1 + gamma for k1 = 2
+        { Fr::copy(0) }
+        { Fr::double(0) }
+        { Fr::copy(6 + 6 + 2 + 2) }
+        { Fr::add(1, 0) }
+
+        // compute b + betaxi * k1 + gamma and send to altstack
+        { Fr::copy(6 + 6 + 6 + 8 + 2) }
+        { Fr::add(1, 0) }
+        { Fr::toaltstack() }
+
+        // compute betaxi * k2 + gamma for k2 = 3
+        { Fr::copy(0) }
+        { Fr::double(0) }
+        { Fr::add(1, 0) }
+        { Fr::copy(6 + 6 + 2 + 1) }
+        { Fr::add(1, 0) }
+
+        // compute c + betaxi * k2 + gamma and send to altstack
+        { Fr::copy(6 + 6 + 6 + 7 + 1) }
+        { Fr::add(1, 0) }
+
+        // compute t2 = (a + betaxi + gamma) * (b + betaxi * k1 + gamma) * (c + betaxi * k2 + gamma) * z
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 6 + 6 + 1) }
+        { Fr::mul() }
+
+        // send t2 to the altstack
+        { Fr::toaltstack() }
+
+        // compute beta * s1 + gamma + a
+        { Fr::copy(6 + 6 + 5) }
+        { Fr::copy(6 + 6 + 6 + 4 + 1) }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 2 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::copy(6 + 6 + 6 + 9 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::toaltstack() }
+
+        // compute beta * s2 + gamma + b
+        { Fr::copy(6 + 6 + 5) }
+        { Fr::copy(6 + 6 + 6 + 3 + 1) }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 2 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::copy(6 + 6 + 6 + 8 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::toaltstack() }
+
+        // compute beta * s3 + gamma + c
+        { Fr::copy(6 + 6 + 5) }
+        { Fr::copy(6 + 6 + 6 + 2 + 1) }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 2 + 1) }
+        { Fr::add(1, 0) }
+        { Fr::copy(6 + 6 + 6 + 7 + 1) }
+        { Fr::add(1, 0) }
+
+        // compute t2' = (beta * s1 + gamma + a) * (beta * s2 + gamma + b) * (beta * s3 + gamma + c) * zw
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 6 + 5 + 1) }
+        { Fr::mul() }
+
+        // compute t2 := t2 - t2'
+        { Fr::fromaltstack() }
+        { Fr::sub(0, 1) }
+
+        // compute t2 := t2 * zhinv
+        { Fr::copy(6 + 6 + 1 + 1) }
+        { Fr::mul() }
+
+        // send the updated t2 to the altstack
+        { Fr::toaltstack() }
+
+        // compute t1 = (z - 1) * L[1] * zhinv
+        { Fr::copy(6 + 6 + 6 + 6) }
+        { Fr::push_one() }
+        { Fr::sub(1, 0) }
+        { Fr::copy(6 + 6 + 1) }
+        { Fr::mul() }
+        { Fr::copy(6 + 6 + 1 + 1) }
+        { Fr::mul() }
+
+        // pull t2 from the altstack
+        { Fr::fromaltstack() }
+
+        // the stack now looks:
+        //   10 + 6 + 6 + 6 Fr elements
+        //   t1
+        //   t2
+        // altstack: num
+
+        // pick H2w3_0, ..., H2w3_2 and compute the corresponding c2Value
+        for i in 0..3 {
+            { Fr::copy(2 + 6 + 5 - i) }
+
+            { Fr::copy(0) } { Fr::square() }
+            { Fr::toaltstack() } { Fr::toaltstack() }
+
+            // c2Value starts with z
+            { Fr::copy(2 + 6 + 6 + 6 + 6) }
+            { Fr::copy(1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+
+            // push this c2Value to the altstack
             { Fr::toaltstack() }
+        }
 
-            // compute quotient1 = alpha * numerator * denh1
-            { Fr::copy(0) }
-            { Fr::copy(3 + 2 + 2) }
-            { Fr::mul() }
-            { Fr::roll(3 + 1 + 2) }
-            { Fr::mul() }
+        // pick H3w3_0, ..., H3w3_2 and compute the corresponding c2Value
+        for i in 0..3 {
+            { Fr::copy(2 + 6 + 2 - i) }
 
-            // compute quotient2 = alpha * alpha * numerator * denh2
-            { Fr::roll(1) }
-            { Fr::roll(3 + 2) }
-            { Fr::mul() }
-            { Fr::roll(3 + 2) }
-            { Fr::square() }
-            { Fr::mul() }
+            { Fr::copy(0) } { Fr::square() }
+            { Fr::toaltstack() } { Fr::toaltstack() }
 
-            // the stack now looks:
-            //    R0, R1, R2
-            //    quotient1, quotient2
-            // altstack: numerator
+            // c2Value starts with zw
+            { Fr::copy(2 + 6 + 6 + 6 + 5) }
+            { Fr::copy(2 + 6 + 6 + 6 + 1 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
+            { Fr::copy(2 + 6 + 6 + 6 + 1) } { Fr::fromaltstack() } { Fr::mul() } { Fr::add(1, 0) }
 
-            // compute the scalar = R0 + quotient1 * R1 + quotient2 * R2
-            { Fr::copy(1) }
-            { Fr::roll(2 + 1 + 1) }
-            { Fr::mul() }
-            { Fr::copy(1) }
-            { Fr::roll(2 + 2) }
-            { Fr::mul() }
-            { Fr::add(1, 0) }
-            { Fr::roll(2 + 1) }
-            { Fr::add(1, 0) }
+            // push this c2Value to the altstack
+            { Fr::toaltstack() }
+        }
 
+        // get all the c1Values out
+        for _ in 0..6 {
             { Fr::fromaltstack() }
+        }
 
-            // Drop useless elements, only reserve y
-            // ... ] } [scalar_j, scalar_e, scalar_f2, scalar_f1]
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
-            // ... y ] } [scalar_j, scalar_e, scalar_f2, scalar_f1]
-            { Fr::copy(49) }
-
-            // ... ] } [scalar_j, scalar_e, scalar_f2, scalar_f1, y]
+        // multiply the corresponding LiS1Inv
+        for i in 0..6 {
+            { Fr::roll(6 - i + 2 + 5 - i) }
+            { Fr::mul() }
             { Fr::toaltstack() }
+        }
 
-            for _ in 0..53 {
-
-                {Fr::drop()}
-            }
+        // drop all the intermediate values
+        for _ in 0..(2 + 6 + 6 + 10) {
+            { Fr::drop() }
+        }
 
-            // [ y, scalar_f1, scalar_f2, scalar_e, scalar_j ] }
-            { Fr::fromaltstack() }
-            { Fr::fromaltstack() }
-            { Fr::fromaltstack() }
-            { Fr::fromaltstack() }
+        // add all the c0Values together
+        { Fr::fromaltstack() }
+        for _ in 1..6 {
             { Fr::fromaltstack() }
+            { Fr::add(1, 0) }
         }
-    }
 
-    /// compute f (5)
-    //[ y scalar_f1, scalar_f2, scalar_e, scalar_j]
-    fn compute_f(
-        c0x: &str,
-        c0y: &str,
-        c0z: &str,
-        c1x: &str,
-        c1y: &str,
-        c1z: &str,
-        c2x: &str,
-        c2y: &str,
-        c2z: &str,
-    ) -> Script {
-        script! {
-
-            // push quotient1, quotient2 (2 elements)
-            { Fr::copy(3)}
-            { Fr::copy(3)}
+        // multiply by the num
+        { Fr::fromaltstack() }
+        { Fr::mul() }
+    }
+}
 
+/// compute fej {53 elements}
+// [beta(52), gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
+// pH1w4_0(40), pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
+// ZH(28), DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
+// LiS1_1(17), LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, L1, L2, PI, r0, r1, r2]
+fn compute_fej() -> Script {
+    script! {
+
+        // push alpha, denh1, denh2, y (4 elements)
+        { Fr::copy(50)}
+        { Fr::copy(28)}
+        { Fr::copy(28)}
+        { Fr::copy(52)}
+
+        // push R0, R1, R2 (3 elements)
+        { Fr::copy(6)}
+        { Fr::copy(6)}
+        { Fr::copy(6)}
+
+        // push H0w8_0, H0w8_1, H0w8_2, H0w8_3, H0w8_4, H0w8_5, H0w8_6, H0w8_7 (8 elements)
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+        { Fr::copy(55)}
+
+        // roll y
+        { Fr::roll(8 + 3) }
+
+        // compute numerator entries
+        for i in 0..8 {
+            { Fr::copy(0) }
+            { Fr::roll(7 - i + 2) }
+            { Fr::sub(1, 0) }
             { Fr::toaltstack() }
-            { Fr::toaltstack() }
+        }
 
-            // push (C0x, C0y), C1, C2 (9 elements)
-            { Fq::push_dec(c0x)}
-            { Fq::push_dec(c0y)}
-            { Fq::push_dec(c0z)}
-            { Fq::push_dec(c1x)}
-            { Fq::push_dec(c1y)}
-            { Fq::push_dec(c1z)}
-            { Fq::push_dec(c2x)}
-            { Fq::push_dec(c2y)}
-            { Fq::push_dec(c2z)}
-
-            { G1Projective::roll(1) }
-            { Fr::fromaltstack() }
-            { G1Projective::scalar_mul() }
+        // drop y
+        { Fr::drop() }
 
-            { G1Projective::roll(1) }
+        // compute numerator
+        { Fr::fromaltstack() }
+        for _ in 0..7 {
             { Fr::fromaltstack() }
-            { G1Projective::scalar_mul() }
+            { Fr::mul() }
+        }
 
-            { G1Projective::add() }
-            { G1Projective::add() }
+        // copy the numerator in the altstack
+        { Fr::copy(0) }
+        { Fr::toaltstack() }
+
+        // compute quotient1 = alpha * numerator * denh1
+        { Fr::copy(0) }
+        { Fr::copy(3 + 2 + 2) }
+        { Fr::mul() }
+        { Fr::roll(3 + 1 + 2) }
+        { Fr::mul() }
+
+        // compute quotient2 = alpha * alpha * numerator * denh2
+        { Fr::roll(1) }
+        { Fr::roll(3 + 2) }
+        { Fr::mul() }
+        { Fr::roll(3 + 2) }
+        { Fr::square() }
+        { Fr::mul() }
+
+        // the stack now looks:
+        //    R0, R1, R2
+        //    quotient1, quotient2
+        // altstack: numerator
+
+        // compute the scalar = R0 + quotient1 * R1 + quotient2 * R2
+        { Fr::copy(1) }
+        { Fr::roll(2 + 1 + 1) }
+        { Fr::mul() }
+        { Fr::copy(1) }
+        { Fr::roll(2 + 2) }
+        { Fr::mul() }
+        { Fr::add(1, 0) }
+        { Fr::roll(2 + 1) }
+        { Fr::add(1, 0) }
+
+        { Fr::fromaltstack() }
+
+        // Drop useless elements, only reserve y
+        // ... ] } [scalar_j, scalar_e, scalar_f2, scalar_f1]
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+        // ... y ] } [scalar_j, scalar_e, scalar_f2, scalar_f1]
+        { Fr::copy(49) }
+
+        // ... ] } [scalar_j, scalar_e, scalar_f2, scalar_f1, y]
+        { Fr::toaltstack() }
+
+        for _ in 0..53 {
 
+            {Fr::drop()}
         }
+
+        // [ y, scalar_f1, scalar_f2, scalar_e, scalar_j ] }
+        { Fr::fromaltstack() }
+        { Fr::fromaltstack() }
+        { Fr::fromaltstack() }
+        { Fr::fromaltstack() }
+        { Fr::fromaltstack() }
     }
+}
 
-    /// compute f (5)
-    //[ y scalar_f1, scalar_f2, scalar_e, scalar_j]
-    fn compute_f_opt(
-        c0x: &str,
-        c0y: &str,
-        c0z: &str,
-        c1x: &str,
-        c1y: &str,
-        c1z: &str,
-        c2x: &str,
-        c2y: &str,
-        c2z: &str,
-    ) -> Script {
-        script! {
-
-            // push quotient1, quotient2 (2 elements)
-            { Fr::copy(3)}
-            { Fr::copy(3)}
+/// compute f (5), the commitments are given as projective [x, y, z] decimal strings
+//[ y scalar_f1, scalar_f2, scalar_e, scalar_j]
+fn compute_f_opt(c0: [&str; 3], c1: [&str; 3], c2: [&str; 3]) -> Script {
+    let ([c0x, c0y, c0z], [c1x, c1y, c1z], [c2x, c2y, c2z]) = (c0, c1, c2);
+    script! {
+
+        // push quotient1, quotient2 (2 elements)
+        { Fr::copy(3)}
+        { Fr::copy(3)}
+
+        { Fr::toaltstack() }
+        { Fr::toaltstack() }
+
+        // push (C0x, C0y), C1, C2 (9 elements)
+        { Fq::push_dec(c0x)}
+        { Fq::push_dec(c0y)}
+        { Fq::push_dec(c0z)}
+        { Fq::push_dec(c1x)}
+        { Fq::push_dec(c1y)}
+        { Fq::push_dec(c1z)}
+        { Fq::push_dec(c2x)}
+        { Fq::push_dec(c2y)}
+        { Fq::push_dec(c2z)}
+
+        { G1Projective::roll(1) } // [c0, c2, c1, q1; q2]
+        { Fr::fromaltstack() }
+        { Fq::roll(6)} {Fq::roll(6)} {Fq::roll(6)} // [c0, c1, q1, c2]
+        { Fr::fromaltstack() } // [c0, c1, q1, c2, q2]
+        { G1Projective::batched_scalar_mul::<2>() }
+        { G1Projective::add() }
 
-            { Fr::toaltstack() }
-            { Fr::toaltstack() }
+    }
+}
 
-            // push (C0x, C0y), C1, C2 (9 elements)
-            { Fq::push_dec(c0x)}
-            { Fq::push_dec(c0y)}
-            { Fq::push_dec(c0z)}
-            { Fq::push_dec(c1x)}
-            { Fq::push_dec(c1y)}
-            { Fq::push_dec(c1z)}
-            { Fq::push_dec(c2x)}
-            { Fq::push_dec(c2y)}
-            { Fq::push_dec(c2z)}
-
-            { G1Projective::roll(1) } // [c0, c2, c1, q1; q2]
-            { Fr::fromaltstack() }
-            { Fq::roll(6)} {Fq::roll(6)} {Fq::roll(6)} // [c0, c1, q1, c2]
-            { Fr::fromaltstack() } // [c0, c1, q1, c2, q2]
-            { G1Projective::batched_scalar_mul::<2>() }
-            { G1Projective::add() }
+// todo
+/// fflonk_pairing_with_c_wi
+// compute j (60)
+// stack input: [A1.x, A1.y]
+fn fflonk_pairing_with_c_wi(
+    w2: ark_bn254::g1::G1Affine,
+    c: ark_bn254::Fq12,
+    c_inv: ark_bn254::Fq12,
+    wi: ark_bn254::Fq12,
+    constant_1: &G2Prepared,
+    constant_2: &G2Prepared,
+) -> Script {
+    script! {
+        // [A1.x, A1.y]
+        { utils::from_eval_point_in_stack() }
+        // [A1.x', A1.y'] = [-A1.x/A1.y, 1/A1.y]
+        { utils::from_eval_point(w2) }
+        // [w2.x', w2.y'] = [-w2.x/w2.y, 1/w2.y]
+        // [A1.x', A1.y', w2.x', w2.y']
+        { fq12_push(c) }
+        { fq12_push(c_inv) }
+        { fq12_push(wi) }
+        // [A1.x', A1.y', w2.x', w2.y', c, c_inv, wi]
+        { Pairing::dual_miller_loop_with_c_wi(constant_1, constant_2, true) }
+    }
+}
 
+// FFLONK verifying key, see `verification_key.json` exported by snarkjs
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifyingKey {
+    pub n_public: usize,
+    pub power: u32,
+    pub k1: ark_bn254::Fr,
+    pub k2: ark_bn254::Fr,
+    pub w: ark_bn254::Fr,
+    pub w3: ark_bn254::Fr,
+    pub w4: ark_bn254::Fr,
+    pub w8: ark_bn254::Fr,
+    pub wr: ark_bn254::Fr,
+    pub x_2: ark_bn254::G2Affine,
+    pub c0: ark_bn254::G1Affine,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Evaluations {
+    pub ql: ark_bn254::Fr,
+    pub qr: ark_bn254::Fr,
+    pub qm: ark_bn254::Fr,
+    pub qo: ark_bn254::Fr,
+    pub qc: ark_bn254::Fr,
+    pub s1: ark_bn254::Fr,
+    pub s2: ark_bn254::Fr,
+    pub s3: ark_bn254::Fr,
+    pub a: ark_bn254::Fr,
+    pub b: ark_bn254::Fr,
+    pub c: ark_bn254::Fr,
+    pub z: ark_bn254::Fr,
+    pub zw: ark_bn254::Fr,
+    pub t1w: ark_bn254::Fr,
+    pub t2w: ark_bn254::Fr,
+    pub inv: ark_bn254::Fr,
+}
+
+// FFLONK proof, see `proof.json` exported by snarkjs
+#[derive(Clone, Debug, PartialEq)]
+pub struct Proof {
+    pub c1: ark_bn254::G1Affine,
+    pub c2: ark_bn254::G1Affine,
+    pub w1: ark_bn254::G1Affine,
+    pub w2: ark_bn254::G1Affine,
+    pub evaluations: Evaluations,
+}
+
+#[derive(Deserialize)]
+struct SnarkjsVerifyingKey {
+    protocol: String,
+    #[serde(rename = "nPublic")]
+    n_public: usize,
+    power: u32,
+    k1: String,
+    k2: String,
+    w: String,
+    w3: String,
+    w4: String,
+    w8: String,
+    wr: String,
+    #[serde(rename = "X_2")]
+    x_2: Vec<Vec<String>>,
+    #[serde(rename = "C0")]
+    c0: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SnarkjsPolynomials {
+    #[serde(rename = "C1")]
+    c1: Vec<String>,
+    #[serde(rename = "C2")]
+    c2: Vec<String>,
+    #[serde(rename = "W1")]
+    w1: Vec<String>,
+    #[serde(rename = "W2")]
+    w2: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SnarkjsEvaluations {
+    ql: String,
+    qr: String,
+    qm: String,
+    qo: String,
+    qc: String,
+    s1: String,
+    s2: String,
+    s3: String,
+    a: String,
+    b: String,
+    c: String,
+    z: String,
+    zw: String,
+    t1w: String,
+    t2w: String,
+    inv: String,
+}
+
+#[derive(Deserialize)]
+struct SnarkjsProof {
+    polynomials: SnarkjsPolynomials,
+    evaluations: SnarkjsEvaluations,
+    protocol: String,
+}
+
+impl VerifyingKey {
+    pub fn from_snarkjs_json(json: &str) -> Result<Self, String> {
+        let vk: SnarkjsVerifyingKey = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if vk.protocol != "fflonk" {
+            return Err(format!("unsupported protocol: {}", vk.protocol));
         }
+        Ok(VerifyingKey {
+            n_public: vk.n_public,
+            power: vk.power,
+            k1: parse_dec(&vk.k1)?,
+            k2: parse_dec(&vk.k2)?,
+            w: parse_dec(&vk.w)?,
+            w3: parse_dec(&vk.w3)?,
+            w4: parse_dec(&vk.w4)?,
+            w8: parse_dec(&vk.w8)?,
+            wr: parse_dec(&vk.wr)?,
+            x_2: snarkjs_g2(&vk.x_2, "X_2")?,
+            c0: snarkjs_g1(&vk.c0, "C0")?,
+        })
     }
+}
 
-    /// compute e (6)
-    // [y, scalar_f1, scalar_f2, scalar_e, scalar_j, f.x, f.y, f.z]
-    fn compute_e(g1x: &str, g1y: &str, g1z: &str) -> Script {
-        script! {
+impl Proof {
+    pub fn from_snarkjs_json(json: &str) -> Result<Self, String> {
+        let proof: SnarkjsProof = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if proof.protocol != "fflonk" {
+            return Err(format!("unsupported protocol: {}", proof.protocol));
+        }
+        let (p, e) = (proof.polynomials, proof.evaluations);
+        Ok(Proof {
+            c1: snarkjs_g1(&p.c1, "C1")?,
+            c2: snarkjs_g1(&p.c2, "C2")?,
+            w1: snarkjs_g1(&p.w1, "W1")?,
+            w2: snarkjs_g1(&p.w2, "W2")?,
+            evaluations: Evaluations {
+                ql: parse_dec(&e.ql)?,
+                qr: parse_dec(&e.qr)?,
+                qm: parse_dec(&e.qm)?,
+                qo: parse_dec(&e.qo)?,
+                qc: parse_dec(&e.qc)?,
+                s1: parse_dec(&e.s1)?,
+                s2: parse_dec(&e.s2)?,
+                s3: parse_dec(&e.s3)?,
+                a: parse_dec(&e.a)?,
+                b: parse_dec(&e.b)?,
+                c: parse_dec(&e.c)?,
+                z: parse_dec(&e.z)?,
+                zw: parse_dec(&e.zw)?,
+                t1w: parse_dec(&e.t1w)?,
+                t2w: parse_dec(&e.t2w)?,
+                inv: parse_dec(&e.inv)?,
+            },
+        })
+    }
+}
 
-            { Fq::toaltstack() }
-            { Fq::toaltstack() }
-            { Fq::toaltstack() }
+// Off-chain hints of the final pairing check e(A1, G2) * e(W2, -X_2) = 1
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hints {
+    // A1 = F - E - J + y * W2, recomputed in script
+    pub a1: ark_bn254::G1Affine,
+    pub c: ark_bn254::Fq12,
+    pub c_inv: ark_bn254::Fq12,
+    pub wi: ark_bn254::Fq12,
+    // c^{p^3}, the expected output of the miller loop with c and wi
+    pub hint: ark_bn254::Fq12,
+}
 
-            // push the scalar
-            { Fr::copy(1)}
-            { Fr::toaltstack()}
+fn dec<F: PrimeField>(x: F) -> String {
+    let n: BigUint = x.into();
+    n.to_string()
+}
 
-            { Fq::push_dec(g1x) }
-            { Fq::push_dec(g1y) }
-            { Fq::push_dec(g1z) }
+// Lagrange basis over `points`, evaluated at `y`
+fn lagrange_at(points: &[ark_bn254::Fr], y: ark_bn254::Fr) -> Vec<ark_bn254::Fr> {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, xi)| {
+            let (mut num, mut den) = (ark_bn254::Fr::one(), ark_bn254::Fr::one());
+            for (j, xj) in points.iter().enumerate() {
+                if i != j {
+                    num *= y - xj;
+                    den *= *xi - xj;
+                }
+            }
+            num / den
+        })
+        .collect()
+}
 
-            // [y, scalar_f1, scalar_f2, scalar_e, scalar_j, e.x, e.y, e.z ] | [f.z, f.y, f.x]
-            { Fr::fromaltstack() }
-            { G1Projective::scalar_mul() }
+#[derive(Clone, Copy, Debug)]
+pub struct Verifier;
+
+impl Verifier {
+    // beta, gamma, xiseed, alpha, y
+    fn compute_challenges(
+        vk: &VerifyingKey,
+        proof: &Proof,
+        public_inputs: &[ark_bn254::Fr],
+    ) -> [ark_bn254::Fr; 5] {
+        let ev = &proof.evaluations;
+
+        // The transcript of snarkjs, hashed with blake3
+        let mut transcript = NativeTranscript::<Blake3>::new();
+        transcript.absorb_g1(vk.c0);
+        for input in public_inputs.iter() {
+            transcript.absorb_fr(*input);
+        }
+        transcript.absorb_g1(proof.c1);
+        let beta = transcript.squeeze_challenge();
+
+        transcript.absorb_fr(beta);
+        let gamma = transcript.squeeze_challenge();
+
+        transcript.absorb_fr(gamma);
+        transcript.absorb_g1(proof.c2);
+        let xiseed = transcript.squeeze_challenge();
+
+        transcript.absorb_fr(xiseed);
+        for eval in [
+            ev.ql, ev.qr, ev.qm, ev.qo, ev.qc, ev.s1, ev.s2, ev.s3, ev.a, ev.b, ev.c, ev.z, ev.zw,
+            ev.t1w, ev.t2w,
+        ] {
+            transcript.absorb_fr(eval);
         }
+        let alpha = transcript.squeeze_challenge();
+
+        transcript.absorb_fr(alpha);
+        transcript.absorb_g1(proof.w1);
+        let y = transcript.squeeze_challenge();
+
+        [beta, gamma, xiseed, alpha, y]
     }
 
-    /// compute j (11)
-    /// [ y, scalar_f1, scalar_f2, scalar_e, scalar_j, e.x, e.y, e.z] | [f.z, f.y, f.x]
-    fn compute_j(w1x: &str, w1y: &str) -> Script {
-        script! {
-            // to alt stack: | [f.z, f.y, f.z, e.z, e.y, e.z ]
-            { Fq::toaltstack() }
-            { Fq::toaltstack() }
-            { Fq::toaltstack() }
+    // A1 = F - E - J + y * W2, mirrors the verifier of snarkjs
+    pub fn compute_a1(
+        vk: &VerifyingKey,
+        proof: &Proof,
+        public_inputs: &[ark_bn254::Fr],
+    ) -> ark_bn254::G1Affine {
+        let ev = &proof.evaluations;
+        let [beta, gamma, xiseed, alpha, y] = Self::compute_challenges(vk, proof, public_inputs);
+
+        // roots
+        let h0 = xiseed.pow([3_u64]);
+        let h0w8: Vec<_> = (0..8_u64).map(|i| h0 * vk.w8.pow([i])).collect();
+        let h1 = h0.square();
+        let h1w4: Vec<_> = (0..4_u64).map(|i| h1 * vk.w4.pow([i])).collect();
+        let h2 = h1 * xiseed.square();
+        let h2w3: Vec<_> = (0..3_u64).map(|i| h2 * vk.w3.pow([i])).collect();
+        let h3 = h2 * vk.wr;
+        let h3w3: Vec<_> = (0..3_u64).map(|i| h3 * vk.w3.pow([i])).collect();
+        let xi = h2.pow([3_u64]);
+
+        // vanishing polynomial, lagrange polynomials and public input polynomial at xi
+        let n = ark_bn254::Fr::from(1_u64 << vk.power);
+        let zh = xi.pow([1_u64 << vk.power]) - ark_bn254::Fr::one();
+        let zh_inv = zh.inverse().unwrap();
+        let l: Vec<_> = (0..public_inputs.len().max(1) as u64)
+            .map(|i| {
+                let wi = vk.w.pow([i]);
+                wi * zh / (n * (xi - wi))
+            })
+            .collect();
+        let pi = -public_inputs
+            .iter()
+            .zip(l.iter())
+            .map(|(input, li)| *input * li)
+            .sum::<ark_bn254::Fr>();
+
+        // r0, r1, r2 interpolate C0, C1, C2 at the opening points
+        let li_s0 = lagrange_at(&h0w8, y);
+        let r0 = (0..8)
+            .map(|i| {
+                let h = h0w8[i];
+                let c0 = [ev.ql, ev.qr, ev.qo, ev.qm, ev.qc, ev.s1, ev.s2, ev.s3]
+                    .iter()
+                    .rev()
+                    .fold(ark_bn254::Fr::zero(), |acc, coeff| acc * h + coeff);
+                c0 * li_s0[i]
+            })
+            .sum::<ark_bn254::Fr>();
+
+        let t0 = (ev.ql * ev.a + ev.qr * ev.b + ev.qm * ev.a * ev.b + ev.qo * ev.c + ev.qc + pi)
+            * zh_inv;
+        let li_s1 = lagrange_at(&h1w4, y);
+        let r1 = (0..4)
+            .map(|i| {
+                let h = h1w4[i];
+                (ev.a + h * ev.b + h.square() * ev.c + h.pow([3_u64]) * t0) * li_s1[i]
+            })
+            .sum::<ark_bn254::Fr>();
+
+        let betaxi = beta * xi;
+        let num = (ev.a + betaxi + gamma)
+            * (ev.b + betaxi * vk.k1 + gamma)
+            * (ev.c + betaxi * vk.k2 + gamma)
+            * ev.z;
+        let den = (ev.a + beta * ev.s1 + gamma)
+            * (ev.b + beta * ev.s2 + gamma)
+            * (ev.c + beta * ev.s3 + gamma)
+            * ev.zw;
+        let t1 = (ev.z - ark_bn254::Fr::one()) * l[0] * zh_inv;
+        let t2 = (num - den) * zh_inv;
+        let h23: Vec<_> = h2w3.iter().chain(h3w3.iter()).cloned().collect();
+        let li_s2 = lagrange_at(&h23, y);
+        let r2 = (0..3)
+            .map(|i| {
+                let h = h2w3[i];
+                (ev.z + h * t1 + h.square() * t2) * li_s2[i]
+            })
+            .chain((0..3).map(|i| {
+                let h = h3w3[i];
+                (ev.zw + h * ev.t1w + h.square() * ev.t2w) * li_s2[i + 3]
+            }))
+            .sum::<ark_bn254::Fr>();
+
+        // F, E, J
+        let mul_h0 = h0w8.iter().map(|h| y - h).product::<ark_bn254::Fr>();
+        let mul_h1 = h1w4.iter().map(|h| y - h).product::<ark_bn254::Fr>();
+        let mul_h2 = h23.iter().map(|h| y - h).product::<ark_bn254::Fr>();
+        let quotient1 = alpha * mul_h0 / mul_h1;
+        let quotient2 = alpha.square() * mul_h0 / mul_h2;
+
+        let f = vk.c0.into_group() + proof.c1 * quotient1 + proof.c2 * quotient2;
+        let e = ark_bn254::G1Affine::generator() * (r0 + r1 * quotient1 + r2 * quotient2);
+        let j = proof.w1 * mul_h0;
+        (f - e - j + proof.w2 * y).into_affine()
+    }
 
-            // push the scalar
-            { Fr::toaltstack() }
-            // push G1x, G1y (3 elements)
-            { Fq::push_dec(w1x) }
-            { Fq::push_dec(w1y) }
-            { Fq::push_dec("1") }
-            { Fr::fromaltstack() }
-            { G1Projective::scalar_mul() }
-            // [y, scalar_f1, scalar_f2, scalar_e, j.x, j.y, j.z ] | [f.z, f.y, f.x, e.z, e.y, e.x]
+    pub fn compute_hints(
+        vk: &VerifyingKey,
+        proof: &Proof,
+        public_inputs: &[ark_bn254::Fr],
+    ) -> Hints {
+        let (exp, sign) = if LAMBDA.gt(&P_POW3) {
+            (&*LAMBDA - &*P_POW3, true)
+        } else {
+            (&*P_POW3 - &*LAMBDA, false)
+        };
+
+        let a1 = Self::compute_a1(vk, proof, public_inputs);
+        let f = ark_bn254::Bn254::multi_miller_loop_affine(
+            [a1, proof.w2],
+            [ark_bn254::G2Affine::generator(), -vk.x_2],
+        )
+        .0;
+        let (c, wi) = compute_c_wi(f);
+        let c_inv = c.inverse().unwrap();
+        let hint = if sign {
+            f * wi * (c_inv.pow(exp.to_u64_digits()))
+        } else {
+            f * wi * (c_inv.pow(exp.to_u64_digits()).inverse().unwrap())
+        };
+        assert_eq!(hint, c.pow(P_POW3.to_u64_digits()), "hint isn't correct!");
+
+        Hints {
+            a1,
+            c,
+            c_inv,
+            wi,
+            hint,
         }
     }
 
-    /// verify pairings
-    /// compute j (14)
-    // [y, scalar_f1, scalar_f2, scalar_e, j.x, j.y, j.z ] | [f.z, f.y, f.x, e.z, e.y, e.x]
-    fn checkpairing_a1(proof_w2x: &str, proof_w2y: &str) -> Script {
-        script! {
-            // ] | [f.z, f.y, f.x, e.z, e.y, e.x, j.z, j.y, j.x]
+    // The script inlines the verifying key, the proof and the public inputs, the hints are
+    // returned alongside so they can be checked or reused off-chain
+    //
+    // Only circuits with two public inputs and the default snarkjs cosets (k1 = 2, k2 = 3) are
+    // supported, the stack layout of the script depends on both, other keys are rejected
+    pub fn verify_proof(
+        vk: &VerifyingKey,
+        proof: &Proof,
+        public_inputs: &[ark_bn254::Fr],
+    ) -> Result<(Script, Hints), String> {
+        if vk.n_public != 2 {
+            return Err(format!(
+                "unsupported verifying key: {} public inputs, only 2 are supported",
+                vk.n_public
+            ));
+        }
+        if public_inputs.len() != vk.n_public {
+            return Err(format!(
+                "wrong number of public inputs: {}, expected {}",
+                public_inputs.len(),
+                vk.n_public
+            ));
+        }
+        if vk.k1 != ark_bn254::Fr::from(2_u64) || vk.k2 != ark_bn254::Fr::from(3_u64) {
+            return Err(format!(
+                "unsupported verifying key: k1 = {}, k2 = {}, only k1 = 2 and k2 = 3 are supported",
+                dec(vk.k1),
+                dec(vk.k2)
+            ));
+        }
+
+        let hints = Self::compute_hints(vk, proof, public_inputs);
+
+        let e = &proof.evaluations;
+        let (c0, c1, c2, w1, w2) = (vk.c0, proof.c1, proof.c2, proof.w1, proof.w2);
+        let (inp_1, inp_2) = (dec(public_inputs[0]), dec(public_inputs[1]));
+        let w8: Vec<_> = (1..8_u64).map(|i| dec(vk.w8.pow([i]))).collect();
+        let (w3, w3_2) = (dec(vk.w3), dec(vk.w3.square()));
+        let (w4, w4_2, w4_3) = (dec(vk.w4), dec(vk.w4.square()), dec(vk.w4.pow([3_u64])));
+        let (w, wr, n) = (dec(vk.w), dec(vk.wr), (1_u64 << vk.power).to_string());
+        let (ql, qr, qm, qo, qc) = (dec(e.ql), dec(e.qr), dec(e.qm), dec(e.qo), dec(e.qc));
+        let (s1, s2, s3) = (dec(e.s1), dec(e.s2), dec(e.s3));
+        let (a, b, c, z, zw) = (dec(e.a), dec(e.b), dec(e.c), dec(e.z), dec(e.zw));
+        let (t1w, t2w, inv) = (dec(e.t1w), dec(e.t2w), dec(e.inv));
+        let (c0_x, c0_y, c1_x, c1_y) = (dec(c0.x), dec(c0.y), dec(c1.x), dec(c1.y));
+        let (c2_x, c2_y) = (dec(c2.x), dec(c2.y));
+        let roots = DecRoots {
+            w8: [&w8[0], &w8[1], &w8[2], &w8[3], &w8[4], &w8[5], &w8[6]],
+            w3: &w3,
+            w3_2: &w3_2,
+            w4: &w4,
+            w4_2: &w4_2,
+            w4_3: &w4_3,
+            wr: &wr,
+        };
+        let evaluations = DecEvaluations {
+            ql: &ql,
+            qr: &qr,
+            qm: &qm,
+            qo: &qo,
+            qc: &qc,
+            s1: &s1,
+            s2: &s2,
+            s3: &s3,
+            a: &a,
+            b: &b,
+            c: &c,
+            z: &z,
+            zw: &zw,
+            t1w: &t1w,
+            t2w: &t2w,
+        };
+
+        let q0_prepared = G2Prepared::from_affine(ark_bn254::G2Affine::generator());
+        let q1_prepared = G2Prepared::from_affine(-vk.x_2);
+
+        let script = script! {
+            { compute_challenges(c0, proof, public_inputs) }
+            { compute_challenges_xin(&roots, vk.power) }
+            // [beta, gamma, alpha, y, pH0w8_0, ..., pH0w8_7, pH1w4_0, ..., pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH3w3_2, xi, zh]
+
+            { compute_inversions(&w, &inv, &n) }
+            { compute_lagranges(&w) }
+            { compute_pi(&inp_1, &inp_2) }
+            { compute_r0(&evaluations) }
+            { compute_r1(&evaluations) }
+            { compute_r2(&evaluations, &w) }
+            { compute_fej() }
+            { compute_f_opt([&c0_x, &c0_y, "1"], [&c1_x, &c1_y, "1"], [&c2_x, &c2_y, "1"]) }
+            // [y, scalar_f1, scalar_f2, scalar_e, scalar_j, F]
+
+            // save F
             { Fq::toaltstack() }
             { Fq::toaltstack() }
             { Fq::toaltstack() }
 
-            // ] | [f, e, j]
-            { Fr::roll(3) }
-            // ] | [f, e, j, y]
+            // [ | -G1, e_scalar]
+            { Fr::copy(1) }
             { Fr::toaltstack() }
+            { Fq::push_dec("1") }
+            { Fq::push_dec("2") }
+            { Fq::push_dec("1") }
+            { G1Projective::neg() }
+            { G1Projective::toaltstack() }
 
-            // W2 ] | [f, e, j, y]
-            { Fq::push_dec(proof_w2x) }
-            { Fq::push_dec(proof_w2y) }
+            // [ | -W1, j_scalar, -G1, e_scalar]
+            { Fr::toaltstack() }
+            { Fq::push_dec(&dec(w1.x)) }
+            { Fq::push_dec(&dec(w1.y)) }
             { Fq::push_dec("1") }
+            { G1Projective::neg() }
+            { G1Projective::toaltstack() }
 
-            // W2, y ] | [f, e, j]
+            // [W2, y | -W1, j_scalar, -G1, e_scalar]
+            { Fr::roll(3) }
+            { Fr::toaltstack() }
+            { Fq::push_dec(&dec(w2.x)) }
+            { Fq::push_dec(&dec(w2.y)) }
+            { Fq::push_dec("1") }
             { Fr::fromaltstack() }
 
-            // W2 * y ] | [f, e, j]
-            { G1Projective::scalar_mul() }
-
-            // W2 * y, j, e, f ]
+            { Fr::fromaltstack() }
             { G1Projective::fromaltstack() }
+            { Fr::fromaltstack() }
             { G1Projective::fromaltstack() }
-            // W2 * y, j, e] | [ f ]
-            // W2 * y, j + e] | [ f ]
-            { G1Projective::add() }
-            // W2 * y, - (j + e)] | [ f ]
-            { G1Projective::neg() }
-            // W2 * y - (j + e)] | [ f ]
-            { G1Projective::add() }
-            // W2 * y - (j + e), f ]
+
+            // A1 = y * W2 - J - E + F
+            { G1Projective::batched_scalar_mul::<3>() }
             { G1Projective::fromaltstack() }
-            // A1 = w2 * y + f - (e + j)
             { G1Projective::add() }
 
+            // drop the remaining scalars
             { G1Projective::toaltstack() }
-
             { Fr::drop() }
             { Fr::drop() }
             { Fr::drop() }
-
             { G1Projective::fromaltstack() }
             { G1Projective::into_affine() }
 
-        }
+            // e(A1, G2) * e(W2, -X_2) = 1
+            { fflonk_pairing_with_c_wi(w2, hints.c, hints.c_inv, hints.wi, &q0_prepared, &q1_prepared) }
+            { fq12_push(hints.hint) }
+            { Fq12::equalverify() }
+
+            OP_TRUE
+        };
+
+        Ok((script, hints))
     }
+}
 
-    // todo
-    /// fflonk_pairing_with_c_wi
-    // compute j (60)
-    // stack input: [A1.x, A1.y]
-    fn fflonk_pairing_with_c_wi(
-        w2: ark_bn254::g1::G1Affine,
-        c: ark_bn254::Fq12,
-        c_inv: ark_bn254::Fq12,
-        wi: ark_bn254::Fq12,
-        constant_1: &G2Prepared,
-        constant_2: &G2Prepared,
-    ) -> Script {
-        script! {
-            // [A1.x, A1.y]
-            { utils::from_eval_point_in_stack() }
-            // [A1.x', A1.y'] = [-A1.x/A1.y, 1/A1.y]
-            { utils::from_eval_point(w2) }
-            // [w2.x', w2.y'] = [-w2.x/w2.y, 1/w2.y]
-            // [A1.x', A1.y', w2.x', w2.y']
-            { fq12_push(c) }
-            { fq12_push(c_inv) }
-            { fq12_push(wi) }
-            // [A1.x', A1.y', w2.x', w2.y', c, c_inv, wi]
-            { Pairing::dual_miller_loop_with_c_wi(constant_1, constant_2, true) }
-        }
+#[allow(non_snake_case)]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::execute_script_as_chunks;
+    use ark_bn254::Bn254;
+    use ark_ec::pairing::Pairing as ArkPairing;
+    use ark_ec::CurveGroup;
+    use ark_ff::Field;
+    use num_bigint::BigUint;
+    use num_traits::Num;
+    use std::str::FromStr;
+
+    fn circom_ref_c0() -> ark_bn254::G1Affine {
+        VerifyingKey::from_snarkjs_json(include_str!("circom_ref/verification_key.json"))
+            .unwrap()
+            .c0
+    }
+
+    fn circom_ref_proof() -> Proof {
+        Proof::from_snarkjs_json(include_str!("circom_ref/proof.json")).unwrap()
+    }
+
+    fn circom_ref_public_inputs() -> Vec<ark_bn254::Fr> {
+        crate::groth16::loader::public_inputs_from_snarkjs_json(include_str!(
+            "circom_ref/public.json"
+        ))
+        .unwrap()
     }
 
-    // refer table 3 of https://eprint.iacr.org/2009/457.pdf
-    // a: Fp12 which is cubic residue
-    // c: random Fp12 which is cubic non-residue
-    // s: satisfying p^12 - 1 = 3^s * t
-    // t: satisfying p^12 - 1 = 3^s * t
-    // k: k = (t + 1) // 3
-    fn tonelli_shanks_cubic(
-        a: ark_bn254::Fq12,
-        c: ark_bn254::Fq12,
-        s: u32,
-        t: BigUint,
-        k: BigUint,
-    ) -> ark_bn254::Fq12 {
-        let mut r = a.pow(t.to_u64_digits());
-        let e = 3_u32.pow(s - 1);
-        let exp = 3_u32.pow(s) * &t;
-
-        // compute cubic root of (a^t)^-1, say h
-        let (mut h, cc, mut c) = (
-            ark_bn254::Fq12::ONE,
-            c.pow([e as u64]),
-            c.inverse().unwrap(),
+    #[test]
+    fn test_compute_challenges() {
+        let (c0, proof, public_inputs) = (
+            circom_ref_c0(),
+            circom_ref_proof(),
+            circom_ref_public_inputs(),
         );
-        for i in 1..(s as i32) {
-            let delta = (s as i32) - i - 1;
-            let d = if delta < 0 {
-                r.pow((&exp / 3_u32.pow((-delta) as u32)).to_u64_digits())
-            } else {
-                r.pow([3_u32.pow(delta as u32).to_u64().unwrap()])
-            };
-            if d == cc {
-                (h, r) = (h * c, r * c.pow([3_u64]));
-            } else if d == cc.pow([2_u64]) {
-                (h, r) = (h * c.pow([2_u64]), r * c.pow([3_u64]).pow([2_u64]));
+        let vk = VerifyingKey::from_snarkjs_json(include_str!("circom_ref/verification_key.json"))
+            .unwrap();
+        let [beta, gamma, xiseed, alpha, y] =
+            Verifier::compute_challenges(&vk, &proof, &public_inputs);
+
+        let script = script! {
+            { compute_challenges(c0, &proof, &public_inputs) }
+            for challenge in [xiseed, y, alpha, gamma, beta] {
+                { fr_push(challenge) }
+                { Fr::equalverify(1, 0) }
             }
-            c = c.pow([3_u64])
-        }
+            OP_TRUE
+        };
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
+    }
 
-        // recover cubic root of a
-        r = a.pow(k.to_u64_digits()) * h;
-        if t == 3_u32 * k + 1_u32 {
-            r = r.inverse().unwrap();
-        }
+    #[test]
+    fn test_fflonk_verifier_rejects_unsupported_vk() {
+        let vk = VerifyingKey::from_snarkjs_json(include_str!("circom_ref/verification_key.json"))
+            .unwrap();
+        let proof = Proof::from_snarkjs_json(include_str!("circom_ref/proof.json")).unwrap();
+        let public_inputs = crate::groth16::loader::public_inputs_from_snarkjs_json(include_str!(
+            "circom_ref/public.json"
+        ))
+        .unwrap();
 
-        assert_eq!(r.pow([3_u64]), a);
-        r
+        assert!(Verifier::verify_proof(&vk, &proof, &public_inputs[..1]).is_err());
+
+        let mut three_inputs = vk.clone();
+        three_inputs.n_public = 3;
+        let inputs = [public_inputs.clone(), vec![ark_bn254::Fr::from(1_u64)]].concat();
+        assert!(Verifier::verify_proof(&three_inputs, &proof, &inputs).is_err());
+
+        let mut other_cosets = vk.clone();
+        other_cosets.k1 = ark_bn254::Fr::from(5_u64);
+        assert!(Verifier::verify_proof(&other_cosets, &proof, &public_inputs).is_err());
     }
 
-    // refer from Algorithm 5 of "On Proving Pairings"(https://eprint.iacr.org/2024/640.pdf)
-    fn compute_c_wi(f: ark_bn254::Fq12) -> (ark_bn254::Fq12, ark_bn254::Fq12) {
-        let p = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap();
-        let r = BigUint::from_str(
-            "21888242871839275222246405745257275088548364400416034343698204186575808495617",
-        )
+    #[test]
+    fn test_fflonk_verifier_with_circom_proof() {
+        let vk = VerifyingKey::from_snarkjs_json(include_str!("circom_ref/verification_key.json"))
+            .unwrap();
+        let proof = Proof::from_snarkjs_json(include_str!("circom_ref/proof.json")).unwrap();
+        let public_inputs = crate::groth16::loader::public_inputs_from_snarkjs_json(include_str!(
+            "circom_ref/public.json"
+        ))
         .unwrap();
-        let lambda = BigUint::from_str(
-            "10486551571378427818905133077457505975146652579011797175399169355881771981095211883813744499745558409789005132135496770941292989421431235276221147148858384772096778432243207188878598198850276842458913349817007302752534892127325269"
-        ).unwrap();
-        let s = 3_u32;
-        let exp = p.pow(12_u32) - 1_u32;
-        let h = &exp / &r;
-        let t = &exp / 3_u32.pow(s);
-        let k = (&t + 1_u32) / 3_u32;
-        let m = &lambda / &r;
-        let d = 3_u32;
-        let mm = &m / d;
-
-        let mut prng = ChaCha20Rng::seed_from_u64(0);
-        let cofactor_cubic = 3_u32.pow(s - 1) * &t;
-
-        // make f is r-th residue, but it's not cubic residue
-        assert_eq!(f.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
-        assert_ne!(f.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
-
-        // sample a proper scalar w which is cubic non-residue
-        let w = {
-            let (mut w, mut z) = (ark_bn254::Fq12::ONE, ark_bn254::Fq12::ONE);
-            while w == ark_bn254::Fq12::ONE {
-                // choose z which is 3-th non-residue
-                let mut legendre = ark_bn254::Fq12::ONE;
-                while legendre == ark_bn254::Fq12::ONE {
-                    z = ark_bn254::Fq12::rand(&mut prng);
-                    legendre = z.pow(cofactor_cubic.to_u64_digits());
-                }
-                // obtain w which is t-th power of z
-                w = z.pow(t.to_u64_digits());
-            }
-            w
-        };
-        // make sure 27-th root w, is 3-th non-residue and r-th residue
-        assert_ne!(w.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
-        assert_eq!(w.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
-
-        // just two option, w and w^2, since w^3 must be cubic residue, leading f*w^3 must not be cubic residue
-        let mut wi = w;
-        if (f * wi).pow(cofactor_cubic.to_u64_digits()) != ark_bn254::Fq12::ONE {
-            assert_eq!(
-                (f * w * w).pow(cofactor_cubic.to_u64_digits()),
-                ark_bn254::Fq12::ONE
-            );
-            wi = w * w;
-        }
-        assert_eq!(wi.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
-
-        assert_eq!(lambda, &d * &mm * &r);
-        // f1 is scaled f
-        let f1 = f * wi;
-
-        // r-th root of f1, say f2
-        let r_inv = r.modinv(&h).unwrap();
-        assert_ne!(r_inv, BigUint::one());
-        let f2 = f1.pow(r_inv.to_u64_digits());
-        assert_ne!(f2, ark_bn254::Fq12::ONE);
-
-        // m'-th root of f, say f3
-        let mm_inv = mm.modinv(&(r * h)).unwrap();
-        assert_ne!(mm_inv, BigUint::one());
-        let f3 = f2.pow(mm_inv.to_u64_digits());
-        assert_eq!(f3.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
-        assert_ne!(f3, ark_bn254::Fq12::ONE);
-
-        // d-th (cubic) root, say c
-        let c = tonelli_shanks_cubic(f3, w, s, t, k);
-        assert_ne!(c, ark_bn254::Fq12::ONE);
-        assert_eq!(c.pow(lambda.to_u64_digits()), f * wi);
-
-        (c, wi)
+
+        let (script, hints) = Verifier::verify_proof(&vk, &proof, &public_inputs).unwrap();
+        assert_eq!(
+            hints.a1.x,
+            ark_bn254::Fq::from_str(
+                "20865350835208408676546961342790205276489974377099408142235427601412988269867"
+            )
+            .unwrap()
+        );
+
+        println!("fflonk.verifier = {} bytes", script.len());
+        let exec_result = execute_script(script);
+        assert!(exec_result.success);
     }
 
     #[test]
@@ -2236,7 +2403,7 @@ mod test {
             "246513590391103489634602289097178521809",
             "138371009144214353742010089705444713455",
         );
-        let (xi, ql, qr, qm, qo, qc, s1, s2, s3, a, b, c, z, zw, t1w, t2w) = (
+        let (_xiseed, ql, qr, qm, qo, qc, s1, s2, s3, a, b, c, z, zw, t1w, t2w) = (
             "12675309311304482509247823029963782393309524866265275290730041635615278736000",
             "4305584171954448775801758618991977283131671407134816099015723841718827300684",
             "12383383973686840675128398394454489421896122330596726461131121746926747341189",
@@ -2293,11 +2460,6 @@ mod test {
 
         let (g1_x, g1_y, g1_z) = ("1", "2", "1");
 
-        let hash_128 = blake3_var_length(128);
-        let hash_32 = blake3_var_length(32);
-        let hash_512 = blake3_var_length(512);
-        let hash_64 = blake3_var_length(64);
-
         // ****************** prepare for pairing_verify **************************
         // exp = 6x + 2 + p - p^2 = lambda - p^3
         let p_pow3 = &BigUint::from_str_radix(Fq::MODULUS, 16).unwrap().pow(3_u32);
@@ -2397,63 +2559,42 @@ mod test {
 
         assert_eq!(hint, c_ori.pow(p_pow3.to_u64_digits()));
 
+        let roots = DecRoots {
+            w8: [w8_1, w8_2, w8_3, w8_4, w8_5, w8_6, w8_7],
+            w3,
+            w3_2,
+            w4,
+            w4_2,
+            w4_3,
+            wr,
+        };
+        let evaluations = DecEvaluations {
+            ql,
+            qr,
+            qm,
+            qo,
+            qc,
+            s1,
+            s2,
+            s3,
+            a,
+            b,
+            c,
+            z,
+            zw,
+            t1w,
+            t2w,
+        };
         let script = script! {
-            // compute challenge beta and check
-            {  compute_challenges_beta(&hash_128, c0_x, c0_y, c1_x, c1_y, inp_1, inp_2) }
-            // [beta]
-
-            // compute challenge gamma and check
-            { compute_challenges_gamma(&hash_32) }
-            // [beta, gamma]
-
-            // // compute alpha
-            { compute_challenges_alpha(&hash_512,
-                xi,
-                ql,
-                qr,
-                qm,
-                qo,
-                qc,
-                s1,
-                s2,
-                s3,
-                a,
-                b,
-                c,
-                z,
-                zw,
-                t1w,
-                t2w) }
-            // [beta, gamma, alpha]
-
-            //// compute challenges_y
-            { compute_challenges_y(&hash_64, w1_x, w1_y) }
-            // [beta, gamma, alpha, y]
-
-            { compute_challenges_xiseed(&hash_64, c2_x, c2_y) }
+            // compute the challenges beta, gamma, alpha, y and xiseed
+            { compute_challenges(circom_ref_c0(), &circom_ref_proof(), &circom_ref_public_inputs()) }
             // [beta, gamma, alpha, y, xiseed]
 
-            {
-                compute_challenges_xin(
-                    w8_1,
-                    w8_2,
-                    w8_3,
-                    w8_4,
-                    w8_5,
-                    w8_6,
-                    w8_7,
-                    w3,
-                    w3_2,
-                    w4,
-                    w4_2,
-                    w4_3,
-                    wr,
-                )
-            }
+            { compute_challenges_xin(&roots, 18) }
             // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
             // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh]
 
-            { compute_inversions(w1, inv) }
+            { compute_inversions(w1, inv, "262144") }
             // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
             // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
             // ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, ...]
@@ -2468,18 +2609,18 @@ mod test {
             // ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
             // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, pi]
 
-            { compute_r0(ql, qr, qo, qm, qc, s1, s2, s3) }
+            { compute_r0(&evaluations) }
             // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
             // ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
             // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, pi, r0]
 
-            { compute_r1(ql, qr, qm, qo, qc, a, b, c) }
+            { compute_r1(&evaluations) }
 
-            { compute_r2(a, b, c, z, zw, s1, s2, s3, t1w, t2w, w1) }
+            { compute_r2(&evaluations, w1) }
 
             { compute_fej() }
 
-            {compute_f_opt(c0_x, c0_y, c0_z, c1_x, c1_y, c1_z, c2_x, c2_y, c2_z)}
+            { compute_f_opt([c0_x, c0_y, c0_z], [c1_x, c1_y, c1_z], [c2_x, c2_y, c2_z]) }
 
 
             // save f
@@ -2548,7 +2689,6 @@ mod test {
         assert!(exec_result.success);
     }
 
-
     #[test]
     fn test_fflonk_verifier_as_chunks() {
         let (c0_x, c0_y, c0_z, c1_x, c1_y, c1_z, inp_1, inp_2) = (
@@ -2561,7 +2701,7 @@ mod test {
             "246513590391103489634602289097178521809",
             "138371009144214353742010089705444713455",
         );
-        let (xi, ql, qr, qm, qo, qc, s1, s2, s3, a, b, c, z, zw, t1w, t2w) = (
+        let (_xiseed, ql, qr, qm, qo, qc, s1, s2, s3, a, b, c, z, zw, t1w, t2w) = (
             "12675309311304482509247823029963782393309524866265275290730041635615278736000",
             "4305584171954448775801758618991977283131671407134816099015723841718827300684",
             "12383383973686840675128398394454489421896122330596726461131121746926747341189",
@@ -2618,11 +2758,6 @@ mod test {
 
         let (g1_x, g1_y, g1_z) = ("1", "2", "1");
 
-        let hash_128 = blake3_var_length(128);
-        let hash_32 = blake3_var_length(32);
-        let hash_512 = blake3_var_length(512);
-        let hash_64 = blake3_var_length(64);
-
         // ****************** prepare for pairing_verify **************************
         // exp = 6x + 2 + p - p^2 = lambda - p^3
         let p_pow3 = &BigUint::from_str_radix(Fq::MODULUS, 16).unwrap().pow(3_u32);
@@ -2722,63 +2857,42 @@ mod test {
 
         assert_eq!(hint, c_ori.pow(p_pow3.to_u64_digits()));
 
+        let roots = DecRoots {
+            w8: [w8_1, w8_2, w8_3, w8_4, w8_5, w8_6, w8_7],
+            w3,
+            w3_2,
+            w4,
+            w4_2,
+            w4_3,
+            wr,
+        };
+        let evaluations = DecEvaluations {
+            ql,
+            qr,
+            qm,
+            qo,
+            qc,
+            s1,
+            s2,
+            s3,
+            a,
+            b,
+            c,
+            z,
+            zw,
+            t1w,
+            t2w,
+        };
         let script = script! {
-            // compute challenge beta and check
-            {  compute_challenges_beta(&hash_128, c0_x, c0_y, c1_x, c1_y, inp_1, inp_2) }
-            // [beta]
-
-            // compute challenge gamma and check
-            { compute_challenges_gamma(&hash_32) }
-            // [beta, gamma]
-
-            // // compute alpha
-            { compute_challenges_alpha(&hash_512,
-                xi,
-                ql,
-                qr,
-                qm,
-                qo,
-                qc,
-                s1,
-                s2,
-                s3,
-                a,
-                b,
-                c,
-                z,
-                zw,
-                t1w,
-                t2w) }
-            // [beta, gamma, alpha]
-
-            //// compute challenges_y
-            { compute_challenges_y(&hash_64, w1_x, w1_y) }
-            // [beta, gamma, alpha, y]
-
-            { compute_challenges_xiseed(&hash_64, c2_x, c2_y) }
+            // compute the challenges beta, gamma, alpha, y and xiseed
+            { compute_challenges(circom_ref_c0(), &circom_ref_proof(), &circom_ref_public_inputs()) }
             // [beta, gamma, alpha, y, xiseed]
 
-            {
-                compute_challenges_xin(
-                    w8_1,
-                    w8_2,
-                    w8_3,
-                    w8_4,
-                    w8_5,
-                    w8_6,
-                    w8_7,
-                    w3,
-                    w3_2,
-                    w4,
-                    w4_2,
-                    w4_3,
-                    wr,
-                )
-            }
+            { compute_challenges_xin(&roots, 18) }
             // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
             // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh]
 
-            { compute_inversions(w1, inv) }
+            { compute_inversions(w1, inv, "262144") }
             // [beta, gamma, alpha, y, pH0w8_0, pH0w8_1, pH0w8_2, pH0w8_3, pH0w8_4, pH0w8_5, pH0w8_6, pH0w8_7,
             // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
             // ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, ...]
@@ -2793,18 +2907,18 @@ mod test {
             // ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
             // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, pi]
 
-            { compute_r0(ql, qr, qo, qm, qc, s1, s2, s3) }
+            { compute_r0(&evaluations) }
             // pH1w4_0, pH1w4_1, pH1w4_2, pH1w4_3, pH2w3_0, pH2w3_1, pH2w3_2, pH3w3_0, pH3w3_1, pH2w3_2, xi, zh,
             // ZH, DenH1, DenH2, LiS0_1, LiS0_2, LiS0_3, LiS0_4, LiS0_5, LiS0_6, LiS0_7, LiS0_8,
             // LiS1_1, LiS1_2, LiS1_3, LiS1_4, LiS2_1, LiS2_2, LiS2_3, LiS3_1, LiS3_2, LiS3_3, Li_1, Li_2, pi, r0]
 
-            { compute_r1(ql, qr, qm, qo, qc, a, b, c) }
+            { compute_r1(&evaluations) }
 
-            { compute_r2(a, b, c, z, zw, s1, s2, s3, t1w, t2w, w1) }
+            { compute_r2(&evaluations, w1) }
 
             { compute_fej() }
 
-            {compute_f_opt(c0_x, c0_y, c0_z, c1_x, c1_y, c1_z, c2_x, c2_y, c2_z)}
+            { compute_f_opt([c0_x, c0_y, c0_z], [c1_x, c1_y, c1_z], [c2_x, c2_y, c2_z]) }
 
 
             // save f
@@ -2869,7 +2983,13 @@ mod test {
         };
         println!("fflonk.checkpairing_miller_loop = {} bytes", script.len());
         let interval = script.max_op_if_interval();
-        println!("Max if interval: {:?} difference: {}, debug info: {}, {}", interval, interval.1 - interval.0, script.debug_info(interval.0), script.debug_info(interval.1));
+        println!(
+            "Max if interval: {:?} difference: {}, debug info: {}, {}",
+            interval,
+            interval.1 - interval.0,
+            script.debug_info(interval.0),
+            script.debug_info(interval.1)
+        );
         let exec_result = execute_script_as_chunks(script, 3_000_000, 2_000_000);
         println!("{}", exec_result);
         assert!(exec_result.success);
@@ -0,0 +1,12 @@
+pragma circom 2.0.0;
+
+template Multiplier() {
+    signal input a; // private
+    signal input b; // private
+
+    signal output c;
+
+    c <== a * b;
+}
+
+component main = Multiplier();
@@ -0,0 +1,555 @@
+// Import Groth16 verifying keys, proofs and public inputs produced by circuit tooling
+//
+// snarkjs (circom) exports `verification_key.json`, `proof.json` and `public.json` with field
+// elements as decimal strings and points in projective form normalized to z = 1.
+// gnark exports the big-endian point encoding of gnark-crypto, compressed by default
+// (`WriteTo`) or uncompressed (`WriteRawTo`), the flag lives in the top two bits of the first byte.
+//
+// Every point is checked to be on the curve and in the prime order subgroup before it is
+// handed to the verifier.
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::short_weierstrass::SWCurveConfig;
+use ark_ff::{Field, PrimeField, Zero};
+use ark_groth16::{Proof, VerifyingKey};
+use num_bigint::BigUint;
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+struct SnarkjsVerifyingKey {
+    protocol: String,
+    curve: String,
+    #[serde(rename = "nPublic")]
+    n_public: usize,
+    vk_alpha_1: Vec<String>,
+    vk_beta_2: Vec<Vec<String>>,
+    vk_gamma_2: Vec<Vec<String>>,
+    vk_delta_2: Vec<Vec<String>>,
+    #[serde(rename = "IC")]
+    ic: Vec<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct SnarkjsProof {
+    #[serde(default)]
+    protocol: Option<String>,
+    #[serde(default)]
+    curve: Option<String>,
+    pi_a: Vec<String>,
+    pi_b: Vec<Vec<String>>,
+    pi_c: Vec<String>,
+}
+
+fn check_snarkjs_header(protocol: &str, curve: &str) -> Result<(), String> {
+    if protocol != "groth16" {
+        return Err(format!("unsupported protocol: {}", protocol));
+    }
+    if curve != "bn128" && curve != "bn254" {
+        return Err(format!("unsupported curve: {}", curve));
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_dec<F: PrimeField>(s: &str) -> Result<F, String> {
+    let n = BigUint::from_str(s).map_err(|e| format!("invalid decimal {}: {}", s, e))?;
+    let modulus: BigUint = F::MODULUS.into();
+    if n >= modulus {
+        return Err(format!("{} is not a canonical field element", s));
+    }
+    Ok(F::from(n))
+}
+
+fn parse_be<F: PrimeField>(bytes: &[u8]) -> Result<F, String> {
+    let n = BigUint::from_bytes_be(bytes);
+    let modulus: BigUint = F::MODULUS.into();
+    if n >= modulus {
+        return Err(format!(
+            "0x{} is not a canonical field element",
+            hex::encode(bytes)
+        ));
+    }
+    Ok(F::from(n))
+}
+
+fn checked_g1(x: Fq, y: Fq, name: &str) -> Result<G1Affine, String> {
+    let p = G1Affine::new_unchecked(x, y);
+    if !p.is_on_curve() {
+        return Err(format!("{} is not on the curve", name));
+    }
+    if !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(format!("{} is not in the prime order subgroup", name));
+    }
+    Ok(p)
+}
+
+fn checked_g2(x: Fq2, y: Fq2, name: &str) -> Result<G2Affine, String> {
+    let p = G2Affine::new_unchecked(x, y);
+    if !p.is_on_curve() {
+        return Err(format!("{} is not on the twist", name));
+    }
+    if !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(format!("{} is not in the prime order subgroup", name));
+    }
+    Ok(p)
+}
+
+// [x, y, "1"]
+pub(crate) fn snarkjs_g1(coords: &[String], name: &str) -> Result<G1Affine, String> {
+    if coords.len() != 3 {
+        return Err(format!("{} must have 3 coordinates", name));
+    }
+    if parse_dec::<Fq>(&coords[2])? != Fq::ONE {
+        return Err(format!("{} is not normalized to z = 1", name));
+    }
+    checked_g1(parse_dec(&coords[0])?, parse_dec(&coords[1])?, name)
+}
+
+// [[x.c0, x.c1], [y.c0, y.c1], ["1", "0"]]
+pub(crate) fn snarkjs_g2(coords: &[Vec<String>], name: &str) -> Result<G2Affine, String> {
+    if coords.len() != 3 || coords.iter().any(|c| c.len() != 2) {
+        return Err(format!("{} must have 3 coordinates over Fq2", name));
+    }
+    let fq2 = |c: &Vec<String>| -> Result<Fq2, String> {
+        Ok(Fq2::new(parse_dec(&c[0])?, parse_dec(&c[1])?))
+    };
+    if fq2(&coords[2])? != Fq2::ONE {
+        return Err(format!("{} is not normalized to z = 1", name));
+    }
+    checked_g2(fq2(&coords[0])?, fq2(&coords[1])?, name)
+}
+
+pub fn vk_from_snarkjs_json(json: &str) -> Result<VerifyingKey<Bn254>, String> {
+    let vk: SnarkjsVerifyingKey = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    check_snarkjs_header(&vk.protocol, &vk.curve)?;
+    if vk.ic.len() != vk.n_public + 1 {
+        return Err(format!(
+            "IC has {} points, expected nPublic + 1 = {}",
+            vk.ic.len(),
+            vk.n_public + 1
+        ));
+    }
+
+    Ok(VerifyingKey {
+        alpha_g1: snarkjs_g1(&vk.vk_alpha_1, "vk_alpha_1")?,
+        beta_g2: snarkjs_g2(&vk.vk_beta_2, "vk_beta_2")?,
+        gamma_g2: snarkjs_g2(&vk.vk_gamma_2, "vk_gamma_2")?,
+        delta_g2: snarkjs_g2(&vk.vk_delta_2, "vk_delta_2")?,
+        gamma_abc_g1: vk
+            .ic
+            .iter()
+            .enumerate()
+            .map(|(i, p)| snarkjs_g1(p, &format!("IC[{}]", i)))
+            .collect::<Result<Vec<_>, _>>()?,
+    })
+}
+
+pub fn proof_from_snarkjs_json(json: &str) -> Result<Proof<Bn254>, String> {
+    let proof: SnarkjsProof = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    check_snarkjs_header(
+        proof.protocol.as_deref().unwrap_or("groth16"),
+        proof.curve.as_deref().unwrap_or("bn128"),
+    )?;
+
+    Ok(Proof {
+        a: snarkjs_g1(&proof.pi_a, "pi_a")?,
+        b: snarkjs_g2(&proof.pi_b, "pi_b")?,
+        c: snarkjs_g1(&proof.pi_c, "pi_c")?,
+    })
+}
+
+pub fn public_inputs_from_snarkjs_json(json: &str) -> Result<Vec<Fr>, String> {
+    let inputs: Vec<String> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    inputs.iter().map(|s| parse_dec(s)).collect()
+}
+
+const FLAG_MASK: u8 = 0b11 << 6;
+const FLAG_UNCOMPRESSED: u8 = 0b00 << 6;
+const FLAG_COMPRESSED_INFINITY: u8 = 0b01 << 6;
+const FLAG_COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+const FLAG_COMPRESSED_LARGEST: u8 = 0b11 << 6;
+
+// gnark: y is lexicographically largest when y > (p - 1) / 2, comparing a1 first for Fq2
+fn fq_is_largest(y: Fq) -> bool {
+    let half: BigUint = (BigUint::from(Fq::MODULUS) - 1_u32) / 2_u32;
+    BigUint::from(y) > half
+}
+
+fn fq2_is_largest(y: Fq2) -> bool {
+    if y.c1.is_zero() {
+        fq_is_largest(y.c0)
+    } else {
+        fq_is_largest(y.c1)
+    }
+}
+
+struct GnarkReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> GnarkReader<'a> {
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < n {
+            return Err("unexpected end of input".to_string());
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn flag(&self) -> Result<u8, String> {
+        match self.bytes.first() {
+            Some(b) => Ok(b & FLAG_MASK),
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+
+    // Reads a field element whose first byte may carry the encoding flag
+    fn fq(&mut self, clear_flag: bool) -> Result<Fq, String> {
+        let mut buf = self.take(32)?.to_vec();
+        if clear_flag {
+            buf[0] &= !FLAG_MASK;
+        }
+        parse_be(&buf)
+    }
+
+    // Fq2 is encoded as a1 || a0
+    fn fq2(&mut self, clear_flag: bool) -> Result<Fq2, String> {
+        let c1 = self.fq(clear_flag)?;
+        let c0 = self.fq(false)?;
+        Ok(Fq2::new(c0, c1))
+    }
+
+    fn fr(&mut self) -> Result<Fr, String> {
+        parse_be(self.take(32)?)
+    }
+
+    fn g1(&mut self, name: &str) -> Result<G1Affine, String> {
+        let flag = self.flag()?;
+        match flag {
+            FLAG_UNCOMPRESSED => {
+                let x = self.fq(false)?;
+                let y = self.fq(false)?;
+                checked_g1(x, y, name)
+            }
+            FLAG_COMPRESSED_SMALLEST | FLAG_COMPRESSED_LARGEST => {
+                let x = self.fq(true)?;
+                let y2 = x * x * x + ark_bn254::g1::Config::COEFF_B;
+                let mut y = y2
+                    .sqrt()
+                    .ok_or_else(|| format!("{} is not on the curve", name))?;
+                if fq_is_largest(y) != (flag == FLAG_COMPRESSED_LARGEST) {
+                    y = -y;
+                }
+                checked_g1(x, y, name)
+            }
+            FLAG_COMPRESSED_INFINITY => Err(format!("{} is the point at infinity", name)),
+            _ => unreachable!(),
+        }
+    }
+
+    fn g2(&mut self, name: &str) -> Result<G2Affine, String> {
+        let flag = self.flag()?;
+        match flag {
+            FLAG_UNCOMPRESSED => {
+                let x = self.fq2(false)?;
+                let y = self.fq2(false)?;
+                checked_g2(x, y, name)
+            }
+            FLAG_COMPRESSED_SMALLEST | FLAG_COMPRESSED_LARGEST => {
+                let x = self.fq2(true)?;
+                let y2 = x * x * x + ark_bn254::g2::Config::COEFF_B;
+                let mut y = y2
+                    .sqrt()
+                    .ok_or_else(|| format!("{} is not on the twist", name))?;
+                if fq2_is_largest(y) != (flag == FLAG_COMPRESSED_LARGEST) {
+                    y = -y;
+                }
+                checked_g2(x, y, name)
+            }
+            FLAG_COMPRESSED_INFINITY => Err(format!("{} is the point at infinity", name)),
+            _ => unreachable!(),
+        }
+    }
+
+    // Trailing commitment data of gnark >= 0.9, only commitment free circuits map to arkworks
+    fn no_commitments(&mut self) -> Result<(), String> {
+        if !self.is_empty() && self.u32()? != 0 {
+            return Err("circuits with commitments are not supported".to_string());
+        }
+        Ok(())
+    }
+}
+
+pub fn vk_from_gnark_bytes(bytes: &[u8]) -> Result<VerifyingKey<Bn254>, String> {
+    let mut reader = GnarkReader { bytes };
+
+    // [α]1, [β]1, [β]2, [γ]2, [δ]1, [δ]2, uint32(len(K)), [K]1
+    let alpha_g1 = reader.g1("alpha_g1")?;
+    reader.g1("beta_g1")?;
+    let beta_g2 = reader.g2("beta_g2")?;
+    let gamma_g2 = reader.g2("gamma_g2")?;
+    reader.g1("delta_g1")?;
+    let delta_g2 = reader.g2("delta_g2")?;
+    let n = reader.u32()? as usize;
+    let gamma_abc_g1 = (0..n)
+        .map(|i| reader.g1(&format!("K[{}]", i)))
+        .collect::<Result<Vec<_>, _>>()?;
+    if gamma_abc_g1.is_empty() {
+        return Err("K must contain at least one point".to_string());
+    }
+    reader.no_commitments()?;
+
+    Ok(VerifyingKey {
+        alpha_g1,
+        beta_g2,
+        gamma_g2,
+        delta_g2,
+        gamma_abc_g1,
+    })
+}
+
+pub fn proof_from_gnark_bytes(bytes: &[u8]) -> Result<Proof<Bn254>, String> {
+    let mut reader = GnarkReader { bytes };
+
+    // [Ar]1, [Bs]2, [Krs]1
+    let a = reader.g1("Ar")?;
+    let b = reader.g2("Bs")?;
+    let c = reader.g1("Krs")?;
+    reader.no_commitments()?;
+
+    Ok(Proof { a, b, c })
+}
+
+// Binary public witness: uint32(nbPublic), uint32(nbSecret), uint32(len), [Fr] big-endian
+pub fn public_inputs_from_gnark_bytes(bytes: &[u8]) -> Result<Vec<Fr>, String> {
+    let mut reader = GnarkReader { bytes };
+
+    let n_public = reader.u32()? as usize;
+    let n_secret = reader.u32()?;
+    if n_secret != 0 {
+        return Err("witness contains secret inputs".to_string());
+    }
+    let n = reader.u32()? as usize;
+    if n != n_public {
+        return Err(format!("witness has {} elements, expected {}", n, n_public));
+    }
+    let inputs = (0..n).map(|_| reader.fr()).collect::<Result<Vec<_>, _>>()?;
+    if !reader.is_empty() {
+        return Err("trailing bytes after public witness".to_string());
+    }
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_std::UniformRand;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn dec<F: PrimeField>(x: F) -> String {
+        let n: BigUint = x.into();
+        n.to_string()
+    }
+
+    fn g1_json(p: G1Affine) -> String {
+        format!(r#"["{}", "{}", "1"]"#, dec(p.x), dec(p.y))
+    }
+
+    fn g2_json(p: G2Affine) -> String {
+        format!(
+            r#"[["{}", "{}"], ["{}", "{}"], ["1", "0"]]"#,
+            dec(p.x.c0),
+            dec(p.x.c1),
+            dec(p.y.c0),
+            dec(p.y.c1)
+        )
+    }
+
+    fn fq_be(x: Fq) -> Vec<u8> {
+        let n: BigUint = x.into();
+        let bytes = n.to_bytes_be();
+        let mut buf = vec![0_u8; 32 - bytes.len()];
+        buf.extend(bytes);
+        buf
+    }
+
+    fn g1_gnark(p: G1Affine, compressed: bool) -> Vec<u8> {
+        if !compressed {
+            return [fq_be(p.x), fq_be(p.y)].concat();
+        }
+        let mut buf = fq_be(p.x);
+        buf[0] |= if fq_is_largest(p.y) {
+            FLAG_COMPRESSED_LARGEST
+        } else {
+            FLAG_COMPRESSED_SMALLEST
+        };
+        buf
+    }
+
+    fn g2_gnark(p: G2Affine, compressed: bool) -> Vec<u8> {
+        if !compressed {
+            return [fq_be(p.x.c1), fq_be(p.x.c0), fq_be(p.y.c1), fq_be(p.y.c0)].concat();
+        }
+        let mut buf = [fq_be(p.x.c1), fq_be(p.x.c0)].concat();
+        buf[0] |= if fq2_is_largest(p.y) {
+            FLAG_COMPRESSED_LARGEST
+        } else {
+            FLAG_COMPRESSED_SMALLEST
+        };
+        buf
+    }
+
+    fn random_vk(prng: &mut ChaCha20Rng) -> VerifyingKey<Bn254> {
+        VerifyingKey {
+            alpha_g1: G1Affine::rand(prng),
+            beta_g2: G2Affine::rand(prng),
+            gamma_g2: G2Affine::rand(prng),
+            delta_g2: G2Affine::rand(prng),
+            gamma_abc_g1: (0..3).map(|_| G1Affine::rand(prng)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_snarkjs_loader() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let vk = random_vk(&mut prng);
+        let proof = Proof::<Bn254> {
+            a: G1Affine::rand(&mut prng),
+            b: G2Affine::rand(&mut prng),
+            c: G1Affine::rand(&mut prng),
+        };
+        let inputs = vec![Fr::rand(&mut prng), Fr::rand(&mut prng)];
+
+        let vk_json = format!(
+            r#"{{"protocol": "groth16", "curve": "bn128", "nPublic": 2,
+                "vk_alpha_1": {}, "vk_beta_2": {}, "vk_gamma_2": {}, "vk_delta_2": {},
+                "IC": [{}]}}"#,
+            g1_json(vk.alpha_g1),
+            g2_json(vk.beta_g2),
+            g2_json(vk.gamma_g2),
+            g2_json(vk.delta_g2),
+            vk.gamma_abc_g1
+                .iter()
+                .map(|p| g1_json(*p))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let proof_json = format!(
+            r#"{{"pi_a": {}, "pi_b": {}, "pi_c": {}, "protocol": "groth16", "curve": "bn128"}}"#,
+            g1_json(proof.a),
+            g2_json(proof.b),
+            g1_json(proof.c)
+        );
+        let public_json = format!(r#"["{}", "{}"]"#, dec(inputs[0]), dec(inputs[1]));
+
+        assert_eq!(vk_from_snarkjs_json(&vk_json).unwrap(), vk);
+        assert_eq!(proof_from_snarkjs_json(&proof_json).unwrap(), proof);
+        assert_eq!(
+            public_inputs_from_snarkjs_json(&public_json).unwrap(),
+            inputs
+        );
+
+        // tamper with pi_a.y, the point falls off the curve
+        let bad_json = proof_json.replacen(&dec(proof.a.y), &dec(proof.a.y + Fq::ONE), 1);
+        assert!(proof_from_snarkjs_json(&bad_json).is_err());
+    }
+
+    #[test]
+    fn test_gnark_loader() {
+        let mut prng = ChaCha20Rng::seed_from_u64(1);
+        let vk = random_vk(&mut prng);
+        let proof = Proof::<Bn254> {
+            a: G1Affine::rand(&mut prng),
+            b: G2Affine::rand(&mut prng),
+            c: G1Affine::rand(&mut prng),
+        };
+
+        for compressed in [true, false] {
+            let mut vk_bytes = [
+                g1_gnark(vk.alpha_g1, compressed),
+                g1_gnark(G1Affine::rand(&mut prng), compressed),
+                g2_gnark(vk.beta_g2, compressed),
+                g2_gnark(vk.gamma_g2, compressed),
+                g1_gnark(G1Affine::rand(&mut prng), compressed),
+                g2_gnark(vk.delta_g2, compressed),
+                (vk.gamma_abc_g1.len() as u32).to_be_bytes().to_vec(),
+            ]
+            .concat();
+            for p in vk.gamma_abc_g1.iter() {
+                vk_bytes.extend(g1_gnark(*p, compressed));
+            }
+            // no commitments
+            vk_bytes.extend(0_u32.to_be_bytes());
+            vk_bytes.extend(0_u32.to_be_bytes());
+
+            let proof_bytes = [
+                g1_gnark(proof.a, compressed),
+                g2_gnark(proof.b, compressed),
+                g1_gnark(proof.c, compressed),
+            ]
+            .concat();
+
+            assert_eq!(vk_from_gnark_bytes(&vk_bytes).unwrap(), vk);
+            assert_eq!(proof_from_gnark_bytes(&proof_bytes).unwrap(), proof);
+        }
+
+        let inputs = vec![Fr::rand(&mut prng), Fr::rand(&mut prng)];
+        let mut witness_bytes = [
+            2_u32.to_be_bytes(),
+            0_u32.to_be_bytes(),
+            2_u32.to_be_bytes(),
+        ]
+        .concat();
+        for x in inputs.iter() {
+            let n: BigUint = (*x).into();
+            let bytes = n.to_bytes_be();
+            witness_bytes.extend(vec![0_u8; 32 - bytes.len()]);
+            witness_bytes.extend(bytes);
+        }
+        assert_eq!(
+            public_inputs_from_gnark_bytes(&witness_bytes).unwrap(),
+            inputs
+        );
+
+        // truncated proof
+        let proof_bytes = [g1_gnark(proof.a, false), g2_gnark(proof.b, false)].concat();
+        assert!(proof_from_gnark_bytes(&proof_bytes).is_err());
+    }
+
+    // `circom_ref` holds a Groth16 setup and proof of `multiplier.circom` for a = 3, b = 11, laid out
+    // as snarkjs' `verification_key.json`, `proof.json` and `public.json`
+    #[test]
+    fn test_snarkjs_fixture() {
+        use crate::execute_script_without_stack_limit;
+        use crate::groth16::verifier::Verifier;
+        use crate::treepp::script;
+        use ark_crypto_primitives::snark::SNARK;
+        use ark_groth16::Groth16;
+
+        let vk = vk_from_snarkjs_json(include_str!("circom_ref/verification_key.json")).unwrap();
+        let proof = proof_from_snarkjs_json(include_str!("circom_ref/proof.json")).unwrap();
+        let inputs = public_inputs_from_snarkjs_json(include_str!("circom_ref/public.json")).unwrap();
+        assert_eq!(inputs, vec![Fr::from(33_u64)]);
+
+        assert!(Groth16::<Bn254>::verify(&vk, &inputs, &proof).unwrap());
+
+        let script = script! {
+            { Verifier::witness(&inputs, &proof, &vk) }
+            { Verifier::lock_script(&vk) }
+        };
+        let exec_result = execute_script_without_stack_limit(script);
+        assert!(exec_result.success);
+
+        // a different public input must not verify
+        assert!(!Groth16::<Bn254>::verify(&vk, &[Fr::from(34_u64)], &proof).unwrap());
+    }
+}
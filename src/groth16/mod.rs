@@ -1,4 +1,5 @@
 pub mod constants;
+pub mod loader;
 pub mod offchain_checker;
 
 
@@ -59,9 +59,11 @@ fn tonelli_shanks_cubic(
     r
 }
 
-// Finding C
-// refer from Algorithm 5 of "On Proving Pairings"(https://eprint.iacr.org/2024/640.pdf)
-pub fn compute_c_wi(f: ark_bn254::Fq12) -> (ark_bn254::Fq12, ark_bn254::Fq12) {
+// Sample the 27-th root of unity w, which is 3-th non-residue and r-th residue
+//
+// The sampling is deterministic, so the verifier script can embed w and w^2 as the only
+// valid choices of wi
+pub fn compute_w() -> ark_bn254::Fq12 {
     let p = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap();
     let r = BigUint::from_str(
         "21888242871839275222246405745257275088548364400416034343698204186575808495617",
@@ -71,18 +73,10 @@ pub fn compute_c_wi(f: ark_bn254::Fq12) -> (ark_bn254::Fq12, ark_bn254::Fq12) {
     let exp = p.pow(12_u32) - 1_u32;
     let h = &exp / &r;
     let t = &exp / 3_u32.pow(s);
-    let k = (&t + 1_u32) / 3_u32;
-    let m = &*LAMBDA / &r;
-    let d = 3_u32;
-    let mm = &m / d;
 
     let mut prng = ChaCha20Rng::seed_from_u64(0);
     let cofactor_cubic = 3_u32.pow(s - 1) * &t;
 
-    // make f is r-th residue, but it's not cubic residue
-    assert_eq!(f.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
-    assert_ne!(f.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
-
     // sample a proper scalar w which is cubic non-residue
     let w = {
         let (mut w, mut z) = (ark_bn254::Fq12::ONE, ark_bn254::Fq12::ONE);
@@ -101,6 +95,33 @@ pub fn compute_c_wi(f: ark_bn254::Fq12) -> (ark_bn254::Fq12, ark_bn254::Fq12) {
     // make sure 27-th root w, is 3-th non-residue and r-th residue
     assert_ne!(w.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
     assert_eq!(w.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
+    w
+}
+
+// Finding C
+// refer from Algorithm 5 of "On Proving Pairings"(https://eprint.iacr.org/2024/640.pdf)
+pub fn compute_c_wi(f: ark_bn254::Fq12) -> (ark_bn254::Fq12, ark_bn254::Fq12) {
+    let p = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap();
+    let r = BigUint::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let s = 3_u32;
+    let exp = p.pow(12_u32) - 1_u32;
+    let h = &exp / &r;
+    let t = &exp / 3_u32.pow(s);
+    let k = (&t + 1_u32) / 3_u32;
+    let m = &*LAMBDA / &r;
+    let d = 3_u32;
+    let mm = &m / d;
+
+    let cofactor_cubic = 3_u32.pow(s - 1) * &t;
+
+    // make f is r-th residue, but it's not cubic residue
+    assert_eq!(f.pow(h.to_u64_digits()), ark_bn254::Fq12::ONE);
+    assert_ne!(f.pow(cofactor_cubic.to_u64_digits()), ark_bn254::Fq12::ONE);
+
+    let w = compute_w();
 
     // just two option, w and w^2, since w^3 must be cubic residue, leading f*w^3 must not be cubic residue
     let mut wi = w;
@@ -1,5 +1,6 @@
 use crate::{execute_script_as_chunks, execute_script_without_stack_limit};
 use crate::groth16::verifier::Verifier;
+use crate::treepp::*;
 use ark_bn254::Bn254;
 use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
 use ark_ec::pairing::Pairing;
@@ -115,3 +116,69 @@ fn test_groth16_verifier_as_chunks() {
 
     assert!(exec_result.success);
 }
+
+#[test]
+fn test_groth16_verifier_lock_script() {
+    type E = Bn254;
+    let k = 6;
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let circuit = DummyCircuit::<<E as Pairing>::ScalarField> {
+        a: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+        b: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+        num_variables: 10,
+        num_constraints: 1 << k,
+    };
+    let (pk, vk) = Groth16::<E>::setup(circuit, &mut rng).unwrap();
+
+    let c = circuit.a.unwrap() * circuit.b.unwrap();
+
+    let proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+
+    // the lock script is generated before the proof exists
+    let start = start_timer!(|| "collect_script");
+    let lock_script = Verifier::lock_script(&vk);
+    end_timer!(start);
+    println!("groth16::test_verifier_lock_script = {} bytes", lock_script.len());
+
+    let witness = Verifier::witness(&vec![c], &proof, &vk);
+    let script = script! {
+        { witness }
+        { lock_script }
+    };
+
+    let start = start_timer!(|| "execute_script");
+    let exec_result = execute_script_without_stack_limit(script);
+    end_timer!(start);
+
+    assert!(exec_result.success);
+}
+
+#[test]
+fn test_groth16_verifier_lock_script_rejects_off_subgroup_b() {
+    type E = Bn254;
+    let k = 6;
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+    let circuit = DummyCircuit::<<E as Pairing>::ScalarField> {
+        a: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+        b: Some(<E as Pairing>::ScalarField::rand(&mut rng)),
+        num_variables: 10,
+        num_constraints: 1 << k,
+    };
+    let (pk, vk) = Groth16::<E>::setup(circuit, &mut rng).unwrap();
+
+    let c = circuit.a.unwrap() * circuit.b.unwrap();
+
+    let mut proof = Groth16::<E>::prove(&pk, circuit, &mut rng).unwrap();
+    // B is on the twist, but outside of G2, the final exponentiation hints are arbitrary since
+    // the script has to fail at the subgroup check before the pairing
+    proof.b = crate::bn254::utils::g2_off_subgroup_rand(&mut rng);
+    let fake_c = ark_bn254::Fq12::rand(&mut rng);
+
+    let script = script! {
+        { Verifier::witness_with_hints(&vec![c], &proof, fake_c, 1) }
+        { Verifier::lock_script(&vk) }
+    };
+    let exec_result = execute_script_without_stack_limit(script);
+
+    assert!(!exec_result.success);
+}
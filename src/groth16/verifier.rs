@@ -1,13 +1,16 @@
+use crate::bn254::curves;
 use crate::bn254::ell_coeffs::G2Prepared;
 use crate::bn254::fp254impl::Fp254Impl;
 use crate::bn254::fq::Fq;
 use crate::bn254::fq12::Fq12;
-use crate::bn254::msm::msm_with_constant_bases;
+use crate::bn254::fq2::Fq2;
+use crate::bn254::fr::Fr;
+use crate::bn254::msm::{msm_with_constant_bases, msm_with_constant_bases_in_stack, MSM_WINDOW};
 use crate::bn254::pairing::Pairing;
 use crate::bn254::utils;
 use crate::bn254::utils::fq12_push;
 use crate::groth16::constants::{LAMBDA, P_POW3};
-use crate::groth16::offchain_checker::compute_c_wi;
+use crate::groth16::offchain_checker::{compute_c_wi, compute_w};
 use crate::treepp::{script, Script};
 use ark_bn254::{Bn254, G1Projective};
 use ark_ec::pairing::Pairing as ark_Pairing;
@@ -16,6 +19,7 @@ use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
 use ark_ff::Field;
 use ark_groth16::{Proof, VerifyingKey};
 use core::ops::Neg;
+use num_bigint::BigUint;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Verifier;
@@ -119,6 +123,191 @@ impl Verifier {
             { check_pairing(&q_prepared, hint) }
         }
     }
+
+    // Locking script which only depends on the verifying key, the proof is provided by `witness`
+    //
+    // Input stack (witness): [L(B), public_inputs, C, A, S(B), B, c, c_inv, wi_selector]
+    //     L(B): line coefficients of the non-fixed G2 point B, see `utils::non_fixed_line_coeffs_push`
+    //     S(B): lines of [6x^2]B for the subgroup check, see `utils::g2_subgroup_hints_push`
+    //     wi_selector: 1 if wi = w, 0 if wi = w^2
+    //
+    // Checks done by the script:
+    //     A and C are on the curve
+    //     B is on the twist and in the G2 subgroup
+    //     c * c_inv = 1
+    //     f * c_inv^lambda * wi = c^{p^3}, where f is the miller loop of the four pairings
+    pub fn lock_script(vk: &VerifyingKey<Bn254>) -> Script {
+        let (q1, q2, q3) = (
+            vk.gamma_g2.into_group().neg().into_affine(),
+            vk.delta_g2.into_group().neg().into_affine(),
+            -vk.beta_g2,
+        );
+        let q_prepared = vec![
+            G2Prepared::from_affine(q1),
+            G2Prepared::from_affine(q2),
+            G2Prepared::from_affine(q3),
+        ];
+
+        let w = compute_w();
+
+        script! {
+            // move the proof and the hints to the altstack
+            OP_TOALTSTACK
+            { Fq12::toaltstack() }
+            { Fq12::toaltstack() }
+
+            // 0. B is on the twist and in G2
+            { Fq2::copy(2) }
+            { Fq2::copy(2) }
+            { curves::G2Affine::is_on_curve() }
+            OP_VERIFY
+            { utils::check_g2_subgroup_in_stack() }
+
+            { Fq2::toaltstack() }
+            { Fq2::toaltstack() }
+            { Fq::toaltstack() }
+            { Fq::toaltstack() }
+            { Fq::toaltstack() }
+            { Fq::toaltstack() }
+            // [L(B), public_inputs | wi_selector, c_inv, c, B, A, C]
+
+            // 1. msm of public inputs, p1 = gamma_abc_g1[0] + sum(public_inputs[i] * gamma_abc_g1[i + 1])
+            { msm_with_constant_bases_in_stack(&vk.gamma_abc_g1[1..], MSM_WINDOW) }
+            { curves::G1Projective::push(vk.gamma_abc_g1[0].into_group()) }
+            { curves::G1Projective::add() }
+            { curves::G1Projective::into_affine() }
+            { utils::from_eval_point_in_stack() }
+            // [L(B), P1']
+
+            // 2. constants
+            { constants() }
+            { Fq2::roll(6) }
+            // [L(B), beta_12, beta_13, beta_22, P1']
+
+            // 3. variants of G1 points, p2 = C, p3 = alpha, p4 = A
+            { Fq::fromaltstack() }
+            { Fq::fromaltstack() }
+            { Fq::copy(1) }
+            { Fq::copy(1) }
+            { curves::G1Affine::is_on_curve() }
+            OP_VERIFY
+            { utils::from_eval_point_in_stack() }
+
+            { utils::from_eval_point(vk.alpha_g1) }
+
+            { Fq::fromaltstack() }
+            { Fq::fromaltstack() }
+            { Fq::copy(1) }
+            { Fq::copy(1) }
+            { curves::G1Affine::is_on_curve() }
+            OP_VERIFY
+            { utils::from_eval_point_in_stack() }
+            // [L(B), beta_12, beta_13, beta_22, P1', P2', P3', P4' | wi_selector, c_inv, c, B]
+
+            // 4. the only non-fixed G2 point, q4 = B
+            { Fq2::fromaltstack() }
+            { Fq2::fromaltstack() }
+
+            // 5. hints for verifying final exp, check c * c_inv = 1
+            { Fq12::fromaltstack() }
+            { Fq12::fromaltstack() }
+            { Fq12::copy(12) }
+            { Fq12::copy(12) }
+            { Fq12::mul(12, 0) }
+            { Fq12::push_one() }
+            { Fq12::equalverify() }
+
+            // wi is either w or w^2
+            OP_FROMALTSTACK
+            OP_IF
+                { fq12_push(w) }
+            OP_ELSE
+                { fq12_push(w * w) }
+            OP_ENDIF
+            // [L(B), beta_12, beta_13, beta_22, P1', P2', P3', P4', Q4, c, c_inv, wi]
+
+            // expected result of the miller loop, c^{p^3}
+            { Fq12::copy(24) }
+            { Fq12::frobenius_map(3) }
+            { Fq12::toaltstack() }
+
+            // accumulator of q4, say t4
+            { Fq2::copy(38) }
+            { Fq2::copy(38) }
+            // [L(B), beta_12, beta_13, beta_22, P1', P2', P3', P4', Q4, c, c_inv, wi, T4 | c^{p^3}]
+
+            // 6. verify pairing
+            { Pairing::quad_miller_loop_with_c_wi_non_fixed(q_prepared) }
+            { Fq12::fromaltstack() }
+            { Fq12::equalverify() }
+            OP_TRUE
+        }
+    }
+
+    // Witness to unlock `lock_script`, pushes [L(B), public_inputs, C, A, S(B), B, c, c_inv, wi_selector]
+    //
+    // The verifying key is only needed to compute the miller loop hints c and wi
+    pub fn witness(
+        public_inputs: &Vec<<Bn254 as ark_Pairing>::ScalarField>,
+        proof: &Proof<Bn254>,
+        vk: &VerifyingKey<Bn254>,
+    ) -> Script {
+        assert_eq!(public_inputs.len() + 1, vk.gamma_abc_g1.len());
+
+        let scalars = [
+            vec![<Bn254 as ark_Pairing>::ScalarField::ONE],
+            public_inputs.clone(),
+        ]
+        .concat();
+        let msm_g1 =
+            G1Projective::msm(&vk.gamma_abc_g1, &scalars).expect("failed to calculate msm");
+
+        // G1/G2 points for pairings
+        let (p1, p2, p3, p4) = (msm_g1.into_affine(), proof.c, vk.alpha_g1, proof.a);
+        let (q1, q2, q3, q4) = (
+            vk.gamma_g2.into_group().neg().into_affine(),
+            vk.delta_g2.into_group().neg().into_affine(),
+            -vk.beta_g2,
+            proof.b,
+        );
+
+        // hint from arkworks
+        let f = Bn254::multi_miller_loop_affine([p1, p2, p3, p4], [q1, q2, q3, q4]).0;
+        let (c, wi) = compute_c_wi(f);
+        let wi_selector = if wi == compute_w() { 1 } else { 0 };
+
+        Self::witness_with_hints(public_inputs, proof, c, wi_selector)
+    }
+
+    // Same as `witness`, but with the final exponentiation hints c and wi_selector given
+    pub(crate) fn witness_with_hints(
+        public_inputs: &Vec<<Bn254 as ark_Pairing>::ScalarField>,
+        proof: &Proof<Bn254>,
+        c: ark_bn254::Fq12,
+        wi_selector: u32,
+    ) -> Script {
+        let c_inv = c.inverse().unwrap();
+
+        script! {
+            { utils::non_fixed_line_coeffs_push(&G2Prepared::from_affine(proof.b)) }
+
+            for public_input in public_inputs {
+                { Fr::push_u32_le(&BigUint::from(*public_input).to_u32_digits()) }
+            }
+
+            { Fq::push_u32_le(&BigUint::from(proof.c.x).to_u32_digits()) }
+            { Fq::push_u32_le(&BigUint::from(proof.c.y).to_u32_digits()) }
+            { Fq::push_u32_le(&BigUint::from(proof.a.x).to_u32_digits()) }
+            { Fq::push_u32_le(&BigUint::from(proof.a.y).to_u32_digits()) }
+            { utils::g2_subgroup_hints_push(proof.b) }
+            { utils::fq2_push(proof.b.x) }
+            { utils::fq2_push(proof.b.y) }
+
+            { utils::fq12_push(c) }
+            { utils::fq12_push(c_inv) }
+            { wi_selector }
+        }
+    }
 }
 
 // Groth16's pairing verifier
@@ -452,6 +452,234 @@ pub fn blake3_160_var_length(num_bytes: usize) -> Script {
     }
 }
 
+//
+// Streaming Blake3
+//
+
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+
+// Compress the 64-byte block right below the XOR table.
+// The chaining value is taken from the altstack, or is the IV at the start of a chunk.
+// The block is dropped and the new chaining value is put onto the altstack.
+fn compress_block(cv_from_altstack: bool, counter: u32, block_len: u32, flags: u32) -> Script {
+    let mut env = ptr_init();
+    script! {
+        // Push the second half of the state
+        { u32_push(flags) }
+        { u32_push(block_len) }
+        { u32_push(0) }
+        { u32_push(counter) }
+        for i in (0..4).rev() {
+            { u32_push(IV[i]) }
+        }
+
+        // Push the chaining value on top of it
+        if cv_from_altstack {
+            for _ in 0..8 {
+                { u32_fromaltstack() }
+            }
+            for i in 1..8 {
+                { u32_roll(i) }
+            }
+        } else {
+            for i in (0..8).rev() {
+                { u32_push(IV[i]) }
+            }
+        }
+
+        { compress(&mut env, 16) }
+
+        // Drop the consumed block
+        { 321 }
+        for _ in 0..63 {
+            OP_DUP OP_ROLL OP_DROP
+        }
+        OP_1SUB OP_ROLL OP_DROP
+
+        // Save the chaining value
+        for _ in 0..8 {
+            { u32_toaltstack() }
+        }
+
+        // Clean up the other half of the state
+        for _ in 0..8 {
+            { u32_drop() }
+        }
+    }
+}
+
+// Merge the two chaining values on top of the altstack into their parent.
+// The right child is on top, the parent's chaining value replaces both.
+fn compress_parent(flags: u32) -> Script {
+    // The left child is on top of the right child, right below the state
+    let mut env: Env = Env::new();
+    for i in 0..16 {
+        env.insert(S(i), i);
+    }
+    for i in 0..8 {
+        env.insert(M(i), 16 + 7 - i);
+        env.insert(M(i + 8), 24 + 7 - i);
+    }
+
+    script! {
+        for _ in 0..16 {
+            { u32_fromaltstack() }
+        }
+
+        // Push the state, keyed with the IV
+        { u32_push(flags | PARENT) }
+        { u32_push(BLOCK_LEN as u32) }
+        { u32_push(0) }
+        { u32_push(0) }
+        for i in (0..4).rev() {
+            { u32_push(IV[i]) }
+        }
+        for i in (0..8).rev() {
+            { u32_push(IV[i]) }
+        }
+
+        { compress(&mut env, 32) }
+
+        // Save the chaining value
+        for _ in 0..8 {
+            { u32_toaltstack() }
+        }
+
+        // Clean up the other half of the state and both children
+        for _ in 0..24 {
+            { u32_drop() }
+        }
+    }
+}
+
+/// A resumable Blake3 hasher for messages of arbitrary (but known) length.
+///
+/// The message is consumed in 64-byte blocks, zero-padded to `num_blocks() * 64` bytes,
+/// with the first byte on top of the stack. Between calls the hasher state (the current
+/// chunk's chaining value and the stack of pending subtree chaining values) is kept in
+/// `state_len()` stack elements, so it can be carried across script chunks.
+#[derive(Debug, Clone)]
+pub struct Blake3Stream {
+    num_bytes: usize,
+    blocks_absorbed: usize,
+    cv_stack_len: usize,
+}
+
+impl Blake3Stream {
+    pub fn new(num_bytes: usize) -> Self {
+        Blake3Stream {
+            num_bytes,
+            blocks_absorbed: 0,
+            cv_stack_len: 0,
+        }
+    }
+
+    /// Number of 64-byte blocks of the padded message (an empty message is one block)
+    pub fn num_blocks(&self) -> usize { core::cmp::max(1, (self.num_bytes + BLOCK_LEN - 1) / BLOCK_LEN) }
+
+    pub fn remaining_blocks(&self) -> usize { self.num_blocks() - self.blocks_absorbed }
+
+    /// Number of stack elements occupied by the hasher state between calls
+    pub fn state_len(&self) -> usize {
+        let has_chunk_cv = self.blocks_absorbed % (CHUNK_LEN / BLOCK_LEN) != 0
+            || (self.blocks_absorbed > 0 && self.remaining_blocks() == 0);
+        (self.cv_stack_len + has_chunk_cv as usize) * 32
+    }
+
+    /// Absorb the next `num_blocks` blocks from the top of the stack, above the hasher state.
+    /// Every block is dropped as soon as it is compressed.
+    pub fn absorb(&mut self, num_blocks: usize) -> Script {
+        assert!(num_blocks > 0 && num_blocks <= self.remaining_blocks(),
+                "Cannot absorb {} blocks, {} remaining", num_blocks, self.remaining_blocks());
+
+        let state_len = self.state_len();
+        let blocks_per_chunk = CHUNK_LEN / BLOCK_LEN;
+
+        let mut compressions = Vec::new();
+        for _ in 0..num_blocks {
+            let block = self.blocks_absorbed;
+            let chunk = block / blocks_per_chunk;
+            let is_last = block == self.num_blocks() - 1;
+
+            let block_len = if is_last { self.num_bytes - block * BLOCK_LEN } else { BLOCK_LEN };
+            let mut flags = 0;
+            if block % blocks_per_chunk == 0 {
+                flags |= CHUNK_START;
+            }
+            if block % blocks_per_chunk == blocks_per_chunk - 1 || is_last {
+                flags |= CHUNK_END;
+            }
+            if is_last && chunk == 0 {
+                flags |= ROOT;
+            }
+
+            compressions.push(compress_block(
+                block % blocks_per_chunk != 0,
+                chunk as u32,
+                block_len as u32,
+                flags,
+            ));
+            self.blocks_absorbed += 1;
+
+            // A completed chunk which is not the last one joins the tree,
+            // merging with the subtrees of the same size
+            if flags & CHUNK_END != 0 && !is_last {
+                let mut total_chunks = chunk + 1;
+                while total_chunks & 1 == 0 {
+                    compressions.push(compress_parent(0));
+                    self.cv_stack_len -= 1;
+                    total_chunks >>= 1;
+                }
+                self.cv_stack_len += 1;
+            }
+        }
+
+        script! {
+            // Move the hasher state to the altstack, the current chaining value on top
+            for _ in 0..state_len {
+                { BLOCK_LEN * num_blocks } OP_ROLL OP_TOALTSTACK
+            }
+
+            u8_push_xor_table
+            { compressions }
+            u8_drop_xor_table
+
+            for _ in 0..self.state_len() {
+                OP_FROMALTSTACK
+            }
+        }
+    }
+
+    /// Merge the pending subtrees into the root and leave the 32-byte digest on the stack.
+    pub fn finalize(&self) -> Script {
+        assert_eq!(self.remaining_blocks(), 0, "All blocks must be absorbed before finalizing");
+
+        script! {
+            if self.cv_stack_len > 0 {
+                for _ in 0..self.state_len() {
+                    OP_TOALTSTACK
+                }
+
+                u8_push_xor_table
+                for i in 0..self.cv_stack_len {
+                    { compress_parent(if i == self.cv_stack_len - 1 { ROOT } else { 0 }) }
+                }
+                u8_drop_xor_table
+
+                for _ in 0..8 {
+                    { u32_fromaltstack() }
+                }
+            }
+        }
+    }
+}
+
 pub fn push_bytes_hex(hex: &str) -> Script {
     let hex: String = hex
         .chars()
@@ -572,6 +800,71 @@ mod tests {
         assert!(res.success);
     }
 
+    // Push the given blocks of the zero-padded message, the first byte on top
+    fn push_blocks(message: &[u8], blocks: std::ops::Range<usize>) -> Script {
+        let mut padded = message.to_vec();
+        padded.resize(core::cmp::max(1, (message.len() + 63) / 64) * 64, 0);
+        script! {
+            for byte in padded[blocks.start * 64..blocks.end * 64].iter().rev() {
+                { *byte as u32 }
+            }
+        }
+    }
+
+    fn blake3_stream_script(message: &[u8], blocks_per_call: usize) -> Script {
+        let mut stream = Blake3Stream::new(message.len());
+        let mut calls = Vec::new();
+        while stream.remaining_blocks() > 0 {
+            let start = stream.num_blocks() - stream.remaining_blocks();
+            let end = core::cmp::min(start + blocks_per_call, stream.num_blocks());
+            calls.push(push_blocks(message, start..end));
+            calls.push(stream.absorb(end - start));
+        }
+        script! {
+            { calls }
+            { stream.finalize() }
+            { push_bytes_hex(::blake3::hash(message).to_hex().as_str()) }
+            { blake3_hash_equalverify() }
+            OP_TRUE
+        }
+    }
+
+    #[test]
+    fn test_blake3_stream_single_chunk() {
+        for num_bytes in [0, 60, 64, 200] {
+            let message = (0..num_bytes).map(|i| (i * 7 + 3) as u8).collect::<Vec<_>>();
+            let res = execute_script(blake3_stream_script(&message, 4));
+            assert!(res.success, "num_bytes = {}", num_bytes);
+        }
+    }
+
+    #[test]
+    fn test_blake3_stream_multi_chunk() {
+        // One, two (partial and full), three and four chunks exercise both parent merges and
+        // the root merge
+        for num_bytes in [1024, 1100, 2048, 2560, 3100] {
+            let message = (0..num_bytes).map(|i| (i * 13 + 5) as u8).collect::<Vec<_>>();
+            let script = blake3_stream_script(&message, 4);
+            println!("Blake3 stream {} bytes size: {:?}", num_bytes, script.len());
+            let res = execute_script(script);
+            assert!(res.success, "num_bytes = {}", num_bytes);
+        }
+    }
+
+    #[test]
+    fn test_blake3_stream_state_len() {
+        let mut stream = Blake3Stream::new(3100);
+        assert_eq!(stream.num_blocks(), 49);
+        assert_eq!(stream.state_len(), 0);
+        let _ = stream.absorb(16);
+        assert_eq!(stream.state_len(), 32);
+        let _ = stream.absorb(17);
+        // The first two chunks merged, the third one is in progress
+        assert_eq!(stream.state_len(), 64);
+        let _ = stream.absorb(16);
+        assert_eq!(stream.state_len(), 96);
+    }
+
     #[test]
     fn test_blake3_160() {
         let hex_out = "290eef2c4633e64835e2ea6395e9fc3e8bf459a7";
@@ -1,5 +1,7 @@
 pub mod blake3;
 //pub mod blake3_u4;
+pub mod script_hasher;
 pub mod sha256;
 pub mod sha256_u4;
+pub mod transcript;
 //pub mod sha256_u4_stack;
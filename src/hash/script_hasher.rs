@@ -0,0 +1,205 @@
+use crate::hash::{blake3, sha256, sha256_u4};
+use crate::treepp::{script, Script};
+use sha2::{Digest, Sha256 as Sha256Reference};
+
+// Note: `blake3_u4` and `sha256_u4_stack` are not part of the build yet, so they have no hasher.
+
+/// How a byte string is laid out on the stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackLayout {
+    /// One byte per element, the first byte on top
+    Bytes,
+    /// Two nibbles per byte (high nibble first), the first nibble deepest
+    Nibbles,
+    /// Little-endian u32 words with their least significant byte on top, the last word on top
+    U32Words,
+}
+
+impl StackLayout {
+    /// Number of stack elements used to store `num_bytes` bytes
+    pub fn num_elements(&self, num_bytes: usize) -> usize {
+        match self {
+            StackLayout::Bytes | StackLayout::U32Words => num_bytes,
+            StackLayout::Nibbles => num_bytes * 2,
+        }
+    }
+
+    pub fn push(&self, bytes: &[u8]) -> Script {
+        match self {
+            StackLayout::Bytes => script! {
+                for byte in bytes.iter().rev() {
+                    { *byte as u32 }
+                }
+            },
+            StackLayout::Nibbles => script! {
+                for byte in bytes {
+                    { (*byte >> 4) as u32 }
+                    { (*byte & 0xf) as u32 }
+                }
+            },
+            StackLayout::U32Words => {
+                assert_eq!(bytes.len() % 4, 0, "U32Words layout requires whole words");
+                script! {
+                    for word in bytes.chunks(4) {
+                        for byte in word.iter().rev() {
+                            { *byte as u32 }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A hash function implemented in script, together with its stack requirements
+pub trait ScriptHasher {
+    const NAME: &'static str;
+
+    /// Size of the digest in bytes
+    const DIGEST_LEN: usize;
+
+    /// Layout of the message expected on top of the stack
+    const INPUT_LAYOUT: StackLayout;
+
+    /// Layout of the digest left on top of the stack
+    const OUTPUT_LAYOUT: StackLayout;
+
+    /// Number of stack elements temporarily taken by lookup tables, 0 if none are needed
+    const LOOKUP_TABLES_SIZE: usize;
+
+    /// Largest message (in bytes) known to fit into the stack limit
+    const MAX_INPUT_LEN: usize;
+
+    /// Hash the message of `num_bytes` bytes on top of the stack, replacing it with the digest
+    fn hash(num_bytes: usize) -> Script;
+
+    /// Off-chain reference implementation
+    fn reference_hash(message: &[u8]) -> Vec<u8>;
+
+    fn push_input(message: &[u8]) -> Script { Self::INPUT_LAYOUT.push(message) }
+
+    fn push_digest(digest: &[u8]) -> Script {
+        assert_eq!(digest.len(), Self::DIGEST_LEN);
+        Self::OUTPUT_LAYOUT.push(digest)
+    }
+
+    /// Fail unless the two digests on top of the stack are equal
+    fn digest_equalverify() -> Script {
+        let len = Self::OUTPUT_LAYOUT.num_elements(Self::DIGEST_LEN);
+        script! {
+            for i in 0..len {
+                { len - i } OP_ROLL OP_EQUALVERIFY
+            }
+        }
+    }
+
+    fn script_size(num_bytes: usize) -> usize { Self::hash(num_bytes).len() }
+}
+
+/// `sha256::sha256`, operating on bytes
+pub struct Sha256;
+
+impl ScriptHasher for Sha256 {
+    const NAME: &'static str = "sha256";
+    const DIGEST_LEN: usize = 32;
+    const INPUT_LAYOUT: StackLayout = StackLayout::Bytes;
+    const OUTPUT_LAYOUT: StackLayout = StackLayout::Bytes;
+    // The u8 XOR table and the round constants
+    const LOOKUP_TABLES_SIZE: usize = 256 + 64 * 4;
+    // Longer messages are hashed chunk by chunk too but no longer fit into the stack limit
+    const MAX_INPUT_LEN: usize = sha256::SHA256_MAX_BYTES;
+
+    fn hash(num_bytes: usize) -> Script {
+        assert!(num_bytes <= Self::MAX_INPUT_LEN);
+        sha256::sha256(num_bytes)
+    }
+
+    fn reference_hash(message: &[u8]) -> Vec<u8> { Sha256Reference::digest(message).to_vec() }
+}
+
+/// `sha256_u4::sha256`, operating on nibbles
+pub struct Sha256U4;
+
+impl ScriptHasher for Sha256U4 {
+    const NAME: &'static str = "sha256_u4";
+    const DIGEST_LEN: usize = 32;
+    const INPUT_LAYOUT: StackLayout = StackLayout::Nibbles;
+    const OUTPUT_LAYOUT: StackLayout = StackLayout::Nibbles;
+    // The rrot, half XOR/AND and half lookup tables, plus the add tables for single-block messages
+    const LOOKUP_TABLES_SIZE: usize = 96 + 136 + 16 + 130;
+    // A bitcoin block header
+    const MAX_INPUT_LEN: usize = 80;
+
+    fn hash(num_bytes: usize) -> Script {
+        assert!(num_bytes <= Self::MAX_INPUT_LEN);
+        sha256_u4::sha256(num_bytes as u32)
+    }
+
+    fn reference_hash(message: &[u8]) -> Vec<u8> { Sha256Reference::digest(message).to_vec() }
+}
+
+/// `blake3::blake3_var_length`, operating on bytes
+pub struct Blake3;
+
+impl ScriptHasher for Blake3 {
+    const NAME: &'static str = "blake3";
+    const DIGEST_LEN: usize = 32;
+    const INPUT_LAYOUT: StackLayout = StackLayout::Bytes;
+    const OUTPUT_LAYOUT: StackLayout = StackLayout::U32Words;
+    // The u8 XOR table
+    const LOOKUP_TABLES_SIZE: usize = 256;
+    const MAX_INPUT_LEN: usize = 512;
+
+    fn hash(num_bytes: usize) -> Script { blake3::blake3_var_length(num_bytes) }
+
+    fn reference_hash(message: &[u8]) -> Vec<u8> { ::blake3::hash(message).as_bytes().to_vec() }
+}
+
+/// `blake3::blake3_160_var_length`, blake3 truncated to 20 bytes as used by Winternitz hash signatures
+#[allow(non_camel_case_types)]
+pub struct Blake3_160;
+
+impl ScriptHasher for Blake3_160 {
+    const NAME: &'static str = "blake3_160";
+    const DIGEST_LEN: usize = 20;
+    const INPUT_LAYOUT: StackLayout = StackLayout::Bytes;
+    const OUTPUT_LAYOUT: StackLayout = StackLayout::U32Words;
+    const LOOKUP_TABLES_SIZE: usize = 256;
+    const MAX_INPUT_LEN: usize = 512;
+
+    fn hash(num_bytes: usize) -> Script { blake3::blake3_160_var_length(num_bytes) }
+
+    fn reference_hash(message: &[u8]) -> Vec<u8> { ::blake3::hash(message).as_bytes()[..20].to_vec() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::treepp::*;
+
+    fn check_hasher<H: ScriptHasher>(num_bytes: usize) {
+        let message = (0..num_bytes).map(|i| (i * 31 + 7) as u8).collect::<Vec<_>>();
+        let script = script! {
+            { H::push_input(&message) }
+            { H::hash(num_bytes) }
+            { H::push_digest(&H::reference_hash(&message)) }
+            { H::digest_equalverify() }
+            OP_TRUE
+        };
+        println!("{}({}): {} bytes", H::NAME, num_bytes, H::script_size(num_bytes));
+        let res = execute_script(script);
+        assert!(res.success, "{} failed for {} bytes", H::NAME, num_bytes);
+        assert_eq!(res.final_stack.len(), 1);
+    }
+
+    #[test]
+    fn test_script_hashers() {
+        for num_bytes in [32, 60, 80] {
+            check_hasher::<Sha256>(num_bytes);
+            check_hasher::<Sha256U4>(num_bytes);
+            check_hasher::<Blake3>(num_bytes);
+            check_hasher::<Blake3_160>(num_bytes);
+        }
+        check_hasher::<Sha256>(Sha256::MAX_INPUT_LEN);
+    }
+}
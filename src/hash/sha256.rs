@@ -27,6 +27,8 @@ const INITSTATE: [u32; 8] = [
 ];
 
 /// sha256 take indefinite length input on the top of stack and return 256 bit (64 byte)
+/// The message waits on the altstack and only the 64-byte chunk being compressed is moved to
+/// the stack, see `SHA256_MAX_BYTES` for the longest message fitting into the stack limit
 pub fn sha256(num_bytes: usize) -> Script {
     if num_bytes == 32 {
         return sha256_32bytes();
@@ -34,32 +36,29 @@ pub fn sha256(num_bytes: usize) -> Script {
     if num_bytes == 80 {
         return sha256_80bytes();
     }
-    let mut chunks_size: usize = num_bytes / 64 + 1;
-    if (num_bytes % 64) > 55 {
-        chunks_size += 1;
-    }
+    let chunks_size = sha256_chunks(num_bytes);
 
     script! {
         {push_reverse_bytes_to_alt(num_bytes)}
 
-        // top of stack: [ [n bytes input] ]
+        // top of stack: [] | [n bytes input]
         {u8_push_xor_table()}
-        {sha256_k()}
-        // top of stack: [ [64 byte chunks]... ]
-        {padding_add_roll(num_bytes)}
         {sha256_init()}
-        // top of stack: [ [64 byte chunks]..., state[0-7]]
+        // top of stack: [state[0-7]] | [n bytes input]
         for i in 0..chunks_size {
-            {sha256_transform(8 + ((chunks_size as u32) - i as u32) *16 + 64 + 1, 8 + ((chunks_size as u32) - i as u32)  *16)}
+            {padding_chunk_add_roll(num_bytes, i)}
+            // move the state above the 64 byte chunk
+            for _ in 0..8 {
+                {u32_roll(16 + 7)}
+            }
+            // top of stack: [64 byte chunk, state[0-7]] | [remaining bytes]
+            {sha256_transform_with_constants(8 + 16 + 1)}
         }
 
         {sha256_final()}
         for _ in 0..8 {
             {u32_toaltstack()}
         }
-        for _ in 0..64 {
-            {u32_drop()}
-        }
         {u8_drop_xor_table()}
 
         for _ in 0..8 {
@@ -68,6 +67,15 @@ pub fn sha256(num_bytes: usize) -> Script {
     }
 }
 
+/// Number of 64 byte chunks of the padded message
+fn sha256_chunks(num_bytes: usize) -> usize {
+    let mut chunks_size: usize = num_bytes / 64 + 1;
+    if (num_bytes % 64) > 55 {
+        chunks_size += 1;
+    }
+    chunks_size
+}
+
 pub fn sha256_32bytes() -> Script {
     script! {
         {push_reverse_bytes_to_alt(32)}
@@ -127,6 +135,29 @@ pub fn sha256_80bytes() -> Script {
     }
 }
 
+/// The largest message `sha256` can hash within the stack limit: the unprocessed bytes wait on
+/// the altstack next to the XOR table, the message schedule and the chunk being compressed,
+/// which leaves room for four full chunks and the padded last one
+pub const SHA256_MAX_BYTES: usize = 4 * 64 + 55;
+
+/// Double sha256 of an 80-byte block header, i.e. the block hash in internal byte order
+pub fn sha256d_80bytes() -> Script {
+    script! {
+        {sha256_80bytes()}
+        {sha256_32bytes()}
+    }
+}
+
+/// Double sha256 of a message of fixed length, e.g. the txid of a transaction serialized without witness
+pub fn sha256d(num_bytes: usize) -> Script {
+    assert!(num_bytes <= SHA256_MAX_BYTES,
+            "sha256d supports at most {} bytes due to the stack limit", SHA256_MAX_BYTES);
+    script! {
+        {sha256(num_bytes)}
+        {sha256_32bytes()}
+    }
+}
+
 /// reorder bytes for u32
 pub fn padding_add_roll(num_bytes: usize) -> Script {
     assert!(num_bytes < 512);
@@ -153,6 +184,34 @@ pub fn padding_add_roll(num_bytes: usize) -> Script {
     }
 }
 
+/// Pull the `chunk`-th 64 byte chunk of the padded message from the altstack and reorder it
+/// for u32, the padding is added to the chunks it falls into
+pub fn padding_chunk_add_roll(num_bytes: usize, chunk: usize) -> Script {
+    let start = chunk * 64;
+    let message_num = num_bytes.saturating_sub(start).min(64);
+    let has_separator = start <= num_bytes && num_bytes < start + 64;
+    let is_last = chunk + 1 == sha256_chunks(num_bytes);
+    let padding_num = 64 - message_num - has_separator as usize - if is_last { 8 } else { 0 };
+
+    script! {
+        for _ in 0..message_num {
+            OP_FROMALTSTACK
+        }
+        if has_separator {
+            {0x80}
+        }
+        {push_to_stack(0, padding_num)}
+        if is_last {
+            {u32_push(0)}
+            {u32_push((num_bytes as u32) * 8)}
+        }
+
+        for i in 1..16 {
+            {u32_roll(i)}
+        }
+    }
+}
+
 /// push all init state into stack
 pub fn sha256_init() -> Vec<Script> {
     let mut state: [u32; 8] = INITSTATE;
@@ -186,6 +245,18 @@ pub fn sha256_k() -> Vec<Script> {
 /// intput: [m[15], m[14], ..., m[0], state[7], state[6], ..., state[0]]
 /// output: [state[7], state[6], ..., state[0]]
 pub fn sha256_transform(xor_depth: u32, k_depth: u32) -> Script {
+    transform(xor_depth, Some(k_depth))
+}
+
+/// sha256 transform pushing the round constants instead of picking them from `sha256_k`,
+/// which costs about the same script size and saves the stack space of the table
+pub fn sha256_transform_with_constants(xor_depth: u32) -> Script { transform(xor_depth, None) }
+
+fn transform(xor_depth: u32, k_depth: Option<u32>) -> Script {
+    let push_k = |i: u32| match k_depth {
+        Some(k_depth) => u32_pick(k_depth + 44 + i),
+        None => u32_push(K[i as usize]),
+    };
     script! {
         // push old state to alt stack
         for _ in 0..8 {
@@ -243,7 +314,7 @@ pub fn sha256_transform(xor_depth: u32, k_depth: u32) -> Script {
             {ch(1, 2, 3, xor_depth+44)}
             {u32_add_drop(0, 1)}
 
-            {u32_pick(k_depth+44+i)} // pick k
+            {push_k(i)}
             {u32_add_drop(0, 1)}
 
             {u32_pick(4+63-i)} // pick m
@@ -916,8 +987,15 @@ pub fn maj(x: u32, y: u32, z: u32, stack_depth: u32) -> Script {
 mod tests {
     use crate::hash::blake3::push_bytes_hex;
     use crate::hash::sha256::*;
-    use crate::treepp::{execute_script, script};
+    use crate::treepp::{execute_script, script, Script};
     use crate::u32::u32_std::{u32_equal, u32_equalverify};
+    use bitcoin::blockdata::constants::genesis_block;
+    use bitcoin::consensus::serialize;
+    use bitcoin::hashes::Hash;
+    use bitcoin::{
+        absolute, transaction, Amount, Network, OutPoint, ScriptBuf, Sequence, Transaction, TxIn,
+        TxOut, Txid, Witness,
+    };
     use sha2::{Digest, Sha256};
 
     fn rrot(x: u32, n: usize) -> u32 {
@@ -968,6 +1046,94 @@ mod tests {
         assert!(res.success);
     }
 
+    #[test]
+    fn test_sha256_multiple_chunks() {
+        for num_bytes in [0, 55, 56, 64, 119, 120, 200, SHA256_MAX_BYTES] {
+            let data = (0..num_bytes).map(|i| (i * 7 + 3) as u8).collect::<Vec<_>>();
+            let digest = Sha256::digest(&data);
+            let script = script! {
+                {push_bytes_hex(&hex::encode(&data))}
+                {sha256(num_bytes)}
+                {push_bytes_hex(&hex::encode(digest))}
+                {digest_equalverify()}
+                OP_TRUE
+            };
+            println!("sha256({}): {} bytes", num_bytes, sha256(num_bytes).len());
+            let res = execute_script(script);
+            assert!(res.success, "sha256 failed for {} bytes", num_bytes);
+        }
+    }
+
+    // Fail unless the two 32-byte digests on top of the stack are equal
+    fn digest_equalverify() -> Script {
+        script! {
+            for i in 0..32 {
+                {32 - i} OP_ROLL OP_EQUALVERIFY
+            }
+        }
+    }
+
+    #[test]
+    fn test_sha256d_80bytes() {
+        let mut header = genesis_block(Network::Bitcoin).header;
+        header.nonce += 1;
+        header.time += 600;
+        let bytes = serialize(&header);
+        assert_eq!(bytes.len(), 80);
+
+        let script = script! {
+            {push_bytes_hex(&hex::encode(&bytes))}
+            {sha256d_80bytes()}
+            {push_bytes_hex(&hex::encode(header.block_hash().to_byte_array()))}
+            {digest_equalverify()}
+            OP_TRUE
+        };
+        println!("sha256d_80bytes: {} bytes", sha256d_80bytes().len());
+        let res = execute_script(script);
+        assert!(res.success);
+    }
+
+    #[test]
+    fn test_sha256d_txid() {
+        // the second transaction needs more than two chunks
+        for (num_inputs, num_outputs) in [(1, 1), (3, 2)] {
+            let tx = Transaction {
+                version: transaction::Version::TWO,
+                lock_time: absolute::LockTime::ZERO,
+                input: (0..num_inputs)
+                    .map(|vout| TxIn {
+                        previous_output: OutPoint::new(Txid::from_byte_array([7; 32]), vout),
+                        script_sig: ScriptBuf::new(),
+                        sequence: Sequence::MAX,
+                        witness: Witness::new(),
+                    })
+                    .collect(),
+                output: vec![
+                    TxOut {
+                        value: Amount::from_sat(100_000),
+                        script_pubkey: ScriptBuf::from_bytes(
+                            [vec![0x51, 0x20], vec![0x42; 32]].concat(),
+                        ),
+                    };
+                    num_outputs
+                ],
+            };
+            let bytes = serialize(&tx);
+            assert!(bytes.len() <= SHA256_MAX_BYTES);
+
+            let script = script! {
+                {push_bytes_hex(&hex::encode(&bytes))}
+                {sha256d(bytes.len())}
+                {push_bytes_hex(&hex::encode(tx.compute_txid().to_byte_array()))}
+                {digest_equalverify()}
+                OP_TRUE
+            };
+            println!("sha256d({}): {} bytes", bytes.len(), sha256d(bytes.len()).len());
+            let res = execute_script(script);
+            assert!(res.success);
+        }
+    }
+
     #[test]
     fn test_padding_add_roll() {
         let hex_in = "6162636462636465636465666465666765666768666768696768696a68696a6b696a6b6c6a6b6c6d6b6c6d6e6c6d6e6f6d6e6f706e6f7071";
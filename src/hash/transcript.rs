@@ -0,0 +1,235 @@
+use std::marker::PhantomData;
+
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+
+use crate::bn254::curves::G1Affine;
+use crate::bn254::fp254impl::Fp254Impl;
+use crate::bn254::fq::Fq;
+use crate::bn254::fr::Fr;
+use crate::hash::script_hasher::{ScriptHasher, StackLayout};
+use crate::treepp::{script, Script};
+
+// Field elements are absorbed as 32 big-endian bytes, G1 points are compressed with the
+// sign of y in the most significant bit, as in the transcript of snarkjs.
+const ELEMENT_LEN: usize = 32;
+
+fn check_hasher<H: ScriptHasher>() {
+    assert_eq!(H::INPUT_LAYOUT, StackLayout::Bytes, "{} does not hash bytes", H::NAME);
+    assert_eq!(H::DIGEST_LEN, 32, "{} does not have a 32-byte digest", H::NAME);
+}
+
+// Rearrange the digest on top of the stack into the layout of `Fp254Impl::from_hash`
+fn digest_to_u32_words(layout: StackLayout) -> Script {
+    match layout {
+        StackLayout::U32Words => script! {},
+        StackLayout::Bytes => {
+            // Byte i is at depth i. Roll the bytes to the top in the order of the words,
+            // the least significant byte of each word last.
+            let mut stack: Vec<usize> = (0..ELEMENT_LEN).rev().collect();
+            let mut rolls = Vec::new();
+            for word in 0..ELEMENT_LEN / 4 {
+                for byte in (0..4).rev() {
+                    let index = stack.iter().position(|b| *b == 4 * word + byte).unwrap();
+                    rolls.push(stack.len() - 1 - index);
+                    let b = stack.remove(index);
+                    stack.push(b);
+                }
+            }
+            script! {
+                for depth in rolls {
+                    { depth } OP_ROLL
+                }
+            }
+        }
+        StackLayout::Nibbles => unreachable!("hashers operating on nibbles are rejected"),
+    }
+}
+
+/// Fiat-Shamir transcript in script.
+/// Absorbed elements are kept as bytes on the altstack until the next challenge is squeezed,
+/// so the altstack has to be balanced in between.
+pub struct Transcript<H: ScriptHasher> {
+    num_bytes: usize,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ScriptHasher> Default for Transcript<H> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<H: ScriptHasher> Transcript<H> {
+    pub fn new() -> Self {
+        check_hasher::<H>();
+        Transcript {
+            num_bytes: 0,
+            _hasher: PhantomData,
+        }
+    }
+
+    fn absorb_bytes(&mut self, to_bytes: Script) -> Script {
+        self.num_bytes += ELEMENT_LEN;
+        assert!(self.num_bytes <= H::MAX_INPUT_LEN,
+                "{} cannot hash {} bytes", H::NAME, self.num_bytes);
+        script! {
+            { to_bytes }
+            for _ in 0..ELEMENT_LEN {
+                OP_TOALTSTACK
+            }
+        }
+    }
+
+    /// Absorb the Fq element on top of the stack
+    pub fn absorb_fq(&mut self) -> Script { self.absorb_bytes(Fq::convert_to_be_bytes()) }
+
+    /// Absorb the Fr element on top of the stack
+    pub fn absorb_fr(&mut self) -> Script { self.absorb_bytes(Fr::convert_to_be_bytes()) }
+
+    /// Absorb the affine G1 point (x, y) on top of the stack
+    pub fn absorb_g1(&mut self) -> Script { self.absorb_bytes(G1Affine::convert_to_compressed()) }
+
+    /// Hash everything absorbed so far into an Fr challenge left on top of the stack,
+    /// then start over. Chaining challenges is done by absorbing a copy of the last one.
+    pub fn squeeze_challenge(&mut self) -> Script {
+        assert!(self.num_bytes > 0, "Nothing to squeeze");
+        let num_bytes = std::mem::take(&mut self.num_bytes);
+        script! {
+            for _ in 0..num_bytes {
+                OP_FROMALTSTACK
+            }
+            { H::hash(num_bytes) }
+            { digest_to_u32_words(H::OUTPUT_LAYOUT) }
+            { Fr::from_hash() }
+        }
+    }
+}
+
+/// Off-chain counterpart of `Transcript`, producing the same challenges
+pub struct NativeTranscript<H: ScriptHasher> {
+    bytes: Vec<u8>,
+    _hasher: PhantomData<H>,
+}
+
+impl<H: ScriptHasher> Default for NativeTranscript<H> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<H: ScriptHasher> NativeTranscript<H> {
+    pub fn new() -> Self {
+        check_hasher::<H>();
+        NativeTranscript {
+            bytes: vec![],
+            _hasher: PhantomData,
+        }
+    }
+
+    fn element_bytes<F: PrimeField>(x: F) -> Vec<u8> {
+        let bytes = BigUint::from(x.into_bigint()).to_bytes_be();
+        let mut padded = vec![0_u8; ELEMENT_LEN - bytes.len()];
+        padded.extend(bytes);
+        padded
+    }
+
+    pub fn absorb_fq(&mut self, x: ark_bn254::Fq) { self.bytes.extend(Self::element_bytes(x)); }
+
+    pub fn absorb_fr(&mut self, x: ark_bn254::Fr) { self.bytes.extend(Self::element_bytes(x)); }
+
+    pub fn absorb_g1(&mut self, p: ark_bn254::G1Affine) {
+        let mut bytes = Self::element_bytes(p.x);
+        let y: BigUint = p.y.into();
+        let modulus: BigUint = ark_bn254::Fq::MODULUS.into();
+        if y >= (modulus + 1_u32) / 2_u32 {
+            bytes[0] += 0x80;
+        }
+        self.bytes.extend(bytes);
+    }
+
+    pub fn squeeze_challenge(&mut self) -> ark_bn254::Fr {
+        let digest = H::reference_hash(&std::mem::take(&mut self.bytes));
+        ark_bn254::Fr::from_be_bytes_mod_order(&digest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hash::script_hasher::{Blake3, Sha256};
+    use crate::treepp::*;
+    use ark_std::UniformRand;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn check_transcript<H: ScriptHasher>(p: ark_bn254::G1Affine, a: ark_bn254::Fr, b: ark_bn254::Fq) {
+        let mut native = NativeTranscript::<H>::new();
+        native.absorb_g1(p);
+        native.absorb_fr(a);
+        native.absorb_fq(b);
+        let c1 = native.squeeze_challenge();
+        native.absorb_fr(c1);
+        let c2 = native.squeeze_challenge();
+
+        let mut transcript = Transcript::<H>::new();
+        let script = script! {
+            { Fq::push_u32_le(&BigUint::from(p.x).to_u32_digits()) }
+            { Fq::push_u32_le(&BigUint::from(p.y).to_u32_digits()) }
+            { transcript.absorb_g1() }
+            { Fr::push_u32_le(&BigUint::from(a).to_u32_digits()) }
+            { transcript.absorb_fr() }
+            { Fq::push_u32_le(&BigUint::from(b).to_u32_digits()) }
+            { transcript.absorb_fq() }
+            { transcript.squeeze_challenge() }
+            { Fr::copy(0) }
+            { transcript.absorb_fr() }
+            { transcript.squeeze_challenge() }
+
+            { Fr::push_u32_le(&BigUint::from(c2).to_u32_digits()) }
+            { Fr::equalverify(1, 0) }
+            { Fr::push_u32_le(&BigUint::from(c1).to_u32_digits()) }
+            { Fr::equal(1, 0) }
+        };
+        println!("transcript with {}: {} bytes", H::NAME, script.len());
+        let res = execute_script(script);
+        assert!(res.success, "{}", H::NAME);
+    }
+
+    #[test]
+    fn test_transcript() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let p = ark_bn254::G1Affine::rand(&mut prng);
+        let a = ark_bn254::Fr::rand(&mut prng);
+        let b = ark_bn254::Fq::rand(&mut prng);
+
+        check_transcript::<Blake3>(p, a, b);
+        check_transcript::<Sha256>(p, a, b);
+    }
+
+    #[test]
+    fn test_native_transcript_fflonk_beta() {
+        // beta of the fflonk verifier of snarkjs, see `fflonk::compute_challenges`
+        let c0 = ark_bn254::G1Affine::new(
+            "303039279492065453055049758769758984569666029850327527958551993331680103359".parse().unwrap(),
+            "15061669176783843627135305167141360334623983780813847469326507992811672859575".parse().unwrap(),
+        );
+        let c1 = ark_bn254::G1Affine::new(
+            "8993820735255461694205287896466659762517378169680151817278189507219986014273".parse().unwrap(),
+            "20608602847008036615737932995836476570376266531776948091942386633580114403199".parse().unwrap(),
+        );
+
+        let mut transcript = NativeTranscript::<Blake3>::new();
+        transcript.absorb_g1(c0);
+        transcript.absorb_fr("246513590391103489634602289097178521809".parse().unwrap());
+        transcript.absorb_fr("138371009144214353742010089705444713455".parse().unwrap());
+        transcript.absorb_g1(c1);
+        let beta = transcript.squeeze_challenge();
+        assert_eq!(
+            beta,
+            "485596931070696584921673007746559446164232583596250406637950679013042540061".parse().unwrap()
+        );
+
+        transcript.absorb_fr(beta);
+        assert_eq!(
+            transcript.squeeze_challenge(),
+            "19250037324033436581569284153336383290774316882310310865823706333327285195728".parse().unwrap()
+        );
+    }
+}
@@ -9,17 +9,23 @@ pub mod treepp {
 use core::fmt;
 use std::{cmp::min, fs::File, io::Write};
 
-use bitcoin::{hashes::Hash, hex::DisplayHex, Opcode, ScriptBuf, TapLeafHash, Transaction};
+use bitcoin::{
+    hashes::Hash, hex::DisplayHex, taproot::LeafVersion, Opcode, ScriptBuf, TapLeafHash,
+    Transaction, TxOut,
+};
 use bitcoin_scriptexec::{Exec, ExecCtx, ExecError, ExecStats, Options, Stack, TxTemplate};
 
 pub mod bigint;
 pub mod bn254;
 pub mod bridge;
+pub mod chunker;
+pub mod cost;
 pub mod fflonk;
 pub mod groth16;
 pub mod hash;
 pub mod pseudo;
 pub mod signatures;
+pub mod tracer;
 pub mod u32;
 pub mod u4;
 
@@ -105,7 +111,10 @@ impl fmt::Display for ExecuteInfo {
     }
 }
 
-pub fn execute_script(script: treepp::Script) -> ExecuteInfo {
+pub fn execute_script(script: treepp::Script) -> ExecuteInfo { execute_script_buf(script.compile()) }
+
+/// Execute an already compiled script, e.g. a leaf assembled from several parts
+pub fn execute_script_buf(script: ScriptBuf) -> ExecuteInfo {
     let mut exec = Exec::new(
         ExecCtx::Tapscript,
         Options::default(),
@@ -120,7 +129,7 @@ pub fn execute_script(script: treepp::Script) -> ExecuteInfo {
             input_idx: 0,
             taproot_annex_scriptleaf: Some((TapLeafHash::all_zeros(), None)),
         },
-        script.compile(),
+        script,
         vec![],
     )
     .expect("error creating exec");
@@ -141,6 +150,52 @@ pub fn execute_script(script: treepp::Script) -> ExecuteInfo {
     }
 }
 
+/// Execute a tapscript leaf spent by input `input_index` of `tx`, so that signatures and
+/// timelocks are checked against the transaction. `prevouts` are the outputs spent by all
+/// inputs of `tx`, and `witness` is the stack the leaf starts with, the top element last.
+pub fn execute_leaf(
+    tx: &Transaction,
+    input_index: usize,
+    prevouts: &[TxOut],
+    leaf_script: &ScriptBuf,
+    witness: Vec<Vec<u8>>,
+) -> ExecuteInfo {
+    assert_eq!(tx.input.len(), prevouts.len(), "one prevout per input is required");
+    let leaf_hash = TapLeafHash::from_script(leaf_script, LeafVersion::TapScript);
+    let annex = tx.input[input_index]
+        .witness
+        .taproot_annex()
+        .map(|annex| annex.to_vec());
+    let mut exec = Exec::new(
+        ExecCtx::Tapscript,
+        Options::default(),
+        TxTemplate {
+            tx: tx.clone(),
+            prevouts: prevouts.to_vec(),
+            input_idx: input_index,
+            taproot_annex_scriptleaf: Some((leaf_hash, annex)),
+        },
+        leaf_script.clone(),
+        witness,
+    )
+    .expect("error creating exec");
+
+    loop {
+        if exec.exec_next().is_err() {
+            break;
+        }
+    }
+    let res = exec.result().unwrap();
+    ExecuteInfo {
+        success: res.success,
+        error: res.error.clone(),
+        last_opcode: res.opcode,
+        final_stack: FmtStack(exec.stack().clone()),
+        remaining_script: exec.remaining_script().to_asm_string(),
+        stats: exec.stats().clone(),
+    }
+}
+
 pub fn run(script: treepp::Script) {
     let exec_result = execute_script(script);
     if !exec_result.success {
@@ -0,0 +1,273 @@
+//
+// Tracing executor
+//
+// Executes a script step by step, recording stack and altstack snapshots, and maps every
+// snapshot back to the gadget that emitted the instruction through a `SourceMap`.
+//
+
+use std::fs;
+use std::ops::Range;
+
+use bitcoin::hashes::Hash;
+use bitcoin::script::Instruction;
+use bitcoin::{ScriptBuf, TapLeafHash, Transaction};
+use bitcoin_scriptexec::{Exec, ExecCtx, Options, Stack, TxTemplate};
+use serde::{Deserialize, Serialize};
+
+use crate::treepp::Script;
+
+/// Byte ranges of a compiled script and the gadgets that emitted them
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    ranges: Vec<(Range<usize>, String)>,
+}
+
+impl SourceMap {
+    /// Path of the innermost gadgets covering `offset`, e.g. "pairing/fq12_mul/fq6_mul"
+    pub fn lookup(&self, offset: usize) -> Option<String> {
+        let names = self
+            .ranges
+            .iter()
+            .filter(|(range, _)| range.contains(&offset))
+            .map(|(_, name)| name.as_str())
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            None
+        } else {
+            Some(names.join("/"))
+        }
+    }
+}
+
+/// Script builder recording which gadget emitted which bytes
+#[derive(Default)]
+pub struct AnnotatedScript {
+    bytes: Vec<u8>,
+    source_map: SourceMap,
+}
+
+impl AnnotatedScript {
+    pub fn new() -> Self { Self::default() }
+
+    /// Append a gadget under `name`
+    pub fn push(mut self, name: &str, script: Script) -> Self {
+        let start = self.bytes.len();
+        self.bytes.extend(script.compile().as_bytes());
+        self.source_map.ranges.push((start..self.bytes.len(), name.to_string()));
+        self
+    }
+
+    /// Append an annotated script under `name`, keeping its own annotations nested below it
+    pub fn push_annotated(mut self, name: &str, script: AnnotatedScript) -> Self {
+        let start = self.bytes.len();
+        self.bytes.extend(script.bytes);
+        // Outer ranges come first so that lookups list the outermost gadget first
+        self.source_map.ranges.push((start..self.bytes.len(), name.to_string()));
+        for (range, inner) in script.source_map.ranges {
+            self.source_map.ranges.push((range.start + start..range.end + start, inner));
+        }
+        self
+    }
+
+    pub fn compile(&self) -> ScriptBuf { ScriptBuf::from_bytes(self.bytes.clone()) }
+
+    pub fn source_map(&self) -> &SourceMap { &self.source_map }
+}
+
+pub struct TraceOptions {
+    /// Take a snapshot every that many steps. The final state is always recorded.
+    pub snapshot_interval: usize,
+    pub enforce_stack_limit: bool,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        TraceOptions {
+            snapshot_interval: 1,
+            enforce_stack_limit: true,
+        }
+    }
+}
+
+/// State before executing `instruction`, or the final state if there is none left
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub step: usize,
+    /// Byte offset of the instruction in the script
+    pub offset: usize,
+    pub instruction: Option<String>,
+    pub gadget: Option<String>,
+    /// Stack elements as hex numbers as in `FmtStack`, the top last
+    pub stack: Vec<String>,
+    pub altstack: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub success: bool,
+    pub error: Option<String>,
+    /// Number of instructions executed successfully
+    pub num_steps: usize,
+    pub steps: Vec<TraceStep>,
+}
+
+impl Trace {
+    pub fn to_json(&self) -> String { serde_json::to_string_pretty(self).expect("Unable to serialize trace") }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid trace: {}", e))
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_json()).map_err(|e| format!("Unable to write {}: {}", path, e))
+    }
+
+    pub fn read(path: &str) -> Result<Self, String> {
+        Self::from_json(&fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path, e))?)
+    }
+
+    /// The last snapshot, i.e. where execution stopped
+    pub fn last(&self) -> &TraceStep { self.steps.last().expect("A trace has a final state") }
+}
+
+fn snapshot(stack: &Stack) -> Vec<String> {
+    stack
+        .iter_str()
+        .map(|mut item| {
+            item.reverse();
+            format!("0x{}", hex::encode(item))
+        })
+        .collect()
+}
+
+fn describe(instruction: Instruction) -> String {
+    match instruction {
+        Instruction::Op(opcode) => opcode.to_string(),
+        Instruction::PushBytes(bytes) if bytes.is_empty() => "OP_0".to_string(),
+        Instruction::PushBytes(bytes) => format!("<{}>", hex::encode(bytes.as_bytes())),
+    }
+}
+
+/// Execute `script`, recording snapshots and the gadgets of `source_map`
+pub fn trace_script(script: ScriptBuf, source_map: &SourceMap, options: &TraceOptions) -> Trace {
+    assert!(options.snapshot_interval > 0);
+    let script_len = script.len();
+    let mut opts = Options::default();
+    opts.enforce_stack_limit = options.enforce_stack_limit;
+    let mut exec = Exec::new(
+        ExecCtx::Tapscript,
+        opts,
+        TxTemplate {
+            tx: Transaction {
+                version: bitcoin::transaction::Version::TWO,
+                lock_time: bitcoin::locktime::absolute::LockTime::ZERO,
+                input: vec![],
+                output: vec![],
+            },
+            prevouts: vec![],
+            input_idx: 0,
+            taproot_annex_scriptleaf: Some((TapLeafHash::all_zeros(), None)),
+        },
+        script,
+        vec![],
+    )
+    .expect("error creating exec");
+
+    let mut steps = vec![];
+    let mut step = 0;
+    let mut offset;
+    loop {
+        offset = script_len - exec.remaining_script().len();
+        if step % options.snapshot_interval == 0 {
+            let instruction = exec.remaining_script().instructions().next().and_then(|i| i.ok());
+            steps.push(TraceStep {
+                step,
+                offset,
+                instruction: instruction.map(describe),
+                gadget: source_map.lookup(offset),
+                stack: snapshot(exec.stack()),
+                altstack: snapshot(exec.altstack()),
+            });
+        }
+        if exec.exec_next().is_err() {
+            break;
+        }
+        step += 1;
+    }
+
+    let res = exec.result().unwrap();
+    // The final state, at the instruction that failed if any
+    steps.push(TraceStep {
+        step,
+        offset,
+        instruction: res.opcode.map(|opcode| opcode.to_string()),
+        gadget: source_map.lookup(offset),
+        stack: snapshot(exec.stack()),
+        altstack: snapshot(exec.altstack()),
+    });
+    Trace {
+        success: res.success,
+        error: res.error.as_ref().map(|e| format!("{:?}", e)),
+        num_steps: step,
+        steps,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn254::fp254impl::Fp254Impl;
+    use crate::bn254::fq::Fq;
+    use crate::treepp::*;
+
+    fn annotated() -> AnnotatedScript {
+        let inner = AnnotatedScript::new()
+            .push("one", script! { OP_1 })
+            .push("two", script! { OP_2 });
+        AnnotatedScript::new()
+            .push_annotated("push", inner)
+            .push("add", script! { OP_ADD 3 OP_EQUALVERIFY })
+            .push("fail", script! { OP_0 OP_VERIFY })
+    }
+
+    #[test]
+    fn test_source_map() {
+        let script = annotated();
+        let source_map = script.source_map();
+        assert_eq!(script.compile().len(), 7);
+        assert_eq!(source_map.lookup(0), Some("push/one".to_string()));
+        assert_eq!(source_map.lookup(1), Some("push/two".to_string()));
+        assert_eq!(source_map.lookup(3), Some("add".to_string()));
+        assert_eq!(source_map.lookup(7), None);
+    }
+
+    #[test]
+    fn test_trace_script() {
+        let script = annotated();
+        let trace = trace_script(script.compile(), script.source_map(), &TraceOptions::default());
+        assert!(!trace.success);
+        assert_eq!(trace.num_steps, 6);
+        // One snapshot per step and the final state
+        assert_eq!(trace.steps.len(), 8);
+        assert_eq!(trace.steps[2].instruction, Some("OP_ADD".to_string()));
+        assert_eq!(trace.steps[2].stack, vec!["0x01", "0x02"]);
+        assert_eq!(trace.last().gadget, Some("fail".to_string()));
+
+        let json = trace.to_json();
+        assert_eq!(Trace::from_json(&json).unwrap(), trace);
+    }
+
+    #[test]
+    fn test_trace_interval() {
+        let script = AnnotatedScript::new()
+            .push("a", script! { { Fq::push_one() } })
+            .push("b", script! { { Fq::push_one() } })
+            .push("mul", Fq::mul())
+            .push("check", script! { { Fq::push_one() } { Fq::equalverify(1, 0) } OP_TRUE });
+        let options = TraceOptions { snapshot_interval: 100, ..Default::default() };
+        let trace = trace_script(script.compile(), script.source_map(), &options);
+        assert!(trace.success);
+        assert_eq!(trace.steps.len(), trace.num_steps / 100 + 2);
+        assert_eq!(trace.last().stack, vec!["0x01"]);
+    }
+}
@@ -1,6 +1,7 @@
 use bitcoin::{consensus::encode::serialize_hex, Amount};
 
 use bitvm::bridge::{
+    assert_program::{assert_program_stacks, sign_assert_stacks},
     connectors::base::TaprootConnector,
     graphs::base::ONE_HUNDRED,
     transactions::{
@@ -23,16 +24,26 @@ async fn test_assert_tx() {
     )
     .await;
 
-    let mut assert_tx =
-        AssertTransaction::new(&config.operator_context, Input { outpoint, amount });
-
-    let secret_nonces_0 = assert_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = assert_tx.push_nonces(&config.verifier_1_context);
-
-    assert_tx.pre_sign(&config.verifier_0_context, &secret_nonces_0);
-    assert_tx.pre_sign(&config.verifier_1_context, &secret_nonces_1);
-
-    let tx = assert_tx.finalize();
+    let mut assert_tx = AssertTransaction::new(
+        &config.operator_context,
+        &config.connector_b,
+        &config.connector_c,
+        Input { outpoint, amount },
+    );
+
+    let nonces_0 = assert_tx.push_nonces(&config.verifier_0_context).unwrap();
+    let nonces_1 = assert_tx.push_nonces(&config.verifier_1_context).unwrap();
+
+    assert_tx
+        .pre_sign(&config.verifier_0_context, &nonces_0)
+        .unwrap();
+    assert_tx
+        .pre_sign(&config.verifier_1_context, &nonces_1)
+        .unwrap();
+
+    let commitment =
+        sign_assert_stacks(&config.connector_c_winternitz_secrets, &assert_program_stacks());
+    let tx = assert_tx.finalize_with_commitment(&commitment);
     println!("Script Path Spend Transaction: {:?}\n", tx);
     let result = config.client_0.esplora.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
@@ -17,7 +17,7 @@ async fn test_challenge_tx() {
     let config = setup_test().await;
 
     // We re-use the depositor private key to imitate a third-party
-    let crowdfunding_keypair = &config.depositor_context.depositor_keypair;
+    let crowdfunding_signer = config.depositor_context.depositor_signer.as_ref();
     let crowdfunding_public_key = &config.depositor_context.depositor_public_key;
 
     let amount_0 = Amount::from_sat(DUST_AMOUNT);
@@ -70,31 +70,34 @@ async fn test_challenge_tx() {
             amount: amount_0,
         },
         input_amount_crowdfunding_total,
-    );
+    )
+    .unwrap();
 
-    challenge_tx.add_inputs_and_output(
-        &config.depositor_context,
-        &vec![
-            InputWithScript {
-                outpoint: OutPoint {
-                    txid: crowdfunding_utxos[0].txid,
-                    vout: crowdfunding_utxos[0].vout,
+    challenge_tx
+        .add_inputs_and_output(
+            &config.depositor_context,
+            &vec![
+                InputWithScript {
+                    outpoint: OutPoint {
+                        txid: crowdfunding_utxos[0].txid,
+                        vout: crowdfunding_utxos[0].vout,
+                    },
+                    amount: amount_1,
+                    script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
                 },
-                amount: amount_1,
-                script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
-            },
-            InputWithScript {
-                outpoint: OutPoint {
-                    txid: crowdfunding_utxos[1].txid,
-                    vout: crowdfunding_utxos[1].vout,
+                InputWithScript {
+                    outpoint: OutPoint {
+                        txid: crowdfunding_utxos[1].txid,
+                        vout: crowdfunding_utxos[1].vout,
+                    },
+                    amount: amount_1,
+                    script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
                 },
-                amount: amount_1,
-                script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
-            },
-        ],
-        crowdfunding_keypair,
-        refund_address.script_pubkey(),
-    );
+            ],
+            crowdfunding_signer,
+            refund_address.script_pubkey(),
+        )
+        .unwrap();
 
     let tx = challenge_tx.finalize();
     println!("Script Path Spend Transaction: {:?}\n", tx);
@@ -23,6 +23,7 @@ async fn test_merge_add_new_graph() {
         version: data.version + 1,
         peg_in_graphs: vec![new_peg_in_graph.clone()],
         peg_out_graphs: vec![new_peg_out_graph.clone()],
+        challenge_crowdfundings: vec![],
     };
 
     assert_eq!(data.peg_in_graphs.len(), 1);
@@ -69,7 +70,8 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
     let peg_in_graph_id = config
         .client_0
         .create_peg_in_graph(input, &config.depositor_evm_address)
-        .await;
+        .await
+        .unwrap();
 
     config
         .client_0
@@ -80,7 +82,8 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
                 amount,
             },
         )
-        .await;
+        .await
+        .unwrap();
 
     let new_peg_in_graph = PegInGraph::new(
         &config.depositor_context,
@@ -89,7 +92,8 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             amount: Amount::from_sat(INITIAL_AMOUNT),
         },
         &config.depositor_evm_address,
-    );
+    )
+    .unwrap();
 
     let (new_peg_out_graph, _) = PegOutGraph::new(
         &config.operator_context,
@@ -98,7 +102,8 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             outpoint: peg_out_outpoint,
             amount,
         },
-    );
+    )
+    .unwrap();
 
     return (config.client_0, new_peg_in_graph, new_peg_out_graph);
 }
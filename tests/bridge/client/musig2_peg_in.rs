@@ -41,7 +41,8 @@ async fn test_musig2_peg_in() {
 
     let graph_id = depositor_operator_verifier_0_client
         .create_peg_in_graph(Input { outpoint, amount }, &config.depositor_evm_address)
-        .await;
+        .await
+        .unwrap();
     println!("Depositor: Created new graph {graph_id}");
 
     println!("Depositor: Mining peg in deposit...");
@@ -57,7 +58,9 @@ async fn test_musig2_peg_in() {
     depositor_operator_verifier_0_client.sync().await;
 
     println!("Verifier 0: Generating nonces...");
-    depositor_operator_verifier_0_client.push_peg_in_nonces(&graph_id);
+    depositor_operator_verifier_0_client
+        .push_peg_in_nonces(&graph_id)
+        .unwrap();
 
     println!("Verifier 0: Saving state changes to remote...");
     depositor_operator_verifier_0_client.flush().await;
@@ -67,7 +70,7 @@ async fn test_musig2_peg_in() {
     verifier_1_client.sync().await;
 
     println!("Verifier 1: Generating nonces...");
-    verifier_1_client.push_peg_in_nonces(&graph_id);
+    verifier_1_client.push_peg_in_nonces(&graph_id).unwrap();
 
     println!("Verifier 1: Saving state changes to remote...");
     verifier_1_client.flush().await;
@@ -77,7 +80,9 @@ async fn test_musig2_peg_in() {
     depositor_operator_verifier_0_client.sync().await;
 
     println!("Verifier 0: Pre-signing...");
-    depositor_operator_verifier_0_client.pre_sign_peg_in(&graph_id);
+    depositor_operator_verifier_0_client
+        .pre_sign_peg_in(&graph_id)
+        .unwrap();
 
     println!("Verifier 0: Saving state changes to remote...");
     depositor_operator_verifier_0_client.flush().await;
@@ -87,7 +92,7 @@ async fn test_musig2_peg_in() {
     verifier_1_client.sync().await;
 
     println!("Verifier 1: Pre-signing...");
-    verifier_1_client.pre_sign_peg_in(&graph_id);
+    verifier_1_client.pre_sign_peg_in(&graph_id).unwrap();
 
     println!("Verifier 1: Saving state changes to remote...");
     verifier_1_client.flush().await;
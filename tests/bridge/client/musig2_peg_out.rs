@@ -204,7 +204,8 @@ async fn test_musig2_peg_out_peg_out() {
     eprintln!("Broadcasting peg out...");
     depositor_operator_verifier_0_client
         .broadcast_peg_out(&peg_out_graph_id, input)
-        .await;
+        .await
+        .unwrap();
 }
 
 async fn create_peg_out_graph(
@@ -285,32 +286,42 @@ async fn create_peg_out_graph(
                 amount: kick_off_input_amount,
             },
         )
-        .await;
+        .await
+        .unwrap();
 
     eprintln!("Verifier 0 push peg-out nonces");
-    depositor_operator_verifier_0_client.push_peg_out_nonces(&peg_out_graph_id);
+    depositor_operator_verifier_0_client
+        .push_peg_out_nonces(&peg_out_graph_id)
+        .unwrap();
     depositor_operator_verifier_0_client.flush().await;
 
     eprintln!("Verifier 1 push peg-out nonces");
     verifier_1_client.sync().await;
-    verifier_1_client.push_peg_out_nonces(&peg_out_graph_id);
+    verifier_1_client
+        .push_peg_out_nonces(&peg_out_graph_id)
+        .unwrap();
     verifier_1_client.flush().await;
 
     eprintln!("Verifier 0 pre-sign peg-out");
     depositor_operator_verifier_0_client.sync().await;
-    depositor_operator_verifier_0_client.pre_sign_peg_out(&peg_out_graph_id);
+    depositor_operator_verifier_0_client
+        .pre_sign_peg_out(&peg_out_graph_id)
+        .unwrap();
     depositor_operator_verifier_0_client.flush().await;
 
     eprintln!("Verifier 1 pre-sign peg-out");
     verifier_1_client.sync().await;
-    verifier_1_client.pre_sign_peg_out(&peg_out_graph_id);
+    verifier_1_client
+        .pre_sign_peg_out(&peg_out_graph_id)
+        .unwrap();
     verifier_1_client.flush().await;
 
     eprintln!("Broadcasting kick-off 1...");
     depositor_operator_verifier_0_client.sync().await;
     depositor_operator_verifier_0_client
         .broadcast_kick_off_1(&peg_out_graph_id)
-        .await;
+        .await
+        .unwrap();
 
     // Wait for peg-in deposit transaction to be mined
     println!("Waiting for peg-out kick-off tx...");
@@ -320,7 +331,8 @@ async fn create_peg_out_graph(
         eprintln!("Broadcasting start time...");
         depositor_operator_verifier_0_client
             .broadcast_start_time(&peg_out_graph_id)
-            .await;
+            .await
+            .unwrap();
 
         println!("Waiting for peg-out start time tx...");
         sleep(Duration::from_secs(TX_WAIT_TIME)).await;
@@ -334,7 +346,8 @@ async fn create_peg_out_graph(
         eprintln!("Broadcasting kick-off 2...");
         depositor_operator_verifier_0_client
             .broadcast_kick_off_2(&peg_out_graph_id, &get_superblock_message(&sb, &sb_hash))
-            .await;
+            .await
+            .unwrap();
 
         println!("Waiting for peg-out kick-off 2 tx...");
         sleep(Duration::from_secs(TX_WAIT_TIME)).await;
@@ -359,7 +372,8 @@ async fn create_peg_out_graph(
                 &vec![challenge_crowdfunding_input],
                 generate_pay_to_pubkey_script(&config.depositor_context.depositor_public_key),
             )
-            .await;
+            .await
+            .unwrap();
 
         println!("Waiting for peg-out challenge tx...");
         sleep(Duration::from_secs(TX_WAIT_TIME)).await;
@@ -403,22 +417,23 @@ async fn create_peg_in_graph(
             },
             depositor_evm_address,
         )
-        .await;
+        .await
+        .unwrap();
 
     client_0.broadcast_peg_in_deposit(&graph_id).await;
-    client_0.push_peg_in_nonces(&graph_id);
+    client_0.push_peg_in_nonces(&graph_id).unwrap();
     client_0.flush().await;
 
     client_1.sync().await;
-    client_1.push_peg_in_nonces(&graph_id);
+    client_1.push_peg_in_nonces(&graph_id).unwrap();
     client_1.flush().await;
 
     client_0.sync().await;
-    client_0.pre_sign_peg_in(&graph_id);
+    client_0.pre_sign_peg_in(&graph_id).unwrap();
     client_0.flush().await;
 
     client_1.sync().await;
-    client_1.pre_sign_peg_in(&graph_id);
+    client_1.pre_sign_peg_in(&graph_id).unwrap();
     client_1.flush().await;
 
     // Wait for peg-in deposit transaction to be mined
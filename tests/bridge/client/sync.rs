@@ -30,7 +30,8 @@ async fn test_sync() {
     let peg_in_graph_id = config
         .client_0
         .create_peg_in_graph(Input { outpoint, amount }, &config.depositor_evm_address)
-        .await;
+        .await
+        .unwrap();
 
     config
         .client_0
@@ -49,7 +50,8 @@ async fn test_sync() {
                 amount,
             },
         )
-        .await;
+        .await
+        .unwrap();
 
     println!("Save to remote");
     config.client_0.flush().await;
@@ -1,32 +1,34 @@
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
 
 use bitcoin::{Amount, OutPoint, Txid};
 
 use bitvm::bridge::{
     client::client::{BitVMClient, BitVMClientPublicData},
+    contexts::depositor::DepositorContext,
     graphs::{
-        base::{FEE_AMOUNT, INITIAL_AMOUNT},
+        base::{DEPOSITOR_SECRET, FEE_AMOUNT, INITIAL_AMOUNT},
         peg_in::PegInGraph,
         peg_out::PegOutGraph,
     },
     scripts::generate_burn_script,
+    signer::local::LocalSigner,
     transactions::{base::Input, pre_signed::PreSignedTransaction},
 };
 
-use super::super::setup::setup_test;
+use super::super::setup::{setup_test, SetupConfig};
 
 #[tokio::test]
 async fn test_validate_success() {
-    let (data, _) = setup_and_create_graphs().await;
+    let (data, _, config) = setup_and_create_graphs().await;
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &config.depositor_context.params);
 
     assert!(is_data_valid);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_previous_output() {
-    let (mut data, peg_in_outpoint) = setup_and_create_graphs().await;
+    let (mut data, peg_in_outpoint, config) = setup_and_create_graphs().await;
 
     let changed_outpoint = OutPoint {
         txid: peg_in_outpoint.txid,
@@ -36,60 +38,91 @@ async fn test_validate_invalid_previous_output() {
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.input[0].previous_output = changed_outpoint;
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &config.depositor_context.params);
 
     assert_eq!(is_data_valid, false);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_script_sig() {
-    let (mut data, _) = setup_and_create_graphs().await;
+    let (mut data, _, config) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.input[0].script_sig = generate_burn_script();
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &config.depositor_context.params);
 
     assert_eq!(is_data_valid, false);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_sequence() {
-    let (mut data, _) = setup_and_create_graphs().await;
+    let (mut data, _, config) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.input[0].sequence = bitcoin::Sequence(100);
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &config.depositor_context.params);
 
     assert_eq!(is_data_valid, false);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_value() {
-    let (mut data, _) = setup_and_create_graphs().await;
+    let (mut data, _, config) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.output[0].value = Amount::from_sat(1);
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &config.depositor_context.params);
 
     assert_eq!(is_data_valid, false);
 }
 
 #[tokio::test]
 async fn test_validate_invalid_script_pubkey() {
-    let (mut data, _) = setup_and_create_graphs().await;
+    let (mut data, _, config) = setup_and_create_graphs().await;
 
     let deposit_tx = data.peg_in_graphs[1].peg_in_deposit_transaction.tx_mut();
     deposit_tx.output[0].script_pubkey = generate_burn_script();
 
-    let is_data_valid = BitVMClient::validate_data(&data);
+    let is_data_valid = BitVMClient::validate_data(&data, &config.depositor_context.params);
 
     assert_eq!(is_data_valid, false);
 }
 
-async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
+#[tokio::test]
+async fn test_validate_different_params() {
+    let (mut data, peg_in_outpoint, config) = setup_and_create_graphs().await;
+
+    // a well formed graph whose refund the depositor can take a block earlier than agreed
+    let mut params = config.depositor_context.params.clone();
+    params.peg_in_refund_timelock -= 1;
+    let depositor_context = DepositorContext::new_with_params(
+        config.depositor_context.network,
+        params,
+        Arc::new(LocalSigner::from_secret(DEPOSITOR_SECRET).unwrap()),
+        &config.depositor_context.n_of_n_public_keys,
+    );
+    data.peg_in_graphs.push(
+        PegInGraph::new(
+            &depositor_context,
+            Input {
+                outpoint: peg_in_outpoint,
+                amount: Amount::from_sat(INITIAL_AMOUNT + FEE_AMOUNT),
+            },
+            &config.depositor_evm_address,
+        )
+        .unwrap(),
+    );
+    assert!(data.peg_in_graphs[2].validate());
+
+    let is_data_valid = BitVMClient::validate_data(&data, &config.depositor_context.params);
+
+    assert_eq!(is_data_valid, false);
+}
+
+async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint, SetupConfig) {
     let config = setup_test().await;
 
     let amount_0 = Amount::from_sat(INITIAL_AMOUNT + FEE_AMOUNT + 1);
@@ -112,7 +145,8 @@ async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
             amount: amount_0,
         },
         &config.depositor_evm_address,
-    );
+    )
+    .unwrap();
 
     let peg_in_graph_1 = PegInGraph::new(
         &config.depositor_context,
@@ -121,7 +155,8 @@ async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
             amount: amount_1,
         },
         &config.depositor_evm_address,
-    );
+    )
+    .unwrap();
 
     let (peg_out_graph, _) = PegOutGraph::new(
         &config.operator_context,
@@ -130,13 +165,15 @@ async fn setup_and_create_graphs() -> (BitVMClientPublicData, OutPoint) {
             outpoint: peg_out_outpoint,
             amount: amount_0,
         },
-    );
+    )
+    .unwrap();
 
     let data = BitVMClientPublicData {
         version: 1,
         peg_in_graphs: vec![peg_in_graph_0, peg_in_graph_1],
         peg_out_graphs: vec![peg_out_graph],
+        challenge_crowdfundings: vec![],
     };
 
-    return (data, peg_in_outpoint);
+    return (data, peg_in_outpoint, config);
 }
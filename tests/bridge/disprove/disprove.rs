@@ -7,6 +7,7 @@ mod tests {
     };
 
     use bitvm::bridge::{
+        assert_program::{assert_program_stacks, sign_assert_stacks},
         connectors::base::TaprootConnector,
         graphs::base::{DUST_AMOUNT, FEE_AMOUNT, INITIAL_AMOUNT},
         scripts::generate_pay_to_pubkey_script,
@@ -40,6 +41,7 @@ mod tests {
 
         let mut disprove_tx = DisproveTransaction::new(
             &config.operator_context,
+            &config.connector_c,
             Input {
                 outpoint: outpoint_0,
                 amount: amount_0,
@@ -51,11 +53,26 @@ mod tests {
             1,
         );
 
-        let secret_nonces_0 = disprove_tx.push_nonces(&config.verifier_0_context);
-        let secret_nonces_1 = disprove_tx.push_nonces(&config.verifier_1_context);
+        let nonces_0 = disprove_tx.push_nonces(&config.verifier_0_context).unwrap();
+        let nonces_1 = disprove_tx.push_nonces(&config.verifier_1_context).unwrap();
 
-        disprove_tx.pre_sign(&config.verifier_0_context, &secret_nonces_0);
-        disprove_tx.pre_sign(&config.verifier_1_context, &secret_nonces_1);
+        disprove_tx
+            .pre_sign(&config.verifier_0_context, &nonces_0)
+            .unwrap();
+        disprove_tx
+            .pre_sign(&config.verifier_1_context, &nonces_1)
+            .unwrap();
+
+        // The operator committed to a wrong output of chunk 1
+        let mut wrong_stacks = assert_program_stacks();
+        wrong_stacks[1][0] ^= 1;
+        let commitment = sign_assert_stacks(&config.connector_c_winternitz_secrets, &wrong_stacks);
+        let script_index = disprove_tx.find_disprove_script_index(&commitment);
+        assert_eq!(script_index, Some(1));
+
+        let verifier_reward_script =
+            generate_pay_to_pubkey_script(&config.verifier_0_context.verifier_public_key);
+        disprove_tx.add_input_output(1, &commitment, verifier_reward_script);
 
         let tx = disprove_tx.finalize();
         println!("Script Path Spend Transaction: {:?}\n", tx);
@@ -89,6 +106,7 @@ mod tests {
 
         let mut disprove_tx = DisproveTransaction::new(
             &config.operator_context,
+            &config.connector_c,
             Input {
                 outpoint: outpoint_0,
                 amount: amount_0,
@@ -100,11 +118,15 @@ mod tests {
             1,
         );
 
-        let secret_nonces_0 = disprove_tx.push_nonces(&config.verifier_0_context);
-        let secret_nonces_1 = disprove_tx.push_nonces(&config.verifier_1_context);
+        let nonces_0 = disprove_tx.push_nonces(&config.verifier_0_context).unwrap();
+        let nonces_1 = disprove_tx.push_nonces(&config.verifier_1_context).unwrap();
 
-        disprove_tx.pre_sign(&config.verifier_0_context, &secret_nonces_0);
-        disprove_tx.pre_sign(&config.verifier_1_context, &secret_nonces_1);
+        disprove_tx
+            .pre_sign(&config.verifier_0_context, &nonces_0)
+            .unwrap();
+        disprove_tx
+            .pre_sign(&config.verifier_1_context, &nonces_1)
+            .unwrap();
 
         let mut tx = disprove_tx.finalize();
 
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use bitcoin::{Amount, OutPoint, Transaction};
+
+use bitvm::bridge::{
+    connectors::base::{ConnectorId, TaprootConnector},
+    graphs::{
+        base::{GraphTransaction, FEE_AMOUNT, INITIAL_AMOUNT},
+        export::{ExportEdge, GraphExport},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    scripts::generate_pay_to_pubkey_script_address,
+    transactions::{base::Input, pre_signed::PreSignedTransaction},
+};
+
+use super::super::{
+    helper::generate_stub_outpoint,
+    setup::{setup_test, SetupConfig},
+};
+
+async fn create_graphs(config: &SetupConfig) -> (PegInGraph, PegOutGraph) {
+    let amount = Amount::from_sat(INITIAL_AMOUNT + FEE_AMOUNT);
+    let outpoint = generate_stub_outpoint(
+        &config.client_0,
+        &generate_pay_to_pubkey_script_address(
+            config.depositor_context.network,
+            &config.depositor_context.depositor_public_key,
+        ),
+        amount,
+    )
+    .await;
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        Input { outpoint, amount },
+        &config.depositor_evm_address,
+    )
+    .unwrap();
+
+    let kick_off_outpoint = generate_stub_outpoint(
+        &config.client_0,
+        &generate_pay_to_pubkey_script_address(
+            config.operator_context.network,
+            &config.operator_context.operator_public_key,
+        ),
+        amount,
+    )
+    .await;
+    let (peg_out_graph, _) = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        Input {
+            outpoint: kick_off_outpoint,
+            amount,
+        },
+    )
+    .unwrap();
+
+    (peg_in_graph, peg_out_graph)
+}
+
+// Every edge is an input of its target spending the output of its source, and every input
+// spending an output of another transaction of the graph is an edge
+fn assert_edges_match_inputs(
+    export: &GraphExport,
+    transactions: &HashMap<GraphTransaction, Transaction>,
+) {
+    for edge in export.edges.iter() {
+        let from = &transactions[&edge.from];
+        let outpoint = OutPoint {
+            txid: from.compute_txid(),
+            vout: edge.vout,
+        };
+        assert!(
+            transactions[&edge.to]
+                .input
+                .iter()
+                .any(|input| input.previous_output == outpoint),
+            "{:?} does not spend {:?}:{}",
+            edge.to,
+            edge.from,
+            edge.vout
+        );
+        assert_eq!(from.output[edge.vout as usize].value, edge.amount);
+
+        let node = export
+            .nodes
+            .iter()
+            .find(|node| node.transaction == edge.from)
+            .unwrap();
+        if let Some(txid) = node.txid {
+            assert_eq!(txid, outpoint.txid);
+        }
+    }
+
+    for (transaction, tx) in transactions.iter() {
+        for input in tx.input.iter() {
+            let from = transactions
+                .iter()
+                .find(|(_, from)| from.compute_txid() == input.previous_output.txid);
+            if let Some((from, _)) = from {
+                assert!(
+                    export.edges.iter().any(|edge| edge.from == *from
+                        && edge.vout == input.previous_output.vout
+                        && edge.to == *transaction),
+                    "No edge for {:?} spending {:?}:{}",
+                    transaction,
+                    from,
+                    input.previous_output.vout
+                );
+            }
+        }
+    }
+}
+
+fn find_edge(
+    export: &GraphExport,
+    to: GraphTransaction,
+    connector: ConnectorId,
+) -> &ExportEdge {
+    export
+        .edges
+        .iter()
+        .find(|edge| edge.to == to && edge.connector == connector)
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_peg_in_graph_export() {
+    let config = setup_test().await;
+    let (peg_in_graph, _) = create_graphs(&config).await;
+
+    let export = peg_in_graph.export();
+    let transactions = HashMap::from([
+        (
+            GraphTransaction::PegInDeposit,
+            peg_in_graph.peg_in_deposit_transaction.tx().clone(),
+        ),
+        (
+            GraphTransaction::PegInRefund,
+            peg_in_graph.peg_in_refund_transaction.tx().clone(),
+        ),
+        (
+            GraphTransaction::PegInConfirm,
+            peg_in_graph.peg_in_confirm_transaction.tx().clone(),
+        ),
+    ]);
+    assert_edges_match_inputs(&export, &transactions);
+    assert_eq!(export.edges.len(), 2);
+
+    let refund = find_edge(&export, GraphTransaction::PegInRefund, ConnectorId::ConnectorZ);
+    assert_eq!(refund.leaf, Some(0));
+    assert_eq!(refund.script, config.connector_z.generate_taproot_leaf_script(0));
+    assert!(refund.timelock.is_some());
+    assert_eq!(refund.signers, vec!["depositor".to_string()]);
+    assert!(refund.description.contains("[depositor]"));
+
+    let confirm = find_edge(&export, GraphTransaction::PegInConfirm, ConnectorId::ConnectorZ);
+    assert_eq!(confirm.leaf, Some(1));
+    assert_eq!(confirm.script, config.connector_z.generate_taproot_leaf_script(1));
+    assert!(confirm.timelock.is_none());
+    assert!(confirm.description.contains("[n-of-n]"));
+
+    // nothing is confirmed yet
+    assert!(export
+        .nodes
+        .iter()
+        .all(|node| node.confirmation_height.is_none()));
+    assert_eq!(export.state, "Created");
+}
+
+#[tokio::test]
+async fn test_peg_out_graph_export() {
+    let config = setup_test().await;
+    let (peg_in_graph, peg_out_graph) = create_graphs(&config).await;
+
+    let export = peg_out_graph.export();
+    let mut transactions: HashMap<GraphTransaction, Transaction> =
+        peg_out_graph.pre_signed_transactions().into_iter().collect();
+    transactions.insert(
+        GraphTransaction::PegInConfirm,
+        peg_in_graph.peg_in_confirm_transaction.tx().clone(),
+    );
+    assert_edges_match_inputs(&export, &transactions);
+
+    let take_1 = find_edge(&export, GraphTransaction::Take1, ConnectorId::Connector0);
+    assert_eq!(take_1.from, GraphTransaction::PegInConfirm);
+    assert_eq!(take_1.script, config.connector_0.generate_taproot_leaf_script(0));
+    assert!(take_1.description.contains("[n-of-n]"));
+
+    let take_2 = find_edge(&export, GraphTransaction::Take2, ConnectorId::Connector0);
+    assert_eq!(take_2.script, config.connector_0.generate_taproot_leaf_script(1));
+
+    let disprove = find_edge(&export, GraphTransaction::Disprove, ConnectorId::Connector5);
+    assert_eq!(disprove.from, GraphTransaction::Assert);
+    assert_eq!(disprove.script, config.connector_5.generate_taproot_leaf_script(1));
+
+    // the disprove leaf of connector C is only known at broadcast
+    let disprove = find_edge(&export, GraphTransaction::Disprove, ConnectorId::ConnectorC);
+    assert_eq!(disprove.leaf, None);
+
+    // the rendered diagrams name every transaction and connector of the graph
+    let dot = export.to_dot();
+    let mermaid = export.to_mermaid();
+    for node in export.nodes.iter() {
+        assert!(dot.contains(&format!("\"{:?}\" [label=", node.transaction)));
+        assert!(mermaid.contains(&format!("    {:?}[\"", node.transaction)));
+    }
+    for edge in export.edges.iter() {
+        assert!(dot.contains(&edge.description));
+        assert!(mermaid.contains(&edge.description));
+    }
+}
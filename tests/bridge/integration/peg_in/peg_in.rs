@@ -45,7 +45,8 @@ async fn test_peg_in_success() {
         &config.depositor_context,
         &config.depositor_evm_address,
         deposit_input,
-    );
+    )
+    .unwrap();
 
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
@@ -69,13 +70,22 @@ async fn test_peg_in_success() {
         &config.depositor_context,
         &config.depositor_evm_address,
         confirm_input,
-    );
+    )
+    .unwrap();
 
-    let secret_nonces_0 = peg_in_confirm.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = peg_in_confirm.push_nonces(&config.verifier_1_context);
+    let nonces_0 = peg_in_confirm
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let nonces_1 = peg_in_confirm
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
-    peg_in_confirm.pre_sign(&config.verifier_0_context, &secret_nonces_0);
-    peg_in_confirm.pre_sign(&config.verifier_1_context, &secret_nonces_1);
+    peg_in_confirm
+        .pre_sign(&config.verifier_0_context, &nonces_0)
+        .unwrap();
+    peg_in_confirm
+        .pre_sign(&config.verifier_1_context, &nonces_1)
+        .unwrap();
 
     let peg_in_confirm_tx = peg_in_confirm.finalize();
     let confirm_txid = peg_in_confirm_tx.compute_txid();
@@ -141,7 +151,8 @@ async fn test_peg_in_time_lock_not_surpassed() {
         &config.depositor_context,
         &config.depositor_evm_address,
         deposit_input,
-    );
+    )
+    .unwrap();
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
 
@@ -163,7 +174,8 @@ async fn test_peg_in_time_lock_not_surpassed() {
         &config.depositor_context,
         &config.depositor_evm_address,
         refund_input,
-    );
+    )
+    .unwrap();
     let peg_in_refund_tx = peg_in_refund.finalize();
 
     // mine peg-in refund
@@ -206,7 +218,8 @@ async fn test_peg_in_time_lock_surpassed() {
         &config.depositor_context,
         &config.depositor_evm_address,
         deposit_input,
-    );
+    )
+    .unwrap();
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
 
@@ -228,7 +241,8 @@ async fn test_peg_in_time_lock_surpassed() {
         &config.depositor_context,
         &config.depositor_evm_address,
         refund_input,
-    );
+    )
+    .unwrap();
     let peg_in_refund_tx = peg_in_refund.finalize();
     let refund_txid = peg_in_refund_tx.compute_txid();
 
@@ -76,13 +76,16 @@ async fn test_challenge_success() {
         &config.operator_context,
         challenge_kick_off_input,
         challenge_input_amount,
-    );
-    challenge.add_inputs_and_output(
-        &config.depositor_context,
-        &vec![challenge_crowdfunding_input],
-        &config.depositor_context.depositor_keypair,
-        generate_pay_to_pubkey_script(&config.depositor_context.depositor_public_key),
-    ); // add crowdfunding input
+    )
+    .unwrap();
+    challenge
+        .add_inputs_and_output(
+            &config.depositor_context,
+            &vec![challenge_crowdfunding_input],
+            config.depositor_context.depositor_signer.as_ref(),
+            generate_pay_to_pubkey_script(&config.depositor_context.depositor_public_key),
+        )
+        .unwrap(); // add crowdfunding input
     let challenge_tx = challenge.finalize();
     let challenge_txid = challenge_tx.compute_txid();
 
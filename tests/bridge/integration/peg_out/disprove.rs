@@ -1,5 +1,6 @@
 use bitcoin::{Address, Amount, OutPoint};
 use bitvm::bridge::{
+    assert_program::{assert_program_stacks, sign_assert_stacks},
     graphs::base::{FEE_AMOUNT, INITIAL_AMOUNT},
     scripts::generate_pay_to_pubkey_script_address,
     transactions::{
@@ -34,6 +35,7 @@ async fn test_disprove_success() {
         &config.client_0,
         &config.operator_context,
         &kick_off_2_funding_utxo_address,
+        &config.connector_b,
         kick_off_2_input_amount,
     )
     .await;
@@ -47,22 +49,35 @@ async fn test_disprove_success() {
         },
         amount: kick_off_2_tx.output[vout as usize].value,
     };
-    let mut assert = AssertTransaction::new(&config.operator_context, assert_input_0);
+    let mut assert = AssertTransaction::new(
+        &config.operator_context,
+        &config.connector_b,
+        &config.connector_c,
+        assert_input_0,
+    );
 
-    let secret_nonces_0 = assert.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = assert.push_nonces(&config.verifier_1_context);
+    let nonces_0 = assert.push_nonces(&config.verifier_0_context).unwrap();
+    let nonces_1 = assert.push_nonces(&config.verifier_1_context).unwrap();
 
-    assert.pre_sign(&config.verifier_0_context, &secret_nonces_0);
-    assert.pre_sign(&config.verifier_1_context, &secret_nonces_1);
+    assert
+        .pre_sign(&config.verifier_0_context, &nonces_0)
+        .unwrap();
+    assert
+        .pre_sign(&config.verifier_1_context, &nonces_1)
+        .unwrap();
 
-    let assert_tx = assert.finalize();
+    // the operator commits to a wrong output of the last chunk of the assert program
+    let mut wrong_stacks = assert_program_stacks();
+    let last_chunk = wrong_stacks.len() - 1;
+    wrong_stacks[last_chunk][0] ^= 1;
+    let commitment = sign_assert_stacks(&config.connector_c_winternitz_secrets, &wrong_stacks);
+    let assert_tx = assert.finalize_with_commitment(&commitment);
     let assert_txid = assert_tx.compute_txid();
     let assert_result = config.client_0.esplora.broadcast(&assert_tx).await;
     assert!(assert_result.is_ok());
 
     // disprove
     let vout = 1;
-    let script_index = 1;
     let disprove_input_0 = Input {
         outpoint: OutPoint {
             txid: assert_txid,
@@ -80,25 +95,32 @@ async fn test_disprove_success() {
         amount: assert_tx.output[vout as usize].value,
     };
 
+    let script_index = last_chunk as u32;
     let mut disprove = DisproveTransaction::new(
         &config.operator_context,
+        &config.connector_c,
         disprove_input_0,
         disprove_input_1,
         script_index,
     );
+    assert_eq!(disprove.find_disprove_script_index(&commitment), Some(script_index));
 
-    let secret_nonces_0 = disprove.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = disprove.push_nonces(&config.verifier_1_context);
+    let nonces_0 = disprove.push_nonces(&config.verifier_0_context).unwrap();
+    let nonces_1 = disprove.push_nonces(&config.verifier_1_context).unwrap();
 
-    disprove.pre_sign(&config.verifier_0_context, &secret_nonces_0);
-    disprove.pre_sign(&config.verifier_1_context, &secret_nonces_1);
+    disprove
+        .pre_sign(&config.verifier_0_context, &nonces_0)
+        .unwrap();
+    disprove
+        .pre_sign(&config.verifier_1_context, &nonces_1)
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
         &config.withdrawer_context.withdrawer_public_key,
     );
     let verifier_reward_script = reward_address.script_pubkey(); // send reward to withdrawer address
-    disprove.add_input_output(script_index, verifier_reward_script);
+    disprove.add_input_output(script_index, &commitment, verifier_reward_script);
 
     let disprove_tx = disprove.finalize();
     let disprove_txid = disprove_tx.compute_txid();
@@ -60,31 +60,41 @@ async fn test_kick_off_timeout_success() {
         kick_off_timeout_input_0,
     );
 
-    let secret_nonces_0 = kick_off_timeout.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = kick_off_timeout.push_nonces(&config.verifier_1_context);
+    let nonces_0 = kick_off_timeout
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let nonces_1 = kick_off_timeout
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
     let verifier_0_connector_1 = Connector1::new_for_validation(
         config.verifier_0_context.network,
         &config.operator_context.operator_taproot_public_key, // Verifiers get this via remote storage.
         &config.verifier_0_context.n_of_n_taproot_public_key,
         &config.connector_1.winternitz_public_keys, // Verifiers get this via remote storage.
+        &config.verifier_0_context.params,
     );
-    kick_off_timeout.pre_sign(
-        &config.verifier_0_context,
-        &verifier_0_connector_1,
-        &secret_nonces_0,
-    );
+    kick_off_timeout
+        .pre_sign(
+            &config.verifier_0_context,
+            &verifier_0_connector_1,
+            &nonces_0,
+        )
+        .unwrap();
     let verifier_1_connector_1 = Connector1::new_for_validation(
         config.verifier_0_context.network,
         &config.operator_context.operator_taproot_public_key,
         &config.verifier_0_context.n_of_n_taproot_public_key,
         &config.connector_1.winternitz_public_keys,
+        &config.verifier_1_context.params,
     );
-    kick_off_timeout.pre_sign(
-        &config.verifier_1_context,
-        &verifier_1_connector_1,
-        &secret_nonces_1,
-    );
+    kick_off_timeout
+        .pre_sign(
+            &config.verifier_1_context,
+            &verifier_1_connector_1,
+            &nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
@@ -68,21 +68,29 @@ async fn test_start_time_timeout_success() {
         start_time_timeout_input_1,
     );
 
-    let secret_nonces_0 = start_time_timeout.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = start_time_timeout.push_nonces(&config.verifier_1_context);
+    let nonces_0 = start_time_timeout
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let nonces_1 = start_time_timeout
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
-    start_time_timeout.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_0,
-    );
-    start_time_timeout.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_1,
-    );
+    start_time_timeout
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_1,
+            &config.connector_2,
+            &nonces_0,
+        )
+        .unwrap();
+    start_time_timeout
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_1,
+            &config.connector_2,
+            &nonces_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
@@ -77,6 +77,7 @@ async fn test_take_1_success() {
     let kick_off_2 = KickOff2Transaction::new(
         &config.operator_context,
         &config.connector_1,
+        &config.connector_b,
         kick_off_2_input_0,
     );
     let kick_off_2_tx = kick_off_2.finalize();
@@ -123,17 +124,23 @@ async fn test_take_1_success() {
 
     let mut take_1 = Take1Transaction::new(
         &config.operator_context,
+        &config.connector_b,
         take_1_input_0,
         take_1_input_1,
         take_1_input_2,
         take_1_input_3,
-    );
+    )
+    .unwrap();
 
-    let secret_nonces_0 = take_1.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = take_1.push_nonces(&config.verifier_1_context);
+    let nonces_0 = take_1.push_nonces(&config.verifier_0_context).unwrap();
+    let nonces_1 = take_1.push_nonces(&config.verifier_1_context).unwrap();
 
-    take_1.pre_sign(&config.verifier_0_context, &secret_nonces_0);
-    take_1.pre_sign(&config.verifier_1_context, &secret_nonces_1);
+    take_1
+        .pre_sign(&config.verifier_0_context, &nonces_0)
+        .unwrap();
+    take_1
+        .pre_sign(&config.verifier_1_context, &nonces_1)
+        .unwrap();
 
     let take_1_tx = take_1.finalize();
     let take_1_txid = take_1_tx.compute_txid();
@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use bitcoin::{Address, Amount, OutPoint};
 use bitvm::bridge::{
+    assert_program::{assert_program_stacks, sign_assert_stacks},
     connectors::base::TaprootConnector,
     graphs::base::{FEE_AMOUNT, INITIAL_AMOUNT},
     scripts::generate_pay_to_pubkey_script_address,
@@ -54,6 +55,9 @@ async fn test_take_2_success() {
         &config.verifier_0_context,
         &config.verifier_1_context,
         &assert_funding_address,
+        &config.connector_b,
+        &config.connector_c,
+        &sign_assert_stacks(&config.connector_c_winternitz_secrets, &assert_program_stacks()),
         assert_input_amount,
     )
     .await;
@@ -94,17 +98,23 @@ async fn test_take_2_success() {
 
     let mut take_2 = Take2Transaction::new(
         &config.operator_context,
+        &config.connector_c,
         take_2_input_0,
         take_2_input_1,
         take_2_input_2,
         take_2_input_3,
-    );
+    )
+    .unwrap();
 
-    let secret_nonces_0 = take_2.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = take_2.push_nonces(&config.verifier_1_context);
+    let nonces_0 = take_2.push_nonces(&config.verifier_0_context).unwrap();
+    let nonces_1 = take_2.push_nonces(&config.verifier_1_context).unwrap();
 
-    take_2.pre_sign(&config.verifier_0_context, &secret_nonces_0);
-    take_2.pre_sign(&config.verifier_1_context, &secret_nonces_1);
+    take_2
+        .pre_sign(&config.verifier_0_context, &nonces_0)
+        .unwrap();
+    take_2
+        .pre_sign(&config.verifier_1_context, &nonces_1)
+        .unwrap();
 
     let take_2_tx = take_2.finalize();
     let take_2_txid = take_2_tx.compute_txid();
@@ -1,7 +1,11 @@
 use bitcoin::{Address, Amount, Transaction, Txid};
 use bitvm::bridge::{
+    assert_program::AssertCommitment,
     client::client::BitVMClient,
-    connectors::{connector_1::Connector1, connector_2::Connector2, connector_6::Connector6},
+    connectors::{
+        connector_1::Connector1, connector_2::Connector2, connector_6::Connector6,
+        connector_b::ConnectorB, connector_c::ConnectorC,
+    },
     contexts::{depositor::DepositorContext, operator::OperatorContext, verifier::VerifierContext},
     transactions::{
         assert::AssertTransaction,
@@ -50,12 +54,14 @@ pub async fn create_and_mine_kick_off_2_tx(
     client: &BitVMClient,
     operator_context: &OperatorContext,
     kick_off_2_funding_utxo_address: &Address,
+    connector_b: &ConnectorB,
     input_amount: Amount,
 ) -> (Transaction, Txid, Connector1) {
     let (connector_1, _) = Connector1::new(
         operator_context.network,
         &operator_context.operator_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
+        &operator_context.params,
     );
     let kick_off_2_funding_outpoint =
         generate_stub_outpoint(&client, kick_off_2_funding_utxo_address, input_amount).await;
@@ -63,7 +69,12 @@ pub async fn create_and_mine_kick_off_2_tx(
         outpoint: kick_off_2_funding_outpoint,
         amount: input_amount,
     };
-    let kick_off_2 = KickOff2Transaction::new(&operator_context, &connector_1, kick_off_2_input);
+    let kick_off_2 = KickOff2Transaction::new(
+        &operator_context,
+        &connector_1,
+        &connector_b,
+        kick_off_2_input,
+    );
     let kick_off_2_tx = kick_off_2.finalize();
     let kick_off_2_txid = kick_off_2_tx.compute_txid();
 
@@ -80,6 +91,9 @@ pub async fn create_and_mine_assert_tx(
     verifier_0_context: &VerifierContext,
     verifier_1_context: &VerifierContext,
     assert_funding_utxo_address: &Address,
+    connector_b: &ConnectorB,
+    connector_c: &ConnectorC,
+    commitment: &AssertCommitment,
     input_amount: Amount,
 ) -> (Transaction, Txid) {
     // create assert tx
@@ -89,15 +103,16 @@ pub async fn create_and_mine_assert_tx(
         outpoint: assert_funding_outpoint,
         amount: input_amount,
     };
-    let mut assert = AssertTransaction::new(&operator_context, assert_input);
+    let mut assert =
+        AssertTransaction::new(&operator_context, &connector_b, &connector_c, assert_input);
 
-    let secret_nonces_0 = assert.push_nonces(&verifier_0_context);
-    let secret_nonces_1 = assert.push_nonces(&verifier_1_context);
+    let nonces_0 = assert.push_nonces(&verifier_0_context).unwrap();
+    let nonces_1 = assert.push_nonces(&verifier_1_context).unwrap();
 
-    assert.pre_sign(&verifier_0_context, &secret_nonces_0);
-    assert.pre_sign(&verifier_1_context, &secret_nonces_1);
+    assert.pre_sign(&verifier_0_context, &nonces_0).unwrap();
+    assert.pre_sign(&verifier_1_context, &nonces_1).unwrap();
 
-    let assert_tx = assert.finalize();
+    let assert_tx = assert.finalize_with_commitment(commitment);
     let assert_txid = assert_tx.compute_txid();
 
     // mine assert tx
@@ -124,13 +139,17 @@ pub async fn create_and_mine_peg_in_confirm_tx(
         amount: input_amount,
     };
     let mut peg_in_confirm =
-        PegInConfirmTransaction::new(depositor_context, evm_address, confirm_input);
+        PegInConfirmTransaction::new(depositor_context, evm_address, confirm_input).unwrap();
 
-    let secret_nonces_0 = peg_in_confirm.push_nonces(&verifier_0_context);
-    let secret_nonces_1 = peg_in_confirm.push_nonces(&verifier_1_context);
+    let nonces_0 = peg_in_confirm.push_nonces(&verifier_0_context).unwrap();
+    let nonces_1 = peg_in_confirm.push_nonces(&verifier_1_context).unwrap();
 
-    peg_in_confirm.pre_sign(&verifier_0_context, &secret_nonces_0);
-    peg_in_confirm.pre_sign(&verifier_1_context, &secret_nonces_1);
+    peg_in_confirm
+        .pre_sign(&verifier_0_context, &nonces_0)
+        .unwrap();
+    peg_in_confirm
+        .pre_sign(&verifier_1_context, &nonces_1)
+        .unwrap();
 
     let peg_in_confirm_tx = peg_in_confirm.finalize();
     let peg_in_confirm_txid = peg_in_confirm_tx.compute_txid();
@@ -25,6 +25,7 @@ async fn test_kick_off_2_tx() {
     let mut kick_off_2_tx = KickOff2Transaction::new(
         &config.operator_context,
         &config.connector_1,
+        &config.connector_b,
         Input {
             outpoint: funding_outpoint0,
             amount: input_value0,
@@ -37,12 +38,14 @@ async fn test_kick_off_2_tx() {
         time: 45678,
         weight: 9012345,
     };
-    kick_off_2_tx.sign_input_0(
-        &config.operator_context,
-        &config.connector_1,
-        &config.connector_1_winternitz_secrets[&0],
-        &get_superblock_message(&sb, &sb_hash),
-    );
+    kick_off_2_tx
+        .sign_input_0(
+            &config.operator_context,
+            &config.connector_1,
+            &config.connector_1_winternitz_secrets[&0],
+            &get_superblock_message(&sb, &sb_hash),
+        )
+        .unwrap();
 
     let tx = kick_off_2_tx.finalize();
     // println!("Script Path Spend Transaction: {:?}\n", tx);
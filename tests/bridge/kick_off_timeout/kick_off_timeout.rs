@@ -3,6 +3,7 @@ use bitcoin::{consensus::encode::serialize_hex, Amount};
 use bitvm::bridge::{
     connectors::base::TaprootConnector,
     graphs::base::ONE_HUNDRED,
+    scripts::generate_pay_to_pubkey_script,
     transactions::{
         base::{BaseTransaction, Input},
         kick_off_timeout::KickOffTimeoutTransaction,
@@ -32,21 +33,26 @@ async fn test_kick_off_timeout_tx() {
         },
     );
 
-    let secret_nonces_0 = kick_off_timeout_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = kick_off_timeout_tx.push_nonces(&config.verifier_1_context);
-
-    kick_off_timeout_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_1,
-        &secret_nonces_0,
-    );
-    kick_off_timeout_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_1,
-        &secret_nonces_1,
-    );
-
+    let nonces_0 = kick_off_timeout_tx
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let nonces_1 = kick_off_timeout_tx
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
+
+    kick_off_timeout_tx
+        .pre_sign(&config.verifier_0_context, &config.connector_1, &nonces_0)
+        .unwrap();
+    kick_off_timeout_tx
+        .pre_sign(&config.verifier_1_context, &config.connector_1, &nonces_1)
+        .unwrap();
+
+    // the output must be added before finalizing, or the broadcast tx lacks it
+    let output_script_pubkey =
+        generate_pay_to_pubkey_script(&config.verifier_0_context.verifier_public_key);
+    kick_off_timeout_tx.add_output(output_script_pubkey.clone());
     let tx = kick_off_timeout_tx.finalize();
+    assert_eq!(tx.output[1].script_pubkey, output_script_pubkey);
     println!("Script Path Spend Transaction: {:?}\n", tx);
     let result = config.client_0.esplora.broadcast(&tx).await;
     println!("Txid: {:?}", tx.compute_txid());
@@ -6,6 +6,7 @@ pub mod client;
 pub mod data_store;
 pub mod disprove;
 pub mod disprove_chain;
+pub mod export;
 pub mod faucet;
 pub mod helper;
 pub mod integration;
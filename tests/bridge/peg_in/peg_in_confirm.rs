@@ -27,13 +27,22 @@ async fn test_peg_in_confirm_tx() {
         &config.depositor_context,
         &config.depositor_evm_address,
         Input { outpoint, amount },
-    );
-
-    let secret_nonces_0 = peg_in_confirm_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = peg_in_confirm_tx.push_nonces(&config.verifier_1_context);
-
-    peg_in_confirm_tx.pre_sign(&config.verifier_0_context, &secret_nonces_0);
-    peg_in_confirm_tx.pre_sign(&config.verifier_1_context, &secret_nonces_1);
+    )
+    .unwrap();
+
+    let nonces_0 = peg_in_confirm_tx
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let nonces_1 = peg_in_confirm_tx
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
+
+    peg_in_confirm_tx
+        .pre_sign(&config.verifier_0_context, &nonces_0)
+        .unwrap();
+    peg_in_confirm_tx
+        .pre_sign(&config.verifier_1_context, &nonces_1)
+        .unwrap();
 
     let tx = peg_in_confirm_tx.finalize();
     println!("Script Path Spend Transaction: {:?}\n", tx);
@@ -30,7 +30,8 @@ async fn test_peg_in_deposit_tx() {
         &config.depositor_context,
         &config.depositor_evm_address,
         Input { outpoint, amount },
-    );
+    )
+    .unwrap();
 
     println!(
         "Depositor public key: {:?}\n",
@@ -27,7 +27,8 @@ async fn test_peg_in_refund_tx() {
         &config.depositor_context,
         &config.depositor_evm_address,
         Input { outpoint, amount },
-    );
+    )
+    .unwrap();
 
     let tx = peg_in_refund_tx.finalize();
     println!("Script Path Spend Transaction: {:?}\n", tx);
@@ -21,14 +21,22 @@ async fn test_assert_tx_serialization() {
     )
     .await;
 
-    let mut assert_tx =
-        AssertTransaction::new(&config.operator_context, Input { outpoint, amount });
-
-    let secret_nonces_0 = assert_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = assert_tx.push_nonces(&config.verifier_1_context);
-
-    assert_tx.pre_sign(&config.verifier_0_context, &secret_nonces_0);
-    assert_tx.pre_sign(&config.verifier_1_context, &secret_nonces_1);
+    let mut assert_tx = AssertTransaction::new(
+        &config.operator_context,
+        &config.connector_b,
+        &config.connector_c,
+        Input { outpoint, amount },
+    );
+
+    let nonces_0 = assert_tx.push_nonces(&config.verifier_0_context).unwrap();
+    let nonces_1 = assert_tx.push_nonces(&config.verifier_1_context).unwrap();
+
+    assert_tx
+        .pre_sign(&config.verifier_0_context, &nonces_0)
+        .unwrap();
+    assert_tx
+        .pre_sign(&config.verifier_1_context, &nonces_1)
+        .unwrap();
 
     let json = serialize(&assert_tx);
     assert!(json.len() > 0);
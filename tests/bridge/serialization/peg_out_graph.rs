@@ -33,7 +33,8 @@ async fn test_peg_out_graph_serialization() {
         &config.depositor_context,
         Input { outpoint, amount },
         &config.depositor_evm_address,
-    );
+    )
+    .unwrap();
 
     let kick_off_amount = Amount::from_sat(INITIAL_AMOUNT + FEE_AMOUNT); // Arbitrary amount
 
@@ -54,7 +55,8 @@ async fn test_peg_out_graph_serialization() {
             outpoint: kick_off_outpoint,
             amount: kick_off_amount,
         },
-    );
+    )
+    .unwrap();
 
     let json = serialize(&peg_out_graph);
     assert!(json.len() > 0);
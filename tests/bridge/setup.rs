@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use bitcoin::{Network, PublicKey};
 
 use bitvm::bridge::{
+    assert_program::assert_chunks,
     client::client::BitVMClient,
     connectors::{
         connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
@@ -48,6 +49,7 @@ pub struct SetupConfig {
     pub connector_1_winternitz_secrets: HashMap<u8, WinternitzSecret>,
     pub connector_2_winternitz_secrets: HashMap<u8, WinternitzSecret>,
     pub connector_6_winternitz_secrets: HashMap<u8, WinternitzSecret>,
+    pub connector_c_winternitz_secrets: HashMap<u8, WinternitzSecret>,
 }
 
 pub async fn setup_test() -> SetupConfig {
@@ -73,10 +75,12 @@ pub async fn setup_test() -> SetupConfig {
     let withdrawer_context =
         WithdrawerContext::new(source_network, WITHDRAWER_SECRET, &n_of_n_public_keys);
 
+    // No registered operators, so that peg-ins can be pre-signed before any peg-out graph exists
     let client_0 = BitVMClient::new(
         source_network,
         destination_network,
         &n_of_n_public_keys,
+        &[],
         Some(DEPOSITOR_SECRET),
         Some(OPERATOR_SECRET),
         Some(VERIFIER_0_SECRET),
@@ -89,6 +93,7 @@ pub async fn setup_test() -> SetupConfig {
         source_network,
         destination_network,
         &n_of_n_public_keys,
+        &[],
         Some(DEPOSITOR_SECRET),
         Some(OPERATOR_SECRET),
         Some(VERIFIER_1_SECRET),
@@ -102,16 +107,12 @@ pub async fn setup_test() -> SetupConfig {
         &operator_context.operator_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
     );
-    let connector_b = ConnectorB::new(source_network, &operator_context.n_of_n_taproot_public_key);
-    let connector_c = ConnectorC::new(
-        source_network,
-        &operator_context.operator_taproot_public_key,
-    );
     let connector_z = ConnectorZ::new(
         source_network,
         DEPOSITOR_EVM_ADDRESS,
         &depositor_context.depositor_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
+        &depositor_context.params,
     );
     let connector_0 = Connector0::new(source_network, &operator_context.n_of_n_taproot_public_key);
 
@@ -119,19 +120,32 @@ pub async fn setup_test() -> SetupConfig {
         source_network,
         &operator_context.operator_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
+        &operator_context.params,
     );
     let (mut connector_2, _) = Connector2::new(
         source_network,
         &operator_context.operator_taproot_public_key,
         &operator_context.n_of_n_taproot_public_key,
     );
-    let connector_3 = Connector3::new(source_network, &operator_context.operator_public_key);
-    let connector_4 = Connector4::new(source_network, &operator_context.operator_public_key);
+    let connector_3 = Connector3::new(
+        source_network,
+        &operator_context.operator_public_key,
+        &operator_context.params,
+    );
+    let connector_4 = Connector4::new(
+        source_network,
+        &operator_context.operator_public_key,
+        &operator_context.params,
+    );
     let connector_5 = Connector5::new(source_network, &operator_context.n_of_n_taproot_public_key);
     let (mut connector_6, _) = Connector6::new(
         source_network,
         &operator_context.operator_taproot_public_key,
     );
+    let (mut connector_c, _) = ConnectorC::new(
+        source_network,
+        &operator_context.operator_taproot_public_key,
+    );
 
     // Swap out Winternitz secrets for testing.
     let (connector_1_winternitz_secrets, connector_1_winternitz_public_keys) =
@@ -143,6 +157,18 @@ pub async fn setup_test() -> SetupConfig {
     connector_1.winternitz_public_keys = connector_1_winternitz_public_keys;
     connector_2.winternitz_public_keys = connector_2_winternitz_public_keys;
     connector_6.winternitz_public_keys = connector_6_winternitz_public_keys;
+    let assert_chunk_indexes: Vec<u8> = (0..assert_chunks().len() as u8).collect();
+    let (connector_c_winternitz_secrets, connector_c_winternitz_public_keys) =
+        get_test_winternitz_keys(&assert_chunk_indexes);
+    connector_c.winternitz_public_keys = connector_c_winternitz_public_keys;
+
+    // Connector B checks the assert commitment against connector C's keys
+    let connector_b = ConnectorB::new(
+        source_network,
+        &operator_context.n_of_n_taproot_public_key,
+        &operator_context.params,
+        &connector_c.winternitz_public_keys,
+    );
 
     return SetupConfig {
         client_0,
@@ -168,6 +194,7 @@ pub async fn setup_test() -> SetupConfig {
         connector_1_winternitz_secrets,
         connector_2_winternitz_secrets,
         connector_6_winternitz_secrets,
+        connector_c_winternitz_secrets,
     };
 }
 
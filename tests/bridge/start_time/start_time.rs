@@ -30,13 +30,15 @@ async fn test_start_time_tx() {
         },
     );
 
-    let start_time_block = get_start_time_block();
-    start_time_tx.sign(
-        &config.operator_context,
-        &config.connector_2,
-        &config.connector_2_winternitz_secrets[&0],
-        start_time_block,
-    );
+    let start_time_block = get_start_time_block(&config.operator_context.params);
+    start_time_tx
+        .sign(
+            &config.operator_context,
+            &config.connector_2,
+            &config.connector_2_winternitz_secrets[&0],
+            start_time_block,
+        )
+        .unwrap();
 
     let tx = start_time_tx.finalize();
     println!("Script Path Spend Transaction: {:?}\n", tx);
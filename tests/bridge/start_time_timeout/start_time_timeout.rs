@@ -39,21 +39,29 @@ async fn test_start_time_timeout_tx() {
         },
     );
 
-    let secret_nonces_0 = start_time_timeout_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = start_time_timeout_tx.push_nonces(&config.verifier_1_context);
+    let nonces_0 = start_time_timeout_tx
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let nonces_1 = start_time_timeout_tx
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
-    start_time_timeout_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_0,
-    );
-    start_time_timeout_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_0,
-    );
+    start_time_timeout_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_1,
+            &config.connector_2,
+            &nonces_0,
+        )
+        .unwrap();
+    start_time_timeout_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_1,
+            &config.connector_2,
+            &nonces_1,
+        )
+        .unwrap();
 
     let tx = start_time_timeout_tx.finalize();
     println!("Script Path Spend Transaction: {:?}\n", tx);
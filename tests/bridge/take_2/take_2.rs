@@ -37,6 +37,7 @@ async fn test_take_2_tx() {
 
     let mut take_2_tx = Take2Transaction::new(
         &config.operator_context,
+        &config.connector_c,
         Input {
             outpoint: funding_outpoint0,
             amount: input_value0,
@@ -53,13 +54,18 @@ async fn test_take_2_tx() {
             outpoint: funding_outpoint3,
             amount: input_value3,
         },
-    );
+    )
+    .unwrap();
 
-    let secret_nonces_0 = take_2_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = take_2_tx.push_nonces(&config.verifier_1_context);
+    let nonces_0 = take_2_tx.push_nonces(&config.verifier_0_context).unwrap();
+    let nonces_1 = take_2_tx.push_nonces(&config.verifier_1_context).unwrap();
 
-    take_2_tx.pre_sign(&config.verifier_0_context, &secret_nonces_0);
-    take_2_tx.pre_sign(&config.verifier_1_context, &secret_nonces_1);
+    take_2_tx
+        .pre_sign(&config.verifier_0_context, &nonces_0)
+        .unwrap();
+    take_2_tx
+        .pre_sign(&config.verifier_1_context, &nonces_1)
+        .unwrap();
 
     let tx = take_2_tx.finalize();
     println!("Script Path Spend Transaction: {:?}\n", tx);
@@ -112,7 +112,8 @@ async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint) {
             amount,
         },
         &config.depositor_evm_address,
-    );
+    )
+    .unwrap();
 
     let (peg_out_graph, _) = PegOutGraph::new(
         &config.operator_context,
@@ -121,7 +122,8 @@ async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint) {
             outpoint: peg_out_outpoint,
             amount,
         },
-    );
+    )
+    .unwrap();
 
     return (peg_in_graph, peg_out_graph, peg_in_outpoint);
 }